@@ -0,0 +1,44 @@
+use crate::Error;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    instruction::Instruction,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::Transaction,
+};
+
+// Accounts and transactions of a cluster, implemented by RpcClient and by mocks in tests.
+pub trait Cluster {
+    // Data of an account, None if it does not exist.
+    fn account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>, Error>;
+
+    fn minimum_balance_for_rent_exemption(&self, len: usize) -> Result<u64, Error>;
+
+    // Sends one transaction paid by the first signer and waits until it is confirmed.
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), Error>;
+}
+
+impl Cluster for RpcClient {
+    fn account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>, Error> {
+        Ok(self
+            .get_account_with_commitment(pubkey, self.commitment())?
+            .value
+            .map(|account| account.data))
+    }
+
+    fn minimum_balance_for_rent_exemption(&self, len: usize) -> Result<u64, Error> {
+        Ok(self.get_minimum_balance_for_rent_exemption(len)?)
+    }
+
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), Error> {
+        let payer = signers.first().ok_or("Transaction without signer.")?;
+        let transaction = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&payer.pubkey()),
+            &signers.to_vec(),
+            self.get_latest_blockhash()?,
+        );
+        self.send_and_confirm_transaction(&transaction)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,9 @@
+use crate::Error;
+use light_client::decode;
+
+// Program account as JSON, see light_client::decode for the fields of every account type.
+pub fn decode_account(data: &[u8]) -> Result<String, Error> {
+    Ok(serde_json::to_string_pretty(&decode::decode_account(
+        data,
+    )?)?)
+}
@@ -0,0 +1,82 @@
+use crate::cluster::Cluster;
+use crate::status::transaction_status;
+use crate::Error;
+use light_client::{pack_instructions, LightTransaction};
+use light_protocol_program::IX_ORDER;
+use solana_sdk::signature::{Keypair, Signer};
+
+// Index of the next instruction of a transaction, IX_ORDER.len() once it is complete.
+// A transaction without tmp storage pda was not sent yet or completed, the last instruction
+// closes the tmp storage pda and creates the leaves pda.
+pub fn next_instruction_index<C: Cluster>(
+    cluster: &C,
+    transaction: &LightTransaction,
+    signer: &Keypair,
+) -> Result<usize, Error> {
+    let pdas = transaction.pdas();
+    match cluster.account_data(&pdas.tmp_storage)? {
+        Some(data) => {
+            let status = transaction_status(&data)?;
+            if status.signer != signer.pubkey() {
+                return Err(format!(
+                    "The transaction is signed by {}, not by {}.",
+                    status.signer,
+                    signer.pubkey()
+                )
+                .into());
+            }
+            Ok(status.current_instruction_index)
+        }
+        None if cluster.account_data(&pdas.leaves)?.is_some() => Ok(IX_ORDER.len()),
+        None => Ok(0),
+    }
+}
+
+// Runs a transaction to completion from its current instruction index. After a failed
+// transaction the index is read again from the tmp storage pda, so instructions which
+// landed are not sent twice. Fails after more than max_retries consecutive failed transactions.
+// progress is called with the next instruction index after every sent transaction.
+pub fn drive<C: Cluster>(
+    cluster: &C,
+    transaction: &LightTransaction,
+    signer: &Keypair,
+    max_instructions: usize,
+    max_retries: usize,
+    mut progress: impl FnMut(usize),
+) -> Result<(), Error> {
+    // After a relayer hand-off the signer is not the relayer of the external data.
+    let instructions = if signer.pubkey() == transaction.ext_data.relayer {
+        transaction.instructions()?
+    } else {
+        (0..IX_ORDER.len())
+            .map(|index| transaction.instruction(index, &signer.pubkey()))
+            .collect::<Result<Vec<_>, _>>()?
+    };
+    let mut failures = 0;
+    let mut last_index = None;
+    loop {
+        let index = next_instruction_index(cluster, transaction, signer)?;
+        if index >= IX_ORDER.len() {
+            return Ok(());
+        }
+        // Every confirmed transaction advances the index.
+        if failures == 0 && matches!(last_index, Some(last_index) if index <= last_index) {
+            return Err(format!("No progress at instruction {}.", index).into());
+        }
+        last_index = Some(index);
+
+        let mut sent = index;
+        for batch in pack_instructions(&instructions[index..], &signer.pubkey(), max_instructions) {
+            if let Err(error) = cluster.send(&batch, &[signer]) {
+                failures += 1;
+                if failures > max_retries {
+                    return Err(error);
+                }
+                break;
+            }
+            failures = 0;
+            sent += batch.len();
+            progress(sent);
+        }
+    }
+}
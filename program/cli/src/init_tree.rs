@@ -0,0 +1,58 @@
+use light_protocol_program::admin_config::find_admin_config;
+use light_protocol_program::poseidon_merkle_tree::state::MerkleTree;
+use light_protocol_program::utils::config::MERKLE_TREE_ACC_BYTES_ARRAY;
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_instruction, sysvar,
+};
+
+pub const INIT_MERKLE_TREE_SELECTOR: u8 = 240;
+
+// Creates the account of a new Merkle tree, owned by the program and rent exempt with lamports.
+pub fn create_merkle_tree_account_instruction(
+    program_id: &Pubkey,
+    payer: &Pubkey,
+    merkle_tree: &Pubkey,
+    lamports: u64,
+) -> Instruction {
+    system_instruction::create_account(
+        payer,
+        merkle_tree,
+        lamports,
+        MerkleTree::LEN as u64,
+        program_id,
+    )
+}
+
+// Initializes merkle_tree with the empty Merkle tree of height 18, signed by the admin
+// authority. Signers of an admin multisig are passed after the admin config pda.
+pub fn init_merkle_tree_instruction(
+    program_id: &Pubkey,
+    admin: &Pubkey,
+    merkle_tree: &Pubkey,
+    multisig_signers: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(*admin, true),
+        AccountMeta::new(*merkle_tree, false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(find_admin_config(program_id).0, false),
+    ];
+    accounts.extend(
+        multisig_signers
+            .iter()
+            .map(|signer| AccountMeta::new_readonly(*signer, true)),
+    );
+    // The selector is data[8] of the bincode encoded vector.
+    Instruction::new_with_bincode(*program_id, &[INIT_MERKLE_TREE_SELECTOR].to_vec(), accounts)
+}
+
+// Transactions only accept the Merkle trees of MERKLE_TREE_ACC_BYTES_ARRAY, other initialized
+// trees can't be used before they are added to the program.
+pub fn is_whitelisted_merkle_tree(merkle_tree: &Pubkey) -> bool {
+    MERKLE_TREE_ACC_BYTES_ARRAY
+        .iter()
+        .any(|(merkle_tree_bytes, _)| *merkle_tree_bytes == merkle_tree.to_bytes())
+}
@@ -0,0 +1,13 @@
+#![deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+
+// Operator commands of light-cli, main.rs parses the arguments and calls these modules.
+// Commands read and send through a Cluster, RpcClient for any RPC endpoint including a local
+// test validator.
+pub mod cluster;
+pub mod decode;
+pub mod drive;
+pub mod init_tree;
+pub mod status;
+pub mod tx_file;
+
+pub type Error = Box<dyn std::error::Error>;
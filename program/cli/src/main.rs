@@ -0,0 +1,203 @@
+#![deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+
+use clap::{crate_version, value_t, App, AppSettings, Arg, ArgMatches, SubCommand};
+use light_cli::cluster::Cluster;
+use light_cli::decode::decode_account;
+use light_cli::drive::drive;
+use light_cli::init_tree::{
+    create_merkle_tree_account_instruction, init_merkle_tree_instruction,
+    is_whitelisted_merkle_tree,
+};
+use light_cli::status::transaction_status;
+use light_cli::tx_file::TxFile;
+use light_cli::Error;
+use light_protocol_program::poseidon_merkle_tree::state::MerkleTree;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::{
+    commitment_config::CommitmentConfig,
+    program_pack::Pack,
+    pubkey::Pubkey,
+    signature::{read_keypair_file, Keypair, Signer},
+};
+use std::{env, process, str::FromStr};
+
+fn keypair(path: &str) -> Result<Keypair, Error> {
+    read_keypair_file(path)
+        .map_err(|error| format!("Can't read keypair {}: {}", path, error).into())
+}
+
+fn pubkey(matches: &ArgMatches, name: &str) -> Result<Pubkey, Error> {
+    let pubkey = matches.value_of(name).unwrap_or_default();
+    Pubkey::from_str(pubkey).map_err(|_| format!("Invalid pubkey {}.", pubkey).into())
+}
+
+fn account_data(cluster: &RpcClient, account: &Pubkey) -> Result<Vec<u8>, Error> {
+    cluster
+        .account_data(account)?
+        .ok_or_else(|| format!("Account {} does not exist.", account).into())
+}
+
+fn init_tree(cluster: &RpcClient, payer: &Keypair, matches: &ArgMatches) -> Result<(), Error> {
+    let program_id = pubkey(matches, "program-id")?;
+    let merkle_tree = keypair(matches.value_of("merkle-tree").unwrap_or_default())?;
+    let multisig_signers = matches
+        .values_of("multisig-signer")
+        .map(|paths| paths.map(keypair).collect::<Result<Vec<_>, _>>())
+        .transpose()?
+        .unwrap_or_default();
+
+    let mut instructions = vec![];
+    // The account is only created if it does not exist, i.e. to retry the initialization.
+    if cluster.account_data(&merkle_tree.pubkey())?.is_none() {
+        instructions.push(create_merkle_tree_account_instruction(
+            &program_id,
+            &payer.pubkey(),
+            &merkle_tree.pubkey(),
+            cluster.minimum_balance_for_rent_exemption(MerkleTree::LEN)?,
+        ));
+    }
+    instructions.push(init_merkle_tree_instruction(
+        &program_id,
+        &payer.pubkey(),
+        &merkle_tree.pubkey(),
+        &multisig_signers
+            .iter()
+            .map(|signer| signer.pubkey())
+            .collect::<Vec<_>>(),
+    ));
+    let mut signers = vec![payer, &merkle_tree];
+    signers.extend(multisig_signers.iter());
+    cluster.send(&instructions, &signers)?;
+
+    println!("Initialized Merkle tree {}.", merkle_tree.pubkey());
+    if !is_whitelisted_merkle_tree(&merkle_tree.pubkey()) {
+        println!(
+            "The Merkle tree is not in MERKLE_TREE_ACC_BYTES_ARRAY, transactions can't use it yet."
+        );
+    }
+    Ok(())
+}
+
+fn run() -> Result<(), Error> {
+    let default_keypair = format!(
+        "{}/.config/solana/id.json",
+        env::var("HOME").unwrap_or_default()
+    );
+    let matches = App::new("light-cli")
+        .version(crate_version!())
+        .about("Operator commands of the Light Protocol program")
+        .setting(AppSettings::SubcommandRequiredElseHelp)
+        .arg(
+            Arg::with_name("url")
+                .long("url")
+                .short("u")
+                .takes_value(true)
+                .default_value("http://localhost:8899")
+                .help("RPC endpoint"),
+        )
+        .arg(
+            Arg::with_name("keypair")
+                .long("keypair")
+                .short("k")
+                .takes_value(true)
+                .default_value(&default_keypair)
+                .help("Keypair of the payer, the admin or the relayer"),
+        )
+        .subcommand(
+            SubCommand::with_name("init-tree")
+                .about("Creates and initializes a Merkle tree account")
+                .arg(
+                    Arg::with_name("program-id")
+                        .long("program-id")
+                        .takes_value(true)
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("merkle-tree")
+                        .help("Keypair of the Merkle tree account")
+                        .required(true),
+                )
+                .arg(
+                    Arg::with_name("multisig-signer")
+                        .long("multisig-signer")
+                        .takes_value(true)
+                        .multiple(true)
+                        .number_of_values(1)
+                        .help("Keypair of an admin multisig signer"),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("decode")
+                .about("Prints a program account")
+                .arg(Arg::with_name("account").required(true)),
+        )
+        .subcommand(
+            SubCommand::with_name("status")
+                .about("Prints the phase and step of a transaction")
+                .arg(
+                    Arg::with_name("tmp-storage")
+                        .help("Tmp storage pda of the transaction")
+                        .required(true),
+                ),
+        )
+        .subcommand(
+            SubCommand::with_name("drive")
+                .about("Runs a transaction to completion, resumes a started transaction")
+                .arg(Arg::with_name("tx").help("Transaction file").required(true))
+                .arg(
+                    Arg::with_name("max-instructions")
+                        .long("max-instructions")
+                        .takes_value(true)
+                        .default_value("1")
+                        .help("Instructions per transaction, 1 with a transaction wide compute budget"),
+                )
+                .arg(
+                    Arg::with_name("max-retries")
+                        .long("max-retries")
+                        .takes_value(true)
+                        .default_value("5"),
+                ),
+        )
+        .get_matches();
+
+    let cluster = RpcClient::new_with_commitment(
+        matches.value_of("url").unwrap_or_default().to_string(),
+        CommitmentConfig::confirmed(),
+    );
+    let signer = || keypair(matches.value_of("keypair").unwrap_or_default());
+    match matches.subcommand() {
+        ("init-tree", Some(matches)) => init_tree(&cluster, &signer()?, matches),
+        ("decode", Some(matches)) => {
+            let data = account_data(&cluster, &pubkey(matches, "account")?)?;
+            println!("{}", decode_account(&data)?);
+            Ok(())
+        }
+        ("status", Some(matches)) => {
+            let data = account_data(&cluster, &pubkey(matches, "tmp-storage")?)?;
+            println!("{}", transaction_status(&data)?);
+            Ok(())
+        }
+        ("drive", Some(matches)) => {
+            let transaction =
+                TxFile::load(matches.value_of("tx").unwrap_or_default())?.transaction()?;
+            drive(
+                &cluster,
+                &transaction,
+                &signer()?,
+                value_t!(matches, "max-instructions", usize)?,
+                value_t!(matches, "max-retries", usize)?,
+                |index| println!("instruction {}", index),
+            )?;
+            println!("Transaction {} completed.", transaction.pdas().tmp_storage);
+            Ok(())
+        }
+        _ => Err("Unknown command.".into()),
+    }
+}
+
+fn main() {
+    if let Err(error) = run() {
+        eprintln!("error: {}", error);
+        process::exit(1);
+    }
+}
@@ -0,0 +1,52 @@
+use light_client::{phase, Phase};
+use light_protocol_program::error::LightError;
+use light_protocol_program::state::ChecksAndTransferState;
+use light_protocol_program::utils::config::TMP_STORAGE_ACCOUNT_TYPE;
+use light_protocol_program::IX_ORDER;
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+use std::fmt;
+
+// Progress of a transaction from its tmp storage pda. current_instruction_index is the index
+// of the next instruction in IX_ORDER, the tmp storage pda is closed by the last instruction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionStatus {
+    pub signer: Pubkey,
+    pub merkle_tree_index: u8,
+    pub current_instruction_index: usize,
+    // None once the computation has finished.
+    pub phase: Option<Phase>,
+    // IX_ORDER id of the next instruction.
+    pub step: Option<u8>,
+}
+
+pub fn transaction_status(tmp_storage_data: &[u8]) -> Result<TransactionStatus, ProgramError> {
+    let tmp_storage = ChecksAndTransferState::unpack(tmp_storage_data)?;
+    if tmp_storage.account_type != TMP_STORAGE_ACCOUNT_TYPE {
+        return Err(LightError::InvalidAccountType.into());
+    }
+    let index = tmp_storage.current_instruction_index;
+    Ok(TransactionStatus {
+        signer: Pubkey::new(&tmp_storage.signing_address),
+        merkle_tree_index: tmp_storage.merkle_tree_index,
+        current_instruction_index: index,
+        phase: phase(index),
+        step: IX_ORDER.get(index).copied(),
+    })
+}
+
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "signer: {}", self.signer)?;
+        writeln!(f, "merkle tree index: {}", self.merkle_tree_index)?;
+        writeln!(
+            f,
+            "instruction: {}/{}",
+            self.current_instruction_index,
+            IX_ORDER.len()
+        )?;
+        match (self.phase, self.step) {
+            (Some(phase), Some(step)) => write!(f, "phase: {:?}, step: {}", phase, step),
+            _ => write!(f, "phase: finished"),
+        }
+    }
+}
@@ -0,0 +1,102 @@
+use crate::Error;
+use light_client::LightTransaction;
+use light_protocol_program::post_withdraw_call::{PostWithdrawCall, PostWithdrawCallAccount};
+use light_protocol_program::relayer_handoff::RelayerHandoffCommitment;
+use light_protocol_program::send_data::SendData;
+use light_protocol_program::utils::config::get_merkle_tree_asset;
+use serde::{Deserialize, Serialize};
+use solana_program::pubkey::Pubkey;
+use std::{fs, str::FromStr};
+
+// Transaction file of the drive command (tx.json). Pubkeys are base58 strings.
+// send_data is the instruction data of the send data instruction without selector, including
+// the post withdraw call and relayer hand-off commitments. The asset is the asset of the
+// Merkle tree index in the external data.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TxFile {
+    pub program_id: String,
+    pub send_data: Vec<u8>,
+    pub merkle_tree_token: String,
+    #[serde(default)]
+    pub user_token: Option<String>,
+    #[serde(default)]
+    pub relayer_token: Option<String>,
+    #[serde(default)]
+    pub treasury: Option<String>,
+    // Required if the send data has a post withdraw call commitment.
+    #[serde(default)]
+    pub post_withdraw_call: Option<PostWithdrawCallFile>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PostWithdrawCallFile {
+    pub program_id: String,
+    pub accounts: Vec<PostWithdrawCallAccountFile>,
+    pub data: Vec<u8>,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct PostWithdrawCallAccountFile {
+    pub pubkey: String,
+    pub is_writable: bool,
+}
+
+fn pubkey(pubkey: &str) -> Result<Pubkey, Error> {
+    Pubkey::from_str(pubkey).map_err(|_| format!("Invalid pubkey {}.", pubkey).into())
+}
+
+fn optional_pubkey(optional: &Option<String>) -> Result<Option<Pubkey>, Error> {
+    optional.as_deref().map(pubkey).transpose()
+}
+
+impl TxFile {
+    pub fn load(path: &str) -> Result<Self, Error> {
+        Ok(serde_json::from_str(&fs::read_to_string(path)?)?)
+    }
+
+    pub fn transaction(&self) -> Result<LightTransaction, Error> {
+        let send_data = SendData::unpack(&self.send_data)?;
+        let post_withdraw_call = match &self.post_withdraw_call {
+            Some(call) => Some(PostWithdrawCall {
+                program_id: pubkey(&call.program_id)?,
+                accounts: call
+                    .accounts
+                    .iter()
+                    .map(|account| {
+                        Ok(PostWithdrawCallAccount {
+                            pubkey: pubkey(&account.pubkey)?,
+                            is_writable: account.is_writable,
+                        })
+                    })
+                    .collect::<Result<Vec<_>, Error>>()?,
+                data: call.data.clone(),
+            }),
+            None => None,
+        };
+        // The call is part of the tx integrity hash, a different call fails the last instruction.
+        if post_withdraw_call.as_ref().map(|call| call.commitment()) != send_data.post_withdraw_call
+        {
+            return Err(
+                "Post withdraw call does not match the commitment of the send data.".into(),
+            );
+        }
+        let relayer_handoff = send_data
+            .relayer_handoff
+            .as_ref()
+            .map(|commitment| RelayerHandoffCommitment::decode(&commitment[..]))
+            .transpose()?;
+        Ok(LightTransaction {
+            program_id: pubkey(&self.program_id)?,
+            asset: get_merkle_tree_asset(send_data.ext_data.merkle_tree_index)?,
+            proof: send_data.proof,
+            public_inputs: send_data.public_inputs,
+            ext_data: send_data.ext_data,
+            merkle_tree_token: pubkey(&self.merkle_tree_token)?,
+            user_token: optional_pubkey(&self.user_token)?,
+            relayer_token: optional_pubkey(&self.relayer_token)?,
+            treasury: optional_pubkey(&self.treasury)?,
+            post_withdraw_call,
+            relayer_handoff,
+        })
+    }
+}
@@ -0,0 +1,77 @@
+use light_cli::decode::decode_account;
+use light_cli::status::transaction_status;
+use light_client::Phase;
+use light_protocol_program::error::LightError;
+use light_protocol_program::poseidon_merkle_tree::state::MerkleTree;
+use light_protocol_program::state::ChecksAndTransferState;
+use light_protocol_program::utils::config::{
+    INIT_BYTES_MERKLE_TREE_18, NULLIFIER_ACCOUNT_TYPE, NULLIFIER_ACCOUNT_VERSION,
+    TMP_STORAGE_ACCOUNT_TYPE, TMP_STORAGE_ACCOUNT_VERSION,
+};
+use light_protocol_program::{IX_ORDER, VERIFICATION_END_INDEX};
+use serde_json::Value;
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+// signing_address at 4..36, current_instruction_index at 212..220
+fn tmp_storage(signer: &Pubkey, index: usize) -> Vec<u8> {
+    let mut data = vec![0u8; ChecksAndTransferState::LEN];
+    data[0] = TMP_STORAGE_ACCOUNT_VERSION;
+    data[1] = TMP_STORAGE_ACCOUNT_TYPE;
+    data[4..36].copy_from_slice(&signer.to_bytes());
+    data[212..220].copy_from_slice(&index.to_le_bytes());
+    data
+}
+
+#[test]
+fn status_should_show_phase_and_step() {
+    let signer = Pubkey::new_unique();
+    let status = transaction_status(&tmp_storage(&signer, 1)).unwrap();
+    assert_eq!(status.signer, signer);
+    assert_eq!(status.current_instruction_index, 1);
+    assert_eq!(status.phase, Some(Phase::RootCheck));
+    assert_eq!(status.step, Some(IX_ORDER[1]));
+
+    let status = transaction_status(&tmp_storage(&signer, VERIFICATION_END_INDEX)).unwrap();
+    assert_eq!(status.phase, Some(Phase::MerkleTreeUpdate));
+    assert_eq!(status.step, Some(IX_ORDER[VERIFICATION_END_INDEX]));
+    assert!(status
+        .to_string()
+        .contains(&format!("instruction: {}/1502", VERIFICATION_END_INDEX)));
+
+    let status = transaction_status(&tmp_storage(&signer, IX_ORDER.len())).unwrap();
+    assert_eq!(status.phase, None);
+    assert_eq!(status.step, None);
+    assert!(status.to_string().ends_with("phase: finished"));
+
+    let mut other_account = tmp_storage(&signer, 1);
+    other_account[1] = NULLIFIER_ACCOUNT_TYPE;
+    assert!(transaction_status(&other_account).is_err());
+    assert_eq!(
+        transaction_status(&[0u8; ChecksAndTransferState::LEN]).unwrap_err(),
+        ProgramError::from(LightError::InvalidAccountType)
+    );
+}
+
+#[test]
+fn accounts_should_be_printed_as_json() {
+    let tmp_storage: Value =
+        serde_json::from_str(&decode_account(&tmp_storage(&Pubkey::new_unique(), 1)).unwrap())
+            .unwrap();
+    assert_eq!(tmp_storage["version"], 1);
+    assert_eq!(tmp_storage["account"]["type"], "tmp_storage");
+    assert_eq!(tmp_storage["account"]["phase"], "root_check");
+
+    let mut merkle_tree = vec![0u8; MerkleTree::LEN];
+    merkle_tree[..INIT_BYTES_MERKLE_TREE_18.len()].copy_from_slice(&INIT_BYTES_MERKLE_TREE_18);
+    let merkle_tree: Value = serde_json::from_str(&decode_account(&merkle_tree).unwrap()).unwrap();
+    assert_eq!(merkle_tree["account"]["type"], "merkle_tree");
+    assert_eq!(merkle_tree["account"]["next_index"], 0);
+
+    let nullifier: Value = serde_json::from_str(
+        &decode_account(&[NULLIFIER_ACCOUNT_VERSION, NULLIFIER_ACCOUNT_TYPE]).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(nullifier["account"]["type"], "nullifier");
+    assert!(decode_account(&[1, 12]).is_err());
+    assert!(decode_account(&[1]).is_err());
+}
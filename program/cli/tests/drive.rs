@@ -0,0 +1,178 @@
+use light_cli::cluster::Cluster;
+use light_cli::drive::{drive, next_instruction_index};
+use light_cli::tx_file::{PostWithdrawCallFile, TxFile};
+use light_cli::Error;
+use light_protocol_program::state::ChecksAndTransferState;
+use light_protocol_program::utils::config::{
+    MERKLE_TREE_ACC_BYTES_ARRAY, TMP_STORAGE_ACCOUNT_TYPE, TMP_STORAGE_ACCOUNT_VERSION,
+};
+use light_protocol_program::IX_ORDER;
+use solana_program::{instruction::Instruction, program_pack::Pack, pubkey::Pubkey};
+use solana_sdk::signature::{Keypair, Signer};
+use std::cell::{Cell, RefCell};
+
+#[path = "../../tests/test_utils/fixtures.rs"]
+mod fixtures;
+use crate::fixtures::{read_send_data, PRIV_KEY_DEPOSIT};
+
+fn tx_file() -> TxFile {
+    TxFile {
+        program_id: "TransferLamports111111111111111111112111111".to_string(),
+        send_data: read_send_data("deposit.txt"),
+        merkle_tree_token: Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1).to_string(),
+        user_token: None,
+        relayer_token: None,
+        treasury: None,
+        post_withdraw_call: None,
+    }
+}
+
+// Cluster which advances the current instruction index of the tmp storage pda by the
+// instructions of every transaction, sends in fail_sends fail.
+struct MockCluster {
+    signer: Pubkey,
+    tmp_storage: Pubkey,
+    leaves: Pubkey,
+    expected: Vec<Instruction>,
+    index: Cell<usize>,
+    sends: Cell<usize>,
+    fail_sends: Vec<usize>,
+    sent: RefCell<Vec<usize>>,
+}
+
+impl MockCluster {
+    fn new(signer: &Keypair, index: usize, fail_sends: Vec<usize>) -> Self {
+        let transaction = tx_file().transaction().unwrap();
+        let pdas = transaction.pdas();
+        MockCluster {
+            signer: signer.pubkey(),
+            tmp_storage: pdas.tmp_storage,
+            leaves: pdas.leaves,
+            expected: transaction.instructions().unwrap(),
+            index: Cell::new(index),
+            sends: Cell::new(0),
+            fail_sends,
+            sent: RefCell::new(vec![]),
+        }
+    }
+}
+
+impl Cluster for MockCluster {
+    fn account_data(&self, pubkey: &Pubkey) -> Result<Option<Vec<u8>>, Error> {
+        let index = self.index.get();
+        if *pubkey == self.tmp_storage && index > 0 && index < IX_ORDER.len() {
+            let mut data = vec![0u8; ChecksAndTransferState::LEN];
+            data[0] = TMP_STORAGE_ACCOUNT_VERSION;
+            data[1] = TMP_STORAGE_ACCOUNT_TYPE;
+            data[4..36].copy_from_slice(&self.signer.to_bytes());
+            data[212..220].copy_from_slice(&index.to_le_bytes());
+            Ok(Some(data))
+        } else if *pubkey == self.leaves && index == IX_ORDER.len() {
+            Ok(Some(vec![0u8; 2]))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn minimum_balance_for_rent_exemption(&self, _len: usize) -> Result<u64, Error> {
+        Ok(0)
+    }
+
+    fn send(&self, instructions: &[Instruction], signers: &[&Keypair]) -> Result<(), Error> {
+        let send = self.sends.get();
+        self.sends.set(send + 1);
+        if self.fail_sends.contains(&send) {
+            return Err("blockhash not found".into());
+        }
+        assert_eq!(signers[0].pubkey(), self.signer);
+        let index = self.index.get();
+        assert_eq!(
+            instructions,
+            &self.expected[index..index + instructions.len()]
+        );
+        self.sent.borrow_mut().push(index);
+        self.index.set(index + instructions.len());
+        Ok(())
+    }
+}
+
+#[test]
+fn tx_file_should_build_the_transaction() {
+    let tx_file = tx_file();
+    let json = serde_json::to_string(&tx_file).unwrap();
+    assert_eq!(serde_json::from_str::<TxFile>(&json).unwrap(), tx_file);
+
+    let transaction = tx_file.transaction().unwrap();
+    assert_eq!(transaction.send_data().unwrap(), tx_file.send_data);
+    assert_eq!(
+        transaction.ext_data.relayer,
+        Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap().pubkey()
+    );
+
+    // the send data has no post withdraw call commitment
+    let mut with_call = tx_file.clone();
+    with_call.post_withdraw_call = Some(PostWithdrawCallFile {
+        program_id: Pubkey::new_unique().to_string(),
+        accounts: vec![],
+        data: vec![1],
+    });
+    assert!(with_call.transaction().is_err());
+    let mut invalid_pubkey = tx_file;
+    invalid_pubkey.merkle_tree_token = "merkle tree token".to_string();
+    assert!(invalid_pubkey.transaction().is_err());
+}
+
+#[test]
+fn drive_should_run_the_transaction_to_completion() {
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let transaction = tx_file().transaction().unwrap();
+    let cluster = MockCluster::new(&relayer, 0, vec![]);
+    assert_eq!(
+        next_instruction_index(&cluster, &transaction, &relayer).unwrap(),
+        0
+    );
+
+    let mut progress = vec![];
+    drive(&cluster, &transaction, &relayer, 10, 0, |index| {
+        progress.push(index)
+    })
+    .unwrap();
+    assert_eq!(cluster.index.get(), IX_ORDER.len());
+    assert_eq!(progress.last(), Some(&IX_ORDER.len()));
+    // compute instructions are packed
+    assert_eq!(cluster.sent.borrow()[0], 0);
+    assert!(cluster.sent.borrow().len() < IX_ORDER.len() / 2);
+    assert_eq!(
+        next_instruction_index(&cluster, &transaction, &relayer).unwrap(),
+        IX_ORDER.len()
+    );
+
+    // completed transactions are not sent again
+    drive(&cluster, &transaction, &relayer, 10, 0, |_| {}).unwrap();
+    assert_eq!(cluster.sends.get(), progress.len());
+}
+
+#[test]
+fn drive_should_resume_after_failures() {
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let transaction = tx_file().transaction().unwrap();
+
+    // started by an earlier run
+    let cluster = MockCluster::new(&relayer, 700, vec![3, 4]);
+    drive(&cluster, &transaction, &relayer, 1, 2, |_| {}).unwrap();
+    assert_eq!(cluster.index.get(), IX_ORDER.len());
+    let sent = cluster.sent.borrow();
+    assert_eq!(sent[..4], [700, 701, 702, 703]);
+    assert_eq!(sent.len(), IX_ORDER.len() - 700);
+
+    // three failures in a row exceed two retries
+    let cluster = MockCluster::new(&relayer, 700, vec![3, 4, 5]);
+    assert!(drive(&cluster, &transaction, &relayer, 1, 2, |_| {}).is_err());
+    assert_eq!(cluster.index.get(), 703);
+
+    // the tmp storage pda of another signer
+    let other_signer = Keypair::new();
+    let cluster = MockCluster::new(&other_signer, 700, vec![]);
+    assert!(drive(&cluster, &transaction, &relayer, 1, 2, |_| {}).is_err());
+    assert_eq!(cluster.sends.get(), 0);
+}
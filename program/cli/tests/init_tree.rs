@@ -0,0 +1,64 @@
+use light_cli::init_tree::{
+    create_merkle_tree_account_instruction, init_merkle_tree_instruction,
+    is_whitelisted_merkle_tree, INIT_MERKLE_TREE_SELECTOR,
+};
+use light_protocol_program::admin_config::find_admin_config;
+use light_protocol_program::poseidon_merkle_tree::state::MerkleTree;
+use light_protocol_program::utils::config::MERKLE_TREE_ACC_BYTES_ARRAY;
+use solana_program::{
+    program_pack::Pack, pubkey::Pubkey, system_instruction::SystemInstruction, system_program,
+    sysvar,
+};
+
+#[test]
+fn init_merkle_tree_instruction_should_match_the_processor() {
+    let program_id = Pubkey::new_unique();
+    let admin = Pubkey::new_unique();
+    let merkle_tree = Pubkey::new_unique();
+    let multisig_signer = Pubkey::new_unique();
+    let instruction =
+        init_merkle_tree_instruction(&program_id, &admin, &merkle_tree, &[multisig_signer]);
+
+    // bincode length prefix, the selector is data[8]
+    assert_eq!(instruction.data, vec![1, 0, 0, 0, 0, 0, 0, 0, 240]);
+    assert_eq!(instruction.data[8], INIT_MERKLE_TREE_SELECTOR);
+    assert_eq!(instruction.program_id, program_id);
+    let accounts: Vec<(Pubkey, bool, bool)> = instruction
+        .accounts
+        .iter()
+        .map(|meta| (meta.pubkey, meta.is_signer, meta.is_writable))
+        .collect();
+    assert_eq!(
+        accounts,
+        vec![
+            (admin, true, true),
+            (merkle_tree, false, true),
+            (sysvar::rent::id(), false, false),
+            (find_admin_config(&program_id).0, false, false),
+            (multisig_signer, true, false),
+        ]
+    );
+}
+
+#[test]
+fn merkle_tree_account_should_be_owned_by_the_program() {
+    let program_id = Pubkey::new_unique();
+    let payer = Pubkey::new_unique();
+    let merkle_tree = Pubkey::new_unique();
+    let instruction =
+        create_merkle_tree_account_instruction(&program_id, &payer, &merkle_tree, 1_000);
+    assert_eq!(instruction.program_id, system_program::id());
+    assert_eq!(
+        bincode::deserialize::<SystemInstruction>(&instruction.data).unwrap(),
+        SystemInstruction::CreateAccount {
+            lamports: 1_000,
+            space: MerkleTree::LEN as u64,
+            owner: program_id,
+        }
+    );
+
+    assert!(is_whitelisted_merkle_tree(&Pubkey::new(
+        &MERKLE_TREE_ACC_BYTES_ARRAY[0].0
+    )));
+    assert!(!is_whitelisted_merkle_tree(&merkle_tree));
+}
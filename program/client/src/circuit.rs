@@ -0,0 +1,429 @@
+use crate::merkle_tree::{MerkleProof, MERKLE_TREE_HEIGHT};
+use crate::poseidon_parameters::{
+    poseidon_parameters_2, poseidon_parameters_4, PoseidonCircomRounds2, PoseidonCircomRounds4,
+};
+use crate::utxo::{
+    from_bytes, poseidon_parameters, to_bytes, Keypair, PoseidonCircomCRH2, PoseidonCircomCRH3,
+    PoseidonCircomCRH4, Utxo,
+};
+use crate::PublicInputs;
+use ark_bn254::Bn254;
+use ark_crypto_primitives::crh::{CRHGadget, TwoToOneCRHGadget};
+use ark_ed_on_bn254::Fq;
+use ark_groth16::{
+    create_random_proof, generate_random_parameters, prepare_verifying_key, verify_proof, Proof,
+    ProvingKey, VerifyingKey,
+};
+use ark_r1cs_std::{fields::fp::FpVar, prelude::*};
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystemRef, SynthesisError};
+use ark_std::{rand::Rng, Zero};
+use arkworks_gadgets::poseidon::{
+    circom::constraints::CircomCRHGadget, constraints::PoseidonParametersVar,
+};
+use light_protocol_program::groth16_verifier::parsers::{
+    parse_proof_b_to_bytes, parse_x_group_affine_to_bytes,
+};
+use light_protocol_program::poseidon_merkle_tree::instructions_poseidon::PoseidonCircomRounds3;
+use solana_program::{msg, program_error::ProgramError};
+
+// The 2 inputs 2 outputs transaction circuit in R1CS for local proving. Hashes are the Poseidon
+// of utxo.rs:
+// - the input commitments are in the Merkle tree with the public root, inputs without amount
+//   are not checked
+// - the public nullifiers are the nullifiers of the inputs, they differ
+// - the public leaves are the commitments of the outputs
+// - amounts are at most 64 bits and sum of inputs + public amount = sum of outputs
+// - the tx integrity hash is a public input which is bound to the proof
+// Public inputs are in the order of the send data: root, public amount, tx integrity hash,
+// nullifiers, leaf_right, leaf_left. leaf_left is the commitment of output 0.
+// The multi asset circuit has the asset index of the Merkle tree as 8th public input, every
+// leaf is Poseidon(commitment, asset index) (Utxo::leaf). The asset index is not part of the
+// send data, the program takes it from the whitelisted Merkle tree index.
+// Keys from setup() and setup_multi_asset() are not the verifying keys of the program, proofs
+// of this circuit verify with verify() and in program tests after set_program_verifying_key.
+pub type PoseidonCircomCRH2Gadget = CircomCRHGadget<Fq, PoseidonCircomRounds2>;
+pub type PoseidonCircomCRH3Gadget = CircomCRHGadget<Fq, PoseidonCircomRounds3>;
+pub type PoseidonCircomCRH4Gadget = CircomCRHGadget<Fq, PoseidonCircomRounds4>;
+
+pub const AMOUNT_BITS: usize = 64;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionCircuit {
+    // public inputs
+    pub root: Fq,
+    pub public_amount: Fq,
+    pub tx_integrity_hash: Fq,
+    pub nullifiers: [Fq; 2],
+    pub commitments: [Fq; 2],
+    // None in single asset Merkle trees
+    pub asset_index: Option<u8>,
+    // private inputs
+    pub private_key: Fq,
+    pub inputs: [Utxo; 2],
+    // Siblings of the input commitments from the leaf level, Utxo::index is the path index.
+    pub input_paths: [Vec<Fq>; 2],
+    pub outputs: [Utxo; 2],
+}
+
+// Public amount of a transaction, ext_amount - fees in the field. fees are the relayer fee and
+// the protocol fee, a withdrawal has a negative public amount.
+pub fn public_amount(ext_amount: i64, fees: u64) -> Fq {
+    let ext_amount_field = if ext_amount < 0 {
+        -Fq::from(ext_amount.unsigned_abs())
+    } else {
+        Fq::from(ext_amount as u64)
+    };
+    ext_amount_field - Fq::from(fees)
+}
+
+impl TransactionCircuit {
+    // Inputs are owned by keypair. Inputs with an amount need the Merkle proof of their
+    // commitment at their index against root, inputs without amount can be None.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        keypair: &Keypair,
+        inputs: [Utxo; 2],
+        input_proofs: [Option<&MerkleProof>; 2],
+        outputs: [Utxo; 2],
+        root: &[u8; 32],
+        public_amount: Fq,
+        tx_integrity_hash: Fq,
+    ) -> Result<Self, ProgramError> {
+        TransactionCircuit::with_asset_index(
+            None,
+            keypair,
+            inputs,
+            input_proofs,
+            outputs,
+            root,
+            public_amount,
+            tx_integrity_hash,
+        )
+    }
+
+    // Transaction of the asset of asset_index in a multi asset Merkle tree, the input proofs
+    // are proofs of the leaves of the inputs.
+    #[allow(clippy::too_many_arguments)]
+    pub fn with_asset_index(
+        asset_index: Option<u8>,
+        keypair: &Keypair,
+        inputs: [Utxo; 2],
+        input_proofs: [Option<&MerkleProof>; 2],
+        outputs: [Utxo; 2],
+        root: &[u8; 32],
+        public_amount: Fq,
+        tx_integrity_hash: Fq,
+    ) -> Result<Self, ProgramError> {
+        let mut nullifiers = [Fq::zero(); 2];
+        let mut input_paths = [Vec::new(), Vec::new()];
+        for (i, input) in inputs.iter().enumerate() {
+            nullifiers[i] = input.leaf_nullifier(keypair, asset_index)?;
+            input_paths[i] = match input_proofs[i] {
+                Some(proof) => {
+                    if proof.leaf_index != input.index
+                        || proof.leaf != to_bytes(&input.leaf(asset_index)?)?
+                        || (input.amount != 0 && proof.root != *root)
+                        || !proof.verify()?
+                    {
+                        msg!("Invalid Merkle proof of input {}.", i);
+                        return Err(ProgramError::InvalidArgument);
+                    }
+                    proof
+                        .siblings
+                        .iter()
+                        .map(from_bytes)
+                        .collect::<Result<Vec<Fq>, ProgramError>>()?
+                }
+                None if input.amount == 0 => vec![Fq::zero(); MERKLE_TREE_HEIGHT],
+                None => {
+                    msg!("Input {} with amount requires a Merkle proof.", i);
+                    return Err(ProgramError::InvalidArgument);
+                }
+            };
+        }
+        if nullifiers[0] == nullifiers[1] {
+            msg!("Inputs have the same nullifier.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        let sum_inputs = Fq::from(inputs[0].amount) + Fq::from(inputs[1].amount);
+        let sum_outputs = Fq::from(outputs[0].amount) + Fq::from(outputs[1].amount);
+        if sum_inputs + public_amount != sum_outputs {
+            msg!("Amounts of inputs and outputs don't balance.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok(TransactionCircuit {
+            root: from_bytes(root)?,
+            public_amount,
+            tx_integrity_hash,
+            nullifiers,
+            commitments: [outputs[0].leaf(asset_index)?, outputs[1].leaf(asset_index)?],
+            asset_index,
+            private_key: keypair.private_key,
+            inputs,
+            input_paths,
+            outputs,
+        })
+    }
+
+    // Circuit with zero values, the constraints don't depend on the values.
+    pub fn blank() -> Self {
+        TransactionCircuit::blank_with_asset_index(None)
+    }
+
+    // Blank circuit of multi asset Merkle trees, the asset index is a public input.
+    pub fn blank_multi_asset() -> Self {
+        TransactionCircuit::blank_with_asset_index(Some(0))
+    }
+
+    fn blank_with_asset_index(asset_index: Option<u8>) -> Self {
+        let utxo = Utxo {
+            amount: 0,
+            blinding: Fq::zero(),
+            public_key: Fq::zero(),
+            index: 0,
+        };
+        TransactionCircuit {
+            root: Fq::zero(),
+            public_amount: Fq::zero(),
+            tx_integrity_hash: Fq::zero(),
+            nullifiers: [Fq::zero(); 2],
+            commitments: [Fq::zero(); 2],
+            asset_index,
+            private_key: Fq::zero(),
+            inputs: [utxo; 2],
+            input_paths: [
+                vec![Fq::zero(); MERKLE_TREE_HEIGHT],
+                vec![Fq::zero(); MERKLE_TREE_HEIGHT],
+            ],
+            outputs: [utxo; 2],
+        }
+    }
+
+    pub fn public_inputs(&self) -> Vec<Fq> {
+        let mut public_inputs = vec![
+            self.root,
+            self.public_amount,
+            self.tx_integrity_hash,
+            self.nullifiers[0],
+            self.nullifiers[1],
+            self.commitments[1],
+            self.commitments[0],
+        ];
+        public_inputs.extend(self.asset_index.map(Fq::from));
+        public_inputs
+    }
+
+    // Public inputs as in the send data, without the asset index.
+    pub fn public_inputs_bytes(&self) -> Result<PublicInputs, ProgramError> {
+        Ok(PublicInputs {
+            root: to_bytes(&self.root)?,
+            public_amount: to_bytes(&self.public_amount)?,
+            tx_integrity_hash: to_bytes(&self.tx_integrity_hash)?,
+            nullifiers: [
+                to_bytes(&self.nullifiers[0])?,
+                to_bytes(&self.nullifiers[1])?,
+            ],
+            leaves: [
+                to_bytes(&self.commitments[1])?,
+                to_bytes(&self.commitments[0])?,
+            ],
+        })
+    }
+
+    pub fn prove<R: Rng>(
+        &self,
+        proving_key: &ProvingKey<Bn254>,
+        rng: &mut R,
+    ) -> Result<Proof<Bn254>, SynthesisError> {
+        create_random_proof(self.clone(), proving_key, rng)
+    }
+}
+
+impl ConstraintSynthesizer<Fq> for TransactionCircuit {
+    fn generate_constraints(self, cs: ConstraintSystemRef<Fq>) -> Result<(), SynthesisError> {
+        if self
+            .input_paths
+            .iter()
+            .any(|path| path.len() != MERKLE_TREE_HEIGHT)
+        {
+            return Err(SynthesisError::Unsatisfiable);
+        }
+        let parameters = PoseidonParametersVar::new_constant(cs.clone(), poseidon_parameters())?;
+        let parameters_2 =
+            PoseidonParametersVar::new_constant(cs.clone(), poseidon_parameters_2())?;
+        let parameters_4 =
+            PoseidonParametersVar::new_constant(cs.clone(), poseidon_parameters_4())?;
+
+        // Allocated in the order of the public inputs.
+        let root = FpVar::new_input(cs.clone(), || Ok(self.root))?;
+        let public_amount = FpVar::new_input(cs.clone(), || Ok(self.public_amount))?;
+        let tx_integrity_hash = FpVar::new_input(cs.clone(), || Ok(self.tx_integrity_hash))?;
+        let nullifiers = [
+            FpVar::new_input(cs.clone(), || Ok(self.nullifiers[0]))?,
+            FpVar::new_input(cs.clone(), || Ok(self.nullifiers[1]))?,
+        ];
+        let leaf_right = FpVar::new_input(cs.clone(), || Ok(self.commitments[1]))?;
+        let leaf_left = FpVar::new_input(cs.clone(), || Ok(self.commitments[0]))?;
+        let asset_index = self
+            .asset_index
+            .map(|asset_index| FpVar::new_input(cs.clone(), || Ok(Fq::from(asset_index))))
+            .transpose()?;
+
+        let private_key = FpVar::new_witness(cs.clone(), || Ok(self.private_key))?;
+        let public_key = hash_1(&parameters_2, &private_key)?;
+
+        let mut sum_inputs = FpVar::zero();
+        for (i, input) in self.inputs.iter().enumerate() {
+            let amount = amount_var(cs.clone(), input.amount)?;
+            let blinding = FpVar::new_witness(cs.clone(), || Ok(input.blinding))?;
+            let index = FpVar::new_witness(cs.clone(), || Ok(Fq::from(input.index)))?;
+            let input_leaf = leaf(
+                &parameters,
+                hash_3(&parameters_4, &amount, &public_key, &blinding)?,
+                asset_index.as_ref(),
+            )?;
+
+            let signature = hash_3(&parameters_4, &private_key, &input_leaf, &index)?;
+            let nullifier = hash_3(&parameters_4, &input_leaf, &index, &signature)?;
+            nullifier.enforce_equal(&nullifiers[i])?;
+
+            // The path index is the index of the nullifier, its bits above the height are 0.
+            let index_bits = index.to_bits_le()?;
+            for bit in index_bits[MERKLE_TREE_HEIGHT..].iter() {
+                bit.enforce_equal(&Boolean::FALSE)?;
+            }
+            let mut node = input_leaf;
+            for (level, sibling) in self.input_paths[i].iter().enumerate() {
+                let sibling = FpVar::new_witness(cs.clone(), || Ok(*sibling))?;
+                let is_right = &index_bits[level];
+                let left = FpVar::conditionally_select(is_right, &sibling, &node)?;
+                let right = FpVar::conditionally_select(is_right, &node, &sibling)?;
+                node = hash(&parameters, &left, &right)?;
+            }
+            node.conditional_enforce_equal(&root, &amount.is_eq(&FpVar::zero())?.not())?;
+            sum_inputs += amount;
+        }
+        nullifiers[0].enforce_not_equal(&nullifiers[1])?;
+
+        let mut sum_outputs = FpVar::zero();
+        for (output, output_leaf) in self.outputs.iter().zip([&leaf_left, &leaf_right].iter()) {
+            let amount = amount_var(cs.clone(), output.amount)?;
+            let public_key = FpVar::new_witness(cs.clone(), || Ok(output.public_key))?;
+            let blinding = FpVar::new_witness(cs.clone(), || Ok(output.blinding))?;
+            leaf(
+                &parameters,
+                hash_3(&parameters_4, &amount, &public_key, &blinding)?,
+                asset_index.as_ref(),
+            )?
+            .enforce_equal(output_leaf)?;
+            sum_outputs += amount;
+        }
+        (sum_inputs + public_amount).enforce_equal(&sum_outputs)?;
+
+        // A public input without constraints could be changed for a valid proof.
+        tx_integrity_hash.square()?;
+        Ok(())
+    }
+}
+
+fn hash(
+    parameters: &PoseidonParametersVar<Fq>,
+    left: &FpVar<Fq>,
+    right: &FpVar<Fq>,
+) -> Result<FpVar<Fq>, SynthesisError> {
+    <PoseidonCircomCRH3Gadget as TwoToOneCRHGadget<PoseidonCircomCRH3, Fq>>::evaluate(
+        parameters,
+        &left.to_bytes()?,
+        &right.to_bytes()?,
+    )
+}
+
+fn hash_1(
+    parameters: &PoseidonParametersVar<Fq>,
+    input: &FpVar<Fq>,
+) -> Result<FpVar<Fq>, SynthesisError> {
+    <PoseidonCircomCRH2Gadget as CRHGadget<PoseidonCircomCRH2, Fq>>::evaluate(
+        parameters,
+        &input.to_bytes()?,
+    )
+}
+
+fn hash_3(
+    parameters: &PoseidonParametersVar<Fq>,
+    first: &FpVar<Fq>,
+    second: &FpVar<Fq>,
+    third: &FpVar<Fq>,
+) -> Result<FpVar<Fq>, SynthesisError> {
+    <PoseidonCircomCRH4Gadget as CRHGadget<PoseidonCircomCRH4, Fq>>::evaluate(
+        parameters,
+        &[first.to_bytes()?, second.to_bytes()?, third.to_bytes()?].concat(),
+    )
+}
+
+// Leaf of a commitment, Poseidon(commitment, asset index) in multi asset Merkle trees.
+fn leaf(
+    parameters: &PoseidonParametersVar<Fq>,
+    commitment: FpVar<Fq>,
+    asset_index: Option<&FpVar<Fq>>,
+) -> Result<FpVar<Fq>, SynthesisError> {
+    match asset_index {
+        Some(asset_index) => hash(parameters, &commitment, asset_index),
+        None => Ok(commitment),
+    }
+}
+
+// Amount witness which is at most AMOUNT_BITS bits, sums of amounts can't wrap around.
+fn amount_var(cs: ConstraintSystemRef<Fq>, amount: u64) -> Result<FpVar<Fq>, SynthesisError> {
+    let amount = FpVar::new_witness(cs, || Ok(Fq::from(amount)))?;
+    for bit in amount.to_bits_le()?[AMOUNT_BITS..].iter() {
+        bit.enforce_equal(&Boolean::FALSE)?;
+    }
+    Ok(amount)
+}
+
+// Groth16 keys of the circuit for local proving.
+pub fn setup<R: Rng>(rng: &mut R) -> Result<ProvingKey<Bn254>, SynthesisError> {
+    generate_random_parameters::<Bn254, _, _>(TransactionCircuit::blank(), rng)
+}
+
+// Groth16 keys of the multi asset circuit, 8 public inputs.
+pub fn setup_multi_asset<R: Rng>(rng: &mut R) -> Result<ProvingKey<Bn254>, SynthesisError> {
+    generate_random_parameters::<Bn254, _, _>(TransactionCircuit::blank_multi_asset(), rng)
+}
+
+pub fn verify(
+    verifying_key: &VerifyingKey<Bn254>,
+    public_inputs: &[Fq],
+    proof: &Proof<Bn254>,
+) -> Result<bool, SynthesisError> {
+    verify_proof(&prepare_verifying_key(verifying_key), proof, public_inputs)
+}
+
+// Lets the program verify proofs of setup() instead of proofs of the circom circuit, for program
+// tests only. None restores the verifying key of the program.
+#[cfg(feature = "test-verifying-key")]
+pub fn set_program_verifying_key(verifying_key: Option<&VerifyingKey<Bn254>>) {
+    use light_protocol_program::utils::test_verifying_key::{
+        set_test_verifying_key, TestVerifyingKey,
+    };
+    set_test_verifying_key(verifying_key.map(|verifying_key| {
+        TestVerifyingKey::new(
+            verifying_key.alpha_g1,
+            verifying_key.beta_g2,
+            verifying_key.gamma_g2,
+            verifying_key.delta_g2,
+            verifying_key.gamma_abc_g1.clone(),
+        )
+    }));
+}
+
+// Proof a, b and c in the layout of the send data.
+pub fn proof_bytes(proof: &Proof<Bn254>) -> Vec<u8> {
+    let mut a = vec![0u8; 64];
+    let mut b = vec![0u8; 128];
+    let mut c = vec![0u8; 64];
+    parse_x_group_affine_to_bytes(proof.a, &mut a);
+    parse_proof_b_to_bytes(proof.b, &mut b);
+    parse_x_group_affine_to_bytes(proof.c, &mut c);
+    [a, b, c].concat()
+}
@@ -0,0 +1,356 @@
+use crate::merkle_tree::MERKLE_TREE_HEIGHT;
+use crate::scanner::LeavesAccount;
+use crate::{phase, Phase};
+use arrayref::{array_ref, array_refs};
+use light_protocol_program::admin_config::AdminConfig;
+use light_protocol_program::asset::Asset;
+use light_protocol_program::error::LightError;
+use light_protocol_program::global_config::GlobalConfig;
+use light_protocol_program::merkle_tree_limits::MerkleTreeLimits;
+use light_protocol_program::migration::LEGACY_USER_ACCOUNT_TYPE;
+use light_protocol_program::poseidon_merkle_tree::state::MerkleTree;
+use light_protocol_program::post_withdraw_call::NO_POST_WITHDRAW_CALL;
+use light_protocol_program::protocol_fee::ProtocolFeeConfig;
+use light_protocol_program::relayer_handoff::RelayerHandoff;
+use light_protocol_program::state::ChecksAndTransferState;
+use light_protocol_program::utils::account_header::ACCOUNT_HEADER_LEN;
+use light_protocol_program::utils::config::{
+    ADMIN_CONFIG_ACCOUNT_TYPE, GLOBAL_CONFIG_ACCOUNT_TYPE, LEAVES_PDA_ACCOUNT_TYPE,
+    MERKLE_TREE_ACCOUNT_TYPE, MERKLE_TREE_LIMITS_ACCOUNT_TYPE, NULLIFIER_ACCOUNT_TYPE,
+    PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE, RELAYER_HANDOFF_ACCOUNT_TYPE, TMP_STORAGE_ACCOUNT_TYPE,
+    USER_ACCOUNT_TYPE,
+};
+use light_protocol_program::IX_ORDER;
+use serde::Serialize;
+use solana_program::{msg, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+use std::convert::TryInto;
+
+// Host-side decoder of program accounts for debugging. decode_account identifies an account by
+// its header [version, account_type] and returns its fields with derived information, i.e.
+// the phase of a transaction or the fill level of a Merkle tree.
+// Pubkeys are base58 strings, hashes, field elements and encrypted utxos hex strings in the
+// byte order of the account.
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct DecodedAccount {
+    pub version: u8,
+    pub data_len: usize,
+    pub account: Account,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Account {
+    TmpStorage(TmpStorageInfo),
+    MerkleTree(MerkleTreeInfo),
+    // Nullifier pdas only hold the header, a nullifier is spent if its pda exists.
+    Nullifier,
+    Leaves(LeavesInfo),
+    UserAccount(UserAccountInfo),
+    ProtocolFeeConfig(ProtocolFeeConfigInfo),
+    MerkleTreeLimits(MerkleTreeLimitsInfo),
+    GlobalConfig(GlobalConfigInfo),
+    AdminConfig(AdminConfigInfo),
+    RelayerHandoff(RelayerHandoffInfo),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct TmpStorageInfo {
+    pub signer: String,
+    pub merkle_tree_index: u8,
+    // Index of the next instruction in IX_ORDER.
+    pub current_instruction_index: usize,
+    // Phase and IX_ORDER id of the next instruction, None once the computation has finished.
+    pub phase: Option<Phase>,
+    pub step: Option<u8>,
+    pub found_root: bool,
+    pub root: String,
+    pub public_amount: String,
+    pub tx_integrity_hash: String,
+    pub recipient: String,
+    pub ext_amount: i64,
+    pub relayer_fee: u64,
+    // None without post withdraw call.
+    pub post_withdraw_call_hash: Option<String>,
+    // leaf_right, leaf_left
+    pub leaves: [String; 2],
+    pub nullifiers: [String; 2],
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MerkleTreeInfo {
+    pub levels: usize,
+    pub next_index: u64,
+    // 2^levels leaves
+    pub capacity: u64,
+    // next_index / capacity
+    pub fill_level: f64,
+    // Position of the current root in the root history ring of root_history_size roots.
+    pub current_root_index: u64,
+    pub root_history_size: u64,
+    // Roots of the first insertions were overwritten once the ring wrapped around.
+    pub root_history_wrapped: bool,
+    pub current_root: String,
+    pub filled_subtrees: Vec<String>,
+    pub current_total_deposits: u64,
+    pub pubkey_locked: String,
+    pub time_locked: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct LeavesInfo {
+    pub merkle_tree: String,
+    // Merkle tree index of leaf_left, leaf_right is at left_leaf_index + 1.
+    pub left_leaf_index: u64,
+    pub leaf_left: String,
+    pub leaf_right: String,
+    pub encrypted_utxos: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct UserAccountInfo {
+    // Written with LEGACY_USER_ACCOUNT_TYPE, can be migrated.
+    pub legacy: bool,
+    pub owner: String,
+    pub encrypted_utxos: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct ProtocolFeeConfigInfo {
+    // None for native SOL.
+    pub mint: Option<String>,
+    pub treasury: String,
+    pub fee_bps: u16,
+    pub min_fee: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct MerkleTreeLimitsInfo {
+    pub merkle_tree: String,
+    pub max_deposit: u64,
+    pub max_total_deposits: u64,
+    pub epoch_length: u64,
+    pub max_epoch_withdrawals: u64,
+    pub current_epoch: u64,
+    pub epoch_withdrawals: u64,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct GlobalConfigInfo {
+    pub guardian: String,
+    pub mode: String,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct AdminConfigInfo {
+    pub authority: String,
+    // None if no transfer is pending.
+    pub pending_authority: Option<String>,
+    // 0 without multisig.
+    pub threshold: u8,
+    pub signers: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct RelayerHandoffInfo {
+    pub idle_slots: u64,
+    pub relayers: Vec<String>,
+    pub payer: String,
+    pub last_instruction_index: u64,
+    pub last_slot: u64,
+}
+
+pub fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{:02x}", byte)).collect()
+}
+
+fn pubkeys(pubkeys: &[Pubkey]) -> Vec<String> {
+    pubkeys.iter().map(|pubkey| pubkey.to_string()).collect()
+}
+
+pub fn decode_account(data: &[u8]) -> Result<DecodedAccount, ProgramError> {
+    if data.len() < ACCOUNT_HEADER_LEN {
+        msg!("Account data is shorter than the account header.");
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let account = match data[1] {
+        TMP_STORAGE_ACCOUNT_TYPE => Account::TmpStorage(decode_tmp_storage(data)?),
+        MERKLE_TREE_ACCOUNT_TYPE => Account::MerkleTree(decode_merkle_tree(data)?),
+        NULLIFIER_ACCOUNT_TYPE => Account::Nullifier,
+        LEAVES_PDA_ACCOUNT_TYPE => {
+            let leaves = LeavesAccount::unpack(data)?;
+            Account::Leaves(LeavesInfo {
+                merkle_tree: leaves.merkle_tree.to_string(),
+                left_leaf_index: leaves.left_leaf_index,
+                leaf_left: hex(&leaves.leaf_left),
+                leaf_right: hex(&leaves.leaf_right),
+                encrypted_utxos: hex(&leaves.encrypted_utxos),
+            })
+        }
+        USER_ACCOUNT_TYPE | LEGACY_USER_ACCOUNT_TYPE => {
+            Account::UserAccount(decode_user_account(data)?)
+        }
+        PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE => {
+            let config = ProtocolFeeConfig::unpack(data)?;
+            Account::ProtocolFeeConfig(ProtocolFeeConfigInfo {
+                mint: match Asset::from_asset_id(config.asset_id) {
+                    Asset::Sol => None,
+                    Asset::Spl(mint) => Some(mint.to_string()),
+                },
+                treasury: config.treasury.to_string(),
+                fee_bps: config.fee_schedule.fee_bps,
+                min_fee: config.fee_schedule.min_fee,
+            })
+        }
+        MERKLE_TREE_LIMITS_ACCOUNT_TYPE => {
+            let limits = MerkleTreeLimits::unpack(data)?;
+            Account::MerkleTreeLimits(MerkleTreeLimitsInfo {
+                merkle_tree: limits.merkle_tree_pubkey.to_string(),
+                max_deposit: limits.max_deposit,
+                max_total_deposits: limits.max_total_deposits,
+                epoch_length: limits.epoch_length,
+                max_epoch_withdrawals: limits.max_epoch_withdrawals,
+                current_epoch: limits.current_epoch,
+                epoch_withdrawals: limits.epoch_withdrawals,
+            })
+        }
+        GLOBAL_CONFIG_ACCOUNT_TYPE => {
+            let config = GlobalConfig::unpack(data)?;
+            Account::GlobalConfig(GlobalConfigInfo {
+                guardian: config.guardian.to_string(),
+                mode: format!("{:?}", config.mode),
+            })
+        }
+        ADMIN_CONFIG_ACCOUNT_TYPE => {
+            let config = AdminConfig::unpack(data)?;
+            Account::AdminConfig(AdminConfigInfo {
+                authority: config.authority.to_string(),
+                pending_authority: Some(config.pending_authority)
+                    .filter(|pending_authority| *pending_authority != Pubkey::default())
+                    .map(|pending_authority| pending_authority.to_string()),
+                threshold: config.threshold,
+                signers: pubkeys(&config.signers),
+            })
+        }
+        RELAYER_HANDOFF_ACCOUNT_TYPE => {
+            let handoff = RelayerHandoff::unpack(data)?;
+            Account::RelayerHandoff(RelayerHandoffInfo {
+                idle_slots: handoff.idle_slots,
+                relayers: pubkeys(&handoff.relayers),
+                payer: handoff.payer.to_string(),
+                last_instruction_index: handoff.last_instruction_index,
+                last_slot: handoff.last_slot,
+            })
+        }
+        account_type => {
+            msg!("Unknown account type {}.", account_type);
+            return Err(LightError::InvalidAccountType.into());
+        }
+    };
+    Ok(DecodedAccount {
+        version: data[0],
+        data_len: data.len(),
+        account,
+    })
+}
+
+fn decode_tmp_storage(data: &[u8]) -> Result<TmpStorageInfo, ProgramError> {
+    let tmp_storage = ChecksAndTransferState::unpack(data)?;
+    let index = tmp_storage.current_instruction_index;
+    // proof a, b and c, leaf_right, leaf_left, nullifier 0, nullifier 1, encrypted utxos
+    let proof_leaves_and_nullifiers = &tmp_storage.proof_a_b_c_leaves_and_nullifiers;
+    let field = |start: usize| hex(&proof_leaves_and_nullifiers[start..start + 32]);
+    let bytes8 = |bytes: &[u8]| -> Result<[u8; 8], ProgramError> {
+        bytes
+            .try_into()
+            .map_err(|_| ProgramError::InvalidAccountData)
+    };
+    Ok(TmpStorageInfo {
+        signer: Pubkey::new(&tmp_storage.signing_address).to_string(),
+        merkle_tree_index: tmp_storage.merkle_tree_index,
+        current_instruction_index: index,
+        phase: phase(index),
+        step: IX_ORDER.get(index).copied(),
+        found_root: tmp_storage.found_root != 0,
+        root: hex(&tmp_storage.root_hash),
+        public_amount: hex(&tmp_storage.amount),
+        tx_integrity_hash: hex(&tmp_storage.tx_integrity_hash),
+        recipient: Pubkey::new(&tmp_storage.recipient).to_string(),
+        ext_amount: i64::from_le_bytes(bytes8(&tmp_storage.ext_amount)?),
+        relayer_fee: u64::from_le_bytes(bytes8(&tmp_storage.relayer_fee)?),
+        post_withdraw_call_hash: Some(tmp_storage.post_withdraw_call_hash)
+            .filter(|hash| hash[..] != NO_POST_WITHDRAW_CALL[..])
+            .map(|hash| hex(&hash)),
+        leaves: [field(256), field(288)],
+        nullifiers: [field(320), field(352)],
+    })
+}
+
+// Parsed without MerkleTree::unpack, which expects a valid current root index.
+fn decode_merkle_tree(data: &[u8]) -> Result<MerkleTreeInfo, ProgramError> {
+    if data.len() != MerkleTree::LEN {
+        msg!("Merkle tree length {} != {}", data.len(), MerkleTree::LEN);
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let data = array_ref![data, 0, MerkleTree::LEN];
+    let (
+        _header,
+        levels,
+        filled_subtrees,
+        current_root_index,
+        next_index,
+        root_history_size,
+        roots,
+        current_total_deposits,
+        pubkey_locked,
+        time_locked,
+    ) = array_refs![data, 2, 8, 576, 8, 8, 8, 16000, 8, 32, 8];
+    let levels = usize::from_le_bytes(*levels);
+    let current_root_index = u64::from_le_bytes(*current_root_index);
+    let next_index = u64::from_le_bytes(*next_index);
+    let root_history_size = u64::from_le_bytes(*root_history_size);
+    if levels != MERKLE_TREE_HEIGHT || current_root_index >= root_history_size {
+        msg!(
+            "Invalid Merkle tree levels {} or root index {} of {}.",
+            levels,
+            current_root_index,
+            root_history_size
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+    let current_root = roots
+        .chunks(32)
+        .nth(current_root_index as usize)
+        .ok_or(ProgramError::InvalidAccountData)?;
+    let capacity = 1u64 << levels;
+    Ok(MerkleTreeInfo {
+        levels,
+        next_index,
+        capacity,
+        fill_level: next_index as f64 / capacity as f64,
+        current_root_index,
+        root_history_size,
+        // every insertion of two leaves writes a root
+        root_history_wrapped: next_index / 2 >= root_history_size,
+        current_root: hex(current_root),
+        filled_subtrees: filled_subtrees.chunks(32).map(hex).collect(),
+        current_total_deposits: u64::from_le_bytes(*current_total_deposits),
+        pubkey_locked: Pubkey::new(pubkey_locked).to_string(),
+        time_locked: u64::from_le_bytes(*time_locked),
+    })
+}
+
+// Legacy user accounts can differ in length from the current layout, the encrypted utxos are
+// the remaining bytes.
+fn decode_user_account(data: &[u8]) -> Result<UserAccountInfo, ProgramError> {
+    const OWNER_END: usize = ACCOUNT_HEADER_LEN + 32;
+    if data.len() < OWNER_END {
+        msg!("Invalid user account length {}.", data.len());
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(UserAccountInfo {
+        legacy: data[1] == LEGACY_USER_ACCOUNT_TYPE,
+        owner: Pubkey::new(&data[ACCOUNT_HEADER_LEN..OWNER_END]).to_string(),
+        encrypted_utxos: hex(&data[OWNER_END..]),
+    })
+}
@@ -0,0 +1,129 @@
+use crate::utxo::{from_bytes, to_bytes, Utxo};
+use ark_ed_on_bn254::Fq;
+use ark_std::rand::{CryptoRng, RngCore};
+use crypto_box::{
+    aead::{generic_array::GenericArray, Aead},
+    PublicKey, SalsaBox, SecretKey,
+};
+use light_protocol_program::encrypted_utxos::{
+    check_encrypted_utxos_version, encrypted_utxo_range, ENCRYPTED_UTXOS_VERSION_1,
+    ENCRYPTED_UTXO_NONCE_LEN, ENCRYPTED_UTXO_PLAINTEXT_LEN, EPHEMERAL_PUBKEY_RANGE,
+};
+use light_protocol_program::utils::config::ENCRYPTED_UTXOS_LENGTH;
+use solana_program::{keccak, msg, program_error::ProgramError};
+use std::convert::TryInto;
+
+// Encryption of the two output utxos of a transaction in the envelope of
+// light_protocol_program::encrypted_utxos (version 1). Utxos are encrypted to the x25519
+// encryption key of their owner, which is separate from the utxo keypair.
+
+// Domain separation of encryption keys derived from a seed.
+pub const ENCRYPTION_KEYPAIR_SEED_DOMAIN: &[u8] = b"light utxo encryption keypair";
+
+#[derive(Clone)]
+pub struct EncryptionKeypair {
+    pub secret_key: SecretKey,
+    pub public_key: PublicKey,
+}
+
+impl EncryptionKeypair {
+    pub fn new(secret_key: [u8; 32]) -> Self {
+        let secret_key = SecretKey::from(secret_key);
+        EncryptionKeypair {
+            public_key: secret_key.public_key(),
+            secret_key,
+        }
+    }
+
+    pub fn from_seed(seed: &[u8]) -> Self {
+        EncryptionKeypair::new(keccak::hashv(&[ENCRYPTION_KEYPAIR_SEED_DOMAIN, seed]).0)
+    }
+
+    pub fn random<R: RngCore + CryptoRng>(rng: &mut R) -> Self {
+        let mut secret_key = [0u8; 32];
+        rng.fill_bytes(&mut secret_key);
+        EncryptionKeypair::new(secret_key)
+    }
+}
+
+// Encrypts the output utxos to the encryption keys of their owners.
+// Returns the ENCRYPTED_UTXOS_LENGTH bytes of the send data.
+pub fn encrypt_utxos<R: RngCore + CryptoRng>(
+    outputs: [(&Utxo, &PublicKey); 2],
+    rng: &mut R,
+) -> Result<Vec<u8>, ProgramError> {
+    let ephemeral_keypair = EncryptionKeypair::random(rng);
+    let mut encrypted_utxos = vec![0u8; ENCRYPTED_UTXOS_LENGTH];
+    encrypted_utxos[0] = ENCRYPTED_UTXOS_VERSION_1;
+    encrypted_utxos[EPHEMERAL_PUBKEY_RANGE]
+        .copy_from_slice(ephemeral_keypair.public_key.as_bytes());
+    for (output, (utxo, public_key)) in outputs.iter().enumerate() {
+        let mut nonce = [0u8; ENCRYPTED_UTXO_NONCE_LEN];
+        rng.fill_bytes(&mut nonce);
+        let ciphertext = SalsaBox::new(public_key, &ephemeral_keypair.secret_key)
+            .encrypt(GenericArray::from_slice(&nonce), &plaintext(utxo)?[..])
+            .map_err(|_| ProgramError::InvalidArgument)?;
+        let range = encrypted_utxo_range(output);
+        encrypted_utxos[range.start..range.start + ENCRYPTED_UTXO_NONCE_LEN]
+            .copy_from_slice(&nonce);
+        encrypted_utxos[range.start + ENCRYPTED_UTXO_NONCE_LEN..range.end]
+            .copy_from_slice(&ciphertext);
+    }
+    Ok(encrypted_utxos)
+}
+
+// Trial-decrypts both outputs with the encryption keypair of a wallet, outputs of other
+// owners are None. Decrypted utxos are assigned public_key, the utxo public key of the wallet,
+// and index 0. Callers check the commitment against the leaf and set the Merkle tree index.
+pub fn decrypt_utxos(
+    encrypted_utxos: &[u8],
+    keypair: &EncryptionKeypair,
+    public_key: &Fq,
+) -> Result<[Option<Utxo>; 2], ProgramError> {
+    check_encrypted_utxos_version(ENCRYPTED_UTXOS_VERSION_1, encrypted_utxos).map_err(|_| {
+        msg!("Encrypted utxos are not a version 1 envelope.");
+        ProgramError::InvalidArgument
+    })?;
+    let ephemeral_public_key: [u8; 32] = encrypted_utxos[EPHEMERAL_PUBKEY_RANGE]
+        .try_into()
+        .map_err(|_| ProgramError::InvalidArgument)?;
+    let salsa_box = SalsaBox::new(&PublicKey::from(ephemeral_public_key), &keypair.secret_key);
+    let mut utxos = [None, None];
+    for (output, utxo) in utxos.iter_mut().enumerate() {
+        let range = encrypted_utxo_range(output);
+        let (nonce, ciphertext) = encrypted_utxos[range].split_at(ENCRYPTED_UTXO_NONCE_LEN);
+        // A plaintext which is not a utxo is skipped like a ciphertext of another owner.
+        if let Ok(plaintext) = salsa_box.decrypt(GenericArray::from_slice(nonce), ciphertext) {
+            *utxo = from_plaintext(&plaintext, public_key).ok();
+        }
+    }
+    Ok(utxos)
+}
+
+fn plaintext(utxo: &Utxo) -> Result<[u8; ENCRYPTED_UTXO_PLAINTEXT_LEN], ProgramError> {
+    let mut plaintext = [0u8; ENCRYPTED_UTXO_PLAINTEXT_LEN];
+    plaintext[..8].copy_from_slice(&utxo.amount.to_le_bytes());
+    plaintext[8..].copy_from_slice(&to_bytes(&utxo.blinding)?);
+    Ok(plaintext)
+}
+
+fn from_plaintext(plaintext: &[u8], public_key: &Fq) -> Result<Utxo, ProgramError> {
+    if plaintext.len() != ENCRYPTED_UTXO_PLAINTEXT_LEN {
+        msg!("Invalid utxo plaintext length {}.", plaintext.len());
+        return Err(ProgramError::InvalidArgument);
+    }
+    Ok(Utxo {
+        amount: u64::from_le_bytes(
+            plaintext[..8]
+                .try_into()
+                .map_err(|_| ProgramError::InvalidArgument)?,
+        ),
+        blinding: from_bytes(
+            &plaintext[8..]
+                .try_into()
+                .map_err(|_| ProgramError::InvalidArgument)?,
+        )?,
+        public_key: *public_key,
+        index: 0,
+    })
+}
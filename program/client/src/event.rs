@@ -0,0 +1,50 @@
+use light_protocol_program::event::LightEvent;
+use solana_program::pubkey::Pubkey;
+
+// Decoder of the LightEvents (program/src/event.rs) in transaction logs. The program logs an
+// event with sol_log_data as "Program data: <base64 borsh bytes>" at the end of the last
+// instruction. Any program can log data lines, light_events only decodes the lines logged while
+// the Light Protocol program is the innermost invoked program.
+
+const PROGRAM_DATA_PREFIX: &str = "Program data: ";
+
+// Decodes a single "Program data: " log line, returns None for all other log lines.
+// Callers have to make sure the line was logged by the Light Protocol program.
+pub fn from_log_message(log_message: &str) -> Option<LightEvent> {
+    let data = log_message.strip_prefix(PROGRAM_DATA_PREFIX)?;
+    // sol_log_data logs every field separately, events are logged as a single field.
+    let bytes = base64::decode(data.split_whitespace().next()?).ok()?;
+    LightEvent::decode(&bytes).ok()
+}
+
+// Events of the program program_id in the log messages of a transaction, in log order.
+pub fn light_events(program_id: &Pubkey, log_messages: &[String]) -> Vec<LightEvent> {
+    let program_id = program_id.to_string();
+    // Whether each program of the invoke stack is program_id.
+    let mut invoked_programs: Vec<bool> = Vec::new();
+    let mut events = Vec::new();
+    for log_message in log_messages {
+        if log_message.starts_with(PROGRAM_DATA_PREFIX) {
+            if invoked_programs.last() == Some(&true) {
+                events.extend(from_log_message(log_message));
+            }
+            continue;
+        }
+        // "Program <pubkey> invoke [<depth>]", "Program <pubkey> success" and
+        // "Program <pubkey> failed: <error>" track the invoke stack, log lines of programs
+        // ("Program log: ") don't start with a pubkey.
+        let (program, status) = match log_message
+            .strip_prefix("Program ")
+            .and_then(|line| line.split_once(' '))
+        {
+            Some((program, status)) if program.parse::<Pubkey>().is_ok() => (program, status),
+            _ => continue,
+        };
+        if status.starts_with("invoke [") {
+            invoked_programs.push(program == program_id);
+        } else if status == "success" || status.starts_with("failed") {
+            invoked_programs.pop();
+        }
+    }
+    events
+}
@@ -0,0 +1,262 @@
+#![deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)]
+
+pub mod circuit;
+pub mod decode;
+pub mod encryption;
+pub mod event;
+pub mod merkle_tree;
+pub mod poseidon_parameters;
+pub mod scanner;
+pub mod utxo;
+
+use light_protocol_program::asset::Asset;
+use light_protocol_program::cpi::{
+    compute_instruction, last_instruction, send_data_instruction, Settlement,
+};
+use light_protocol_program::pda::{
+    find_authority_pda, find_escrow_pda, find_leaves_pda, find_nullifier_pda, find_tmp_storage_pda,
+};
+use light_protocol_program::post_withdraw_call::PostWithdrawCall;
+use light_protocol_program::relayer_handoff::{find_relayer_handoff, RelayerHandoffCommitment};
+use light_protocol_program::send_data::SendData;
+pub use light_protocol_program::send_data::{ExtData, PublicInputs};
+use light_protocol_program::{
+    INSERT_LEAVES_NULLIFIER_AND_TRANSFER, IX_ORDER, ROOT_CHECK, VERIFICATION_END_INDEX,
+};
+use serde::Serialize;
+use solana_program::{
+    instruction::Instruction, message::Message, msg, program_error::ProgramError, pubkey::Pubkey,
+};
+use solana_sdk::packet::PACKET_DATA_SIZE;
+
+// Client for shielded transactions, builds the 1502 instructions of a transaction from the
+// proof, its public inputs and the external data, in the order of IX_ORDER.
+// All instructions are signed by the relayer of the external data, after a relayer hand-off
+// the remaining instructions are built for the new signer with instruction().
+
+// Phase of an instruction index, the accounts of an instruction depend on it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    SendData,
+    RootCheck,
+    Verification,
+    MerkleTreeUpdate,
+    Last,
+}
+
+pub fn phase(index: usize) -> Option<Phase> {
+    match index {
+        0 => Some(Phase::SendData),
+        ROOT_CHECK => Some(Phase::RootCheck),
+        index if index < VERIFICATION_END_INDEX => Some(Phase::Verification),
+        index if index < INSERT_LEAVES_NULLIFIER_AND_TRANSFER => Some(Phase::MerkleTreeUpdate),
+        INSERT_LEAVES_NULLIFIER_AND_TRANSFER => Some(Phase::Last),
+        _ => None,
+    }
+}
+
+// Pdas of a transaction, the escrow is only used by SOL deposits.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct TransactionPdas {
+    pub tmp_storage: Pubkey,
+    pub leaves: Pubkey,
+    pub nullifiers: [Pubkey; 2],
+    pub escrow: Pubkey,
+    pub authority: Pubkey,
+    // Only created with a relayer hand-off commitment.
+    pub relayer_handoff: Pubkey,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LightTransaction {
+    pub program_id: Pubkey,
+    // proof a, b and c
+    pub proof: Vec<u8>,
+    pub public_inputs: PublicInputs,
+    pub ext_data: ExtData,
+    pub asset: Asset,
+    pub merkle_tree_token: Pubkey,
+    // SPL deposits, the token account which approved the authority for the deposit amount.
+    // SOL deposits are transferred from the escrow pda.
+    pub user_token: Option<Pubkey>,
+    // Required if the relayer fee is not 0.
+    pub relayer_token: Option<Pubkey>,
+    // Required if the asset has a protocol fee.
+    pub treasury: Option<Pubkey>,
+    pub post_withdraw_call: Option<PostWithdrawCall>,
+    pub relayer_handoff: Option<RelayerHandoffCommitment>,
+}
+
+impl LightTransaction {
+    // Send data with the commitments of the post withdraw call and the relayer hand-off.
+    pub fn typed_send_data(&self) -> SendData {
+        SendData {
+            public_inputs: self.public_inputs.clone(),
+            proof: self.proof.clone(),
+            ext_data: self.ext_data.clone(),
+            post_withdraw_call: self
+                .post_withdraw_call
+                .as_ref()
+                .map(|post_withdraw_call| post_withdraw_call.commitment()),
+            relayer_handoff: self
+                .relayer_handoff
+                .as_ref()
+                .map(|relayer_handoff| relayer_handoff.commitment()),
+        }
+    }
+
+    // Instruction data of the send data instruction without selector, the commitments are
+    // appended in the order of the tx integrity hash.
+    pub fn send_data(&self) -> Result<Vec<u8>, ProgramError> {
+        self.typed_send_data().pack()
+    }
+
+    // Tx integrity hash of the external data and the commitments, the public input of the proof.
+    pub fn tx_integrity_hash(&self) -> Result<[u8; 32], ProgramError> {
+        self.typed_send_data().tx_integrity_hash(&self.asset)
+    }
+
+    // The tmp storage pda is bound to the relayer which sends the data, it does not change
+    // with a relayer hand-off.
+    pub fn pdas(&self) -> TransactionPdas {
+        let tmp_storage = find_tmp_storage_pda(
+            &self.program_id,
+            &self.public_inputs.tx_integrity_hash,
+            &self.ext_data.relayer,
+        )
+        .0;
+        let nullifiers = &self.public_inputs.nullifiers;
+        TransactionPdas {
+            tmp_storage,
+            leaves: find_leaves_pda(&self.program_id, &nullifiers[0]).0,
+            nullifiers: [
+                find_nullifier_pda(&self.program_id, &nullifiers[0]).0,
+                find_nullifier_pda(&self.program_id, &nullifiers[1]).0,
+            ],
+            escrow: find_escrow_pda(&self.program_id, &tmp_storage).0,
+            authority: find_authority_pda(&self.program_id).0,
+            relayer_handoff: find_relayer_handoff(&self.program_id, &tmp_storage).0,
+        }
+    }
+
+    pub fn settlement(&self) -> Result<Settlement, ProgramError> {
+        if self.ext_data.ext_amount > 0 {
+            let user_token = match (self.asset, self.user_token) {
+                (_, Some(user_token)) => user_token,
+                (Asset::Sol, None) => self.pdas().escrow,
+                (Asset::Spl(_), None) => {
+                    msg!("SPL deposits require a user token account.");
+                    return Err(ProgramError::InvalidArgument);
+                }
+            };
+            Ok(Settlement::Deposit { user_token })
+        } else if self.ext_data.ext_amount < 0 {
+            Ok(Settlement::Withdrawal {
+                recipient: self.ext_data.recipient,
+            })
+        } else {
+            Ok(Settlement::Transfer)
+        }
+    }
+
+    // Instruction at index of IX_ORDER signed by signer.
+    pub fn instruction(&self, index: usize, signer: &Pubkey) -> Result<Instruction, ProgramError> {
+        self.instruction_with_pdas(index, signer, &self.pdas())
+    }
+
+    // All instructions of the transaction signed by the relayer.
+    pub fn instructions(&self) -> Result<Vec<Instruction>, ProgramError> {
+        // Pdas are derived once, finding a pda takes several hashes.
+        let pdas = self.pdas();
+        (0..IX_ORDER.len())
+            .map(|index| self.instruction_with_pdas(index, &self.ext_data.relayer, &pdas))
+            .collect()
+    }
+
+    fn instruction_with_pdas(
+        &self,
+        index: usize,
+        signer: &Pubkey,
+        pdas: &TransactionPdas,
+    ) -> Result<Instruction, ProgramError> {
+        let merkle_tree = &self.ext_data.merkle_tree;
+        match phase(index) {
+            Some(Phase::SendData) => {
+                send_data_instruction(&self.program_id, signer, &self.send_data()?)
+            }
+            Some(Phase::RootCheck) | Some(Phase::MerkleTreeUpdate) => Ok(compute_instruction(
+                &self.program_id,
+                signer,
+                &pdas.tmp_storage,
+                merkle_tree,
+                index as u16,
+            )),
+            Some(Phase::Verification) => {
+                let mut instruction = compute_instruction(
+                    &self.program_id,
+                    signer,
+                    &pdas.tmp_storage,
+                    merkle_tree,
+                    index as u16,
+                );
+                // Verification instructions don't read the Merkle tree, without it more
+                // instructions fit into a transaction.
+                instruction.accounts.truncate(2);
+                Ok(instruction)
+            }
+            Some(Phase::Last) => last_instruction(
+                &self.program_id,
+                signer,
+                &pdas.tmp_storage,
+                &self.public_inputs.nullifiers,
+                merkle_tree,
+                &self.merkle_tree_token,
+                &self.asset,
+                self.settlement()?,
+                self.relayer_token.as_ref(),
+                self.treasury.as_ref(),
+                self.post_withdraw_call.as_ref(),
+            ),
+            None => {
+                msg!("Invalid instruction index {}.", index);
+                Err(ProgramError::InvalidArgument)
+            }
+        }
+    }
+}
+
+// Size of a signed transaction with instructions.
+pub fn transaction_size(instructions: &[Instruction], payer: &Pubkey) -> usize {
+    let message = Message::new(instructions, Some(payer));
+    // Less than 128 signatures are encoded in one byte.
+    1 + 64 * usize::from(message.header.num_required_signatures) + message.serialize().len()
+}
+
+// Packs instructions in order into transactions of at most max_instructions instructions
+// which fit into a packet. An instruction which does not fit alone gets its own transaction.
+// Instructions of one transaction share its compute budget if the cluster has a transaction
+// wide compute budget, max_instructions has to be 1 then.
+pub fn pack_instructions(
+    instructions: &[Instruction],
+    payer: &Pubkey,
+    max_instructions: usize,
+) -> Vec<Vec<Instruction>> {
+    let mut transactions: Vec<Vec<Instruction>> = Vec::new();
+    let mut current: Vec<Instruction> = Vec::new();
+    for instruction in instructions {
+        current.push(instruction.clone());
+        if current.len() > 1
+            && (current.len() > max_instructions
+                || transaction_size(&current, payer) > PACKET_DATA_SIZE)
+        {
+            current.pop();
+            transactions.push(current);
+            current = vec![instruction.clone()];
+        }
+    }
+    if !current.is_empty() {
+        transactions.push(current);
+    }
+    transactions
+}
@@ -0,0 +1,291 @@
+use crate::scanner::LeavesAccount;
+use crate::utxo::{from_bytes, poseidon, to_bytes};
+use light_protocol_program::poseidon_merkle_tree::state::MerkleTree;
+use light_protocol_program::poseidon_merkle_tree::state_roots::MerkleTreeRoots;
+use light_protocol_program::utils::config::{INIT_BYTES_MERKLE_TREE_18, ZERO_BYTES_MERKLE_TREE_18};
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+use std::convert::TryInto;
+
+// Off-chain mirror of a Merkle tree account. Leaves are inserted in pairs like the
+// merkle_tree_insert_instructions do: the pair node is H(leaf_left, leaf_right), missing
+// nodes are the zero values of ZERO_BYTES_MERKLE_TREE_18 and the root is at level
+// MERKLE_TREE_HEIGHT. The root after every inserted pair is kept to check the root history.
+pub const MERKLE_TREE_HEIGHT: usize = 18;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum IndexerError {
+    // Leaves pdas are missing before found, expected is the next index of the tree.
+    Gap {
+        expected: u64,
+        found: u64,
+    },
+    // A leaves pda at index differs from the leaves inserted before.
+    Divergence {
+        index: u64,
+    },
+    // The on-chain Merkle tree has another next index than the indexer.
+    OutOfSync {
+        next_index: u64,
+        onchain_next_index: u64,
+    },
+    // The on-chain root at position of the root history differs.
+    RootMismatch {
+        position: u64,
+    },
+    // The on-chain filled subtree of level differs.
+    FilledSubtreeMismatch {
+        level: usize,
+    },
+    Program(ProgramError),
+}
+
+impl From<ProgramError> for IndexerError {
+    fn from(error: ProgramError) -> Self {
+        IndexerError::Program(error)
+    }
+}
+
+// Inclusion path of a leaf, siblings from the leaf level to the level below the root.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerkleProof {
+    pub leaf_index: u64,
+    pub leaf: [u8; 32],
+    pub siblings: Vec<[u8; 32]>,
+    pub root: [u8; 32],
+}
+
+impl MerkleProof {
+    // Bit i of leaf_index is 1 if the node at level i is a right child.
+    pub fn compute_root(&self) -> Result<[u8; 32], ProgramError> {
+        let mut node = self.leaf;
+        for (level, sibling) in self.siblings.iter().enumerate() {
+            node = if (self.leaf_index >> level) & 1 == 0 {
+                hash(&node, sibling)?
+            } else {
+                hash(sibling, &node)?
+            };
+        }
+        Ok(node)
+    }
+
+    pub fn verify(&self) -> Result<bool, ProgramError> {
+        Ok(self.siblings.len() == MERKLE_TREE_HEIGHT && self.compute_root()? == self.root)
+    }
+}
+
+pub struct MerkleTreeIndexer {
+    pub merkle_tree: Pubkey,
+    // layers[0] are the leaves, layers[MERKLE_TREE_HEIGHT] the root.
+    layers: Vec<Vec<[u8; 32]>>,
+    // roots[i] is the root after i inserted pairs, roots[0] the root of the empty tree.
+    roots: Vec<[u8; 32]>,
+    zero_values: Vec<[u8; 32]>,
+}
+
+impl MerkleTreeIndexer {
+    pub fn new(merkle_tree: Pubkey) -> Result<Self, ProgramError> {
+        let mut zero_values = ZERO_BYTES_MERKLE_TREE_18
+            .chunks(32)
+            .map(|zero_value| {
+                zero_value
+                    .try_into()
+                    .map_err(|_| ProgramError::InvalidArgument)
+            })
+            .collect::<Result<Vec<[u8; 32]>, ProgramError>>()?;
+        let top = zero_values[MERKLE_TREE_HEIGHT - 1];
+        zero_values.push(hash(&top, &top)?);
+        Ok(MerkleTreeIndexer {
+            merkle_tree,
+            layers: vec![Vec::new(); MERKLE_TREE_HEIGHT + 1],
+            roots: vec![zero_values[MERKLE_TREE_HEIGHT]],
+            zero_values,
+        })
+    }
+
+    // Zero value of level, the root of an empty subtree of that height.
+    pub fn zero_value(&self, level: usize) -> Option<[u8; 32]> {
+        self.zero_values.get(level).copied()
+    }
+
+    pub fn next_index(&self) -> u64 {
+        self.layers[0].len() as u64
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.roots[self.roots.len() - 1]
+    }
+
+    // Root after the leaves up to next_index were inserted.
+    pub fn root_at(&self, next_index: u64) -> Option<[u8; 32]> {
+        if next_index % 2 != 0 {
+            return None;
+        }
+        self.roots.get((next_index / 2) as usize).copied()
+    }
+
+    pub fn leaf(&self, index: u64) -> Option<[u8; 32]> {
+        self.layers[0].get(index as usize).copied()
+    }
+
+    // Inserts the leaves of a transaction at left_leaf_index. Leaves which were inserted
+    // before are skipped if they are equal.
+    pub fn insert(
+        &mut self,
+        left_leaf_index: u64,
+        leaf_left: [u8; 32],
+        leaf_right: [u8; 32],
+    ) -> Result<(), IndexerError> {
+        let next_index = self.next_index();
+        if left_leaf_index % 2 != 0 {
+            return Err(IndexerError::Divergence {
+                index: left_leaf_index,
+            });
+        }
+        if left_leaf_index < next_index {
+            if self.leaf(left_leaf_index) != Some(leaf_left)
+                || self.leaf(left_leaf_index + 1) != Some(leaf_right)
+            {
+                return Err(IndexerError::Divergence {
+                    index: left_leaf_index,
+                });
+            }
+            return Ok(());
+        }
+        if left_leaf_index > next_index {
+            return Err(IndexerError::Gap {
+                expected: next_index,
+                found: left_leaf_index,
+            });
+        }
+        if next_index >= 1 << MERKLE_TREE_HEIGHT {
+            return Err(ProgramError::InvalidArgument.into());
+        }
+        // Leaves have to be field elements to be hashed.
+        from_bytes(&leaf_left)?;
+        from_bytes(&leaf_right)?;
+        self.layers[0].push(leaf_left);
+        self.layers[0].push(leaf_right);
+        let mut index = (left_leaf_index / 2) as usize;
+        for level in 1..=MERKLE_TREE_HEIGHT {
+            let node = hash(
+                &self.node(level - 1, 2 * index),
+                &self.node(level - 1, 2 * index + 1),
+            )?;
+            if index < self.layers[level].len() {
+                self.layers[level][index] = node;
+            } else {
+                self.layers[level].push(node);
+            }
+            index /= 2;
+        }
+        let root = self.layers[MERKLE_TREE_HEIGHT][0];
+        self.roots.push(root);
+        Ok(())
+    }
+
+    // Inserts the leaves pdas of the Merkle tree ordered by left_leaf_index, leaves pdas of
+    // other trees are skipped. Fails at the first gap or divergence, the leaves pdas before
+    // are inserted.
+    pub fn ingest<'a, I>(&mut self, leaves_accounts: I) -> Result<(), IndexerError>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let mut leaves_accounts = leaves_accounts
+            .into_iter()
+            .map(LeavesAccount::unpack)
+            .collect::<Result<Vec<LeavesAccount>, ProgramError>>()?;
+        leaves_accounts.retain(|leaves_account| leaves_account.merkle_tree == self.merkle_tree);
+        leaves_accounts.sort_by_key(|leaves_account| leaves_account.left_leaf_index);
+        for leaves_account in leaves_accounts {
+            self.insert(
+                leaves_account.left_leaf_index,
+                leaves_account.leaf_left,
+                leaves_account.leaf_right,
+            )?;
+        }
+        Ok(())
+    }
+
+    // Inclusion path of the leaf at leaf_index in the current tree.
+    pub fn proof(&self, leaf_index: u64) -> Result<MerkleProof, IndexerError> {
+        let leaf = self
+            .leaf(leaf_index)
+            .ok_or(IndexerError::Program(ProgramError::InvalidArgument))?;
+        let mut siblings = Vec::with_capacity(MERKLE_TREE_HEIGHT);
+        let mut index = leaf_index as usize;
+        for level in 0..MERKLE_TREE_HEIGHT {
+            siblings.push(self.node(level, index ^ 1));
+            index /= 2;
+        }
+        Ok(MerkleProof {
+            leaf_index,
+            leaf,
+            siblings,
+            root: self.root(),
+        })
+    }
+
+    // Checks the data of the on-chain Merkle tree account against the indexer: the next index,
+    // every root in the root history and the filled subtrees.
+    pub fn verify_onchain(&self, merkle_tree_account_data: &[u8]) -> Result<(), IndexerError> {
+        let merkle_tree = MerkleTree::unpack(merkle_tree_account_data)?;
+        let merkle_tree_roots = MerkleTreeRoots::unpack(merkle_tree_account_data)?;
+        let onchain_next_index = merkle_tree.next_index as u64;
+        if onchain_next_index != self.next_index() {
+            return Err(IndexerError::OutOfSync {
+                next_index: self.next_index(),
+                onchain_next_index,
+            });
+        }
+        let root_history_size = merkle_tree_roots.root_history_size;
+        if root_history_size == 0 || root_history_size as usize * 32 > merkle_tree_roots.roots.len()
+        {
+            return Err(ProgramError::InvalidAccountData.into());
+        }
+        // The root of the last insertion is at current_root_index, older roots before it.
+        let insertions = (self.roots.len() - 1) as u64;
+        for age in 0..root_history_size.min(insertions + 1) {
+            let position = (merkle_tree.current_root_index as u64 + root_history_size - age)
+                % root_history_size;
+            let start = position as usize * 32;
+            if merkle_tree_roots.roots[start..start + 32]
+                != self.roots[(insertions - age) as usize][..]
+            {
+                return Err(IndexerError::RootMismatch { position });
+            }
+        }
+        for (level, filled_subtree) in self.filled_subtrees()?.iter().enumerate() {
+            if merkle_tree.filled_subtrees[level] != filled_subtree[..] {
+                return Err(IndexerError::FilledSubtreeMismatch { level });
+            }
+        }
+        Ok(())
+    }
+
+    // Filled subtrees as stored by the Merkle tree account, the last left node of every level.
+    // Level 0 is never updated.
+    pub fn filled_subtrees(&self) -> Result<Vec<[u8; 32]>, ProgramError> {
+        let mut filled_subtrees = INIT_BYTES_MERKLE_TREE_18[10..10 + MERKLE_TREE_HEIGHT * 32]
+            .chunks(32)
+            .map(|bytes| bytes.try_into().map_err(|_| ProgramError::InvalidArgument))
+            .collect::<Result<Vec<[u8; 32]>, ProgramError>>()?;
+        for (level, filled_subtree) in filled_subtrees.iter_mut().enumerate().skip(1) {
+            let len = self.layers[level].len();
+            if len > 0 {
+                *filled_subtree = self.layers[level][(len - 1) & !1];
+            }
+        }
+        Ok(filled_subtrees)
+    }
+
+    fn node(&self, level: usize, index: usize) -> [u8; 32] {
+        match self.layers[level].get(index) {
+            Some(node) => *node,
+            None => self.zero_values[level],
+        }
+    }
+}
+
+fn hash(left: &[u8; 32], right: &[u8; 32]) -> Result<[u8; 32], ProgramError> {
+    to_bytes(&poseidon(&from_bytes(left)?, &from_bytes(right)?)?)
+}
@@ -0,0 +1,179 @@
+use crate::encryption::{decrypt_utxos, EncryptionKeypair};
+use crate::utxo::{to_bytes, Keypair, Utxo};
+use arrayref::{array_ref, array_refs};
+use light_protocol_program::pda::find_nullifier_pda;
+use light_protocol_program::poseidon_merkle_tree::state::TwoLeavesBytesPda;
+use light_protocol_program::utils::account_header::check_account_header;
+use light_protocol_program::utils::config::{ENCRYPTED_UTXOS_LENGTH, LEAVES_PDA_ACCOUNT_TYPE};
+use solana_program::{msg, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+// Offset of the account type in leaves pdas, getProgramAccounts filters leaves pdas with a
+// memcmp of [LEAVES_PDA_ACCOUNT_TYPE] at this offset and a data size of TwoLeavesBytesPda::LEN.
+pub const LEAVES_PDA_ACCOUNT_TYPE_OFFSET: usize = 1;
+
+// Leaves pda written by the last instruction of a transaction. leaf_left has the Merkle tree
+// index left_leaf_index, leaf_right left_leaf_index + 1.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LeavesAccount {
+    pub left_leaf_index: u64,
+    pub leaf_left: [u8; 32],
+    pub leaf_right: [u8; 32],
+    pub merkle_tree: Pubkey,
+    pub encrypted_utxos: Vec<u8>,
+}
+
+impl LeavesAccount {
+    pub fn unpack(data: &[u8]) -> Result<Self, ProgramError> {
+        if data.len() != TwoLeavesBytesPda::LEN {
+            msg!(
+                "Leaves pda length {} != {}",
+                data.len(),
+                TwoLeavesBytesPda::LEN
+            );
+            return Err(ProgramError::InvalidAccountData);
+        }
+        let data = array_ref![data, 0, TwoLeavesBytesPda::LEN];
+        let (
+            version,
+            account_type,
+            left_leaf_index,
+            leaf_left,
+            leaf_right,
+            merkle_tree,
+            encrypted_utxos,
+        ) = array_refs![data, 1, 1, 8, 32, 32, 32, ENCRYPTED_UTXOS_LENGTH];
+        check_account_header(version[0], account_type[0], LEAVES_PDA_ACCOUNT_TYPE)?;
+        Ok(LeavesAccount {
+            left_leaf_index: u64::from_le_bytes(*left_leaf_index),
+            leaf_left: *leaf_left,
+            leaf_right: *leaf_right,
+            merkle_tree: Pubkey::new_from_array(*merkle_tree),
+            encrypted_utxos: encrypted_utxos.to_vec(),
+        })
+    }
+}
+
+// Rebuilds the unspent utxos of a wallet in one Merkle tree from its leaves pdas.
+// Leaves pdas are trial-decrypted with the encryption keypair, decrypted utxos are only kept
+// if their commitment is the leaf, utxos of multi asset Merkle trees (leaf = Poseidon(commitment,
+// asset index)) are not found. Leaves pdas below next_index were scanned before and are
+// skipped, a scanner is resumed with the next_index and utxos of a previous scan.
+// next_index only advances across leaves pdas without gaps, leaves pdas after a missing one
+// (e.g. not yet returned by the RPC node) are scanned again until the gap is filled.
+pub struct UtxoScanner {
+    pub program_id: Pubkey,
+    pub merkle_tree: Pubkey,
+    pub keypair: Keypair,
+    pub encryption_keypair: EncryptionKeypair,
+    // Merkle tree index after the last scanned leaves pda of the contiguous range from 0.
+    pub next_index: u64,
+    // Owned utxos which were not found spent yet.
+    pub utxos: Vec<Utxo>,
+}
+
+impl UtxoScanner {
+    pub fn new(
+        program_id: Pubkey,
+        merkle_tree: Pubkey,
+        keypair: Keypair,
+        encryption_keypair: EncryptionKeypair,
+    ) -> Self {
+        UtxoScanner {
+            program_id,
+            merkle_tree,
+            keypair,
+            encryption_keypair,
+            next_index: 0,
+            utxos: Vec::new(),
+        }
+    }
+
+    // Adds the owned utxos of leaves pdas at or after next_index, accounts of other Merkle trees
+    // are skipped. Accounts don't have to be ordered, next_index is the end of the contiguous
+    // leaves pdas from next_index afterwards. Returns the number of utxos found.
+    pub fn scan<'a, I>(&mut self, leaves_accounts: I) -> Result<usize, ProgramError>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let mut left_leaf_indices = Vec::new();
+        let mut found = 0;
+        for data in leaves_accounts {
+            let leaves_account = LeavesAccount::unpack(data)?;
+            if leaves_account.merkle_tree != self.merkle_tree
+                || leaves_account.left_leaf_index < self.next_index
+            {
+                continue;
+            }
+            left_leaf_indices.push(leaves_account.left_leaf_index);
+            // Unversioned encrypted utxos can't be decrypted.
+            let utxos = match decrypt_utxos(
+                &leaves_account.encrypted_utxos,
+                &self.encryption_keypair,
+                &self.keypair.public_key,
+            ) {
+                Ok(utxos) => utxos,
+                Err(_) => continue,
+            };
+            let leaves = [leaves_account.leaf_left, leaves_account.leaf_right];
+            for (output, utxo) in utxos.iter().enumerate() {
+                if let Some(utxo) = utxo {
+                    if to_bytes(&utxo.commitment()?)? != leaves[output] {
+                        msg!("Decrypted utxo is not leaf {}.", output);
+                        continue;
+                    }
+                    let index = leaves_account.left_leaf_index + output as u64;
+                    // The same leaves pda can be passed twice.
+                    if self.utxos.iter().any(|utxo| utxo.index == index) {
+                        continue;
+                    }
+                    self.utxos.push(Utxo { index, ..*utxo });
+                    found += 1;
+                }
+            }
+        }
+        left_leaf_indices.sort_unstable();
+        for left_leaf_index in left_leaf_indices {
+            // The same leaves pda can be passed twice.
+            if left_leaf_index + 2 == self.next_index {
+                continue;
+            }
+            if left_leaf_index != self.next_index {
+                break;
+            }
+            self.next_index = left_leaf_index.saturating_add(2);
+        }
+        Ok(found)
+    }
+
+    // Nullifier pda of an owned utxo, it exists once the utxo is spent.
+    pub fn nullifier_pda(&self, utxo: &Utxo) -> Result<Pubkey, ProgramError> {
+        let nullifier = to_bytes(&utxo.nullifier(&self.keypair)?)?;
+        Ok(find_nullifier_pda(&self.program_id, &nullifier).0)
+    }
+
+    // Removes the utxos whose nullifier pda exists and returns the unspent utxos, ordered by
+    // Merkle tree index. Spent utxos are not looked up again.
+    pub fn unspent<F>(&mut self, mut nullifier_pda_exists: F) -> Result<Vec<Utxo>, ProgramError>
+    where
+        F: FnMut(&Pubkey) -> Result<bool, ProgramError>,
+    {
+        let mut unspent = Vec::with_capacity(self.utxos.len());
+        for utxo in self.utxos.iter() {
+            if !nullifier_pda_exists(&self.nullifier_pda(utxo)?)? {
+                unspent.push(*utxo);
+            }
+        }
+        unspent.sort_by_key(|utxo| utxo.index);
+        self.utxos = unspent.clone();
+        Ok(unspent)
+    }
+
+    // Sum of the amounts of the utxos which were not found spent.
+    pub fn balance(&self) -> Result<u64, ProgramError> {
+        self.utxos.iter().try_fold(0u64, |balance, utxo| {
+            balance
+                .checked_add(utxo.amount)
+                .ok_or(ProgramError::InvalidAccountData)
+        })
+    }
+}
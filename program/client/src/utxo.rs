@@ -0,0 +1,162 @@
+use crate::poseidon_parameters::{
+    poseidon_parameters_2, poseidon_parameters_4, PoseidonCircomRounds2, PoseidonCircomRounds4,
+};
+use ark_crypto_primitives::crh::{TwoToOneCRH, CRH};
+use ark_ed_on_bn254::Fq;
+use ark_ff::{
+    bytes::{FromBytes, ToBytes},
+    PrimeField,
+};
+use ark_std::{rand::Rng, UniformRand};
+use arkworks_gadgets::poseidon::{circom::CircomCRH, PoseidonParameters};
+use arkworks_gadgets::utils::{
+    get_mds_poseidon_circom_bn254_x5_3, get_rounds_poseidon_circom_bn254_x5_3,
+};
+use light_protocol_program::poseidon_merkle_tree::instructions_poseidon::PoseidonCircomRounds3;
+use solana_program::{keccak, msg, program_error::ProgramError};
+
+// Utxos of the shielded pool. Hashes are the circom Poseidon of circomlib, Poseidon(inputs):
+// - public key = Poseidon(private key)
+// - commitment = Poseidon(amount, public key, blinding), the leaf in the Merkle tree
+// - signature = Poseidon(private key, commitment, index)
+// - nullifier = Poseidon(commitment, index, signature)
+// index is the Merkle tree index of the commitment. The Merkle tree hashes with the 2 inputs
+// Poseidon (PoseidonCircomRounds3). Leaves of multi asset Merkle trees are
+// Poseidon(commitment, asset index), the signature and the nullifier hash the leaf instead of
+// the commitment. Field elements are 32 bytes little endian like the leaves
+// and nullifiers in the send data.
+pub type PoseidonCircomCRH2 = CircomCRH<Fq, PoseidonCircomRounds2>;
+pub type PoseidonCircomCRH3 = CircomCRH<Fq, PoseidonCircomRounds3>;
+pub type PoseidonCircomCRH4 = CircomCRH<Fq, PoseidonCircomRounds4>;
+
+// Domain separation of private keys derived from a seed.
+pub const KEYPAIR_SEED_DOMAIN: &[u8] = b"light utxo keypair";
+
+pub fn poseidon_parameters() -> PoseidonParameters<Fq> {
+    PoseidonParameters::<Fq>::new(
+        get_rounds_poseidon_circom_bn254_x5_3::<Fq>(),
+        get_mds_poseidon_circom_bn254_x5_3::<Fq>(),
+    )
+}
+
+pub fn poseidon(left: &Fq, right: &Fq) -> Result<Fq, ProgramError> {
+    <PoseidonCircomCRH3 as TwoToOneCRH>::evaluate(
+        &poseidon_parameters(),
+        &to_bytes(left)?,
+        &to_bytes(right)?,
+    )
+    .map_err(|_| ProgramError::InvalidArgument)
+}
+
+pub fn poseidon_1(input: &Fq) -> Result<Fq, ProgramError> {
+    <PoseidonCircomCRH2 as CRH>::evaluate(&poseidon_parameters_2(), &to_bytes(input)?)
+        .map_err(|_| ProgramError::InvalidArgument)
+}
+
+pub fn poseidon_3(first: &Fq, second: &Fq, third: &Fq) -> Result<Fq, ProgramError> {
+    <PoseidonCircomCRH4 as CRH>::evaluate(
+        &poseidon_parameters_4(),
+        &[to_bytes(first)?, to_bytes(second)?, to_bytes(third)?].concat(),
+    )
+    .map_err(|_| ProgramError::InvalidArgument)
+}
+
+pub fn to_bytes(field_element: &Fq) -> Result<[u8; 32], ProgramError> {
+    let mut bytes = [0u8; 32];
+    <Fq as ToBytes>::write(field_element, &mut bytes[..])
+        .map_err(|_| ProgramError::InvalidArgument)?;
+    Ok(bytes)
+}
+
+// Fails for bytes which are not a field element.
+pub fn from_bytes(bytes: &[u8; 32]) -> Result<Fq, ProgramError> {
+    <Fq as FromBytes>::read(&bytes[..]).map_err(|_| {
+        msg!("Bytes are not a field element.");
+        ProgramError::InvalidArgument
+    })
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Keypair {
+    pub private_key: Fq,
+    pub public_key: Fq,
+}
+
+impl Keypair {
+    pub fn new(private_key: Fq) -> Result<Self, ProgramError> {
+        Ok(Keypair {
+            private_key,
+            public_key: poseidon_1(&private_key)?,
+        })
+    }
+
+    // The keccak hash of the seed reduced like the tx integrity hash, i.e. a signature of a
+    // wallet over a fixed message.
+    pub fn from_seed(seed: &[u8]) -> Result<Self, ProgramError> {
+        let hash = keccak::hashv(&[KEYPAIR_SEED_DOMAIN, seed]);
+        Keypair::new(Fq::from_be_bytes_mod_order(&hash.0))
+    }
+
+    pub fn random<R: Rng>(rng: &mut R) -> Result<Self, ProgramError> {
+        Keypair::new(Fq::rand(rng))
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Utxo {
+    pub amount: u64,
+    pub blinding: Fq,
+    pub public_key: Fq,
+    // Merkle tree index of the commitment, 0 until it is inserted.
+    pub index: u64,
+}
+
+impl Utxo {
+    pub fn new<R: Rng>(amount: u64, public_key: Fq, rng: &mut R) -> Self {
+        Utxo {
+            amount,
+            blinding: Fq::rand(rng),
+            public_key,
+            index: 0,
+        }
+    }
+
+    // Input without amount, fills the second input of a transaction.
+    pub fn dummy<R: Rng>(keypair: &Keypair, rng: &mut R) -> Self {
+        Utxo::new(0, keypair.public_key, rng)
+    }
+
+    pub fn commitment(&self) -> Result<Fq, ProgramError> {
+        poseidon_3(&Fq::from(self.amount), &self.public_key, &self.blinding)
+    }
+
+    // Leaf of the utxo, asset_index is None in single asset Merkle trees.
+    pub fn leaf(&self, asset_index: Option<u8>) -> Result<Fq, ProgramError> {
+        let commitment = self.commitment()?;
+        match asset_index {
+            Some(asset_index) => poseidon(&commitment, &Fq::from(asset_index)),
+            None => Ok(commitment),
+        }
+    }
+
+    // Only the owner of the utxo can compute its nullifier.
+    pub fn nullifier(&self, keypair: &Keypair) -> Result<Fq, ProgramError> {
+        self.leaf_nullifier(keypair, None)
+    }
+
+    // Nullifier of the leaf of the utxo in a Merkle tree of asset_index.
+    pub fn leaf_nullifier(
+        &self,
+        keypair: &Keypair,
+        asset_index: Option<u8>,
+    ) -> Result<Fq, ProgramError> {
+        if keypair.public_key != self.public_key {
+            msg!("Keypair does not own the utxo.");
+            return Err(ProgramError::InvalidArgument);
+        }
+        let leaf = self.leaf(asset_index)?;
+        let index = Fq::from(self.index);
+        let signature = poseidon_3(&keypair.private_key, &leaf, &index)?;
+        poseidon_3(&leaf, &index, &signature)
+    }
+}
@@ -0,0 +1,315 @@
+use ark_ed_on_bn254::Fq;
+use ark_relations::r1cs::{ConstraintSynthesizer, ConstraintSystem};
+use ark_std::{test_rng, UniformRand, Zero};
+use light_client::circuit::{
+    proof_bytes, public_amount, setup, setup_multi_asset, verify, TransactionCircuit,
+};
+use light_client::merkle_tree::MerkleTreeIndexer;
+use light_client::utxo::{from_bytes, to_bytes, Keypair, Utxo};
+use light_protocol_program::utils::config::MERKLE_TREE_ACC_BYTES_ARRAY;
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+fn is_satisfied(circuit: &TransactionCircuit) -> bool {
+    let cs = ConstraintSystem::<Fq>::new_ref();
+    circuit.clone().generate_constraints(cs.clone()).unwrap();
+    cs.is_satisfied().unwrap()
+}
+
+fn indexer() -> MerkleTreeIndexer {
+    MerkleTreeIndexer::new(Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0)).unwrap()
+}
+
+fn deposit(keypair: &Keypair, amount: u64, indexer: &MerkleTreeIndexer) -> TransactionCircuit {
+    let mut rng = test_rng();
+    TransactionCircuit::new(
+        keypair,
+        [
+            Utxo::dummy(keypair, &mut rng),
+            Utxo::dummy(keypair, &mut rng),
+        ],
+        [None, None],
+        [
+            Utxo::new(amount, keypair.public_key, &mut rng),
+            Utxo::dummy(keypair, &mut rng),
+        ],
+        &indexer.root(),
+        public_amount(amount as i64, 0),
+        Fq::rand(&mut rng),
+    )
+    .unwrap()
+}
+
+#[test]
+fn public_amounts_should_be_ext_amount_minus_fees() {
+    assert_eq!(public_amount(1_000, 10), Fq::from(990u64));
+    assert_eq!(public_amount(-1_000, 10), -Fq::from(1_010u64));
+    assert_eq!(public_amount(0, 0), Fq::zero());
+    assert_eq!(
+        public_amount(i64::MIN, 0),
+        -Fq::from(i64::MIN.unsigned_abs())
+    );
+}
+
+#[test]
+fn deposits_and_transfers_should_satisfy_the_circuit() {
+    let mut rng = test_rng();
+    let alice = Keypair::from_seed(b"alice").unwrap();
+    let bob = Keypair::from_seed(b"bob").unwrap();
+    let mut indexer = indexer();
+
+    let deposit = deposit(&alice, 1_000, &indexer);
+    assert!(is_satisfied(&deposit));
+    let leaves = deposit.public_inputs_bytes().unwrap().leaves;
+    assert_eq!(
+        leaves[1],
+        to_bytes(&deposit.outputs[0].commitment().unwrap()).unwrap()
+    );
+    indexer.insert(0, leaves[1], leaves[0]).unwrap();
+
+    // spends the deposit at index 0 against the new root
+    let input = Utxo {
+        index: 0,
+        ..deposit.outputs[0]
+    };
+    let proof = indexer.proof(0).unwrap();
+    let transfer = TransactionCircuit::new(
+        &alice,
+        [input, Utxo::dummy(&alice, &mut rng)],
+        [Some(&proof), None],
+        [
+            Utxo::new(400, bob.public_key, &mut rng),
+            Utxo::new(600, alice.public_key, &mut rng),
+        ],
+        &indexer.root(),
+        public_amount(0, 0),
+        Fq::rand(&mut rng),
+    )
+    .unwrap();
+    assert!(is_satisfied(&transfer));
+    assert_eq!(
+        transfer.public_inputs()[3],
+        input.nullifier(&alice).unwrap()
+    );
+
+    // withdrawal of the change with a relayer fee
+    let change = Utxo {
+        index: 3,
+        ..transfer.outputs[1]
+    };
+    indexer
+        .insert(
+            2,
+            to_bytes(&transfer.commitments[0]).unwrap(),
+            to_bytes(&transfer.commitments[1]).unwrap(),
+        )
+        .unwrap();
+    let withdrawal = TransactionCircuit::new(
+        &alice,
+        [change, Utxo::dummy(&alice, &mut rng)],
+        [Some(&indexer.proof(3).unwrap()), None],
+        [Utxo::dummy(&alice, &mut rng), Utxo::dummy(&alice, &mut rng)],
+        &indexer.root(),
+        public_amount(-590, 10),
+        Fq::rand(&mut rng),
+    )
+    .unwrap();
+    assert!(is_satisfied(&withdrawal));
+}
+
+#[test]
+fn invalid_transactions_should_not_satisfy_the_circuit() {
+    let mut rng = test_rng();
+    let alice = Keypair::from_seed(b"alice").unwrap();
+    let mut indexer = indexer();
+    let deposit = deposit(&alice, 1_000, &indexer);
+
+    let mut minted = deposit.clone();
+    minted.outputs[0].amount += 1;
+    minted.commitments[0] = minted.outputs[0].commitment().unwrap();
+    assert!(!is_satisfied(&minted));
+
+    let mut other_nullifier = deposit.clone();
+    other_nullifier.nullifiers[0] = Fq::rand(&mut rng);
+    assert!(!is_satisfied(&other_nullifier));
+
+    let mut other_leaf = deposit.clone();
+    other_leaf.commitments[1] = Fq::rand(&mut rng);
+    assert!(!is_satisfied(&other_leaf));
+
+    // an input with amount which is not in the Merkle tree
+    let leaves = deposit.public_inputs_bytes().unwrap().leaves;
+    indexer.insert(0, leaves[1], leaves[0]).unwrap();
+    let input = Utxo {
+        index: 0,
+        ..deposit.outputs[0]
+    };
+    let proof = indexer.proof(0).unwrap();
+    let outputs = [
+        Utxo::new(1_000, alice.public_key, &mut rng),
+        Utxo::dummy(&alice, &mut rng),
+    ];
+    let transfer = TransactionCircuit::new(
+        &alice,
+        [input, Utxo::dummy(&alice, &mut rng)],
+        [Some(&proof), None],
+        outputs,
+        &indexer.root(),
+        Fq::zero(),
+        Fq::zero(),
+    )
+    .unwrap();
+    assert!(is_satisfied(&transfer));
+    let mut other_root = transfer.clone();
+    other_root.root = from_bytes(&indexer.root_at(0).unwrap()).unwrap();
+    assert!(!is_satisfied(&other_root));
+    let mut other_index = transfer.clone();
+    other_index.inputs[0].index = 2;
+    other_index.nullifiers[0] = other_index.inputs[0].nullifier(&alice).unwrap();
+    assert!(!is_satisfied(&other_index));
+
+    // rejected before proving
+    assert_eq!(
+        TransactionCircuit::new(
+            &alice,
+            [input, Utxo::dummy(&alice, &mut rng)],
+            [None, None],
+            outputs,
+            &indexer.root(),
+            Fq::zero(),
+            Fq::zero(),
+        ),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert_eq!(
+        TransactionCircuit::new(
+            &alice,
+            [input, Utxo::dummy(&alice, &mut rng)],
+            [Some(&proof), None],
+            outputs,
+            &indexer.root(),
+            Fq::from(1u64),
+            Fq::zero(),
+        ),
+        Err(ProgramError::InvalidArgument)
+    );
+    assert_eq!(
+        TransactionCircuit::new(
+            &alice,
+            [input, input],
+            [Some(&proof), Some(&proof)],
+            [outputs[0], Utxo::new(1_000, alice.public_key, &mut rng)],
+            &indexer.root(),
+            Fq::zero(),
+            Fq::zero(),
+        ),
+        Err(ProgramError::InvalidArgument)
+    );
+}
+
+#[test]
+fn local_proofs_should_verify_with_their_public_inputs() {
+    let mut rng = test_rng();
+    let alice = Keypair::from_seed(b"alice").unwrap();
+    let deposit = deposit(&alice, 1_000, &indexer());
+    let proving_key = setup(&mut rng).unwrap();
+    let proof = deposit.prove(&proving_key, &mut rng).unwrap();
+    assert_eq!(proof_bytes(&proof).len(), 256);
+
+    let public_inputs = deposit.public_inputs();
+    assert!(verify(&proving_key.vk, &public_inputs, &proof).unwrap());
+    // the tx integrity hash is bound to the proof
+    let mut other_public_inputs = public_inputs.clone();
+    other_public_inputs[2] += Fq::from(1u64);
+    assert!(!verify(&proving_key.vk, &other_public_inputs, &proof).unwrap());
+    // leaf_right and leaf_left
+    other_public_inputs = public_inputs.clone();
+    other_public_inputs.swap(5, 6);
+    assert!(!verify(&proving_key.vk, &other_public_inputs, &proof).unwrap());
+}
+
+#[test]
+fn multi_asset_transactions_should_be_bound_to_the_asset() {
+    let mut rng = test_rng();
+    let alice = Keypair::from_seed(b"alice").unwrap();
+    let mut indexer = indexer();
+    let deposit = TransactionCircuit::with_asset_index(
+        Some(1),
+        &alice,
+        [Utxo::dummy(&alice, &mut rng), Utxo::dummy(&alice, &mut rng)],
+        [None, None],
+        [
+            Utxo::new(1_000, alice.public_key, &mut rng),
+            Utxo::dummy(&alice, &mut rng),
+        ],
+        &indexer.root(),
+        public_amount(1_000, 0),
+        Fq::rand(&mut rng),
+    )
+    .unwrap();
+    assert!(is_satisfied(&deposit));
+    assert_eq!(
+        deposit.commitments[0],
+        deposit.outputs[0].leaf(Some(1)).unwrap()
+    );
+    let public_inputs = deposit.public_inputs();
+    assert_eq!(public_inputs.len(), 8);
+    assert_eq!(public_inputs[7], Fq::from(1u64));
+
+    // the leaves of other assets are not leaves of the circuit
+    let mut other_asset = deposit.clone();
+    other_asset.asset_index = Some(0);
+    assert!(!is_satisfied(&other_asset));
+
+    let proving_key = setup_multi_asset(&mut rng).unwrap();
+    let proof = deposit.prove(&proving_key, &mut rng).unwrap();
+    assert!(verify(&proving_key.vk, &public_inputs, &proof).unwrap());
+    let mut other_public_inputs = public_inputs.clone();
+    other_public_inputs[7] = Fq::zero();
+    assert!(!verify(&proving_key.vk, &other_public_inputs, &proof).unwrap());
+
+    // the deposited utxo can only be spent with its asset
+    let leaves = deposit.public_inputs_bytes().unwrap().leaves;
+    indexer.insert(0, leaves[1], leaves[0]).unwrap();
+    let input = Utxo {
+        index: 0,
+        ..deposit.outputs[0]
+    };
+    let proof = indexer.proof(0).unwrap();
+    for asset_index in [None, Some(0)].iter() {
+        assert_eq!(
+            TransactionCircuit::with_asset_index(
+                *asset_index,
+                &alice,
+                [input, Utxo::dummy(&alice, &mut rng)],
+                [Some(&proof), None],
+                [
+                    Utxo::new(1_000, alice.public_key, &mut rng),
+                    Utxo::dummy(&alice, &mut rng),
+                ],
+                &indexer.root(),
+                public_amount(0, 0),
+                Fq::rand(&mut rng),
+            ),
+            Err(ProgramError::InvalidArgument)
+        );
+    }
+    let transfer = TransactionCircuit::with_asset_index(
+        Some(1),
+        &alice,
+        [input, Utxo::dummy(&alice, &mut rng)],
+        [Some(&proof), None],
+        [
+            Utxo::new(1_000, alice.public_key, &mut rng),
+            Utxo::dummy(&alice, &mut rng),
+        ],
+        &indexer.root(),
+        public_amount(0, 0),
+        Fq::rand(&mut rng),
+    )
+    .unwrap();
+    assert!(is_satisfied(&transfer));
+    assert_eq!(
+        transfer.nullifiers[0],
+        input.leaf_nullifier(&alice, Some(1)).unwrap()
+    );
+}
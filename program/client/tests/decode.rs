@@ -0,0 +1,302 @@
+use light_client::decode::{
+    decode_account, hex, Account, AdminConfigInfo, MerkleTreeLimitsInfo, ProtocolFeeConfigInfo,
+    RelayerHandoffInfo, UserAccountInfo,
+};
+use light_client::Phase;
+use light_protocol_program::admin_config::AdminConfig;
+use light_protocol_program::error::LightError;
+use light_protocol_program::global_config::{GlobalConfig, ProgramMode};
+use light_protocol_program::merkle_tree_limits::MerkleTreeLimits;
+use light_protocol_program::migration::LEGACY_USER_ACCOUNT_TYPE;
+use light_protocol_program::poseidon_merkle_tree::state::{MerkleTree, TwoLeavesBytesPda};
+use light_protocol_program::protocol_fee::{FeeSchedule, ProtocolFeeConfig};
+use light_protocol_program::relayer_handoff::RelayerHandoff;
+use light_protocol_program::state::ChecksAndTransferState;
+use light_protocol_program::user_account::state::UserAccount;
+use light_protocol_program::utils::config::{
+    ADMIN_CONFIG_ACCOUNT_TYPE, ENCRYPTED_UTXOS_LENGTH, GLOBAL_CONFIG_ACCOUNT_TYPE,
+    INIT_BYTES_MERKLE_TREE_18, LEAVES_PDA_ACCOUNT_TYPE, LEAVES_PDA_ACCOUNT_VERSION,
+    MERKLE_TREE_LIMITS_ACCOUNT_TYPE, NULLIFIER_ACCOUNT_TYPE, NULLIFIER_ACCOUNT_VERSION,
+    PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE, RELAYER_HANDOFF_ACCOUNT_TYPE, TMP_STORAGE_ACCOUNT_TYPE,
+    TMP_STORAGE_ACCOUNT_VERSION, USER_ACCOUNT_TYPE, USER_ACCOUNT_VERSION,
+};
+use light_protocol_program::IX_ORDER;
+use serde_json::json;
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+
+// Offset of proof a, b and c, leaf_right, leaf_left, nullifier 0 and 1 in the tmp storage pda.
+const PROOF_OFFSET: usize = ChecksAndTransferState::LEN - 384 - ENCRYPTED_UTXOS_LENGTH;
+
+fn tmp_storage(signer: &Pubkey, index: usize) -> Vec<u8> {
+    let mut data = vec![0u8; ChecksAndTransferState::LEN];
+    data[0] = TMP_STORAGE_ACCOUNT_VERSION;
+    data[1] = TMP_STORAGE_ACCOUNT_TYPE;
+    data[4..36].copy_from_slice(&signer.to_bytes());
+    data[36..44].copy_from_slice(&10u64.to_le_bytes());
+    data[76..84].copy_from_slice(&(-1_000i64).to_le_bytes());
+    data[212..220].copy_from_slice(&index.to_le_bytes());
+    for (i, field) in data[PROOF_OFFSET + 256..PROOF_OFFSET + 384]
+        .chunks_mut(32)
+        .enumerate()
+    {
+        field.copy_from_slice(&[i as u8 + 1; 32]);
+    }
+    data
+}
+
+fn merkle_tree() -> Vec<u8> {
+    let mut data = vec![0u8; MerkleTree::LEN];
+    data[..INIT_BYTES_MERKLE_TREE_18.len()].copy_from_slice(&INIT_BYTES_MERKLE_TREE_18);
+    data
+}
+
+fn packed<T: Pack>(account: T) -> Vec<u8> {
+    let mut data = vec![0u8; T::LEN];
+    T::pack(account, &mut data).unwrap();
+    data
+}
+
+#[test]
+fn tmp_storage_should_show_the_phase_of_every_instruction() {
+    let signer = Pubkey::new_unique();
+    for (index, phase) in [
+        (1, Some(Phase::RootCheck)),
+        (2, Some(Phase::Verification)),
+        (1266, Some(Phase::MerkleTreeUpdate)),
+        (1501, Some(Phase::Last)),
+        (1502, None),
+    ]
+    .iter()
+    {
+        let decoded = decode_account(&tmp_storage(&signer, *index)).unwrap();
+        assert_eq!(decoded.version, TMP_STORAGE_ACCOUNT_VERSION);
+        assert_eq!(decoded.data_len, ChecksAndTransferState::LEN);
+        let tmp_storage = match decoded.account {
+            Account::TmpStorage(tmp_storage) => tmp_storage,
+            account => panic!("unexpected account {:?}", account),
+        };
+        assert_eq!(tmp_storage.current_instruction_index, *index);
+        assert_eq!(tmp_storage.phase, *phase);
+        assert_eq!(tmp_storage.step, IX_ORDER.get(*index).copied());
+        assert_eq!(tmp_storage.signer, signer.to_string());
+        assert_eq!(tmp_storage.ext_amount, -1_000);
+        assert_eq!(tmp_storage.relayer_fee, 10);
+        assert_eq!(tmp_storage.post_withdraw_call_hash, None);
+        assert_eq!(tmp_storage.leaves, [hex(&[1u8; 32]), hex(&[2u8; 32])]);
+        assert_eq!(tmp_storage.nullifiers, [hex(&[3u8; 32]), hex(&[4u8; 32])]);
+    }
+
+    let mut with_call = tmp_storage(&signer, 1);
+    with_call[148..180].copy_from_slice(&[7u8; 32]);
+    let value = serde_json::to_value(decode_account(&with_call).unwrap()).unwrap();
+    assert_eq!(value["account"]["type"], "tmp_storage");
+    assert_eq!(value["account"]["phase"], "root_check");
+    assert_eq!(value["account"]["post_withdraw_call_hash"], hex(&[7u8; 32]));
+}
+
+#[test]
+fn merkle_tree_should_show_fill_level_and_root_position() {
+    let decoded = match decode_account(&merkle_tree()).unwrap().account {
+        Account::MerkleTree(merkle_tree) => merkle_tree,
+        account => panic!("unexpected account {:?}", account),
+    };
+    assert_eq!(decoded.levels, 18);
+    assert_eq!(decoded.capacity, 1 << 18);
+    assert_eq!(decoded.next_index, 0);
+    assert_eq!(decoded.current_root_index, 0);
+    assert!(!decoded.root_history_wrapped);
+    assert_eq!(
+        decoded.current_root,
+        hex(&INIT_BYTES_MERKLE_TREE_18[610..642])
+    );
+    assert_eq!(decoded.filled_subtrees.len(), 18);
+    assert_eq!(
+        decoded.filled_subtrees[0],
+        hex(&INIT_BYTES_MERKLE_TREE_18[10..42])
+    );
+    let root_history_size = decoded.root_history_size;
+    assert!(root_history_size > 5);
+
+    // half full, the current root at position 5 of the ring
+    let mut data = merkle_tree();
+    data[586..594].copy_from_slice(&5u64.to_le_bytes());
+    data[594..602].copy_from_slice(&(1u64 << 17).to_le_bytes());
+    data[610 + 5 * 32..610 + 6 * 32].copy_from_slice(&[9u8; 32]);
+    let value = serde_json::to_value(decode_account(&data).unwrap()).unwrap();
+    assert_eq!(value["account"]["type"], "merkle_tree");
+    assert_eq!(value["account"]["fill_level"], 0.5);
+    assert_eq!(value["account"]["current_root_index"], 5);
+    assert_eq!(value["account"]["current_root"], hex(&[9u8; 32]));
+    assert_eq!(value["account"]["root_history_wrapped"], true);
+
+    // the root index is outside of the ring
+    data[586..594].copy_from_slice(&root_history_size.to_le_bytes());
+    assert_eq!(decode_account(&data), Err(ProgramError::InvalidAccountData));
+    assert_eq!(
+        decode_account(&merkle_tree()[..MerkleTree::LEN - 1]),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn transaction_accounts_should_be_decoded() {
+    let nullifier = decode_account(&[NULLIFIER_ACCOUNT_VERSION, NULLIFIER_ACCOUNT_TYPE]).unwrap();
+    assert_eq!(nullifier.account, Account::Nullifier);
+    assert_eq!(
+        serde_json::to_value(nullifier).unwrap(),
+        json!({"version": 1, "data_len": 2, "account": {"type": "nullifier"}})
+    );
+
+    let merkle_tree = Pubkey::new_unique();
+    let mut leaves = vec![0u8; TwoLeavesBytesPda::LEN];
+    leaves[0] = LEAVES_PDA_ACCOUNT_VERSION;
+    leaves[1] = LEAVES_PDA_ACCOUNT_TYPE;
+    leaves[2..10].copy_from_slice(&6u64.to_le_bytes());
+    leaves[10..42].copy_from_slice(&[1u8; 32]);
+    leaves[42..74].copy_from_slice(&[2u8; 32]);
+    leaves[74..106].copy_from_slice(&merkle_tree.to_bytes());
+    let value = serde_json::to_value(decode_account(&leaves).unwrap()).unwrap();
+    assert_eq!(value["account"]["type"], "leaves");
+    assert_eq!(value["account"]["left_leaf_index"], 6);
+    assert_eq!(value["account"]["leaf_left"], hex(&[1u8; 32]));
+    assert_eq!(value["account"]["leaf_right"], hex(&[2u8; 32]));
+    assert_eq!(value["account"]["merkle_tree"], merkle_tree.to_string());
+
+    let owner = Pubkey::new_unique();
+    let mut user_account = vec![3u8; UserAccount::LEN];
+    user_account[0] = USER_ACCOUNT_VERSION;
+    user_account[1] = USER_ACCOUNT_TYPE;
+    user_account[2..34].copy_from_slice(&owner.to_bytes());
+    assert_eq!(
+        decode_account(&user_account).unwrap().account,
+        Account::UserAccount(UserAccountInfo {
+            legacy: false,
+            owner: owner.to_string(),
+            encrypted_utxos: hex(&user_account[34..]),
+        })
+    );
+    user_account[1] = LEGACY_USER_ACCOUNT_TYPE;
+    match decode_account(&user_account[..40]).unwrap().account {
+        Account::UserAccount(user_account) => {
+            assert!(user_account.legacy);
+            assert_eq!(user_account.encrypted_utxos, hex(&[3u8; 6]));
+        }
+        account => panic!("unexpected account {:?}", account),
+    }
+}
+
+#[test]
+fn config_accounts_should_be_decoded() {
+    let treasury = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    let fee_config = packed(ProtocolFeeConfig {
+        is_initialized: true,
+        account_type: PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE,
+        asset_id: mint.to_bytes(),
+        treasury,
+        fee_schedule: FeeSchedule {
+            fee_bps: 30,
+            min_fee: 5,
+        },
+    });
+    assert_eq!(
+        decode_account(&fee_config).unwrap().account,
+        Account::ProtocolFeeConfig(ProtocolFeeConfigInfo {
+            mint: Some(mint.to_string()),
+            treasury: treasury.to_string(),
+            fee_bps: 30,
+            min_fee: 5,
+        })
+    );
+
+    let merkle_tree = Pubkey::new_unique();
+    let limits = packed(MerkleTreeLimits {
+        is_initialized: true,
+        account_type: MERKLE_TREE_LIMITS_ACCOUNT_TYPE,
+        merkle_tree_pubkey: merkle_tree,
+        max_deposit: 1,
+        max_total_deposits: 2,
+        epoch_length: 3,
+        max_epoch_withdrawals: 4,
+        current_epoch: 5,
+        epoch_withdrawals: 6,
+    });
+    assert_eq!(
+        decode_account(&limits).unwrap().account,
+        Account::MerkleTreeLimits(MerkleTreeLimitsInfo {
+            merkle_tree: merkle_tree.to_string(),
+            max_deposit: 1,
+            max_total_deposits: 2,
+            epoch_length: 3,
+            max_epoch_withdrawals: 4,
+            current_epoch: 5,
+            epoch_withdrawals: 6,
+        })
+    );
+
+    let guardian = Pubkey::new_unique();
+    let global_config = packed(GlobalConfig {
+        is_initialized: true,
+        account_type: GLOBAL_CONFIG_ACCOUNT_TYPE,
+        guardian,
+        mode: ProgramMode::WithdrawOnly,
+    });
+    let value = serde_json::to_value(decode_account(&global_config).unwrap()).unwrap();
+    assert_eq!(
+        value["account"],
+        json!({"type": "global_config", "guardian": guardian.to_string(), "mode": "WithdrawOnly"})
+    );
+
+    let authority = Pubkey::new_unique();
+    let signers = vec![Pubkey::new_unique(), Pubkey::new_unique()];
+    let admin_config = packed(AdminConfig {
+        is_initialized: true,
+        account_type: ADMIN_CONFIG_ACCOUNT_TYPE,
+        authority,
+        pending_authority: Pubkey::default(),
+        threshold: 2,
+        signers: signers.clone(),
+    });
+    assert_eq!(
+        decode_account(&admin_config).unwrap().account,
+        Account::AdminConfig(AdminConfigInfo {
+            authority: authority.to_string(),
+            pending_authority: None,
+            threshold: 2,
+            signers: signers.iter().map(|signer| signer.to_string()).collect(),
+        })
+    );
+
+    let payer = Pubkey::new_unique();
+    let handoff = packed(RelayerHandoff {
+        is_initialized: true,
+        account_type: RELAYER_HANDOFF_ACCOUNT_TYPE,
+        idle_slots: 100,
+        relayers: signers.clone(),
+        payer,
+        last_instruction_index: 700,
+        last_slot: 42,
+    });
+    assert_eq!(
+        decode_account(&handoff).unwrap().account,
+        Account::RelayerHandoff(RelayerHandoffInfo {
+            idle_slots: 100,
+            relayers: signers.iter().map(|signer| signer.to_string()).collect(),
+            payer: payer.to_string(),
+            last_instruction_index: 700,
+            last_slot: 42,
+        })
+    );
+}
+
+#[test]
+fn unknown_accounts_should_be_rejected() {
+    assert_eq!(decode_account(&[]), Err(ProgramError::InvalidAccountData));
+    assert_eq!(decode_account(&[1]), Err(ProgramError::InvalidAccountData));
+    assert_eq!(
+        decode_account(&[1, 12]),
+        Err(LightError::InvalidAccountType.into())
+    );
+    // config accounts are decoded with their exact length
+    assert!(decode_account(&[1, ADMIN_CONFIG_ACCOUNT_TYPE, 0]).is_err());
+}
@@ -0,0 +1,92 @@
+use ark_std::test_rng;
+use light_client::encryption::{decrypt_utxos, encrypt_utxos, EncryptionKeypair};
+use light_client::utxo::{Keypair, Utxo};
+use light_protocol_program::encrypted_utxos::{
+    check_encrypted_utxos_version, encrypted_utxo_range, ENCRYPTED_UTXOS_VERSION_1,
+};
+use light_protocol_program::utils::config::ENCRYPTED_UTXOS_LENGTH;
+use solana_program::program_error::ProgramError;
+
+#[test]
+fn encrypted_utxos_should_be_decrypted_by_their_owners() {
+    let mut rng = test_rng();
+    let sender = Keypair::from_seed(b"sender").unwrap();
+    let sender_encryption = EncryptionKeypair::from_seed(b"sender");
+    let recipient = Keypair::from_seed(b"recipient").unwrap();
+    let recipient_encryption = EncryptionKeypair::random(&mut rng);
+
+    let payment = Utxo::new(1_000_000_000, recipient.public_key, &mut rng);
+    let change = Utxo::new(500, sender.public_key, &mut rng);
+    let encrypted_utxos = encrypt_utxos(
+        [
+            (&payment, &recipient_encryption.public_key),
+            (&change, &sender_encryption.public_key),
+        ],
+        &mut rng,
+    )
+    .unwrap();
+    assert_eq!(encrypted_utxos.len(), ENCRYPTED_UTXOS_LENGTH);
+    assert_eq!(encrypted_utxos[0], ENCRYPTED_UTXOS_VERSION_1);
+    // accepted by Merkle trees with version 1
+    check_encrypted_utxos_version(ENCRYPTED_UTXOS_VERSION_1, &encrypted_utxos).unwrap();
+
+    let decrypted = decrypt_utxos(
+        &encrypted_utxos,
+        &recipient_encryption,
+        &recipient.public_key,
+    )
+    .unwrap();
+    assert_eq!(decrypted, [Some(payment), None]);
+    assert_eq!(
+        decrypted[0].unwrap().commitment().unwrap(),
+        payment.commitment().unwrap()
+    );
+    let decrypted =
+        decrypt_utxos(&encrypted_utxos, &sender_encryption, &sender.public_key).unwrap();
+    assert_eq!(decrypted, [None, Some(change)]);
+
+    let other = EncryptionKeypair::from_seed(b"other");
+    assert_eq!(
+        decrypt_utxos(&encrypted_utxos, &other, &sender.public_key).unwrap(),
+        [None, None]
+    );
+}
+
+#[test]
+fn modified_encrypted_utxos_should_not_be_decrypted() {
+    let mut rng = test_rng();
+    let keypair = Keypair::from_seed(b"seed").unwrap();
+    let encryption_keypair = EncryptionKeypair::from_seed(b"seed");
+    let utxo = Utxo::new(100, keypair.public_key, &mut rng);
+    let dummy = Utxo::dummy(&keypair, &mut rng);
+    let encrypted_utxos = encrypt_utxos(
+        [
+            (&utxo, &encryption_keypair.public_key),
+            (&dummy, &encryption_keypair.public_key),
+        ],
+        &mut rng,
+    )
+    .unwrap();
+
+    let mut modified = encrypted_utxos.clone();
+    modified[encrypted_utxo_range(0).end - 1] ^= 1;
+    assert_eq!(
+        decrypt_utxos(&modified, &encryption_keypair, &keypair.public_key).unwrap(),
+        [None, Some(dummy)]
+    );
+    let mut unknown_version = encrypted_utxos.clone();
+    unknown_version[0] = 2;
+    assert_eq!(
+        decrypt_utxos(&unknown_version, &encryption_keypair, &keypair.public_key).err(),
+        Some(ProgramError::InvalidArgument)
+    );
+    assert_eq!(
+        decrypt_utxos(
+            &encrypted_utxos[1..],
+            &encryption_keypair,
+            &keypair.public_key
+        )
+        .err(),
+        Some(ProgramError::InvalidArgument)
+    );
+}
@@ -0,0 +1,69 @@
+use borsh::BorshSerialize;
+use light_client::event::{from_log_message, light_events};
+use light_protocol_program::event::{LightEvent, TransactionEvent};
+use light_protocol_program::utils::config::ENCRYPTED_UTXOS_LENGTH;
+use solana_program::pubkey::Pubkey;
+
+fn transaction_event(root: u8) -> TransactionEvent {
+    TransactionEvent {
+        merkle_tree_pubkey: [1u8; 32],
+        root: [root; 32],
+        root_index: 3,
+        left_leaf_index: 4,
+        leaf_left: [5u8; 32],
+        leaf_right: [6u8; 32],
+        nullifiers: [[7u8; 32], [8u8; 32]],
+        encrypted_utxos: vec![9u8; ENCRYPTED_UTXOS_LENGTH],
+        public_amount: 10,
+        relayer_fee: 11,
+        protocol_fee: 12,
+    }
+}
+
+fn program_data(event: &LightEvent) -> String {
+    format!(
+        "Program data: {}",
+        base64::encode(event.try_to_vec().unwrap())
+    )
+}
+
+#[test]
+fn light_event_should_decode_from_log_message() {
+    let event = LightEvent::Withdrawal(transaction_event(2));
+    assert_eq!(from_log_message(&program_data(&event)), Some(event));
+
+    assert_eq!(from_log_message("Program log: Deposited 10"), None);
+    assert_eq!(from_log_message("Program data: AAAA"), None);
+    assert_eq!(from_log_message("Program data: not base64"), None);
+}
+
+#[test]
+fn light_events_should_only_decode_data_of_the_program() {
+    let program_id = Pubkey::new_unique();
+    let caller = Pubkey::new_unique();
+    let deposit = LightEvent::Deposit(transaction_event(2));
+    let withdrawal = LightEvent::Withdrawal(transaction_event(3));
+    let log_messages: Vec<String> = vec![
+        format!("Program {} invoke [1]", program_id),
+        "Program log: Inserting new merkle root.".to_string(),
+        program_data(&deposit),
+        format!("Program {} success", program_id),
+        // A caller program which logs the same data is not the program.
+        format!("Program {} invoke [1]", caller),
+        program_data(&deposit),
+        format!("Program {} invoke [2]", program_id),
+        "Program log: transfer success".to_string(),
+        program_data(&withdrawal),
+        format!(
+            "Program {} consumed 1000 of 200000 compute units",
+            program_id
+        ),
+        format!("Program {} success", program_id),
+        program_data(&deposit),
+        format!("Program {} success", caller),
+    ];
+    assert_eq!(
+        light_events(&program_id, &log_messages),
+        vec![deposit, withdrawal]
+    );
+}
@@ -0,0 +1,168 @@
+#[path = "../../tests/merkle_tree_account_data_after_deposit.rs"]
+mod merkle_tree_account_data_after_deposit;
+#[path = "../../tests/merkle_tree_account_data_after_transfer.rs"]
+mod merkle_tree_account_data_after_transfer;
+
+use crate::merkle_tree_account_data_after_deposit::merkle_tree_account_data_after_deposit::MERKLE_TREE_ACCOUNT_DATA_AFTER_DEPOSIT;
+use crate::merkle_tree_account_data_after_transfer::merkle_tree_account_data_after_transfer::MERKLE_TREE_ACCOUNT_DATA_AFTER_TRANSFER;
+use light_client::merkle_tree::{IndexerError, MerkleTreeIndexer, MERKLE_TREE_HEIGHT};
+use light_protocol_program::poseidon_merkle_tree::state::TwoLeavesBytesPda;
+use light_protocol_program::utils::config::{
+    ENCRYPTED_UTXOS_LENGTH, INIT_BYTES_MERKLE_TREE_18, LEAVES_PDA_ACCOUNT_TYPE,
+    MERKLE_TREE_ACC_BYTES_ARRAY,
+};
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use std::convert::TryInto;
+
+#[path = "../../tests/test_utils/fixtures.rs"]
+mod fixtures;
+use crate::fixtures::read_send_data;
+
+fn merkle_tree() -> Pubkey {
+    Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0)
+}
+
+// Leaves (leaf_left, leaf_right) of the send data of a fixture.
+fn leaves(file: &str) -> ([u8; 32], [u8; 32]) {
+    let send_data = read_send_data(file);
+    (
+        send_data[192..224].try_into().unwrap(),
+        send_data[160..192].try_into().unwrap(),
+    )
+}
+
+fn leaves_account_data(left_leaf_index: usize, leaves: ([u8; 32], [u8; 32])) -> Vec<u8> {
+    let leaves_account = TwoLeavesBytesPda {
+        is_initialized: true,
+        account_type: LEAVES_PDA_ACCOUNT_TYPE,
+        leaf_left: leaves.0.to_vec(),
+        leaf_right: leaves.1.to_vec(),
+        merkle_tree_pubkey: merkle_tree().to_bytes().to_vec(),
+        encrypted_utxos: vec![0u8; ENCRYPTED_UTXOS_LENGTH],
+        left_leaf_index,
+    };
+    let mut data = vec![0u8; TwoLeavesBytesPda::LEN];
+    TwoLeavesBytesPda::pack_into_slice(&leaves_account, &mut data);
+    data
+}
+
+// Leaf which is a field element, not a commitment.
+fn leaf(value: u8) -> [u8; 32] {
+    let mut leaf = [0u8; 32];
+    leaf[0] = value;
+    leaf
+}
+
+#[test]
+fn empty_indexer_should_match_the_initialized_merkle_tree() {
+    let indexer = MerkleTreeIndexer::new(merkle_tree()).unwrap();
+    assert_eq!(indexer.next_index(), 0);
+    assert_eq!(indexer.root()[..], INIT_BYTES_MERKLE_TREE_18[610..642]);
+    // the fixtures prove against the root of the empty tree
+    assert_eq!(indexer.root()[..], read_send_data("deposit.txt")[0..32]);
+}
+
+#[test]
+fn indexer_should_mirror_the_onchain_merkle_tree() {
+    let mut indexer = MerkleTreeIndexer::new(merkle_tree()).unwrap();
+    let deposit = leaves_account_data(0, leaves("deposit.txt"));
+    let transfer = leaves_account_data(2, leaves("internal_transfer.txt"));
+
+    indexer.ingest(vec![deposit.as_slice()]).unwrap();
+    indexer
+        .verify_onchain(&MERKLE_TREE_ACCOUNT_DATA_AFTER_DEPOSIT)
+        .unwrap();
+    // the internal transfer proves against the root after the deposit
+    assert_eq!(
+        indexer.root()[..],
+        read_send_data("internal_transfer.txt")[0..32]
+    );
+    assert_eq!(
+        indexer.verify_onchain(&MERKLE_TREE_ACCOUNT_DATA_AFTER_TRANSFER),
+        Err(IndexerError::OutOfSync {
+            next_index: 2,
+            onchain_next_index: 4
+        })
+    );
+
+    // unordered and with leaves pdas which were ingested before
+    indexer
+        .ingest(vec![transfer.as_slice(), deposit.as_slice()])
+        .unwrap();
+    assert_eq!(indexer.next_index(), 4);
+    indexer
+        .verify_onchain(&MERKLE_TREE_ACCOUNT_DATA_AFTER_TRANSFER)
+        .unwrap();
+    assert_eq!(
+        indexer.root_at(2).unwrap()[..],
+        read_send_data("internal_transfer.txt")[0..32]
+    );
+
+    for leaf_index in 0..4 {
+        let proof = indexer.proof(leaf_index).unwrap();
+        assert_eq!(proof.siblings.len(), MERKLE_TREE_HEIGHT);
+        assert_eq!(proof.root, indexer.root());
+        assert!(proof.verify().unwrap());
+    }
+    assert!(indexer.proof(4).is_err());
+}
+
+#[test]
+fn indexer_should_detect_divergences_from_the_onchain_merkle_tree() {
+    let mut indexer = MerkleTreeIndexer::new(merkle_tree()).unwrap();
+    indexer.insert(0, leaf(1), leaf(2)).unwrap();
+    assert_eq!(
+        indexer.verify_onchain(&MERKLE_TREE_ACCOUNT_DATA_AFTER_DEPOSIT),
+        Err(IndexerError::RootMismatch { position: 1 })
+    );
+
+    // the leaves pda of the deposit differs from the inserted leaves
+    let deposit = leaves_account_data(0, leaves("deposit.txt"));
+    assert_eq!(
+        indexer.ingest(vec![deposit.as_slice()]),
+        Err(IndexerError::Divergence { index: 0 })
+    );
+
+    // the root history is correct but the filled subtree is not
+    let mut indexer = MerkleTreeIndexer::new(merkle_tree()).unwrap();
+    indexer.ingest(vec![deposit.as_slice()]).unwrap();
+    let mut account_data = MERKLE_TREE_ACCOUNT_DATA_AFTER_DEPOSIT.to_vec();
+    // filled_subtrees[1] starts at byte 10 + 32
+    account_data[42] ^= 1;
+    assert_eq!(
+        indexer.verify_onchain(&account_data),
+        Err(IndexerError::FilledSubtreeMismatch { level: 1 })
+    );
+}
+
+#[test]
+fn indexer_should_detect_gaps() {
+    let mut indexer = MerkleTreeIndexer::new(merkle_tree()).unwrap();
+    let first = leaves_account_data(0, (leaf(1), leaf(2)));
+    let third = leaves_account_data(4, (leaf(5), leaf(6)));
+    assert_eq!(
+        indexer.ingest(vec![third.as_slice(), first.as_slice()]),
+        Err(IndexerError::Gap {
+            expected: 2,
+            found: 4
+        })
+    );
+    // the leaves before the gap are inserted
+    assert_eq!(indexer.next_index(), 2);
+    let second = leaves_account_data(2, (leaf(3), leaf(4)));
+    indexer
+        .ingest(vec![third.as_slice(), second.as_slice()])
+        .unwrap();
+    assert_eq!(indexer.next_index(), 6);
+
+    // paths of every leaf prove against the current root
+    for leaf_index in 0..6 {
+        let proof = indexer.proof(leaf_index).unwrap();
+        assert_eq!(proof.leaf, leaf(leaf_index as u8 + 1));
+        assert!(proof.verify().unwrap());
+    }
+    // an older root does not verify the new leaves
+    let mut proof = indexer.proof(5).unwrap();
+    proof.root = indexer.root_at(4).unwrap();
+    assert!(!proof.verify().unwrap());
+}
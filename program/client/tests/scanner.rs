@@ -0,0 +1,296 @@
+use ark_std::test_rng;
+use light_client::encryption::{encrypt_utxos, EncryptionKeypair};
+use light_client::scanner::{LeavesAccount, UtxoScanner, LEAVES_PDA_ACCOUNT_TYPE_OFFSET};
+use light_client::utxo::{to_bytes, Keypair, Utxo};
+use light_protocol_program::error::LightError;
+use light_protocol_program::pda::find_nullifier_pda;
+use light_protocol_program::poseidon_merkle_tree::state::TwoLeavesBytesPda;
+use light_protocol_program::utils::config::{
+    ENCRYPTED_UTXOS_LENGTH, LEAVES_PDA_ACCOUNT_TYPE, MERKLE_TREE_ACC_BYTES_ARRAY,
+};
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+use std::collections::HashSet;
+
+#[path = "../../tests/test_utils/fixtures.rs"]
+mod fixtures;
+use crate::fixtures::program_id;
+
+fn merkle_tree() -> Pubkey {
+    Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0)
+}
+
+// Leaves pda as written by the last instruction.
+fn leaves_account_data(
+    left_leaf_index: usize,
+    leaves: [&Utxo; 2],
+    merkle_tree: &Pubkey,
+    encrypted_utxos: Vec<u8>,
+) -> Vec<u8> {
+    let leaves_account = TwoLeavesBytesPda {
+        is_initialized: true,
+        account_type: LEAVES_PDA_ACCOUNT_TYPE,
+        leaf_left: to_bytes(&leaves[0].commitment().unwrap()).unwrap().to_vec(),
+        leaf_right: to_bytes(&leaves[1].commitment().unwrap()).unwrap().to_vec(),
+        merkle_tree_pubkey: merkle_tree.to_bytes().to_vec(),
+        encrypted_utxos,
+        left_leaf_index,
+    };
+    let mut data = vec![0u8; TwoLeavesBytesPda::LEN];
+    TwoLeavesBytesPda::pack_into_slice(&leaves_account, &mut data);
+    data
+}
+
+struct Wallet {
+    keypair: Keypair,
+    encryption_keypair: EncryptionKeypair,
+}
+
+impl Wallet {
+    fn new(seed: &[u8]) -> Self {
+        Wallet {
+            keypair: Keypair::from_seed(seed).unwrap(),
+            encryption_keypair: EncryptionKeypair::from_seed(seed),
+        }
+    }
+
+    fn scanner(&self) -> UtxoScanner {
+        UtxoScanner::new(
+            program_id(),
+            merkle_tree(),
+            self.keypair,
+            self.encryption_keypair.clone(),
+        )
+    }
+}
+
+fn transaction(
+    left_leaf_index: usize,
+    outputs: [(&Utxo, &Wallet); 2],
+    merkle_tree: &Pubkey,
+) -> Vec<u8> {
+    let encrypted_utxos = encrypt_utxos(
+        [
+            (outputs[0].0, &outputs[0].1.encryption_keypair.public_key),
+            (outputs[1].0, &outputs[1].1.encryption_keypair.public_key),
+        ],
+        &mut test_rng(),
+    )
+    .unwrap();
+    leaves_account_data(
+        left_leaf_index,
+        [outputs[0].0, outputs[1].0],
+        merkle_tree,
+        encrypted_utxos,
+    )
+}
+
+#[test]
+fn leaves_accounts_should_be_unpacked() {
+    let wallet = Wallet::new(b"wallet");
+    let mut rng = test_rng();
+    let utxo = Utxo::new(1, wallet.keypair.public_key, &mut rng);
+    let data = leaves_account_data(4, [&utxo, &utxo], &merkle_tree(), vec![3u8; 222]);
+    assert_eq!(
+        data[LEAVES_PDA_ACCOUNT_TYPE_OFFSET],
+        LEAVES_PDA_ACCOUNT_TYPE
+    );
+    let leaves_account = LeavesAccount::unpack(&data).unwrap();
+    assert_eq!(leaves_account.left_leaf_index, 4);
+    assert_eq!(
+        leaves_account.leaf_left,
+        to_bytes(&utxo.commitment().unwrap()).unwrap()
+    );
+    assert_eq!(leaves_account.merkle_tree, merkle_tree());
+    assert_eq!(
+        leaves_account.encrypted_utxos,
+        vec![3u8; ENCRYPTED_UTXOS_LENGTH]
+    );
+
+    let mut other_account_type = data.clone();
+    other_account_type[LEAVES_PDA_ACCOUNT_TYPE_OFFSET] = LEAVES_PDA_ACCOUNT_TYPE + 1;
+    assert_eq!(
+        LeavesAccount::unpack(&other_account_type),
+        Err(LightError::InvalidAccountType.into())
+    );
+    assert_eq!(
+        LeavesAccount::unpack(&data[1..]),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn scanner_should_find_owned_unspent_utxos() {
+    let mut rng = test_rng();
+    let alice = Wallet::new(b"alice");
+    let bob = Wallet::new(b"bob");
+
+    let deposit = Utxo::new(1_000, alice.keypair.public_key, &mut rng);
+    let dummy = Utxo::dummy(&alice.keypair, &mut rng);
+    let payment = Utxo::new(400, bob.keypair.public_key, &mut rng);
+    let change = Utxo::new(600, alice.keypair.public_key, &mut rng);
+    let other_tree_utxo = Utxo::new(5, alice.keypair.public_key, &mut rng);
+    let leaves_accounts = vec![
+        // unordered like getProgramAccounts
+        transaction(2, [(&payment, &bob), (&change, &alice)], &merkle_tree()),
+        transaction(0, [(&deposit, &alice), (&dummy, &alice)], &merkle_tree()),
+        transaction(
+            0,
+            [(&other_tree_utxo, &alice), (&other_tree_utxo, &alice)],
+            &Pubkey::new_unique(),
+        ),
+        // unversioned encrypted utxos of the fixtures
+        leaves_account_data(4, [&deposit, &deposit], &merkle_tree(), vec![180u8; 222]),
+    ];
+
+    let mut scanner = alice.scanner();
+    assert_eq!(
+        scanner
+            .scan(leaves_accounts.iter().map(|data| data.as_slice()))
+            .unwrap(),
+        3
+    );
+    assert_eq!(scanner.next_index, 6);
+    let indices: Vec<u64> = scanner.utxos.iter().map(|utxo| utxo.index).collect();
+    assert_eq!(
+        indices.iter().cloned().collect::<HashSet<u64>>(),
+        [0u64, 1, 3].iter().cloned().collect()
+    );
+
+    // the deposit was spent in the transaction with left leaf index 2
+    let spent = find_nullifier_pda(
+        &program_id(),
+        &to_bytes(
+            &Utxo {
+                index: 0,
+                ..deposit
+            }
+            .nullifier(&alice.keypair)
+            .unwrap(),
+        )
+        .unwrap(),
+    )
+    .0;
+    let mut lookups = 0;
+    let unspent = scanner
+        .unspent(|nullifier_pda| {
+            lookups += 1;
+            Ok(*nullifier_pda == spent)
+        })
+        .unwrap();
+    assert_eq!(lookups, 3);
+    assert_eq!(
+        unspent,
+        vec![Utxo { index: 1, ..dummy }, Utxo { index: 3, ..change }]
+    );
+    assert_eq!(scanner.balance().unwrap(), 600);
+
+    let mut bob_scanner = bob.scanner();
+    bob_scanner
+        .scan(leaves_accounts.iter().map(|data| data.as_slice()))
+        .unwrap();
+    assert_eq!(
+        bob_scanner.utxos,
+        vec![Utxo {
+            index: 2,
+            ..payment
+        }]
+    );
+}
+
+#[test]
+fn scanner_should_resume_from_the_next_index() {
+    let mut rng = test_rng();
+    let alice = Wallet::new(b"alice");
+    let first = Utxo::new(1, alice.keypair.public_key, &mut rng);
+    let second = Utxo::new(2, alice.keypair.public_key, &mut rng);
+    let third = Utxo::new(3, alice.keypair.public_key, &mut rng);
+    let first_transaction = transaction(0, [(&first, &alice), (&second, &alice)], &merkle_tree());
+
+    let mut scanner = alice.scanner();
+    scanner.scan(vec![first_transaction.as_slice()]).unwrap();
+    assert_eq!(scanner.next_index, 2);
+
+    // a resumed scanner skips the leaves pdas it has seen
+    let mut resumed = alice.scanner();
+    resumed.next_index = scanner.next_index;
+    resumed.utxos = scanner.utxos.clone();
+    let second_transaction = transaction(2, [(&third, &alice), (&third, &alice)], &merkle_tree());
+    assert_eq!(
+        resumed
+            .scan(vec![
+                first_transaction.as_slice(),
+                second_transaction.as_slice()
+            ])
+            .unwrap(),
+        2
+    );
+    assert_eq!(resumed.next_index, 4);
+    assert_eq!(resumed.utxos.len(), 4);
+    // scanning the same leaves pdas again finds nothing
+    assert_eq!(
+        resumed.scan(vec![second_transaction.as_slice()]).unwrap(),
+        0
+    );
+    assert_eq!(resumed.unspent(|_| Ok(false)).unwrap().len(), 4);
+    assert_eq!(resumed.balance().unwrap(), 1 + 2 + 3 + 3);
+}
+
+#[test]
+fn utxos_which_are_not_the_leaf_should_be_skipped() {
+    let mut rng = test_rng();
+    let alice = Wallet::new(b"alice");
+    let utxo = Utxo::new(100, alice.keypair.public_key, &mut rng);
+    let other = Utxo::new(100, alice.keypair.public_key, &mut rng);
+    let encrypted_utxos = encrypt_utxos(
+        [
+            (&utxo, &alice.encryption_keypair.public_key),
+            (&utxo, &alice.encryption_keypair.public_key),
+        ],
+        &mut rng,
+    )
+    .unwrap();
+    // the encrypted utxo of leaf_right is not leaf_right
+    let data = leaves_account_data(0, [&utxo, &other], &merkle_tree(), encrypted_utxos);
+    let mut scanner = alice.scanner();
+    assert_eq!(scanner.scan(vec![data.as_slice()]).unwrap(), 1);
+    assert_eq!(scanner.utxos, vec![utxo]);
+}
+
+#[test]
+fn next_index_should_not_advance_across_a_gap() {
+    let mut rng = test_rng();
+    let alice = Wallet::new(b"alice");
+    let first = Utxo::new(1, alice.keypair.public_key, &mut rng);
+    let second = Utxo::new(2, alice.keypair.public_key, &mut rng);
+    let third = Utxo::new(3, alice.keypair.public_key, &mut rng);
+    let first_transaction = transaction(0, [(&first, &alice), (&first, &alice)], &merkle_tree());
+    let second_transaction = transaction(2, [(&second, &alice), (&second, &alice)], &merkle_tree());
+    let third_transaction = transaction(4, [(&third, &alice), (&third, &alice)], &merkle_tree());
+
+    // the leaves pda at index 2 is missing
+    let mut scanner = alice.scanner();
+    assert_eq!(
+        scanner
+            .scan(vec![
+                third_transaction.as_slice(),
+                first_transaction.as_slice()
+            ])
+            .unwrap(),
+        4
+    );
+    assert_eq!(scanner.next_index, 2);
+
+    // the leaves pdas after the gap are scanned again without duplicating their utxos
+    assert_eq!(
+        scanner
+            .scan(vec![
+                second_transaction.as_slice(),
+                third_transaction.as_slice()
+            ])
+            .unwrap(),
+        2
+    );
+    assert_eq!(scanner.next_index, 6);
+    assert_eq!(scanner.utxos.len(), 6);
+    assert_eq!(scanner.balance().unwrap(), 2 * (1 + 2 + 3));
+}
@@ -0,0 +1,266 @@
+use light_client::{
+    pack_instructions, phase, transaction_size, ExtData, LightTransaction, Phase, PublicInputs,
+};
+use light_protocol_program::asset::Asset;
+use light_protocol_program::cpi::{last_instruction, send_data_instruction, Settlement};
+use light_protocol_program::pda::{find_escrow_pda, find_tmp_storage_pda};
+use light_protocol_program::process_instruction;
+use light_protocol_program::state::InstructionIndex;
+use light_protocol_program::utils::config::MERKLE_TREE_ACC_BYTES_ARRAY;
+use solana_program::{program_error::ProgramError, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::*;
+use solana_sdk::{
+    packet::PACKET_DATA_SIZE, signature::Signer, signer::keypair::Keypair, transaction::Transaction,
+};
+use std::{collections::HashSet, convert::TryInto};
+
+#[path = "../../tests/test_utils/fixtures.rs"]
+mod fixtures;
+use crate::fixtures::{program_id, read_send_data, PRIV_KEY_DEPOSIT};
+
+fn bytes32(send_data: &[u8], start: usize) -> [u8; 32] {
+    send_data[start..start + 32].try_into().unwrap()
+}
+
+// Splits the send data of a fixture into typed fields.
+fn light_transaction(send_data: &[u8]) -> LightTransaction {
+    LightTransaction {
+        program_id: program_id(),
+        proof: send_data[224..480].to_vec(),
+        public_inputs: PublicInputs {
+            root: bytes32(send_data, 0),
+            public_amount: bytes32(send_data, 32),
+            tx_integrity_hash: bytes32(send_data, 64),
+            nullifiers: [bytes32(send_data, 96), bytes32(send_data, 128)],
+            leaves: [bytes32(send_data, 160), bytes32(send_data, 192)],
+        },
+        ext_data: ExtData {
+            recipient: Pubkey::new(&send_data[480..512]),
+            ext_amount: i64::from_le_bytes(send_data[512..520].try_into().unwrap()),
+            relayer: Pubkey::new(&send_data[520..552]),
+            fee: u64::from_le_bytes(send_data[552..560].try_into().unwrap()),
+            merkle_tree: Pubkey::new(&send_data[560..592]),
+            merkle_tree_index: send_data[592],
+            encrypted_utxos: send_data[593..].to_vec(),
+        },
+        asset: Asset::Sol,
+        merkle_tree_token: Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1),
+        user_token: None,
+        relayer_token: None,
+        treasury: None,
+        post_withdraw_call: None,
+        relayer_handoff: None,
+    }
+}
+
+#[test]
+fn phases_should_follow_ix_order() {
+    assert_eq!(phase(0), Some(Phase::SendData));
+    assert_eq!(phase(1), Some(Phase::RootCheck));
+    assert_eq!(phase(2), Some(Phase::Verification));
+    assert_eq!(phase(1265), Some(Phase::Verification));
+    assert_eq!(phase(1266), Some(Phase::MerkleTreeUpdate));
+    assert_eq!(phase(1500), Some(Phase::MerkleTreeUpdate));
+    assert_eq!(phase(1501), Some(Phase::Last));
+    assert_eq!(phase(1502), None);
+}
+
+#[test]
+fn instructions_should_match_the_send_data() {
+    let send_data = read_send_data("deposit.txt");
+    let transaction = light_transaction(&send_data);
+    assert_eq!(transaction.send_data().unwrap(), send_data);
+    assert_eq!(
+        transaction.tx_integrity_hash().unwrap(),
+        bytes32(&send_data, 64)
+    );
+
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap().pubkey();
+    let merkle_tree = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0);
+    let tmp_storage_pda = find_tmp_storage_pda(&program_id(), &send_data[64..96], &relayer).0;
+    // Derived like create_pubkeys_from_ix_data in the onchain tests.
+    let leaves_pda =
+        Pubkey::find_program_address(&[&send_data[96..128], &b"leaves"[..]], &program_id()).0;
+    let nullifier_pdas = [
+        Pubkey::find_program_address(&[&send_data[96..128], &b"nf"[..]], &program_id()).0,
+        Pubkey::find_program_address(&[&send_data[128..160], &b"nf"[..]], &program_id()).0,
+    ];
+    let pdas = transaction.pdas();
+    assert_eq!(pdas.tmp_storage, tmp_storage_pda);
+    assert_eq!(pdas.leaves, leaves_pda);
+    assert_eq!(pdas.nullifiers, nullifier_pdas);
+
+    let instructions = transaction.instructions().unwrap();
+    assert_eq!(instructions.len(), 1502);
+    assert_eq!(
+        instructions[0],
+        send_data_instruction(&program_id(), &relayer, &send_data).unwrap()
+    );
+    let mut compute_data = HashSet::new();
+    for (index, instruction) in instructions.iter().enumerate().take(1501).skip(1) {
+        assert_eq!(instruction.accounts[0].pubkey, relayer);
+        assert!(instruction.accounts[0].is_signer);
+        assert_eq!(instruction.accounts[1].pubkey, tmp_storage_pda);
+        match phase(index).unwrap() {
+            Phase::RootCheck | Phase::MerkleTreeUpdate => {
+                assert_eq!(instruction.accounts.len(), 3);
+                assert_eq!(instruction.accounts[2].pubkey, merkle_tree);
+            }
+            Phase::Verification => assert_eq!(instruction.accounts.len(), 2),
+            phase => panic!("unexpected phase {:?} at {}", phase, index),
+        }
+        // Equal instructions would be deduplicated by the runtime.
+        assert!(compute_data.insert(instruction.data.clone()));
+    }
+    let last = &instructions[1501];
+    assert_eq!(
+        *last,
+        last_instruction(
+            &program_id(),
+            &relayer,
+            &tmp_storage_pda,
+            &transaction.public_inputs.nullifiers,
+            &merkle_tree,
+            &transaction.merkle_tree_token,
+            &Asset::Sol,
+            Settlement::Deposit {
+                user_token: find_escrow_pda(&program_id(), &tmp_storage_pda).0,
+            },
+            None,
+            None,
+            None,
+        )
+        .unwrap()
+    );
+    assert_eq!(last.accounts[2].pubkey, leaves_pda);
+    assert_eq!(last.accounts[3].pubkey, nullifier_pdas[0]);
+    assert_eq!(last.accounts[4].pubkey, nullifier_pdas[1]);
+
+    // After a relayer hand-off the remaining instructions are signed by the new relayer,
+    // the tmp storage pda stays the same.
+    let backup_relayer = Pubkey::new_unique();
+    let instruction = transaction.instruction(1000, &backup_relayer).unwrap();
+    assert_eq!(instruction.accounts[0].pubkey, backup_relayer);
+    assert_eq!(instruction.accounts[1].pubkey, tmp_storage_pda);
+    assert_eq!(
+        transaction.instruction(1502, &relayer),
+        Err(ProgramError::InvalidArgument)
+    );
+}
+
+#[test]
+fn settlement_should_follow_ext_amount() {
+    let send_data = read_send_data("deposit.txt");
+    let mut transaction = light_transaction(&send_data);
+    assert_eq!(
+        transaction.settlement(),
+        Ok(Settlement::Deposit {
+            user_token: transaction.pdas().escrow
+        })
+    );
+    transaction.asset = Asset::Spl(Pubkey::new_unique());
+    assert_eq!(transaction.settlement(), Err(ProgramError::InvalidArgument));
+    let user_token = Pubkey::new_unique();
+    transaction.user_token = Some(user_token);
+    assert_eq!(
+        transaction.settlement(),
+        Ok(Settlement::Deposit { user_token })
+    );
+
+    transaction.ext_data.ext_amount = -100;
+    assert_eq!(
+        transaction.settlement(),
+        Ok(Settlement::Withdrawal {
+            recipient: transaction.ext_data.recipient
+        })
+    );
+    transaction.ext_data.ext_amount = 0;
+    assert_eq!(transaction.settlement(), Ok(Settlement::Transfer));
+
+    transaction.ext_data.encrypted_utxos.pop();
+    assert_eq!(transaction.send_data(), Err(ProgramError::InvalidArgument));
+}
+
+#[test]
+fn instructions_should_be_packed_under_the_packet_size() {
+    let send_data = read_send_data("deposit.txt");
+    let transaction = light_transaction(&send_data);
+    let relayer = transaction.ext_data.relayer;
+    let instructions = transaction.instructions().unwrap();
+
+    let transactions = pack_instructions(&instructions, &relayer, 64);
+    // The send data does not leave space for a compute instruction.
+    assert_eq!(transactions[0], vec![instructions[0].clone()]);
+    assert!(transactions.len() < 1502 / 10);
+    for transaction_instructions in transactions.iter() {
+        assert!(transaction_instructions.len() <= 64);
+        assert!(transaction_size(transaction_instructions, &relayer) <= PACKET_DATA_SIZE);
+    }
+    assert_eq!(transactions.concat(), instructions);
+
+    let transactions = pack_instructions(&instructions, &relayer, 1);
+    assert_eq!(transactions.len(), 1502);
+
+    // The computed size is the size of the signed transaction.
+    let keypair = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let packed = &pack_instructions(&instructions, &relayer, 64)[1];
+    let signed = Transaction::new_signed_with_payer(
+        packed,
+        Some(&relayer),
+        &[&keypair],
+        solana_sdk::hash::Hash::default(),
+    );
+    assert_eq!(
+        transaction_size(packed, &relayer),
+        bincode::serialized_size(&signed).unwrap() as usize
+    );
+}
+
+#[tokio::test]
+async fn packed_send_data_should_be_accepted() {
+    let send_data = read_send_data("deposit.txt");
+    let transaction = light_transaction(&send_data);
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let instructions = transaction.instructions().unwrap();
+    let transactions = pack_instructions(&instructions, &relayer.pubkey(), 64);
+
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    let funding = solana_sdk::system_transaction::transfer(
+        &program_context.payer,
+        &relayer.pubkey(),
+        10_000_000_000,
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(funding)
+        .await
+        .unwrap();
+
+    let send_data_transaction = Transaction::new_signed_with_payer(
+        &transactions[0],
+        Some(&relayer.pubkey()),
+        &[&relayer],
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(send_data_transaction)
+        .await
+        .unwrap();
+    let tmp_storage_account = program_context
+        .banks_client
+        .get_account(transaction.pdas().tmp_storage)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tmp_storage_account.owner, program_id());
+    let instruction_index = InstructionIndex::unpack(&tmp_storage_account.data).unwrap();
+    assert_eq!(instruction_index.signer_pubkey, relayer.pubkey());
+    assert_eq!(instruction_index.current_instruction_index, 1);
+}
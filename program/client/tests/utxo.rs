@@ -0,0 +1,180 @@
+use ark_ed_on_bn254::Fq;
+use ark_std::test_rng;
+use light_client::utxo::{from_bytes, poseidon, poseidon_1, poseidon_3, to_bytes, Keypair, Utxo};
+use light_protocol_program::utils::config::ZERO_BYTES_MERKLE_TREE_18;
+use solana_program::program_error::ProgramError;
+use std::{convert::TryInto, str::FromStr};
+
+#[path = "../../tests/test_utils/fixtures.rs"]
+mod fixtures;
+use crate::fixtures::read_send_data;
+
+fn zero_value(level: usize) -> Fq {
+    from_bytes(
+        &ZERO_BYTES_MERKLE_TREE_18[level * 32..(level + 1) * 32]
+            .try_into()
+            .unwrap(),
+    )
+    .unwrap()
+}
+
+#[test]
+fn poseidon_should_match_circomlib() {
+    // poseidon([1, 2]) of circomlib
+    assert_eq!(
+        poseidon(&Fq::from(1u64), &Fq::from(2u64)).unwrap(),
+        Fq::from_str(
+            "7853200120776062878684798364095072458815029376092732009249414926327459813530"
+        )
+        .unwrap()
+    );
+    // poseidon([1]) and poseidon([1, 2, 3]) of circomlib
+    assert_eq!(
+        poseidon_1(&Fq::from(1u64)).unwrap(),
+        Fq::from_str(
+            "18586133768512220936620570745912940619677854269274689475585506675881198879027"
+        )
+        .unwrap()
+    );
+    assert_eq!(
+        poseidon_3(&Fq::from(1u64), &Fq::from(2u64), &Fq::from(3u64)).unwrap(),
+        Fq::from_str(
+            "6542985608222806190361240322586112750744169038454362455181422643027100751666"
+        )
+        .unwrap()
+    );
+}
+
+// The fixtures don't contain the utxos of their leaves, the hash is checked against the
+// zero values of the on-chain Merkle tree instead.
+#[test]
+fn poseidon_should_match_the_merkle_tree() {
+    for level in 0..17 {
+        assert_eq!(
+            poseidon(&zero_value(level), &zero_value(level)).unwrap(),
+            zero_value(level + 1),
+            "level {}",
+            level
+        );
+    }
+}
+
+#[test]
+fn keypair_should_be_derived_from_seed() {
+    let keypair = Keypair::from_seed(b"seed").unwrap();
+    assert_eq!(keypair, Keypair::from_seed(b"seed").unwrap());
+    assert_ne!(keypair, Keypair::from_seed(b"other seed").unwrap());
+    assert_eq!(
+        keypair.public_key,
+        poseidon_1(&keypair.private_key).unwrap()
+    );
+    assert_eq!(Keypair::new(keypair.private_key).unwrap(), keypair);
+}
+
+#[test]
+fn commitment_and_nullifier_should_be_computed() {
+    let keypair = Keypair::new(Fq::from(3u64)).unwrap();
+    // Poseidon of circomlib with the inputs of the tornado nova circuit
+    assert_eq!(
+        keypair.public_key,
+        Fq::from_str(
+            "6018413527099068561047958932369318610297162528491556075919075208700178480084"
+        )
+        .unwrap()
+    );
+    let utxo = Utxo {
+        amount: 1_000_000_000,
+        blinding: Fq::from(5u64),
+        public_key: keypair.public_key,
+        index: 7,
+    };
+    let commitment = Fq::from_str(
+        "16412554621632996978567214206566487051315383974675597883577804264629459015616",
+    )
+    .unwrap();
+    assert_eq!(utxo.commitment().unwrap(), commitment);
+    assert_eq!(
+        commitment,
+        poseidon_3(
+            &Fq::from(1_000_000_000u64),
+            &keypair.public_key,
+            &Fq::from(5u64)
+        )
+        .unwrap()
+    );
+
+    let nullifier = Fq::from_str(
+        "16506102489219540859784329205579184183520127765331099728116706110624334076415",
+    )
+    .unwrap();
+    assert_eq!(utxo.nullifier(&keypair).unwrap(), nullifier);
+    let signature = poseidon_3(&Fq::from(3u64), &commitment, &Fq::from(7u64)).unwrap();
+    assert_eq!(
+        nullifier,
+        poseidon_3(&commitment, &Fq::from(7u64), &signature).unwrap()
+    );
+
+    // the nullifier depends on the Merkle tree index, the commitment does not
+    let moved = Utxo { index: 8, ..utxo };
+    assert_eq!(moved.commitment().unwrap(), commitment);
+    assert_ne!(moved.nullifier(&keypair).unwrap(), nullifier);
+
+    let other_keypair = Keypair::new(Fq::from(4u64)).unwrap();
+    assert_eq!(
+        utxo.nullifier(&other_keypair),
+        Err(ProgramError::InvalidArgument)
+    );
+
+    let mut rng = test_rng();
+    let dummy = Utxo::dummy(&keypair, &mut rng);
+    assert_eq!(dummy.amount, 0);
+    assert_ne!(
+        dummy.commitment().unwrap(),
+        Utxo::dummy(&keypair, &mut rng).commitment().unwrap()
+    );
+}
+
+#[test]
+fn multi_asset_leaves_should_hash_the_asset_index() {
+    let keypair = Keypair::new(Fq::from(3u64)).unwrap();
+    let utxo = Utxo {
+        amount: 1_000_000_000,
+        blinding: Fq::from(5u64),
+        public_key: keypair.public_key,
+        index: 7,
+    };
+    let commitment = utxo.commitment().unwrap();
+    assert_eq!(utxo.leaf(None).unwrap(), commitment);
+    assert_eq!(
+        utxo.leaf_nullifier(&keypair, None).unwrap(),
+        utxo.nullifier(&keypair).unwrap()
+    );
+
+    let leaf = utxo.leaf(Some(1)).unwrap();
+    assert_eq!(leaf, poseidon(&commitment, &Fq::from(1u64)).unwrap());
+    assert_ne!(leaf, utxo.leaf(Some(0)).unwrap());
+    let signature = poseidon_3(&Fq::from(3u64), &leaf, &Fq::from(7u64)).unwrap();
+    assert_eq!(
+        utxo.leaf_nullifier(&keypair, Some(1)).unwrap(),
+        poseidon_3(&leaf, &Fq::from(7u64), &signature).unwrap()
+    );
+    assert_ne!(
+        utxo.leaf_nullifier(&keypair, Some(1)).unwrap(),
+        utxo.leaf_nullifier(&keypair, Some(0)).unwrap()
+    );
+}
+
+#[test]
+fn commitments_should_be_encoded_like_fixture_leaves() {
+    let send_data = read_send_data("deposit.txt");
+    for leaf in send_data[160..224].chunks(32) {
+        let leaf: [u8; 32] = leaf.try_into().unwrap();
+        assert_eq!(to_bytes(&from_bytes(&leaf).unwrap()).unwrap(), leaf);
+    }
+    let keypair = Keypair::from_seed(b"seed").unwrap();
+    let utxo = Utxo::new(100, keypair.public_key, &mut test_rng());
+    let commitment = to_bytes(&utxo.commitment().unwrap()).unwrap();
+    assert_eq!(from_bytes(&commitment).unwrap(), utxo.commitment().unwrap());
+    // not a field element
+    assert_eq!(from_bytes(&[255u8; 32]), Err(ProgramError::InvalidArgument));
+}
@@ -0,0 +1,9 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use light_protocol_program_fuzz::FuzzInput;
+
+fuzz_target!(|data: &[u8]| {
+    if let Some(mut input) = FuzzInput::decode(data) {
+        let _ = input.process();
+    }
+});
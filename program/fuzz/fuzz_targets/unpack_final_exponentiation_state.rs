@@ -0,0 +1,8 @@
+#![no_main]
+use libfuzzer_sys::fuzz_target;
+use light_protocol_program::groth16_verifier::final_exponentiation::state::FinalExponentiationState;
+use light_protocol_program_fuzz::fuzz_unpack;
+
+fuzz_target!(|data: &[u8]| {
+    fuzz_unpack::<FinalExponentiationState>(data);
+});
@@ -0,0 +1,216 @@
+// Writes the seed corpus for every fuzz target to corpus/<target>/.
+// Instruction data is taken from the program test fixtures in ../tests/test_data,
+// account seeds are the accounts the seed instructions leave behind.
+// Run with `cargo run --bin seed_corpus` from the fuzz directory.
+use light_protocol_program::admin_config::find_admin_config;
+use light_protocol_program::global_config::find_global_config;
+use light_protocol_program::pda::find_tmp_storage_pda;
+use light_protocol_program::poseidon_merkle_tree::state::{MerkleTree, TwoLeavesBytesPda};
+use light_protocol_program::user_account::state::UserAccount;
+use light_protocol_program_fuzz::{
+    program_id, selected_key, FuzzAccount, FuzzInput, MERKLE_TREE_KEY, RENT_SYSVAR_KEY, SIGNER_KEY,
+    SYSTEM_PROGRAM_KEY,
+};
+use serde_json::Value;
+use solana_program::{program_pack::Pack, pubkey::Pubkey};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FIXTURES: [&str; 4] = [
+    "deposit.txt",
+    "deposit_with_wrong_proof.txt",
+    "internal_transfer.txt",
+    "withdraw.txt",
+];
+const LAMPORTS: u64 = 1_000_000_000_000;
+const USER_ACCOUNT_KEY: u8 = 0x10;
+
+fn read_fixture(name: &str) -> Vec<u8> {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../tests/test_data")
+        .join(name);
+    let file = fs::read_to_string(&path).expect("failed to read fixture");
+    let json: Value = serde_json::from_str(&file).expect("fixture is not valid json");
+    json["bytes"][0]
+        .as_str()
+        .expect("fixture has no bytes")
+        .split(',')
+        .filter_map(|byte| byte.trim().parse::<u8>().ok())
+        .collect()
+}
+
+fn write_seed(target: &str, name: &str, seed: &[u8]) {
+    let dir: PathBuf = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("corpus")
+        .join(target);
+    fs::create_dir_all(&dir).expect("failed to create corpus directory");
+    fs::write(dir.join(name), seed).expect("failed to write seed");
+}
+
+// Instruction data of the instructions following the first transact instruction.
+fn compute_instruction_data(i: u8) -> Vec<u8> {
+    vec![3, 0, 0, 0, 0, 0, 0, 0, 98, 99, i]
+}
+
+fn selector_instruction_data(selector: u8) -> Vec<u8> {
+    vec![1, 0, 0, 0, 0, 0, 0, 0, selector]
+}
+
+fn signer() -> FuzzAccount {
+    FuzzAccount::new(selected_key(SIGNER_KEY), true, true, false).with_lamports(LAMPORTS)
+}
+
+fn system_program() -> FuzzAccount {
+    FuzzAccount::new(selected_key(SYSTEM_PROGRAM_KEY), false, false, false)
+}
+
+fn rent_sysvar() -> FuzzAccount {
+    FuzzAccount::new(selected_key(RENT_SYSVAR_KEY), false, false, false)
+}
+
+// The admin config is not created, the signer is the merkle tree init authority.
+fn admin_config() -> FuzzAccount {
+    FuzzAccount::new(find_admin_config(&program_id()).0, false, false, false)
+}
+
+fn process(name: &str, mut input: FuzzInput) -> FuzzInput {
+    write_seed("process_instruction", name, &input.encode());
+    if let Err(error) = input.process() {
+        println!("seed {} returns {:?}", name, error);
+    }
+    input
+}
+
+fn main() {
+    let merkle_tree = process(
+        "init_merkle_tree",
+        FuzzInput {
+            accounts: vec![
+                signer(),
+                FuzzAccount::new(selected_key(MERKLE_TREE_KEY), false, true, true)
+                    .with_lamports(LAMPORTS)
+                    .with_data(vec![0u8; MerkleTree::LEN]),
+                rent_sysvar(),
+                admin_config(),
+            ],
+            instruction_data: selector_instruction_data(240),
+        },
+    )
+    .accounts[1]
+        .clone();
+    for target in [
+        "unpack_merkle_tree",
+        "unpack_init_merkle_tree_bytes",
+        "unpack_merkle_tree_roots",
+    ]
+    .iter()
+    {
+        write_seed(target, "init_merkle_tree", &merkle_tree.data);
+    }
+
+    let user_account = process(
+        "init_user_account",
+        FuzzInput {
+            accounts: vec![
+                signer(),
+                FuzzAccount::new(selected_key(USER_ACCOUNT_KEY), false, true, true)
+                    .with_lamports(LAMPORTS)
+                    .with_data(vec![0u8; UserAccount::LEN]),
+                rent_sysvar(),
+            ],
+            instruction_data: selector_instruction_data(100),
+        },
+    )
+    .accounts[1]
+        .clone();
+    write_seed(
+        "unpack_user_account",
+        "init_user_account",
+        &user_account.data,
+    );
+
+    process(
+        "migrate_user_account",
+        FuzzInput {
+            accounts: vec![
+                signer(),
+                user_account,
+                system_program(),
+                rent_sysvar(),
+                admin_config(),
+            ],
+            instruction_data: selector_instruction_data(241),
+        },
+    );
+
+    for fixture in FIXTURES.iter() {
+        let name = fixture.trim_end_matches(".txt");
+        let instruction_data = read_fixture(fixture);
+        let tmp_storage_pda_key = find_tmp_storage_pda(
+            &program_id(),
+            &instruction_data[9 + 64..9 + 96],
+            &selected_key(SIGNER_KEY),
+        )
+        .0;
+        let tmp_storage_pda = process(
+            &format!("{}_init", name),
+            FuzzInput {
+                accounts: vec![
+                    signer(),
+                    FuzzAccount::new(tmp_storage_pda_key, false, true, true),
+                    system_program(),
+                    rent_sysvar(),
+                    FuzzAccount::new(find_global_config(&program_id()).0, false, false, false),
+                ],
+                instruction_data,
+            },
+        )
+        .accounts[1]
+            .clone();
+        for target in [
+            "unpack_checks_and_transfer_state",
+            "unpack_instruction_index",
+            "unpack_tmp_storage_pda",
+            "unpack_prepare_inputs_state",
+            "unpack_miller_loop_state",
+            "unpack_final_exponentiation_state",
+        ]
+        .iter()
+        {
+            write_seed(target, name, &tmp_storage_pda.data);
+        }
+
+        let tmp_storage_pda = process(
+            &format!("{}_root_check", name),
+            FuzzInput {
+                accounts: vec![signer(), tmp_storage_pda, merkle_tree.clone()],
+                instruction_data: compute_instruction_data(0),
+            },
+        )
+        .accounts[1]
+            .clone();
+        process(
+            &format!("{}_prepare_inputs", name),
+            FuzzInput {
+                accounts: vec![signer(), tmp_storage_pda],
+                instruction_data: compute_instruction_data(1),
+            },
+        );
+    }
+
+    write_seed("unpack_nullifier_state", "uninitialized", &[0, 0]);
+    write_seed("unpack_nullifier_state", "spent", &[1, 3]);
+    let mut two_leaves_bytes_pda = vec![0u8; TwoLeavesBytesPda::LEN];
+    write_seed(
+        "unpack_two_leaves_bytes_pda",
+        "uninitialized",
+        &two_leaves_bytes_pda,
+    );
+    two_leaves_bytes_pda[0] = 1;
+    two_leaves_bytes_pda[1] = 4;
+    write_seed(
+        "unpack_two_leaves_bytes_pda",
+        "initialized",
+        &two_leaves_bytes_pda,
+    );
+}
@@ -0,0 +1,385 @@
+// Shared helpers for the fuzz targets in fuzz_targets/ and the seed corpus generator.
+use light_protocol_program::poseidon_merkle_tree::state::MerkleTree;
+use light_protocol_program::utils::config::{
+    MERKLE_TREE_ACC_BYTES_ARRAY, MERKLE_TREE_INIT_AUTHORITY,
+};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    entrypoint::{ProgramResult, MAX_PERMITTED_DATA_INCREASE, SUCCESS},
+    instruction::Instruction,
+    program_error::ProgramError,
+    program_pack::Pack,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+    system_program,
+    sysvar::{self, rent::Rent},
+};
+use std::str::FromStr;
+use std::sync::Once;
+
+// Same program id as the program tests.
+pub const PROGRAM_ID: &str = "TransferLamports111111111111111111112111111";
+
+pub fn program_id() -> Pubkey {
+    Pubkey::from_str(PROGRAM_ID).unwrap()
+}
+
+struct FuzzSyscallStubs;
+
+impl SyscallStubs for FuzzSyscallStubs {
+    // msg! is called in almost every instruction, printing slows down fuzzing by orders of magnitude.
+    fn sol_log(&self, _message: &str) {}
+
+    fn sol_log_compute_units(&self) {}
+
+    // System program instructions the program invokes are applied to the mocked accounts,
+    // all other cross-program invocations succeed without effect.
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        _signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        if instruction.program_id != system_program::id() || instruction.accounts.len() < 2 {
+            return Ok(());
+        }
+        let find_account_info = |key: &Pubkey| {
+            account_infos
+                .iter()
+                .find(|account_info| account_info.key == key)
+                .ok_or(ProgramError::NotEnoughAccountKeys)
+        };
+        let from = find_account_info(&instruction.accounts[0].pubkey)?;
+        let to = find_account_info(&instruction.accounts[1].pubkey)?;
+        let data = &instruction.data;
+        match read_u32(data, 0) {
+            Some(CREATE_ACCOUNT) => {
+                let lamports = read_u64(data, 4).ok_or(ProgramError::InvalidInstructionData)?;
+                let space = read_u64(data, 12).ok_or(ProgramError::InvalidInstructionData)?;
+                if to.data_len() != 0 || space as usize > SPARE_DATA_LEN {
+                    return Err(ProgramError::InvalidArgument);
+                }
+                transfer_lamports(from, to, lamports)?;
+                to.realloc(space as usize, true)
+            }
+            Some(TRANSFER) => {
+                let lamports = read_u64(data, 4).ok_or(ProgramError::InvalidInstructionData)?;
+                transfer_lamports(from, to, lamports)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    // The default stub does not support sysvars, a default clock reaches the Merkle tree lock.
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        unsafe {
+            std::ptr::write_unaligned(var_addr as *mut Clock, Clock::default());
+        }
+        SUCCESS
+    }
+}
+
+// Bincode enum tags of SystemInstruction.
+const CREATE_ACCOUNT: u32 = 0;
+const TRANSFER: u32 = 2;
+
+fn read_u32(data: &[u8], offset: usize) -> Option<u32> {
+    let mut bytes = [0u8; 4];
+    bytes.copy_from_slice(data.get(offset..offset + 4)?);
+    Some(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(data: &[u8], offset: usize) -> Option<u64> {
+    let mut bytes = [0u8; 8];
+    bytes.copy_from_slice(data.get(offset..offset + 8)?);
+    Some(u64::from_le_bytes(bytes))
+}
+
+fn transfer_lamports(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+    if from.key == to.key {
+        return Ok(());
+    }
+    let from_lamports = from
+        .lamports()
+        .checked_sub(lamports)
+        .ok_or(ProgramError::InsufficientFunds)?;
+    let to_lamports = to
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(ProgramError::InvalidArgument)?;
+    **from.lamports.borrow_mut() = from_lamports;
+    **to.lamports.borrow_mut() = to_lamports;
+    Ok(())
+}
+
+static SYSCALL_STUBS: Once = Once::new();
+
+pub fn set_fuzz_syscall_stubs() {
+    SYSCALL_STUBS.call_once(|| {
+        set_syscall_stubs(Box::new(FuzzSyscallStubs));
+    });
+}
+
+// Unpacks data as account T.
+// Pack::unpack_unchecked is expected to reject data of the wrong length,
+// data of the packed length has to unpack into an error or a value which packs again.
+pub fn fuzz_unpack<T: Pack>(data: &[u8]) {
+    set_fuzz_syscall_stubs();
+    let _ = T::unpack_unchecked(data);
+
+    let mut input = data.to_vec();
+    input.resize(T::LEN, 0);
+    if let Ok(unpacked) = T::unpack_from_slice(&input) {
+        unpacked.pack_into_slice(&mut input);
+    }
+}
+
+// Keys the fuzzer can select with one byte, any other selector is expanded to [selector; 32].
+pub const SIGNER_KEY: u8 = 0;
+pub const SYSTEM_PROGRAM_KEY: u8 = 1;
+pub const RENT_SYSVAR_KEY: u8 = 2;
+pub const MERKLE_TREE_KEY: u8 = 3;
+pub const MERKLE_TREE_TOKEN_KEY: u8 = 4;
+pub const AUTHORITY_KEY: u8 = 5;
+pub const TOKEN_PROGRAM_KEY: u8 = 6;
+pub const EXPLICIT_KEY: u8 = 0xff;
+
+const IS_SIGNER: u8 = 1;
+const IS_WRITABLE: u8 = 1 << 1;
+const IS_PROGRAM_OWNED: u8 = 1 << 2;
+
+const MAX_ACCOUNTS: u8 = 16;
+
+// The host realloc does not enforce MAX_PERMITTED_DATA_INCREASE,
+// every account leaves room to grow to the largest account type.
+const SPARE_DATA_LEN: usize = MAX_PERMITTED_DATA_INCREASE + MerkleTree::LEN;
+
+#[derive(Clone, Debug)]
+pub struct FuzzAccount {
+    pub key: Pubkey,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub is_program_owned: bool,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+}
+
+impl FuzzAccount {
+    pub fn new(key: Pubkey, is_signer: bool, is_writable: bool, is_program_owned: bool) -> Self {
+        FuzzAccount {
+            key,
+            is_signer,
+            is_writable,
+            is_program_owned,
+            lamports: 0,
+            data: Vec::new(),
+        }
+    }
+
+    pub fn with_lamports(mut self, lamports: u64) -> Self {
+        self.lamports = lamports;
+        self
+    }
+
+    pub fn with_data(mut self, data: Vec<u8>) -> Self {
+        self.data = data;
+        self
+    }
+
+    fn owner(&self) -> Pubkey {
+        if self.key == sysvar::rent::id() {
+            sysvar::id()
+        } else if self.is_program_owned {
+            program_id()
+        } else {
+            system_program::id()
+        }
+    }
+}
+
+pub fn selected_key(selector: u8) -> Pubkey {
+    match selector {
+        SIGNER_KEY => Pubkey::new(&MERKLE_TREE_INIT_AUTHORITY),
+        SYSTEM_PROGRAM_KEY => system_program::id(),
+        RENT_SYSVAR_KEY => sysvar::rent::id(),
+        MERKLE_TREE_KEY => Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0),
+        MERKLE_TREE_TOKEN_KEY => Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1),
+        AUTHORITY_KEY => Pubkey::find_program_address(&[&program_id().to_bytes()], &program_id()).0,
+        TOKEN_PROGRAM_KEY => spl_token_id(),
+        _ => Pubkey::new_from_array([selector; 32]),
+    }
+}
+
+fn spl_token_id() -> Pubkey {
+    Pubkey::from_str("TokenkegQfeZyiNwAJbNbGqPpXZgAuYvYtp5Zu5C3Ff3").unwrap()
+}
+
+// The runtime guarantees the content of the rent sysvar account.
+fn rent_sysvar_data() -> Vec<u8> {
+    let rent = Rent::default();
+    [
+        &rent.lamports_per_byte_year.to_le_bytes()[..],
+        &rent.exemption_threshold.to_le_bytes()[..],
+        &[rent.burn_percent][..],
+    ]
+    .concat()
+}
+
+// Input of the process_instruction target:
+// [number of accounts: u8, accounts.., instruction data..]
+// every account is encoded as
+// [flags: u8, key selector: u8, (key: [u8; 32] if selector is EXPLICIT_KEY), lamports: u64, data len: u16, data..]
+#[derive(Clone, Debug)]
+pub struct FuzzInput {
+    pub accounts: Vec<FuzzAccount>,
+    pub instruction_data: Vec<u8>,
+}
+
+impl FuzzInput {
+    pub fn decode(input: &[u8]) -> Option<Self> {
+        let (number_of_accounts, mut rest) = input.split_first()?;
+        let mut accounts = Vec::new();
+        for _ in 0..number_of_accounts % (MAX_ACCOUNTS + 1) {
+            let (flags, remainder) = rest.split_first()?;
+            let (selector, remainder) = remainder.split_first()?;
+            let (key, remainder) = if *selector == EXPLICIT_KEY {
+                if remainder.len() < 32 {
+                    return None;
+                }
+                let (key, remainder) = remainder.split_at(32);
+                (Pubkey::new(key), remainder)
+            } else {
+                (selected_key(*selector), remainder)
+            };
+            if remainder.len() < 10 {
+                return None;
+            }
+            let (lamports, remainder) = remainder.split_at(8);
+            let (data_len, remainder) = remainder.split_at(2);
+            let data_len = usize::from(u16::from_le_bytes([data_len[0], data_len[1]]));
+            if remainder.len() < data_len {
+                return None;
+            }
+            let (data, remainder) = remainder.split_at(data_len);
+            let mut lamports_bytes = [0u8; 8];
+            lamports_bytes.copy_from_slice(lamports);
+
+            let mut account = FuzzAccount::new(
+                key,
+                flags & IS_SIGNER != 0,
+                flags & IS_WRITABLE != 0,
+                flags & IS_PROGRAM_OWNED != 0,
+            )
+            .with_lamports(u64::from_le_bytes(lamports_bytes))
+            .with_data(data.to_vec());
+            if key == sysvar::rent::id() {
+                account.data = rent_sysvar_data();
+            }
+            accounts.push(account);
+            rest = remainder;
+        }
+        Some(FuzzInput {
+            accounts,
+            instruction_data: rest.to_vec(),
+        })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut output = vec![self.accounts.len() as u8];
+        for account in self.accounts.iter() {
+            let mut flags = 0u8;
+            if account.is_signer {
+                flags |= IS_SIGNER;
+            }
+            if account.is_writable {
+                flags |= IS_WRITABLE;
+            }
+            if account.is_program_owned {
+                flags |= IS_PROGRAM_OWNED;
+            }
+            output.push(flags);
+            match (0..EXPLICIT_KEY).find(|selector| selected_key(*selector) == account.key) {
+                Some(selector) => output.push(selector),
+                None => {
+                    output.push(EXPLICIT_KEY);
+                    output.extend_from_slice(&account.key.to_bytes());
+                }
+            }
+            output.extend_from_slice(&account.lamports.to_le_bytes());
+            let data: &[u8] = if account.key == sysvar::rent::id() {
+                &[]
+            } else {
+                &account.data
+            };
+            output.extend_from_slice(&(data.len() as u16).to_le_bytes());
+            output.extend_from_slice(data);
+        }
+        output.extend_from_slice(&self.instruction_data);
+        output
+    }
+
+    // Runs process_instruction against mocked AccountInfos and writes lamports
+    // and data of the accounts back into self.
+    pub fn process(&mut self) -> ProgramResult {
+        set_fuzz_syscall_stubs();
+        let program_id = program_id();
+        let owners: Vec<Pubkey> = self.accounts.iter().map(|a| a.owner()).collect();
+        // Account data is laid out like in the BPF loader input, the data length is stored in
+        // the 8 bytes in front of the data which AccountInfo::realloc writes to.
+        let mut buffers: Vec<Vec<u8>> = self
+            .accounts
+            .iter()
+            .map(|account| {
+                let mut buffer = vec![0u8; 8 + account.data.len() + SPARE_DATA_LEN];
+                buffer[..8].copy_from_slice(&(account.data.len() as u64).to_le_bytes());
+                buffer[8..8 + account.data.len()].copy_from_slice(&account.data);
+                buffer
+            })
+            .collect();
+        let mut lamports: Vec<u64> = self.accounts.iter().map(|a| a.lamports).collect();
+
+        let result = {
+            let account_infos: Vec<AccountInfo> = self
+                .accounts
+                .iter()
+                .zip(owners.iter())
+                .zip(buffers.iter_mut())
+                .zip(lamports.iter_mut())
+                .map(|(((account, owner), buffer), lamports)| {
+                    let data_len = account.data.len();
+                    AccountInfo::new(
+                        &account.key,
+                        account.is_signer,
+                        account.is_writable,
+                        lamports,
+                        &mut buffer[8..8 + data_len],
+                        owner,
+                        false,
+                        0,
+                    )
+                })
+                .collect();
+            let result = light_protocol_program::process_instruction(
+                &program_id,
+                &account_infos,
+                &self.instruction_data,
+            );
+            let data_lens: Vec<usize> = account_infos.iter().map(|a| a.data_len()).collect();
+            drop(account_infos);
+            for ((account, buffer), data_len) in self
+                .accounts
+                .iter_mut()
+                .zip(buffers.iter())
+                .zip(data_lens.into_iter())
+            {
+                account.data = buffer[8..8 + data_len].to_vec();
+            }
+            result
+        };
+        for (account, lamports) in self.accounts.iter_mut().zip(lamports.into_iter()) {
+            account.lamports = lamports;
+        }
+        result
+    }
+}
@@ -0,0 +1,314 @@
+use crate::error::LightError;
+use crate::instructions::create_and_check_pda;
+use crate::utils::account_header::check_account_header;
+use crate::utils::config::{
+    ADMIN_CONFIG_ACCOUNT_TYPE, ADMIN_CONFIG_ACCOUNT_VERSION, MERKLE_TREE_INIT_AUTHORITY,
+};
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    account_info::AccountInfo,
+    msg,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+    sysvar::rent::Rent,
+};
+
+// Admin authority of the program, checked by all admin instructions (selectors 240-244, 246, 248).
+// The admin config pda (derived from b"admin") stores the authority. Until it is created the
+// authority is MERKLE_TREE_INIT_AUTHORITY.
+// - The authority is transferred in two steps, the admin proposes a new authority
+//   and the new authority accepts, a mistyped key can't lock out the admin.
+// - With a threshold > 0 the admin is a multisig, threshold of the signers have to sign
+//   admin instructions, the authority key alone is not sufficient. The admin instruction
+//   signer counts, the other signers are passed after the accounts of the instruction.
+//   Accepting a new authority disables the multisig.
+pub const ADMIN_CONFIG_SEED: &[u8] = b"admin";
+pub const MAX_ADMIN_SIGNERS: usize = 7;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminConfig {
+    pub is_initialized: bool,
+    pub account_type: u8,
+    pub authority: Pubkey,
+    // Pubkey::default() if no transfer is pending.
+    pub pending_authority: Pubkey,
+    pub threshold: u8,
+    pub signers: Vec<Pubkey>,
+}
+
+impl Sealed for AdminConfig {}
+impl IsInitialized for AdminConfig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for AdminConfig {
+    const LEN: usize = 292;
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, AdminConfig::LEN];
+        let (version, account_type, authority, pending_authority, threshold, signers_len, signers) =
+            array_refs![input, 1, 1, 32, 32, 1, 1, 32 * MAX_ADMIN_SIGNERS];
+        check_account_header(version[0], account_type[0], ADMIN_CONFIG_ACCOUNT_TYPE)?;
+        let signers_len = usize::from(signers_len[0]);
+        if signers_len > MAX_ADMIN_SIGNERS || usize::from(threshold[0]) > signers_len {
+            msg!("Invalid admin multisig.");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(AdminConfig {
+            is_initialized: true,
+            account_type: ADMIN_CONFIG_ACCOUNT_TYPE,
+            authority: Pubkey::new(authority),
+            pending_authority: Pubkey::new(pending_authority),
+            threshold: threshold[0],
+            signers: signers
+                .chunks(32)
+                .take(signers_len)
+                .map(Pubkey::new)
+                .collect(),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, AdminConfig::LEN];
+        let (
+            version_dst,
+            account_type_dst,
+            authority_dst,
+            pending_authority_dst,
+            threshold_dst,
+            signers_len_dst,
+            signers_dst,
+        ) = mut_array_refs![dst, 1, 1, 32, 32, 1, 1, 32 * MAX_ADMIN_SIGNERS];
+        *version_dst = [ADMIN_CONFIG_ACCOUNT_VERSION];
+        *account_type_dst = [ADMIN_CONFIG_ACCOUNT_TYPE];
+        *authority_dst = self.authority.to_bytes();
+        *pending_authority_dst = self.pending_authority.to_bytes();
+        *threshold_dst = [self.threshold];
+        *signers_len_dst = [self.signers.len() as u8];
+        *signers_dst = [0u8; 32 * MAX_ADMIN_SIGNERS];
+        for (signer_dst, signer) in signers_dst.chunks_mut(32).zip(self.signers.iter()) {
+            signer_dst.copy_from_slice(&signer.to_bytes());
+        }
+    }
+}
+
+impl AdminConfig {
+    // Checks that the admin signed, signer_account is the signer of the admin instruction
+    // (checked in process_instruction) and co_signers are the remaining accounts of the instruction.
+    pub fn check_admin(
+        &self,
+        signer_account: &AccountInfo,
+        co_signers: &[AccountInfo],
+    ) -> Result<(), ProgramError> {
+        if self.threshold == 0 {
+            if *signer_account.key != self.authority {
+                msg!("Signer is not the admin authority.");
+                return Err(LightError::InvalidAuthority.into());
+            }
+            return Ok(());
+        }
+        // Every multisig signer is counted once.
+        let signed = self
+            .signers
+            .iter()
+            .filter(|signer| {
+                signer_account.key == *signer
+                    || co_signers
+                        .iter()
+                        .any(|account| account.key == *signer && account.is_signer)
+            })
+            .count();
+        if signed < usize::from(self.threshold) {
+            msg!(
+                "{} of {} admin signers signed, threshold {}.",
+                signed,
+                self.signers.len(),
+                self.threshold
+            );
+            return Err(LightError::NotEnoughAdminSigners.into());
+        }
+        Ok(())
+    }
+}
+
+pub fn find_admin_config(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[ADMIN_CONFIG_SEED], program_id)
+}
+
+// Returns None if the admin config was not created yet.
+pub fn load_admin_config(
+    program_id: &Pubkey,
+    admin_config_account: &AccountInfo,
+) -> Result<Option<AdminConfig>, ProgramError> {
+    if *admin_config_account.key != find_admin_config(program_id).0 {
+        msg!("Passed-in admin config is invalid.");
+        return Err(LightError::InvalidPdaAddress.into());
+    }
+    if *admin_config_account.owner != *program_id || admin_config_account.data_is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(AdminConfig::unpack(
+        &admin_config_account.data.borrow(),
+    )?))
+}
+
+// Checks the signers of an admin instruction against the admin config,
+// without admin config the signer has to be MERKLE_TREE_INIT_AUTHORITY.
+pub fn check_admin(
+    program_id: &Pubkey,
+    admin_config_account: &AccountInfo,
+    signer_account: &AccountInfo,
+    co_signers: &[AccountInfo],
+) -> Result<(), ProgramError> {
+    match load_admin_config(program_id, admin_config_account)? {
+        Some(admin_config) => admin_config.check_admin(signer_account, co_signers),
+        None => {
+            if *signer_account.key != Pubkey::new(&MERKLE_TREE_INIT_AUTHORITY) {
+                msg!("Signer is not merkle tree init authority.");
+                return Err(LightError::InvalidAuthority.into());
+            }
+            Ok(())
+        }
+    }
+}
+
+// Loads the admin config and checks the admin, creates the admin config with
+// MERKLE_TREE_INIT_AUTHORITY as authority if it does not exist yet.
+fn load_or_create_admin_config<'a, 'b>(
+    program_id: &Pubkey,
+    signer_account: &'a AccountInfo<'b>,
+    admin_config_account: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    rent: &Rent,
+    co_signers: &[AccountInfo],
+) -> Result<AdminConfig, ProgramError> {
+    check_admin(program_id, admin_config_account, signer_account, co_signers)?;
+    match load_admin_config(program_id, admin_config_account)? {
+        Some(admin_config) => Ok(admin_config),
+        None => {
+            msg!("Creating admin config.");
+            create_and_check_pda(
+                program_id,
+                signer_account,
+                admin_config_account,
+                system_program,
+                rent,
+                &[],
+                ADMIN_CONFIG_SEED,
+                AdminConfig::LEN as u64, //bytes
+                0,                       //lamports
+                true,                    //rent_exempt
+            )?;
+            Ok(AdminConfig {
+                is_initialized: true,
+                account_type: ADMIN_CONFIG_ACCOUNT_TYPE,
+                authority: Pubkey::new(&MERKLE_TREE_INIT_AUTHORITY),
+                pending_authority: Pubkey::default(),
+                threshold: 0,
+                signers: Vec::new(),
+            })
+        }
+    }
+}
+
+// First step of the authority transfer, called by the admin.
+// Replaces a pending authority, Pubkey::default() cancels the transfer.
+// instruction_data: [pending_authority]
+pub fn propose_authority<'a, 'b>(
+    program_id: &Pubkey,
+    signer_account: &'a AccountInfo<'b>,
+    admin_config_account: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    rent: &Rent,
+    co_signers: &[AccountInfo],
+    instruction_data: &[u8],
+) -> Result<(), ProgramError> {
+    let pending_authority = instruction_data
+        .get(..32)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let mut admin_config = load_or_create_admin_config(
+        program_id,
+        signer_account,
+        admin_config_account,
+        system_program,
+        rent,
+        co_signers,
+    )?;
+    admin_config.pending_authority = Pubkey::new(pending_authority);
+    msg!("Proposed authority {:?}", admin_config.pending_authority);
+    AdminConfig::pack_into_slice(&admin_config, &mut admin_config_account.data.borrow_mut());
+    Ok(())
+}
+
+// Second step of the authority transfer, called by the proposed authority.
+pub fn accept_authority(
+    program_id: &Pubkey,
+    signer_account: &AccountInfo,
+    admin_config_account: &AccountInfo,
+) -> Result<(), ProgramError> {
+    let mut admin_config = load_admin_config(program_id, admin_config_account)?
+        .ok_or(ProgramError::UninitializedAccount)?;
+    if admin_config.pending_authority == Pubkey::default()
+        || *signer_account.key != admin_config.pending_authority
+    {
+        msg!("Signer is not the proposed authority.");
+        return Err(LightError::InvalidAuthority.into());
+    }
+    admin_config.authority = admin_config.pending_authority;
+    admin_config.pending_authority = Pubkey::default();
+    admin_config.threshold = 0;
+    admin_config.signers = Vec::new();
+    msg!("Accepted authority {:?}", admin_config.authority);
+    AdminConfig::pack_into_slice(&admin_config, &mut admin_config_account.data.borrow_mut());
+    Ok(())
+}
+
+// Sets the admin multisig, called by the admin. A threshold of 0 disables the multisig.
+// instruction_data: [threshold, number of signers, signers]
+pub fn set_admin_multisig<'a, 'b>(
+    program_id: &Pubkey,
+    signer_account: &'a AccountInfo<'b>,
+    admin_config_account: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    rent: &Rent,
+    co_signers: &[AccountInfo],
+    instruction_data: &[u8],
+) -> Result<(), ProgramError> {
+    let (threshold, signers_len) = match instruction_data {
+        [threshold, signers_len, ..] => (*threshold, usize::from(*signers_len)),
+        _ => return Err(ProgramError::InvalidInstructionData),
+    };
+    let signers: Vec<Pubkey> = instruction_data
+        .get(2..2 + 32 * signers_len)
+        .ok_or(ProgramError::InvalidInstructionData)?
+        .chunks(32)
+        .map(Pubkey::new)
+        .collect();
+    let has_duplicates = signers
+        .iter()
+        .enumerate()
+        .any(|(i, signer)| signers[..i].contains(signer));
+    if signers_len > MAX_ADMIN_SIGNERS || usize::from(threshold) > signers_len || has_duplicates {
+        msg!(
+            "Invalid admin multisig, threshold {} of {} signers.",
+            threshold,
+            signers_len
+        );
+        return Err(LightError::InvalidAdminMultisig.into());
+    }
+    let mut admin_config = load_or_create_admin_config(
+        program_id,
+        signer_account,
+        admin_config_account,
+        system_program,
+        rent,
+        co_signers,
+    )?;
+    admin_config.threshold = threshold;
+    admin_config.signers = signers;
+    AdminConfig::pack_into_slice(&admin_config, &mut admin_config_account.data.borrow_mut());
+    Ok(())
+}
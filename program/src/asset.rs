@@ -0,0 +1,84 @@
+use crate::error::LightError;
+use solana_program::{
+    account_info::AccountInfo, msg, program_error::ProgramError, program_pack::Pack, pubkey::Pubkey,
+};
+
+// Asset id of native SOL, the system program id.
+pub const NATIVE_SOL_ASSET_ID: [u8; 32] = [0u8; 32];
+
+// Every Merkle tree holds exactly one asset, either native SOL or the tokens of one SPL mint.
+// The asset id is the mint pubkey for SPL tokens and NATIVE_SOL_ASSET_ID for SOL.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Asset {
+    Sol,
+    Spl(Pubkey),
+}
+
+impl Asset {
+    pub fn from_asset_id(asset_id: [u8; 32]) -> Self {
+        if asset_id == NATIVE_SOL_ASSET_ID {
+            Asset::Sol
+        } else {
+            Asset::Spl(Pubkey::new_from_array(asset_id))
+        }
+    }
+
+    pub fn asset_id(&self) -> [u8; 32] {
+        match self {
+            Asset::Sol => NATIVE_SOL_ASSET_ID,
+            Asset::Spl(mint) => mint.to_bytes(),
+        }
+    }
+
+    // Checks that token_account holds this asset.
+    // SOL is held directly in program owned accounts (merkle_tree_pda_token),
+    // SPL tokens in token accounts of the mint.
+    pub fn check_pool_account(
+        &self,
+        program_id: &Pubkey,
+        token_account: &AccountInfo,
+    ) -> Result<(), ProgramError> {
+        match self {
+            Asset::Sol => {
+                if *token_account.owner != *program_id {
+                    msg!("Sol pool account is not owned by the program.");
+                    return Err(LightError::InvalidMerkleTreeTokenAccount.into());
+                }
+                Ok(())
+            }
+            Asset::Spl(_) => self.check_token_account(token_account),
+        }
+    }
+
+    // Checks that an spl token account belongs to the mint of this asset.
+    // Sol is transferred to and from system accounts, there is nothing to check.
+    pub fn check_token_account(&self, token_account: &AccountInfo) -> Result<(), ProgramError> {
+        if let Asset::Spl(mint) = self {
+            if *token_account.owner != spl_token::id() {
+                msg!("Token account is not owned by the token program.");
+                return Err(LightError::InvalidTokenAccountMint.into());
+            }
+            let token_account_data =
+                spl_token::state::Account::unpack(&token_account.data.borrow())?;
+            if token_account_data.mint != *mint {
+                msg!(
+                    "Token account mint {:?} != Merkle tree mint {:?}",
+                    token_account_data.mint,
+                    mint
+                );
+                return Err(LightError::InvalidTokenAccountMint.into());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn check_token_program(&self, token_program: &AccountInfo) -> Result<(), ProgramError> {
+        if let Asset::Spl(_) = self {
+            if *token_program.key != spl_token::id() {
+                msg!("Invalid token program {:?}", token_program.key);
+                return Err(ProgramError::IncorrectProgramId);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,176 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+
+use crate::asset::Asset;
+use crate::global_config::find_global_config;
+use crate::instructions::{check_send_data_len, send_data_commitments};
+use crate::merkle_tree_limits::find_merkle_tree_limits;
+use crate::pda::{find_authority_pda, find_leaves_pda, find_nullifier_pda, find_tmp_storage_pda};
+use crate::post_withdraw_call::PostWithdrawCall;
+use crate::protocol_fee::find_protocol_fee_config;
+use crate::relayer_handoff::find_relayer_handoff;
+
+// Instruction builders for programs which shield and unshield funds with cross program invocations.
+//
+// All 1502 instructions of a transaction have to be signed by the same signer. A calling program
+// signs with one of its pdas (invoke_signed). The signer pda pays for the tmp storage, nullifier
+// and leaves pdas and receives the tmp storage lamports when the transaction is complete.
+// - SOL deposits are funded from the signer pda, it has to be a system account without data.
+// - SPL deposits are transferred from user_token, which has to approve the program authority
+//   (pda::find_authority_pda) as delegate for the deposit amount.
+// - Withdrawals are transferred to the recipient committed to in the external data hash,
+//   any account the calling program chooses.
+// - The relayer in the external data hash has to be the signer pda, send data fails otherwise.
+// - The protocol fee of the asset (protocol_fee.rs) is paid from the pool like the relayer fee.
+// - Deposits and withdrawals are subject to the limits of the Merkle tree (merkle_tree_limits.rs).
+// - Send data and the last instruction fail if the program mode (global_config.rs) does not
+//   allow the transaction.
+//
+// Instruction data is bincode encoded like in transactions signed by a relayer, the first byte
+// after the length prefix is the selector.
+
+// Selector of the send data instruction, any byte which is not an admin selector.
+pub const SEND_DATA_SELECTOR: u8 = 0;
+// Compute instructions don't read instruction data, it only makes instructions unique.
+const COMPUTE_DATA_PREFIX: [u8; 2] = [98, 99];
+
+// Transfer of the public amount at the last instruction.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Settlement {
+    // ext_amount > 0. For SOL trees user_token is the escrow pda (pda::find_escrow_pda).
+    Deposit { user_token: Pubkey },
+    // ext_amount == 0.
+    Transfer,
+    // ext_amount < 0.
+    Withdrawal { recipient: Pubkey },
+}
+
+// First instruction, creates the tmp storage pda and stores send_data
+// (public inputs, proof and external data) in it.
+// For withdrawals with a post withdraw call the call commitment is appended to send_data.
+// A relayer hand-off commitment is appended last, the hand-off pda is passed then.
+pub fn send_data_instruction(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    send_data: &[u8],
+) -> Result<Instruction, ProgramError> {
+    check_send_data_len(send_data)?;
+    let tmp_storage_pda = find_tmp_storage_pda(program_id, &send_data[64..96], signer).0;
+    let mut accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new(tmp_storage_pda, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new_readonly(find_global_config(program_id).0, false),
+    ];
+    if let (_, Some(_)) = send_data_commitments(send_data)? {
+        accounts.push(AccountMeta::new(
+            find_relayer_handoff(program_id, &tmp_storage_pda).0,
+            false,
+        ));
+    }
+    Ok(Instruction::new_with_bincode(
+        *program_id,
+        &[&[SEND_DATA_SELECTOR][..], send_data].concat(),
+        accounts,
+    ))
+}
+
+// Root check, proof verification and Merkle tree update instructions (1..1501).
+// Every instruction increments the current instruction index of the tmp storage pda.
+// The Merkle tree is only read by the root check and the Merkle tree update,
+// verification instructions ignore it. nonce is only used to make instructions unique.
+pub fn compute_instruction(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    tmp_storage_pda: &Pubkey,
+    merkle_tree: &Pubkey,
+    nonce: u16,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        *program_id,
+        &[&COMPUTE_DATA_PREFIX[..], &nonce.to_le_bytes()[..]].concat(),
+        vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(*tmp_storage_pda, false),
+            AccountMeta::new(*merkle_tree, false),
+        ],
+    )
+}
+
+// Last instruction (1501), inserts nullifiers and leaves, transfers the public amount,
+// the relayer fee and the protocol fee and closes the tmp storage pda.
+// relayer_token is required if the relayer fee is not 0, treasury if the asset has a
+// protocol fee, post_withdraw_call if a call commitment was sent with the send data.
+// The user token (recipient) and the relayer token slots are always passed, the signer fills
+// unused slots so the post withdraw call accounts start at a fixed position.
+pub fn last_instruction(
+    program_id: &Pubkey,
+    signer: &Pubkey,
+    tmp_storage_pda: &Pubkey,
+    nullifiers: &[[u8; 32]; 2],
+    merkle_tree: &Pubkey,
+    merkle_tree_token: &Pubkey,
+    asset: &Asset,
+    settlement: Settlement,
+    relayer_token: Option<&Pubkey>,
+    treasury: Option<&Pubkey>,
+    post_withdraw_call: Option<&PostWithdrawCall>,
+) -> Result<Instruction, ProgramError> {
+    let protocol_fee_config = find_protocol_fee_config(program_id, asset).0;
+    // The treasury is not read without protocol fee, the signer is passed instead.
+    let treasury = treasury.copied().unwrap_or(*signer);
+    let mut accounts = vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new(*tmp_storage_pda, false),
+        AccountMeta::new(find_leaves_pda(program_id, &nullifiers[0]).0, false),
+        AccountMeta::new(find_nullifier_pda(program_id, &nullifiers[0]).0, false),
+        AccountMeta::new(find_nullifier_pda(program_id, &nullifiers[1]).0, false),
+        AccountMeta::new(*merkle_tree, false),
+        AccountMeta::new(*merkle_tree_token, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(spl_token::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+        AccountMeta::new(find_authority_pda(program_id).0, false),
+        AccountMeta::new_readonly(protocol_fee_config, false),
+        AccountMeta::new(treasury, false),
+        AccountMeta::new(find_merkle_tree_limits(program_id, merkle_tree).0, false),
+        AccountMeta::new_readonly(find_global_config(program_id).0, false),
+    ];
+    let user_token = match settlement {
+        Settlement::Deposit { user_token } => user_token,
+        Settlement::Transfer => *signer,
+        Settlement::Withdrawal { recipient } => recipient,
+    };
+    accounts.push(AccountMeta::new(user_token, false));
+    accounts.push(AccountMeta::new(
+        relayer_token.copied().unwrap_or(*signer),
+        false,
+    ));
+    let mut data = vec![21u8];
+    if let Some(post_withdraw_call) = post_withdraw_call {
+        let call = post_withdraw_call.instruction();
+        accounts.push(AccountMeta::new_readonly(call.program_id, false));
+        accounts.extend(call.accounts);
+        data.extend(post_withdraw_call.try_to_vec()?);
+    }
+    Ok(Instruction::new_with_bincode(*program_id, &data, accounts))
+}
+
+// Invokes an instruction built by the functions above with the calling program's signer pda.
+// account_infos has to contain the program account and all accounts of the instruction.
+pub fn invoke_transact_signed(
+    instruction: &Instruction,
+    account_infos: &[AccountInfo],
+    signer_seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(instruction, account_infos, &[signer_seeds])
+}
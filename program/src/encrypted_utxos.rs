@@ -0,0 +1,77 @@
+use crate::error::LightError;
+use crate::utils::config::ENCRYPTED_UTXOS_LENGTH;
+use solana_program::{msg, program_error::ProgramError};
+use std::ops::Range;
+
+// Envelope of the encrypted utxos (ENCRYPTED_UTXOS_LENGTH bytes) of the two outputs of a
+// transaction, stored in the leaves pda and hashed into the tx integrity hash.
+// Version 1, x25519 + xsalsa20-poly1305 (NaCl box):
+// - [0] version
+// - [1..33] ephemeral x25519 public key, shared by both outputs
+// - [33..113] output 0, nonce (24 bytes) and ciphertext (56 bytes)
+// - [113..193] output 1, nonce (24 bytes) and ciphertext (56 bytes)
+// - [193..222] zero padding
+// The plaintext of an output is [amount (u64 le), blinding (32 bytes le)], the ciphertext
+// is the plaintext and the poly1305 tag. Outputs are encrypted to the encryption key of
+// their owner with a random nonce, the ephemeral key is generated per transaction.
+// Output 0 is the utxo of leaf_left, output 1 the utxo of leaf_right.
+//
+// Merkle trees whitelisted before the envelope store unversioned encrypted utxos,
+// every tree has an encrypted utxos version (MERKLE_TREE_ENCRYPTED_UTXOS_VERSION_ARRAY)
+// which is checked at the send data instruction.
+pub const UNVERSIONED_ENCRYPTED_UTXOS: u8 = 0;
+pub const ENCRYPTED_UTXOS_VERSION_1: u8 = 1;
+
+pub const ENCRYPTED_UTXO_PLAINTEXT_LEN: usize = 8 + 32;
+pub const ENCRYPTED_UTXO_NONCE_LEN: usize = 24;
+pub const ENCRYPTED_UTXO_CIPHERTEXT_LEN: usize = ENCRYPTED_UTXO_PLAINTEXT_LEN + 16;
+pub const ENCRYPTED_UTXO_LEN: usize = ENCRYPTED_UTXO_NONCE_LEN + ENCRYPTED_UTXO_CIPHERTEXT_LEN;
+pub const EPHEMERAL_PUBKEY_RANGE: Range<usize> = 1..33;
+pub const ENCRYPTED_UTXOS_PADDING_START: usize = 33 + 2 * ENCRYPTED_UTXO_LEN;
+
+// Bytes of output 0 or 1, nonce and ciphertext.
+pub fn encrypted_utxo_range(output: usize) -> Range<usize> {
+    let start = EPHEMERAL_PUBKEY_RANGE.end + output * ENCRYPTED_UTXO_LEN;
+    start..start + ENCRYPTED_UTXO_LEN
+}
+
+// Checks the envelope header of encrypted utxos sent to a Merkle tree with version.
+// Unversioned encrypted utxos are not checked.
+pub fn check_encrypted_utxos_version(
+    version: u8,
+    encrypted_utxos: &[u8],
+) -> Result<(), ProgramError> {
+    if encrypted_utxos.len() != ENCRYPTED_UTXOS_LENGTH {
+        msg!(
+            "Encrypted utxos length {} != {}",
+            encrypted_utxos.len(),
+            ENCRYPTED_UTXOS_LENGTH
+        );
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    match version {
+        UNVERSIONED_ENCRYPTED_UTXOS => Ok(()),
+        ENCRYPTED_UTXOS_VERSION_1 => {
+            if encrypted_utxos[0] != ENCRYPTED_UTXOS_VERSION_1 {
+                msg!(
+                    "Encrypted utxos version {} != {}",
+                    encrypted_utxos[0],
+                    ENCRYPTED_UTXOS_VERSION_1
+                );
+                return Err(LightError::InvalidEncryptedUtxosVersion.into());
+            }
+            if encrypted_utxos[ENCRYPTED_UTXOS_PADDING_START..]
+                .iter()
+                .any(|byte| *byte != 0)
+            {
+                msg!("Encrypted utxos padding is not zero.");
+                return Err(LightError::InvalidEncryptedUtxosVersion.into());
+            }
+            Ok(())
+        }
+        _ => {
+            msg!("Unknown encrypted utxos version {}.", version);
+            Err(LightError::InvalidEncryptedUtxosVersion.into())
+        }
+    }
+}
@@ -0,0 +1,139 @@
+use num_derive::FromPrimitive;
+use num_traits::FromPrimitive;
+use solana_program::{
+    decode_error::DecodeError,
+    msg,
+    program_error::{PrintProgramError, ProgramError},
+};
+use thiserror::Error;
+
+// Errors returned by the Light Protocol program as ProgramError::Custom(code).
+// Codes are stable, new variants are appended with the next free code.
+#[derive(Clone, Copy, Debug, Eq, Error, FromPrimitive, PartialEq)]
+pub enum LightError {
+    #[error("Signer account has to be passed in first place.")]
+    MissingSigner = 0,
+    #[error("Signer is not the authority.")]
+    InvalidAuthority = 1,
+    #[error("Signer is not the signer which started the computation.")]
+    InvalidSigner = 2,
+    #[error("Account is not owned by the program.")]
+    InvalidAccountOwner = 3,
+    #[error("Account has the wrong account type.")]
+    InvalidAccountType = 4,
+    #[error("Account layout version is outdated, the account needs to be migrated.")]
+    OutdatedAccountVersion = 5,
+    #[error("Account layout version is not supported.")]
+    UnsupportedAccountVersion = 6,
+    #[error("Instruction index is not valid for this instruction.")]
+    InvalidInstructionIndex = 7,
+    #[error("Computation has already finished.")]
+    ComputationFinished = 8,
+    #[error("Root was not found in the Merkle tree root history.")]
+    RootNotFound = 9,
+    #[error("Root history size is too large.")]
+    InvalidRootHistorySize = 10,
+    #[error("Merkle tree account is invalid.")]
+    InvalidMerkleTree = 11,
+    #[error("Merkle tree token account is invalid.")]
+    InvalidMerkleTreeTokenAccount = 12,
+    #[error("Merkle tree is locked by another transaction.")]
+    MerkleTreeLocked = 13,
+    #[error("Merkle tree is full.")]
+    MerkleTreeFull = 14,
+    #[error("Merkle tree initialization failed.")]
+    MerkleTreeInitFailed = 15,
+    #[error("Nullifier has already been spent.")]
+    NullifierAlreadySpent = 16,
+    #[error("Passed-in pda does not match the derived pda.")]
+    InvalidPdaAddress = 17,
+    #[error("Tx integrity hash verification failed.")]
+    InvalidTxIntegrityHash = 18,
+    #[error("Recipient is not the address specified in the tx integrity hash.")]
+    InvalidRecipient = 19,
+    #[error("Relayer is not the address specified in the tx integrity hash.")]
+    InvalidRelayer = 20,
+    #[error("Public amount is too large.")]
+    PublicAmountTooLarge = 21,
+    #[error("External amount does not match public amount and relayer fee for deposit.")]
+    InvalidDepositAmount = 22,
+    #[error("External amount does not match public amount and relayer fee for withdrawal.")]
+    InvalidWithdrawalAmount = 23,
+    #[error("Arithmetic overflow.")]
+    ArithmeticOverflow = 24,
+    #[error("Zero-knowledge proof verification failed.")]
+    ProofVerificationFailed = 25,
+    #[error("Verifying key is incompatible with the public inputs.")]
+    IncompatibleVerifyingKey = 26,
+    #[error("Account type cannot be migrated.")]
+    AccountNotMigratable = 27,
+    #[error("Account is already at the current layout version.")]
+    AccountAlreadyCurrentVersion = 28,
+    #[error("No layout upgrade exists for this account version.")]
+    MissingLayoutUpgrade = 29,
+    #[error("Token account does not hold the asset of the Merkle tree.")]
+    InvalidTokenAccountMint = 30,
+    #[error("Post withdraw call does not match the call in the tx integrity hash.")]
+    InvalidPostWithdrawCall = 31,
+    #[error("Protocol fee config is invalid.")]
+    InvalidProtocolFeeConfig = 32,
+    #[error("Treasury is not the treasury of the protocol fee config.")]
+    InvalidTreasury = 33,
+    #[error("Deposit exceeds the maximum deposit of the Merkle tree.")]
+    DepositLimitExceeded = 34,
+    #[error("Deposit exceeds the total deposits cap of the Merkle tree.")]
+    TotalDepositsLimitExceeded = 35,
+    #[error("Withdrawal exceeds the withdrawal limit of the current epoch.")]
+    WithdrawalLimitExceeded = 36,
+    #[error("Transaction is not allowed in the current program mode.")]
+    TransactionNotAllowed = 37,
+    #[error("Signer is not the guardian of the global config.")]
+    InvalidGuardian = 38,
+    #[error("Not enough admin multisig signers signed.")]
+    NotEnoughAdminSigners = 39,
+    #[error("Admin multisig threshold or signers are invalid.")]
+    InvalidAdminMultisig = 40,
+    #[error("Signer is not allowed to take over the computation.")]
+    RelayerHandoffNotAllowed = 41,
+    #[error("Encrypted utxos do not have the version of the Merkle tree.")]
+    InvalidEncryptedUtxosVersion = 42,
+    #[error("Bytes are not a canonical field element.")]
+    InvalidFieldElement = 43,
+}
+
+impl From<LightError> for ProgramError {
+    fn from(e: LightError) -> Self {
+        ProgramError::Custom(e as u32)
+    }
+}
+
+impl<T> DecodeError<T> for LightError {
+    fn type_of() -> &'static str {
+        "LightError"
+    }
+}
+
+impl PrintProgramError for LightError {
+    fn print<E>(&self)
+    where
+        E: 'static + std::error::Error + DecodeError<E> + PrintProgramError + FromPrimitive,
+    {
+        msg!("Error: {}", self);
+    }
+}
+
+impl LightError {
+    // Decodes a custom error code returned by the program, i.e. for clients
+    // which receive InstructionError::Custom(code) from a failed transaction.
+    pub fn from_code(code: u32) -> Option<Self> {
+        LightError::from_u32(code)
+    }
+
+    // Returns the LightError wrapped in a ProgramError if there is one.
+    pub fn from_program_error(error: &ProgramError) -> Option<Self> {
+        match error {
+            ProgramError::Custom(code) => LightError::from_code(*code),
+            _ => None,
+        }
+    }
+}
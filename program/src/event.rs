@@ -0,0 +1,53 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, program_error::ProgramError};
+
+// Events are emitted with sol_log_data once a transaction is complete (instruction index 1501).
+// They show up in the transaction logs as "Program data: <base64 borsh bytes>", light-client
+// decodes them from the logs (client/src/event.rs).
+// The variant is the public amount direction, every event inserts two leaves.
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Eq, PartialEq)]
+pub enum LightEvent {
+    // ext_amount > 0, public_amount was transferred into the Merkle tree token account.
+    Deposit(TransactionEvent),
+    // ext_amount == 0, public_amount is 0.
+    Transfer(TransactionEvent),
+    // ext_amount < 0, public_amount was transferred to the recipient.
+    Withdrawal(TransactionEvent),
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Eq, PartialEq)]
+pub struct TransactionEvent {
+    pub merkle_tree_pubkey: [u8; 32],
+    // Root after inserting both leaves and its index in the root history.
+    pub root: [u8; 32],
+    pub root_index: u64,
+    pub left_leaf_index: u64,
+    pub leaf_left: [u8; 32],
+    pub leaf_right: [u8; 32],
+    pub nullifiers: [[u8; 32]; 2],
+    pub encrypted_utxos: Vec<u8>,
+    // Absolute value of ext_amount, the amount transferred into or out of the pool.
+    pub public_amount: u64,
+    pub relayer_fee: u64,
+    // Fee transferred from the pool to the treasury, see protocol_fee.rs.
+    pub protocol_fee: u64,
+}
+
+impl LightEvent {
+    pub fn transaction_event(&self) -> &TransactionEvent {
+        match self {
+            LightEvent::Deposit(event)
+            | LightEvent::Transfer(event)
+            | LightEvent::Withdrawal(event) => event,
+        }
+    }
+
+    pub fn emit(&self) -> Result<(), ProgramError> {
+        sol_log_data(&[&self.try_to_vec()?]);
+        Ok(())
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, ProgramError> {
+        LightEvent::try_from_slice(data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+}
@@ -0,0 +1,210 @@
+use crate::error::LightError;
+use crate::instructions::create_and_check_pda;
+use crate::utils::account_header::check_account_header;
+use crate::utils::config::{GLOBAL_CONFIG_ACCOUNT_TYPE, GLOBAL_CONFIG_ACCOUNT_VERSION};
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    account_info::AccountInfo,
+    msg,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+    sysvar::rent::Rent,
+};
+
+// Circuit breaker of the program. The global config pda (derived from b"config") stores the
+// operating mode, it is checked at the send data instruction and again at the last
+// instruction (1501), transactions which are already in progress are stopped as well.
+// The mode is switched by the guardian, a key designated by the admin (see admin_config).
+// Without global config the program is active.
+pub const GLOBAL_CONFIG_SEED: &[u8] = b"config";
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ProgramMode {
+    // Deposits, transfers and withdrawals.
+    Active = 0,
+    // No transactions.
+    Paused = 1,
+    // Only withdrawals, i.e. to let users exit.
+    WithdrawOnly = 2,
+    // Only deposits.
+    DepositOnly = 3,
+}
+
+impl ProgramMode {
+    pub fn from_u8(mode: u8) -> Result<Self, ProgramError> {
+        match mode {
+            0 => Ok(ProgramMode::Active),
+            1 => Ok(ProgramMode::Paused),
+            2 => Ok(ProgramMode::WithdrawOnly),
+            3 => Ok(ProgramMode::DepositOnly),
+            _ => {
+                msg!("Unknown program mode {}.", mode);
+                Err(ProgramError::InvalidInstructionData)
+            }
+        }
+    }
+
+    // Checks whether a transaction with ext_amount is allowed in this mode.
+    // Transfers (ext_amount == 0) are only allowed in Active mode.
+    pub fn check_transaction(&self, ext_amount: i64) -> Result<(), ProgramError> {
+        let allowed = match self {
+            ProgramMode::Active => true,
+            ProgramMode::Paused => false,
+            ProgramMode::WithdrawOnly => ext_amount < 0,
+            ProgramMode::DepositOnly => ext_amount > 0,
+        };
+        if !allowed {
+            msg!(
+                "Transaction with ext_amount {} not allowed in mode {:?}.",
+                ext_amount,
+                self
+            );
+            return Err(LightError::TransactionNotAllowed.into());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct GlobalConfig {
+    pub is_initialized: bool,
+    pub account_type: u8,
+    pub guardian: Pubkey,
+    pub mode: ProgramMode,
+}
+
+impl Sealed for GlobalConfig {}
+impl IsInitialized for GlobalConfig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for GlobalConfig {
+    const LEN: usize = 35;
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, GlobalConfig::LEN];
+        let (version, account_type, guardian, mode) = array_refs![input, 1, 1, 32, 1];
+        check_account_header(version[0], account_type[0], GLOBAL_CONFIG_ACCOUNT_TYPE)?;
+        Ok(GlobalConfig {
+            is_initialized: true,
+            account_type: GLOBAL_CONFIG_ACCOUNT_TYPE,
+            guardian: Pubkey::new(guardian),
+            mode: ProgramMode::from_u8(mode[0])?,
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, GlobalConfig::LEN];
+        let (version_dst, account_type_dst, guardian_dst, mode_dst) =
+            mut_array_refs![dst, 1, 1, 32, 1];
+        *version_dst = [GLOBAL_CONFIG_ACCOUNT_VERSION];
+        *account_type_dst = [GLOBAL_CONFIG_ACCOUNT_TYPE];
+        *guardian_dst = self.guardian.to_bytes();
+        *mode_dst = [self.mode as u8];
+    }
+}
+
+pub fn find_global_config(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[GLOBAL_CONFIG_SEED], program_id)
+}
+
+// Returns None if the global config was not created yet.
+pub fn load_global_config(
+    program_id: &Pubkey,
+    global_config_account: &AccountInfo,
+) -> Result<Option<GlobalConfig>, ProgramError> {
+    if *global_config_account.key != find_global_config(program_id).0 {
+        msg!("Passed-in global config is invalid.");
+        return Err(LightError::InvalidPdaAddress.into());
+    }
+    if *global_config_account.owner != *program_id || global_config_account.data_is_empty() {
+        return Ok(None);
+    }
+    Ok(Some(GlobalConfig::unpack(
+        &global_config_account.data.borrow(),
+    )?))
+}
+
+// Checks a transaction with ext_amount against the current program mode.
+pub fn check_program_mode(
+    program_id: &Pubkey,
+    global_config_account: &AccountInfo,
+    ext_amount: i64,
+) -> Result<(), ProgramError> {
+    match load_global_config(program_id, global_config_account)? {
+        Some(global_config) => global_config.mode.check_transaction(ext_amount),
+        None => Ok(()),
+    }
+}
+
+// Creates the global config or replaces the guardian, the caller checks the admin signers
+// with admin_config::check_admin.
+// A new global config is active.
+// instruction_data: [guardian]
+pub fn set_guardian<'a, 'b>(
+    program_id: &Pubkey,
+    signer_account: &'a AccountInfo<'b>,
+    global_config_account: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    rent: &Rent,
+    instruction_data: &[u8],
+) -> Result<(), ProgramError> {
+    let guardian = instruction_data
+        .get(..32)
+        .ok_or(ProgramError::InvalidInstructionData)?;
+    let mut global_config = match load_global_config(program_id, global_config_account)? {
+        Some(global_config) => global_config,
+        None => {
+            msg!("Creating global config.");
+            create_and_check_pda(
+                program_id,
+                signer_account,
+                global_config_account,
+                system_program,
+                rent,
+                &[],
+                GLOBAL_CONFIG_SEED,
+                GlobalConfig::LEN as u64, //bytes
+                0,                        //lamports
+                true,                     //rent_exempt
+            )?;
+            GlobalConfig {
+                is_initialized: true,
+                account_type: GLOBAL_CONFIG_ACCOUNT_TYPE,
+                guardian: Pubkey::default(),
+                mode: ProgramMode::Active,
+            }
+        }
+    };
+    global_config.guardian = Pubkey::new(guardian);
+    GlobalConfig::pack_into_slice(&global_config, &mut global_config_account.data.borrow_mut());
+    Ok(())
+}
+
+// Switches the program mode, called by the guardian.
+// instruction_data: [mode]
+pub fn set_program_mode(
+    program_id: &Pubkey,
+    signer_account: &AccountInfo,
+    global_config_account: &AccountInfo,
+    instruction_data: &[u8],
+) -> Result<(), ProgramError> {
+    let mode = ProgramMode::from_u8(
+        *instruction_data
+            .first()
+            .ok_or(ProgramError::InvalidInstructionData)?,
+    )?;
+    let mut global_config = load_global_config(program_id, global_config_account)?
+        .ok_or(ProgramError::UninitializedAccount)?;
+    if *signer_account.key != global_config.guardian {
+        msg!("Signer is not the guardian.");
+        return Err(LightError::InvalidGuardian.into());
+    }
+    msg!("Program mode {:?} -> {:?}", global_config.mode, mode);
+    global_config.mode = mode;
+    GlobalConfig::pack_into_slice(&global_config, &mut global_config_account.data.borrow_mut());
+    Ok(())
+}
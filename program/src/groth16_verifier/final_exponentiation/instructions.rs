@@ -1,3 +1,4 @@
+use crate::error::LightError;
 use crate::groth16_verifier::{
     final_exponentiation::{ranges::*, state::FinalExponentiationState},
     parsers::{
@@ -18,9 +19,13 @@ use ark_ff::{
 use solana_program::{msg, program_error::ProgramError};
 
 pub fn verify_result(main_account_data: &FinalExponentiationState) -> Result<(), ProgramError> {
-    if ALPHA_G1_BETA_G2.to_vec() != main_account_data.y1_range {
+    let alpha_g1_beta_g2 = ALPHA_G1_BETA_G2.to_vec();
+    #[cfg(all(feature = "test-verifying-key", not(target_arch = "bpf")))]
+    let alpha_g1_beta_g2 =
+        crate::utils::test_verifying_key::alpha_g1_beta_g2().unwrap_or(alpha_g1_beta_g2);
+    if alpha_g1_beta_g2 != main_account_data.y1_range {
         msg!("verification failed");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(LightError::ProofVerificationFailed.into());
     }
     Ok(())
 }
@@ -1,11 +1,10 @@
+use crate::utils::account_header::check_account_header;
 use crate::utils::config::{ENCRYPTED_UTXOS_LENGTH, TMP_STORAGE_ACCOUNT_TYPE};
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use solana_program::{
-    msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
 };
-use std::convert::TryInto;
 
 #[derive(Debug, Clone)]
 pub struct FinalExponentiationState {
@@ -150,10 +149,11 @@ impl Pack for FinalExponentiationState {
             384,
             128 + ENCRYPTED_UTXOS_LENGTH
         ];
-        if account_type[0] != TMP_STORAGE_ACCOUNT_TYPE {
-            msg!("Wrong account type.");
-            return Err(ProgramError::InvalidArgument);
-        }
+        check_account_header(
+            _is_initialized[0],
+            account_type[0],
+            TMP_STORAGE_ACCOUNT_TYPE,
+        )?;
         Ok(FinalExponentiationState {
             is_initialized: true,
             account_type: account_type[0],
@@ -187,6 +187,7 @@ impl Pack for FinalExponentiationState {
         })
     }
 
+    // Ranges keep the lengths they are unpacked with, parsers write into them in place.
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, FinalExponentiationState::LEN];
 
@@ -240,35 +241,35 @@ impl Pack for FinalExponentiationState {
         for (i, variable_has_changed) in self.changed_variables.iter().enumerate() {
             if *variable_has_changed {
                 if i == 0 {
-                    *f_f2_range_dst = self.f_f2_range.clone().try_into().unwrap();
+                    f_f2_range_dst.copy_from_slice(&self.f_f2_range);
                 } else if i == 1 {
-                    *f1_r_range_dst = self.f1_r_range.clone().try_into().unwrap();
+                    f1_r_range_dst.copy_from_slice(&self.f1_r_range);
                 } else if i == 2 {
-                    *i_range_dst = self.i_range.clone().try_into().unwrap();
+                    i_range_dst.copy_from_slice(&self.i_range);
                 } else if i == 3 {
-                    *y0_range_dst = self.y0_range.clone().try_into().unwrap();
+                    y0_range_dst.copy_from_slice(&self.y0_range);
                 } else if i == 4 {
-                    *y1_range_dst = self.y1_range.clone().try_into().unwrap();
+                    y1_range_dst.copy_from_slice(&self.y1_range);
                 } else if i == 5 {
-                    *y2_range_dst = self.y2_range.clone().try_into().unwrap();
+                    y2_range_dst.copy_from_slice(&self.y2_range);
                 } else if i == 6 {
-                    *cubic_range_0_dst = self.cubic_range_0.clone().try_into().unwrap();
+                    cubic_range_0_dst.copy_from_slice(&self.cubic_range_0);
                 } else if i == 7 {
-                    *cubic_range_1_dst = self.cubic_range_1.clone().try_into().unwrap();
+                    cubic_range_1_dst.copy_from_slice(&self.cubic_range_1);
                 } else if i == 8 {
-                    *cubic_range_2_dst = self.cubic_range_2.clone().try_into().unwrap();
+                    cubic_range_2_dst.copy_from_slice(&self.cubic_range_2);
                 } else if i == 9 {
-                    *quad_range_0_dst = self.quad_range_0.clone().try_into().unwrap();
+                    quad_range_0_dst.copy_from_slice(&self.quad_range_0);
                 } else if i == 10 {
-                    *quad_range_1_dst = self.quad_range_1.clone().try_into().unwrap();
+                    quad_range_1_dst.copy_from_slice(&self.quad_range_1);
                 } else if i == 11 {
-                    *quad_range_2_dst = self.quad_range_2.clone().try_into().unwrap();
+                    quad_range_2_dst.copy_from_slice(&self.quad_range_2);
                 } else if i == 12 {
-                    *quad_range_3_dst = self.quad_range_3.clone().try_into().unwrap();
+                    quad_range_3_dst.copy_from_slice(&self.quad_range_3);
                 } else if i == 13 {
-                    *fp384_range_dst = self.fp256_range.clone().try_into().unwrap();
+                    fp384_range_dst.copy_from_slice(&self.fp256_range);
                 } else if i == 14 {
-                    *y6_range_dst = self.y6_range.clone().try_into().unwrap();
+                    y6_range_dst.copy_from_slice(&self.y6_range);
                 }
             }
         }
@@ -5,6 +5,7 @@ use solana_program::{
 };
 
 // Light
+use crate::error::LightError;
 use crate::groth16_verifier::{
     final_exponentiation,
     final_exponentiation::{
@@ -57,7 +58,7 @@ impl<'a, 'b> Groth16Processor<'a, 'b> {
             Ok(())
         } else {
             msg!("should not enter here");
-            Err(ProgramError::InvalidArgument)
+            Err(LightError::InvalidInstructionIndex.into())
         }
     }
 
@@ -109,7 +110,7 @@ impl<'a, 'b> Groth16Processor<'a, 'b> {
             let account_prepare_inputs_data =
                 PrepareInputsState::unpack(&self.main_account.data.borrow())?;
             let g_ic_affine =
-                parse_x_group_affine_from_bytes(&account_prepare_inputs_data.x_1_range); // 10k
+                parse_x_group_affine_from_bytes(&account_prepare_inputs_data.x_1_range)?; // 10k
             let p2: ark_ec::bn::G1Prepared<ark_bn254::Parameters> =
                 ark_ec::bn::g1::G1Prepared::from(g_ic_affine);
             miller_loop::processor::move_proofs(
@@ -172,32 +173,32 @@ impl<'a, 'b> Groth16Processor<'a, 'b> {
         //root
         let input1 =
             <Fp256<ark_ed_on_bn254::FqParameters> as FromBytes>::read(&_instruction_data[0..32])
-                .unwrap();
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
         //public amount
         let input2 =
             <Fp256<ark_ed_on_bn254::FqParameters> as FromBytes>::read(&_instruction_data[32..64])
-                .unwrap();
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
         //external data hash
         let input3 =
             <Fp256<ark_ed_on_bn254::FqParameters> as FromBytes>::read(&_instruction_data[64..96])
-                .unwrap();
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
         //inputNullifier0
         let input4 =
             <Fp256<ark_ed_on_bn254::FqParameters> as FromBytes>::read(&_instruction_data[96..128])
-                .unwrap();
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
 
         //inputNullifier1
         let input5 =
             <Fp256<ark_ed_on_bn254::FqParameters> as FromBytes>::read(&_instruction_data[128..160])
-                .unwrap();
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
         //inputCommitment0
         let input6 =
             <Fp256<ark_ed_on_bn254::FqParameters> as FromBytes>::read(&_instruction_data[160..192])
-                .unwrap();
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
         //inputCommitment1
         let input7 =
             <Fp256<ark_ed_on_bn254::FqParameters> as FromBytes>::read(&_instruction_data[192..224])
-                .unwrap();
+                .map_err(|_| ProgramError::InvalidInstructionData)?;
 
         let public_inputs: Vec<Fp256<ark_bn254::FrParameters>> =
             vec![input1, input2, input3, input4, input5, input6, input7];
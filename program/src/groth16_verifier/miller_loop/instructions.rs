@@ -35,7 +35,7 @@ pub fn doubling_step(
     let two_inv = <ark_bn254::Fq2Parameters as Fp2Parameters>::Fp::one()
         .double()
         .inverse()
-        .unwrap();
+        .ok_or(ProgramError::InvalidArgument)?;
     let mut a = r.x * r.y;
     a.mul_assign_by_fp(&two_inv);
     let b = r.y.square();
@@ -85,7 +85,7 @@ pub fn addition_step<B: BnParameters>(
     proof_bytes: &Vec<u8>,
     computation_flag: &str,
 ) -> Result<(), ProgramError> {
-    let mut q = parse_proof_b_from_bytes(proof_bytes);
+    let mut q = parse_proof_b_from_bytes(proof_bytes)?;
 
     let twist_mul_by_q_x = ark_bn254::Parameters::TWIST_MUL_BY_Q_X;
 
@@ -150,7 +150,7 @@ pub fn addition_step<B: BnParameters>(
 }
 
 pub fn init_coeffs1(r_range: &mut Vec<u8>, proof_range: &mut Vec<u8>) -> Result<(), ProgramError> {
-    let proof_b = parse_proof_b_from_bytes(proof_range);
+    let proof_b = parse_proof_b_from_bytes(proof_range)?;
     let r: ark_ec::models::bn::g2::G2HomProjective<ark_bn254::Parameters> =
         ark_ec::models::bn::g2::G2HomProjective {
             x: proof_b.x,
@@ -421,6 +421,10 @@ pub fn ell_instruction_d_c2(
     } else {
         msg!("ERR: coeff uninitialized value");
     }
+    #[cfg(all(feature = "test-verifying-key", not(target_arch = "bpf")))]
+    if let Some(test_coeff) = crate::utils::test_verifying_key::gamma_g2_neg_pc(id) {
+        coeff = test_coeff;
+    }
     if id == 90 {
         // set to 0
         current_coeff_2_range[0] = 0;
@@ -664,6 +668,10 @@ pub fn ell_instruction_d_c3(
     } else {
         msg!("ERR: coeff uninitialized value");
     }
+    #[cfg(all(feature = "test-verifying-key", not(target_arch = "bpf")))]
+    if let Some(test_coeff) = crate::utils::test_verifying_key::delta_g2_neg_pc(id) {
+        coeff = test_coeff;
+    }
     if id == 90 {
         current_coeff_3_range[0] = 0;
     } else {
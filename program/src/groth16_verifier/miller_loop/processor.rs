@@ -17,10 +17,10 @@ pub fn move_proofs(
 ) -> Result<(), ProgramError> {
     let proof_a = parse_x_group_affine_from_bytes(
         &account_prepare_inputs_data.proof_a_b_c_leaves_and_nullifiers[..64].to_vec(),
-    );
+    )?;
     let proof_c = parse_x_group_affine_from_bytes(
         &account_prepare_inputs_data.proof_a_b_c_leaves_and_nullifiers[192..256].to_vec(),
-    );
+    )?;
     let p_1: ark_ec::bn::G1Prepared<ark_bn254::Parameters> =
         ark_ec::bn::g1::G1Prepared::from(proof_a);
     let p_3: ark_ec::bn::G1Prepared<ark_bn254::Parameters> =
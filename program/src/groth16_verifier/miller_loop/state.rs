@@ -1,11 +1,10 @@
+use crate::utils::account_header::check_account_header;
 use crate::utils::config::{ENCRYPTED_UTXOS_LENGTH, TMP_STORAGE_ACCOUNT_TYPE};
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use solana_program::{
-    msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
 };
-use std::convert::TryInto;
 
 // Implements partial pack to save compute budget.
 #[derive(Clone)]
@@ -89,10 +88,11 @@ impl Pack for MillerLoopState {
             1,
             2590 + ENCRYPTED_UTXOS_LENGTH
         ];
-        if account_type[0] != TMP_STORAGE_ACCOUNT_TYPE {
-            msg!("Wrong account type.");
-            return Err(ProgramError::InvalidArgument);
-        }
+        check_account_header(
+            _is_initialized[0],
+            account_type[0],
+            TMP_STORAGE_ACCOUNT_TYPE,
+        )?;
         Ok(MillerLoopState {
             is_initialized: true,
             signing_address: signing_address.to_vec(),
@@ -118,6 +118,7 @@ impl Pack for MillerLoopState {
         })
     }
 
+    // Ranges keep the lengths they are unpacked with, parsers write into them in place.
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, MillerLoopState::LEN];
 
@@ -169,35 +170,33 @@ impl Pack for MillerLoopState {
         for (i, var_has_changed) in self.changed_variables.iter().enumerate() {
             if *var_has_changed {
                 if i == 0 {
-                    *f_range_dst = self.f_range.clone().try_into().unwrap();
+                    f_range_dst.copy_from_slice(&self.f_range);
                 } else if i == 1 {
-                    *coeff_2_range_dst = self.coeff_2_range.clone().try_into().unwrap();
+                    coeff_2_range_dst.copy_from_slice(&self.coeff_2_range);
                 } else if i == 2 {
-                    *coeff_1_range_dst = self.coeff_1_range.clone().try_into().unwrap();
+                    coeff_1_range_dst.copy_from_slice(&self.coeff_1_range);
                 } else if i == 3 {
-                    *coeff_0_range_dst = self.coeff_0_range.clone().try_into().unwrap();
+                    coeff_0_range_dst.copy_from_slice(&self.coeff_0_range);
                 } else if i == 4 {
-                    *p_1_x_range_dst = self.p_1_x_range.clone().try_into().unwrap();
+                    p_1_x_range_dst.copy_from_slice(&self.p_1_x_range);
                 } else if i == 5 {
-                    *p_1_y_range_dst = self.p_1_y_range.clone().try_into().unwrap();
+                    p_1_y_range_dst.copy_from_slice(&self.p_1_y_range);
                 } else if i == 6 {
-                    *p_2_x_range_dst = self.p_2_x_range.clone().try_into().unwrap();
+                    p_2_x_range_dst.copy_from_slice(&self.p_2_x_range);
                 } else if i == 7 {
-                    *p_2_y_range_dst = self.p_2_y_range.clone().try_into().unwrap();
+                    p_2_y_range_dst.copy_from_slice(&self.p_2_y_range);
                 } else if i == 8 {
-                    *p_3_x_range_dst = self.p_3_x_range.clone().try_into().unwrap();
+                    p_3_x_range_dst.copy_from_slice(&self.p_3_x_range);
                 } else if i == 9 {
-                    *p_3_y_range_dst = self.p_3_y_range.clone().try_into().unwrap();
+                    p_3_y_range_dst.copy_from_slice(&self.p_3_y_range);
                 } else if i == 10 {
-                    *r_dst = self.r.clone().try_into().unwrap();
+                    r_dst.copy_from_slice(&self.r);
                 } else if i == 11 {
-                    *proof_b_dst = self.proof_b.clone().try_into().unwrap();
+                    proof_b_dst.copy_from_slice(&self.proof_b);
                 } else if i == 12 {
-                    *current_coeff_2_range_dst =
-                        self.current_coeff_2_range.clone().try_into().unwrap();
+                    current_coeff_2_range_dst.copy_from_slice(&self.current_coeff_2_range);
                 } else if i == 13 {
-                    *current_coeff_3_range_dst =
-                        self.current_coeff_3_range.clone().try_into().unwrap();
+                    current_coeff_3_range_dst.copy_from_slice(&self.current_coeff_3_range);
                 }
             }
         }
@@ -1,10 +1,28 @@
+// Parsers between field elements and the 32 byte ranges of the verifier state.
+// Proof bytes and public inputs are user provided, their parsers return
+// LightError::InvalidFieldElement for values which are not canonical field elements.
+// The other parsers unwrap: writes into 32 byte ranges cannot fail, and the verifier
+// state they read is only written by the program, with canonical values.
+
+use crate::error::LightError;
 use ark_bn254;
 use ark_ec;
 use ark_ff::bytes::{FromBytes, ToBytes};
 use ark_ff::fields::models::quadratic_extension::QuadExtField;
 use ark_ff::Fp256;
 use ark_ff::One;
+use solana_program::{msg, program_error::ProgramError};
+
+// Reads a user provided Fq element.
+fn read_fq(bytes: &[u8]) -> Result<Fp256<ark_bn254::FqParameters>, ProgramError> {
+    <Fp256<ark_bn254::FqParameters> as FromBytes>::read(bytes).map_err(|_| {
+        msg!("Bytes are not a canonical Fq element.");
+        LightError::InvalidFieldElement.into()
+    })
+}
 
+// Writing into a range of field element size cannot fail.
+#[allow(clippy::unwrap_used)]
 pub fn parse_f_to_bytes(
     f: <ark_ec::models::bn::Bn<ark_bn254::Parameters> as ark_ec::PairingEngine>::Fqk,
     range: &mut Vec<u8>,
@@ -97,6 +115,8 @@ pub fn parse_f_to_bytes(
     }
 }
 
+// Reads verifier state, the program only writes canonical values into it.
+#[allow(clippy::unwrap_used)]
 pub fn parse_f_from_bytes(
     range: &Vec<u8>,
 ) -> <ark_ec::models::bn::Bn<ark_bn254::Parameters> as ark_ec::PairingEngine>::Fqk {
@@ -168,12 +188,16 @@ pub fn parse_f_from_bytes(
     f
 }
 
+// Writing into a range of field element size cannot fail.
+#[allow(clippy::unwrap_used)]
 pub fn parse_fp256_to_bytes(fp256: ark_ff::Fp256<ark_bn254::FqParameters>, range: &mut Vec<u8>) {
     let start = 0;
     let end = 32;
     <Fp256<ark_bn254::FqParameters> as ToBytes>::write(&fp256, &mut range[start..end]).unwrap();
 }
 
+// Reads verifier state, the program only writes canonical values into it.
+#[allow(clippy::unwrap_used)]
 pub fn parse_fp256_from_bytes(range: &Vec<u8>) -> ark_ff::Fp256<ark_bn254::FqParameters> {
     let fp256: ark_ff::Fp256<ark_bn254::FqParameters>;
     let start = 0;
@@ -182,6 +206,8 @@ pub fn parse_fp256_from_bytes(range: &Vec<u8>) -> ark_ff::Fp256<ark_bn254::FqPar
     fp256
 }
 
+// Writing into a range of field element size cannot fail.
+#[allow(clippy::unwrap_used)]
 pub fn parse_fp256_ed_to_bytes(
     fp256: ark_ff::Fp256<ark_ed_on_bn254::FqParameters>,
     account: &mut Vec<u8>,
@@ -192,16 +218,14 @@ pub fn parse_fp256_ed_to_bytes(
         .unwrap();
 }
 
+// Reads a public input.
 pub fn parse_fp256_ed_from_bytes(
     account: &Vec<u8>,
-) -> ark_ff::Fp256<ark_ed_on_bn254::FqParameters> {
-    let fp256: ark_ff::Fp256<ark_ed_on_bn254::FqParameters>;
-    let start = 0;
-    let end = 32;
-    fp256 =
-        <Fp256<ark_ed_on_bn254::FqParameters> as FromBytes>::read(&account[start..end]).unwrap();
-
-    fp256
+) -> Result<ark_ff::Fp256<ark_ed_on_bn254::FqParameters>, ProgramError> {
+    <Fp256<ark_ed_on_bn254::FqParameters> as FromBytes>::read(&account[0..32]).map_err(|_| {
+        msg!("Public input is not a canonical field element.");
+        LightError::InvalidFieldElement.into()
+    })
 }
 
 // j: proof.b prep
@@ -229,14 +253,19 @@ pub fn parse_r_from_bytes(
     }
 }
 
+// Reads proof.b, c0 and c1 of x then of y.
 pub fn parse_proof_b_from_bytes(
     range: &Vec<u8>,
-) -> ark_ec::models::bn::g2::G2Affine<ark_bn254::Parameters> {
-    ark_ec::models::bn::g2::G2Affine::<ark_bn254::Parameters>::new(
-        parse_quad_from_bytes(&range[..64].to_vec()),
-        parse_quad_from_bytes(&range[64..].to_vec()),
-        false,
-    )
+) -> Result<ark_ec::models::bn::g2::G2Affine<ark_bn254::Parameters>, ProgramError> {
+    let x = QuadExtField::<ark_ff::Fp2ParamsWrapper<ark_bn254::Fq2Parameters>>::new(
+        read_fq(&range[0..32])?,
+        read_fq(&range[32..64])?,
+    );
+    let y = QuadExtField::<ark_ff::Fp2ParamsWrapper<ark_bn254::Fq2Parameters>>::new(
+        read_fq(&range[64..96])?,
+        read_fq(&range[96..128])?,
+    );
+    Ok(ark_ec::models::bn::g2::G2Affine::<ark_bn254::Parameters>::new(x, y, false))
 }
 
 pub fn parse_proof_b_to_bytes(
@@ -250,6 +279,8 @@ pub fn parse_proof_b_to_bytes(
     *range = [tmp0, tmp1].concat();
 }
 
+// Writing into a range of field element size cannot fail.
+#[allow(clippy::unwrap_used)]
 pub fn parse_quad_to_bytes(
     q: ark_ff::QuadExtField<ark_ff::Fp2ParamsWrapper<ark_bn254::Fq2Parameters>>,
     range: &mut Vec<u8>,
@@ -269,6 +300,8 @@ pub fn parse_quad_to_bytes(
     }
 }
 
+// Reads verifier state, the program only writes canonical values into it.
+#[allow(clippy::unwrap_used)]
 pub fn parse_quad_from_bytes(
     range: &Vec<u8>,
 ) -> ark_ff::QuadExtField<ark_ff::Fp2ParamsWrapper<ark_bn254::Fq2Parameters>> {
@@ -282,6 +315,8 @@ pub fn parse_quad_from_bytes(
     )
 }
 
+// Writing into a range of field element size cannot fail.
+#[allow(clippy::unwrap_used)]
 pub fn parse_cubic_to_bytes(
     c: ark_ff::CubicExtField<ark_ff::Fp6ParamsWrapper<ark_bn254::Fq6Parameters>>,
     range: &mut Vec<u8>,
@@ -314,6 +349,8 @@ pub fn parse_cubic_to_bytes(
     }
 }
 
+// Reads verifier state, the program only writes canonical values into it.
+#[allow(clippy::unwrap_used)]
 pub fn parse_cubic_from_bytes(
     range: &Vec<u8>,
 ) -> ark_ff::CubicExtField<ark_ff::Fp6ParamsWrapper<ark_bn254::Fq6Parameters>> {
@@ -348,6 +385,8 @@ pub fn parse_cubic_from_bytes(
     cubic
 }
 
+// Writing into a range of field element size cannot fail.
+#[allow(clippy::unwrap_used)]
 pub fn parse_cubic_to_bytes_sub(
     c: ark_ff::CubicExtField<ark_ff::Fp6ParamsWrapper<ark_bn254::Fq6Parameters>>,
     range: &mut Vec<u8>,
@@ -381,6 +420,8 @@ pub fn parse_cubic_to_bytes_sub(
     }
 }
 
+// Reads verifier state, the program only writes canonical values into it.
+#[allow(clippy::unwrap_used)]
 pub fn parse_cubic_from_bytes_sub(
     range: &Vec<u8>,
     sub: [usize; 2],
@@ -416,17 +457,20 @@ pub fn parse_cubic_from_bytes_sub(
     cubic
 }
 
-// x
+// x, also reads proof.a and proof.c.
 pub fn parse_x_group_affine_from_bytes(
     account: &Vec<u8>,
-) -> ark_ec::short_weierstrass_jacobian::GroupAffine<ark_bn254::g1::Parameters> {
-    ark_ec::short_weierstrass_jacobian::GroupAffine::<ark_bn254::g1::Parameters>::new(
-        <Fp256<ark_bn254::FqParameters> as FromBytes>::read(&account[0..32]).unwrap(),
-        <Fp256<ark_bn254::FqParameters> as FromBytes>::read(&account[32..64]).unwrap(),
-        false,
-    )
+) -> Result<ark_ec::short_weierstrass_jacobian::GroupAffine<ark_bn254::g1::Parameters>, ProgramError>
+{
+    let x = read_fq(&account[0..32])?;
+    let y = read_fq(&account[32..64])?;
+    Ok(ark_ec::short_weierstrass_jacobian::GroupAffine::new(
+        x, y, false,
+    ))
 }
 
+// Writing into a range of field element size cannot fail.
+#[allow(clippy::unwrap_used)]
 pub fn parse_x_group_affine_to_bytes(
     x: ark_ec::short_weierstrass_jacobian::GroupAffine<ark_bn254::g1::Parameters>,
     account: &mut Vec<u8>,
@@ -435,6 +479,8 @@ pub fn parse_x_group_affine_to_bytes(
     <Fp256<ark_bn254::FqParameters> as ToBytes>::write(&x.y, &mut account[32..64]).unwrap();
 }
 
+// Reads verifier state, the program only writes canonical values into it.
+#[allow(clippy::unwrap_used)]
 pub fn parse_group_projective_from_bytes_254(
     acc1: &[u8],
     acc2: &[u8],
@@ -447,6 +493,8 @@ pub fn parse_group_projective_from_bytes_254(
     )
 }
 
+// Writing into a range of field element size cannot fail.
+#[allow(clippy::unwrap_used)]
 pub fn parse_group_projective_to_bytes_254(
     res: ark_ec::short_weierstrass_jacobian::GroupProjective<ark_bn254::g1::Parameters>,
     acc1: &mut Vec<u8>,
@@ -458,3 +506,16 @@ pub fn parse_group_projective_to_bytes_254(
     <Fp256<ark_bn254::FqParameters> as ToBytes>::write(&res.z, &mut acc3[0..32]).unwrap();
     // i 0..48
 }
+
+// Checks that the proof bytes (a: 64, b: 128, c: 64) are canonical Fq elements,
+// the parsers above read them during the miller loop.
+pub fn check_proof_bytes(proof: &[u8]) -> Result<(), ProgramError> {
+    if proof.len() != 256 {
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    for coordinate in proof.chunks(32) {
+        <Fp256<ark_bn254::FqParameters> as FromBytes>::read(coordinate)
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+    }
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use crate::error::LightError;
 use crate::groth16_verifier::parsers::*;
 use crate::utils::prepared_verifying_key::*;
 use ark_ec::{AffineCurve, ProjectiveCurve};
@@ -46,9 +47,12 @@ pub fn init_pairs_instruction(
         get_gamma_abc_g1_6(),
         get_gamma_abc_g1_7(),
     ];
+    #[cfg(all(feature = "test-verifying-key", not(target_arch = "bpf")))]
+    let pvk_vk_gamma_abc_g1 =
+        crate::utils::test_verifying_key::gamma_abc_g1().unwrap_or(pvk_vk_gamma_abc_g1);
     if (public_inputs.len() + 1) != pvk_vk_gamma_abc_g1.len() {
         msg!("Incompatible Verifying Key");
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(LightError::IncompatibleVerifyingKey.into());
     }
 
     // inits g_ic into range.
@@ -118,8 +122,8 @@ pub fn maths_instruction(
 ) -> Result<(), ProgramError> {
     // Parses res,x,i from range.
     let mut res = parse_group_projective_from_bytes_254(res_x_range, res_y_range, res_z_range);
-    let x = parse_x_group_affine_from_bytes(x_range);
-    let i = parse_fp256_ed_from_bytes(i_range);
+    let x = parse_x_group_affine_from_bytes(x_range)?;
+    let i = parse_fp256_ed_from_bytes(i_range)?;
 
     // create bit: (current i,x * current index).
     // First constructs all bits of current i,x pair.
@@ -294,7 +298,8 @@ pub fn g_ic_into_affine_1(
 ) -> Result<(), ProgramError> {
     let g_ic: ark_ec::short_weierstrass_jacobian::GroupProjective<ark_bn254::g1::Parameters> =
         parse_group_projective_from_bytes_254(g_ic_x_range, g_ic_y_range, g_ic_z_range); // 15k
-    let zinv = ark_ff::Field::inverse(&g_ic.z).unwrap();
+    // g_ic is the point at infinity if z is zero, no proof verifies against it.
+    let zinv = ark_ff::Field::inverse(&g_ic.z).ok_or(LightError::ProofVerificationFailed)?;
     let g_ic_with_zinv: ark_ec::short_weierstrass_jacobian::GroupProjective<
         ark_bn254::g1::Parameters,
     > = ark_ec::short_weierstrass_jacobian::GroupProjective::new(g_ic.x, g_ic.y, zinv);
@@ -372,7 +377,7 @@ mod tests {
             > = reference_g_ic.into();
             assert_eq!(
                 affine_ref,
-                parse_x_group_affine_from_bytes(&account_x_range)
+                parse_x_group_affine_from_bytes(&account_x_range).unwrap()
             );
         }
     }
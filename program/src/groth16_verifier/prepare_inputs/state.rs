@@ -1,11 +1,12 @@
-use crate::utils::config::{ENCRYPTED_UTXOS_LENGTH, TMP_STORAGE_ACCOUNT_TYPE};
+use crate::utils::account_header::check_account_header;
+use crate::utils::config::{
+    ENCRYPTED_UTXOS_LENGTH, TMP_STORAGE_ACCOUNT_TYPE, TMP_STORAGE_ACCOUNT_VERSION,
+};
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use solana_program::{
-    msg,
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
 };
-use std::convert::TryInto;
 
 #[derive(Clone)]
 pub struct PrepareInputsState {
@@ -138,9 +139,12 @@ impl Pack for PrepareInputsState {
             384 + ENCRYPTED_UTXOS_LENGTH
         ];
 
-        if _is_initialized[0] != 0u8 && account_type[0] != TMP_STORAGE_ACCOUNT_TYPE {
-            msg!("Wrong account type.");
-            return Err(ProgramError::InvalidAccountData);
+        if _is_initialized[0] != 0u8 {
+            check_account_header(
+                _is_initialized[0],
+                account_type[0],
+                TMP_STORAGE_ACCOUNT_TYPE,
+            )?;
         }
         Ok(PrepareInputsState {
             is_initialized: true,
@@ -184,6 +188,7 @@ impl Pack for PrepareInputsState {
         })
     }
 
+    // Ranges keep the lengths they are unpacked with, parsers write into them in place.
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, PrepareInputsState::LEN];
 
@@ -267,45 +272,45 @@ impl Pack for PrepareInputsState {
         for (i, var_has_changed) in self.changed_variables.iter().enumerate() {
             if *var_has_changed {
                 if i == 0 {
-                    *i_1_range_dst = self.i_1_range.clone().try_into().unwrap();
+                    i_1_range_dst.copy_from_slice(&self.i_1_range);
                 } else if i == 1 {
-                    *x_1_range_dst = self.x_1_range.clone().try_into().unwrap();
+                    x_1_range_dst.copy_from_slice(&self.x_1_range);
                 } else if i == 2 {
-                    *i_2_range_dst = self.i_2_range.clone().try_into().unwrap();
+                    i_2_range_dst.copy_from_slice(&self.i_2_range);
                 } else if i == 3 {
-                    *x_2_range_dst = self.x_2_range.clone().try_into().unwrap();
+                    x_2_range_dst.copy_from_slice(&self.x_2_range);
                 } else if i == 4 {
-                    *i_3_range_dst = self.i_3_range.clone().try_into().unwrap();
+                    i_3_range_dst.copy_from_slice(&self.i_3_range);
                 } else if i == 5 {
-                    *x_3_range_dst = self.x_3_range.clone().try_into().unwrap();
+                    x_3_range_dst.copy_from_slice(&self.x_3_range);
                 } else if i == 6 {
-                    *i_4_range_dst = self.i_4_range.clone().try_into().unwrap();
+                    i_4_range_dst.copy_from_slice(&self.i_4_range);
                 } else if i == 7 {
-                    *x_4_range_dst = self.x_4_range.clone().try_into().unwrap();
+                    x_4_range_dst.copy_from_slice(&self.x_4_range);
                 } else if i == 8 {
-                    *i_5_range_dst = self.i_5_range.clone().try_into().unwrap();
+                    i_5_range_dst.copy_from_slice(&self.i_5_range);
                 } else if i == 9 {
-                    *x_5_range_dst = self.x_5_range.clone().try_into().unwrap();
+                    x_5_range_dst.copy_from_slice(&self.x_5_range);
                 } else if i == 10 {
-                    *i_6_range_dst = self.i_6_range.clone().try_into().unwrap();
+                    i_6_range_dst.copy_from_slice(&self.i_6_range);
                 } else if i == 11 {
-                    *x_6_range_dst = self.x_6_range.clone().try_into().unwrap();
+                    x_6_range_dst.copy_from_slice(&self.x_6_range);
                 } else if i == 12 {
-                    *i_7_range_dst = self.i_7_range.clone().try_into().unwrap();
+                    i_7_range_dst.copy_from_slice(&self.i_7_range);
                 } else if i == 13 {
-                    *x_7_range_dst = self.x_7_range.clone().try_into().unwrap();
+                    x_7_range_dst.copy_from_slice(&self.x_7_range);
                 } else if i == 14 {
-                    *res_x_range_dst = self.res_x_range.clone().try_into().unwrap();
+                    res_x_range_dst.copy_from_slice(&self.res_x_range);
                 } else if i == 15 {
-                    *res_y_range_dst = self.res_y_range.clone().try_into().unwrap();
+                    res_y_range_dst.copy_from_slice(&self.res_y_range);
                 } else if i == 16 {
-                    *res_z_range_dst = self.res_z_range.clone().try_into().unwrap();
+                    res_z_range_dst.copy_from_slice(&self.res_z_range);
                 } else if i == 17 {
-                    *g_ic_x_range_dst = self.g_ic_x_range.clone().try_into().unwrap();
+                    g_ic_x_range_dst.copy_from_slice(&self.g_ic_x_range);
                 } else if i == 18 {
-                    *g_ic_y_range_dst = self.g_ic_y_range.clone().try_into().unwrap();
+                    g_ic_y_range_dst.copy_from_slice(&self.g_ic_y_range);
                 } else if i == 19 {
-                    *g_ic_z_range_dst = self.g_ic_z_range.clone().try_into().unwrap();
+                    g_ic_z_range_dst.copy_from_slice(&self.g_ic_z_range);
                 }
             }
         }
@@ -319,33 +324,30 @@ impl Pack for PrepareInputsState {
                 } else if i == 2 {
                     *merkle_tree_index_dst = [self.merkle_tree_index; 1];
                 } else if i == 3 {
-                    *signing_address_dst = self.signing_address.clone().try_into().unwrap();
+                    signing_address_dst.copy_from_slice(&self.signing_address);
                 } else if i == 4 {
-                    *relayer_fee_dst = self.relayer_fee.clone().try_into().unwrap();
+                    relayer_fee_dst.copy_from_slice(&self.relayer_fee);
                 } else if i == 5 {
-                    *recipient_dst = self.recipient.clone().try_into().unwrap();
+                    recipient_dst.copy_from_slice(&self.recipient);
                 } else if i == 6 {
-                    *amount_dst = self.amount.clone().try_into().unwrap();
+                    amount_dst.copy_from_slice(&self.amount);
                 } else if i == 7 {
-                    *nullifier_hash_dst = self.nullifier_hash.clone().try_into().unwrap();
+                    nullifier_hash_dst.copy_from_slice(&self.nullifier_hash);
                 } else if i == 8 {
-                    *root_hash_dst = self.root_hash.clone().try_into().unwrap();
+                    root_hash_dst.copy_from_slice(&self.root_hash);
                 } else if i == 9 {
-                    *unused_dst = self.unused.clone().try_into().unwrap();
+                    unused_dst.copy_from_slice(&self.unused);
                 } else if i == 10 {
-                    *tx_integrity_hash_dst = self.tx_integrity_hash.clone().try_into().unwrap();
+                    tx_integrity_hash_dst.copy_from_slice(&self.tx_integrity_hash);
                 } else if i == 11 {
-                    *proof_a_b_c_leaves_and_nullifiers_dst = self
-                        .proof_a_b_c_leaves_and_nullifiers
-                        .clone()
-                        .try_into()
-                        .unwrap();
+                    proof_a_b_c_leaves_and_nullifiers_dst
+                        .copy_from_slice(&self.proof_a_b_c_leaves_and_nullifiers);
                 }
             }
         }
         *current_instruction_index_dst = usize::to_le_bytes(self.current_instruction_index);
         if self.is_initialized {
-            *is_initialized_dst = [1u8; 1];
+            *is_initialized_dst = [TMP_STORAGE_ACCOUNT_VERSION; 1];
         }
     }
 }
@@ -1,16 +1,26 @@
 use ark_ed_on_bn254::Fq;
 use ark_ff::PrimeField;
 
+use crate::asset::Asset;
+use crate::encrypted_utxos::check_encrypted_utxos_version;
+use crate::error::LightError;
+use crate::global_config::check_program_mode;
+use crate::groth16_verifier::parsers::check_proof_bytes;
 use crate::nullifier_state::NullifierState;
+use crate::pda::{nullifier_seeds, tmp_storage_seeds};
+use crate::post_withdraw_call::{commitment_hash, POST_WITHDRAW_CALL_COMMITMENT_LEN};
+use crate::protocol_fee::FeeSchedule;
+use crate::relayer_handoff::{create_relayer_handoff, RELAYER_HANDOFF_COMMITMENT_LEN};
+use crate::send_data::{ExtData, EXT_DATA_RANGE_START};
 use crate::state::ChecksAndTransferState;
 use crate::utils::config::{
-    ENCRYPTED_UTXOS_LENGTH, MERKLE_TREE_ACC_BYTES_ARRAY, TMP_STORAGE_ACCOUNT_TYPE,
+    get_merkle_tree_acc_bytes, get_merkle_tree_asset, get_merkle_tree_encrypted_utxos_version,
+    ENCRYPTED_UTXOS_LENGTH, TMP_STORAGE_ACCOUNT_TYPE,
 };
 use crate::Groth16Processor;
 use ark_ed_on_bn254::FqParameters;
 use ark_ff::{biginteger::BigInteger256, bytes::FromBytes, fields::FpParameters, BigInteger};
-use borsh::BorshSerialize;
-use solana_program::program::invoke_signed;
+use solana_program::program::{invoke, invoke_signed};
 use solana_program::system_instruction;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
@@ -23,41 +33,59 @@ use solana_program::{
 };
 use std::convert::{TryFrom, TryInto};
 
+pub fn parse_ext_amount(ext_amount: &[u8]) -> Result<i64, ProgramError> {
+    let ext_amount: [u8; 8] = ext_amount
+        .try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    Ok(i64::from_le_bytes(ext_amount))
+}
+
 #[allow(clippy::comparison_chain)]
+// Returns the external amount, the relayer fee and the protocol fee.
 pub fn check_external_amount(
     tmp_storage_pda_data: &ChecksAndTransferState,
-) -> Result<(u64, u64), ProgramError> {
-    let ext_amount =
-        i64::from_le_bytes(tmp_storage_pda_data.ext_amount.clone().try_into().unwrap());
-    // ext_amount includes relayer_fee
-    let relayer_fee =
-        u64::from_le_bytes(tmp_storage_pda_data.relayer_fee.clone().try_into().unwrap());
+    fee_schedule: &FeeSchedule,
+) -> Result<(u64, u64, u64), ProgramError> {
+    let ext_amount = parse_ext_amount(&tmp_storage_pda_data.ext_amount)?;
+    // ext_amount includes relayer_fee and protocol_fee
+    let relayer_fee: [u8; 8] = tmp_storage_pda_data.relayer_fee[..]
+        .try_into()
+        .map_err(|_| ProgramError::InvalidAccountData)?;
+    let relayer_fee = u64::from_le_bytes(relayer_fee);
     // pub_amount is the public amount included in public inputs for proof verification
-    let pub_amount = <BigInteger256 as FromBytes>::read(&tmp_storage_pda_data.amount[..]).unwrap();
+    let pub_amount = <BigInteger256 as FromBytes>::read(&tmp_storage_pda_data.amount[..])
+        .map_err(|_| ProgramError::InvalidAccountData)?;
 
     if ext_amount > 0 {
         if pub_amount.0[1] != 0 || pub_amount.0[2] != 0 || pub_amount.0[3] != 0 {
             msg!("Public amount is larger than u64.");
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(LightError::PublicAmountTooLarge.into());
         }
 
         let pub_amount_fits_i64 = i64::try_from(pub_amount.0[0]);
 
         if pub_amount_fits_i64.is_err() {
             msg!("Public amount is larger than i64.");
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(LightError::PublicAmountTooLarge.into());
         }
 
         //check amount
-        if pub_amount.0[0].checked_add(relayer_fee).unwrap() != ext_amount.try_into().unwrap() {
+        let deposit_amount =
+            u64::try_from(ext_amount).map_err(|_| LightError::ArithmeticOverflow)?;
+        let protocol_fee = fee_schedule.fee(deposit_amount)?;
+        let expected_deposit_amount = pub_amount.0[0]
+            .checked_add(relayer_fee)
+            .and_then(|amount| amount.checked_add(protocol_fee))
+            .ok_or(LightError::ArithmeticOverflow)?;
+        if expected_deposit_amount != deposit_amount {
             msg!(
-                "Deposit invalid external amount (relayer_fee) {} != {}",
-                pub_amount.0[0] + relayer_fee,
+                "Deposit invalid external amount (relayer_fee, protocol_fee) {} != {}",
+                expected_deposit_amount,
                 ext_amount
             );
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(LightError::InvalidDepositAmount.into());
         }
-        Ok((ext_amount.try_into().unwrap(), relayer_fee))
+        Ok((deposit_amount, relayer_fee, protocol_fee))
     } else if ext_amount < 0 {
         // calculate ext_amount from pubAmount:
         let mut field = FqParameters::MODULUS;
@@ -66,33 +94,35 @@ pub fn check_external_amount(
         // field.0[0] is the positive value
         if field.0[1] != 0 || field.0[2] != 0 || field.0[3] != 0 {
             msg!("Public amount is larger than u64.");
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(LightError::PublicAmountTooLarge.into());
         }
         let pub_amount_fits_i64 = i64::try_from(pub_amount.0[0]);
         if pub_amount_fits_i64.is_err() {
             msg!("Public amount is larger than i64.");
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(LightError::PublicAmountTooLarge.into());
         }
 
-        if field.0[0]
-            != u64::try_from(-ext_amount)
-                .unwrap()
-                .checked_add(relayer_fee)
-                .unwrap()
-        {
+        // i64::MIN cannot be negated
+        let withdrawal_amount = ext_amount
+            .checked_neg()
+            .and_then(|amount| u64::try_from(amount).ok())
+            .ok_or(LightError::ArithmeticOverflow)?;
+        let protocol_fee = fee_schedule.fee(withdrawal_amount)?;
+        let expected_withdrawal_amount = withdrawal_amount
+            .checked_add(relayer_fee)
+            .and_then(|amount| amount.checked_add(protocol_fee))
+            .ok_or(LightError::ArithmeticOverflow)?;
+        if field.0[0] != expected_withdrawal_amount {
             msg!(
                 "Withdrawal invalid external amount: {} != {}",
-                pub_amount.0[0],
-                relayer_fee + u64::try_from(-ext_amount).unwrap()
+                field.0[0],
+                expected_withdrawal_amount
             );
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(LightError::InvalidWithdrawalAmount.into());
         }
-        Ok(((-ext_amount).try_into().unwrap(), relayer_fee))
-    } else if ext_amount == 0 {
-        Ok((ext_amount.try_into().unwrap(), relayer_fee))
+        Ok((withdrawal_amount, relayer_fee, protocol_fee))
     } else {
-        msg!("Invalid state checking external amount.");
-        Err(ProgramError::InvalidInstructionData)
+        Ok((0, relayer_fee, 0))
     }
 }
 
@@ -152,53 +182,63 @@ pub fn create_and_try_initialize_tmp_storage_pda(
     let system_program_info = next_account_info(account)?;
     let rent_sysvar_info = next_account_info(account)?;
     let rent = &Rent::from_account_info(rent_sysvar_info)?;
-
-    create_and_check_pda(
+    let global_config_account = next_account_info(account)?;
+    check_send_data_len(_instruction_data)?;
+    // Stop new transactions which are not allowed in the current program mode.
+    let ext_amount = parse_ext_amount(&_instruction_data[512..520])?;
+    check_program_mode(program_id, global_config_account, ext_amount)?;
+
+    // The pda is bound to the signer, the signer has to be the relayer committed
+    // to in the tx integrity hash (try_initialize_tmp_storage_pda).
+    create_and_check_pda_with_seeds(
         program_id,
         signer_account,
         account_main,
         system_program_info,
         rent,
-        &_instruction_data[64..96],
-        &b"storage"[..],
+        &tmp_storage_seeds(&_instruction_data[64..96], signer_account.key),
         number_storage_bytes, //bytes
         lamports,             //lamports
         rent_exempt,          //rent_exempt
     )?;
-    try_initialize_tmp_storage_pda(account_main, _instruction_data, signer_account.key)
+    try_initialize_tmp_storage_pda(account_main, _instruction_data, signer_account.key)?;
+    // The hand-off pda is only passed if the send data has a relayer hand-off commitment.
+    if let (_, Some(relayer_handoff)) = send_data_commitments(_instruction_data)? {
+        let relayer_handoff_account = next_account_info(account)?;
+        create_relayer_handoff(
+            program_id,
+            signer_account,
+            account_main,
+            relayer_handoff_account,
+            system_program_info,
+            rent,
+            relayer_handoff,
+        )?;
+    }
+    Ok(())
 }
 
+// The asset id is appended for SPL trees. Native SOL trees keep the hash layout
+// from before assets were introduced, their asset id is implicit.
+// The post withdraw call commitment and the relayer hand-off commitment are appended last
+// if there are any.
 pub fn check_tx_integrity_hash(
-    recipient: Vec<u8>,
-    ext_amount: Vec<u8>,
-    relayer: Vec<u8>,
-    fee: Vec<u8>,
-    tx_integrity_hash: Vec<u8>,
-    merkle_tree_index: u8,
-    encrypted_utxos: Vec<u8>,
-    merkle_tree_pda_pubkey: Vec<u8>,
+    ext_data: &ExtData,
+    tx_integrity_hash: &[u8],
+    asset: &Asset,
+    post_withdraw_call: Option<&[u8]>,
+    relayer_handoff: Option<&[u8]>,
 ) -> Result<(), ProgramError> {
-    let input = [
-        recipient,
-        ext_amount,
-        relayer,
-        fee,
-        merkle_tree_pda_pubkey,
-        vec![merkle_tree_index],
-        encrypted_utxos,
-    ]
-    .concat();
-    // msg!("integrity_hash inputs: {:?}", input);
-    let hash = solana_program::keccak::hash(&input[..]).try_to_vec()?;
+    let hash = ext_data.hash(asset, post_withdraw_call, relayer_handoff)?;
     msg!("hash computed {:?}", hash);
 
-    if Fq::from_be_bytes_mod_order(&hash[..]) != Fq::from_le_bytes_mod_order(&tx_integrity_hash) {
+    if Fq::from_le_bytes_mod_order(&hash) != Fq::from_le_bytes_mod_order(tx_integrity_hash) {
         msg!(
             "tx_integrity_hash verification failed.{:?} != {:?}",
             &hash[..],
             &tx_integrity_hash
         );
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(LightError::InvalidTxIntegrityHash.into());
     }
     Ok(())
 }
@@ -211,14 +251,13 @@ pub fn check_and_insert_nullifier<'a, 'b>(
     rent: &Rent,
     _instruction_data: &[u8],
 ) -> Result<u8, ProgramError> {
-    create_and_check_pda(
+    create_and_check_pda_with_seeds(
         program_id,
         signer_account,
         nullifier_account,
         system_program,
         rent,
-        _instruction_data,
-        &b"nf"[..],
+        &nullifier_seeds(_instruction_data),
         2u64, //nullifier pda length
         0u64, //lamports
         true, //rent_exempt
@@ -241,7 +280,7 @@ pub fn close_account(
     msg!("dest_starting_lamports {}", dest_starting_lamports);
     **dest_account.lamports.borrow_mut() = dest_starting_lamports
         .checked_add(account.lamports())
-        .ok_or(ProgramError::InvalidAccountData)?;
+        .ok_or(LightError::ArithmeticOverflow)?;
     **account.lamports.borrow_mut() = 0;
     msg!("dest_ending_lamports {}", dest_account.lamports());
 
@@ -256,12 +295,12 @@ pub fn sol_transfer(
     let from_starting_lamports = from_account.lamports();
     **from_account.lamports.borrow_mut() = from_starting_lamports
         .checked_sub(amount)
-        .ok_or(ProgramError::InvalidAccountData)?;
+        .ok_or(ProgramError::InsufficientFunds)?;
 
     let dest_starting_lamports = dest_account.lamports();
     **dest_account.lamports.borrow_mut() = dest_starting_lamports
         .checked_add(amount)
-        .ok_or(ProgramError::InvalidAccountData)?;
+        .ok_or(LightError::ArithmeticOverflow)?;
     Ok(())
 }
 
@@ -277,51 +316,100 @@ pub fn create_and_check_pda<'a, 'b>(
     lamports: u64,
     rent_exempt: bool,
 ) -> Result<(), ProgramError> {
-    let derived_pubkey =
-        Pubkey::find_program_address(&[_instruction_data, domain_separation_seed], program_id);
+    create_and_check_pda_with_seeds(
+        program_id,
+        signer_account,
+        passed_in_pda,
+        system_program,
+        rent,
+        &[_instruction_data, domain_separation_seed],
+        number_storage_bytes,
+        lamports,
+        rent_exempt,
+    )
+}
+
+pub fn create_and_check_pda_with_seeds<'a, 'b>(
+    program_id: &Pubkey,
+    signer_account: &'a AccountInfo<'b>,
+    passed_in_pda: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    rent: &Rent,
+    seeds: &[&[u8]],
+    number_storage_bytes: u64,
+    lamports: u64,
+    rent_exempt: bool,
+) -> Result<(), ProgramError> {
+    let derived_pubkey = Pubkey::find_program_address(seeds, program_id);
 
     if derived_pubkey.0 != *passed_in_pda.key {
         msg!("Passed-in pda pubkey != on-chain derived pda pubkey.");
         msg!("On-chain derived pda pubkey {:?}", derived_pubkey);
         msg!("Passed-in pda pubkey {:?}", *passed_in_pda.key);
-        msg!("Instruction data seed  {:?}", _instruction_data);
-        return Err(ProgramError::InvalidInstructionData);
+        msg!("Seeds {:?}", seeds);
+        return Err(LightError::InvalidPdaAddress.into());
     }
 
-    let mut account_lamports = lamports;
-    if rent_exempt {
-        account_lamports += rent.minimum_balance(number_storage_bytes.try_into().unwrap());
+    let data_len =
+        usize::try_from(number_storage_bytes).map_err(|_| ProgramError::InvalidArgument)?;
+    let rent_lamports = if rent_exempt {
+        rent.minimum_balance(data_len)
     } else {
-        account_lamports += rent.minimum_balance(number_storage_bytes.try_into().unwrap()) / 365;
-    }
+        rent.minimum_balance(data_len) / 365
+    };
+    let account_lamports = lamports
+        .checked_add(rent_lamports)
+        .ok_or(LightError::ArithmeticOverflow)?;
     msg!("account_lamports: {}", account_lamports);
-    invoke_signed(
-        &system_instruction::create_account(
-            signer_account.key,   // from_pubkey
-            passed_in_pda.key,    // to_pubkey
-            account_lamports,     // lamports
-            number_storage_bytes, // space
-            program_id,           // owner
-        ),
-        &[
-            signer_account.clone(),
-            passed_in_pda.clone(),
-            system_program.clone(),
-        ],
-        &[&[
-            _instruction_data,
-            domain_separation_seed,
-            &[derived_pubkey.1],
-        ]],
-    )?;
+    let bump = [derived_pubkey.1];
+    let signer_seeds = [seeds, &[&bump[..]]].concat();
+    let pda_accounts = [
+        signer_account.clone(),
+        passed_in_pda.clone(),
+        system_program.clone(),
+    ];
+    let current_lamports = passed_in_pda.lamports();
+    if current_lamports == 0 {
+        invoke_signed(
+            &system_instruction::create_account(
+                signer_account.key,   // from_pubkey
+                passed_in_pda.key,    // to_pubkey
+                account_lamports,     // lamports
+                number_storage_bytes, // space
+                program_id,           // owner
+            ),
+            &pda_accounts,
+            &[&signer_seeds],
+        )?;
+    } else {
+        // create_account fails if the address holds lamports, anyone can transfer
+        // lamports to a pda address before it is created. The pda is created
+        // by topping up the lamports, allocating and assigning instead.
+        msg!("Pda address holds {} lamports.", current_lamports);
+        if account_lamports > current_lamports {
+            invoke(
+                &system_instruction::transfer(
+                    signer_account.key,
+                    passed_in_pda.key,
+                    account_lamports - current_lamports,
+                ),
+                &pda_accounts,
+            )?;
+        }
+        invoke_signed(
+            &system_instruction::allocate(passed_in_pda.key, number_storage_bytes),
+            &pda_accounts,
+            &[&signer_seeds],
+        )?;
+        invoke_signed(
+            &system_instruction::assign(passed_in_pda.key, program_id),
+            &pda_accounts,
+            &[&signer_seeds],
+        )?;
+    }
 
     // Check for rent exemption
-    if rent_exempt
-        && !rent.is_exempt(
-            **passed_in_pda.lamports.borrow(),
-            number_storage_bytes.try_into().unwrap(),
-        )
-    {
+    if rent_exempt && !rent.is_exempt(**passed_in_pda.lamports.borrow(), data_len) {
         msg!("Account is not rent exempt.");
         return Err(ProgramError::AccountNotRentExempt);
     }
@@ -332,12 +420,51 @@ pub const PREPARED_INPUTS_RANGE_START: usize = 0;
 pub const PREPARED_INPUTS_RANGE_END: usize = 224;
 pub const PROOF_A_B_C_RANGE_START: usize = 224;
 pub const PROOF_A_B_C_RANGE_END: usize = 480;
+// Length of the send data, the encrypted utxos start at byte 593.
+pub const SEND_DATA_LEN: usize = 593 + ENCRYPTED_UTXOS_LENGTH;
+
+// Send data is read at fixed offsets, shorter data is rejected before slicing.
+pub fn check_send_data_len(_instruction_data: &[u8]) -> Result<(), ProgramError> {
+    if _instruction_data.len() < SEND_DATA_LEN {
+        msg!(
+            "Instruction data too short. {} < {}",
+            _instruction_data.len(),
+            SEND_DATA_LEN
+        );
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    Ok(())
+}
+
+// Splits the optional commitments after the send data, the post withdraw call commitment
+// and the relayer hand-off commitment. They are told apart by their length.
+pub fn send_data_commitments(
+    _instruction_data: &[u8],
+) -> Result<(Option<&[u8]>, Option<&[u8]>), ProgramError> {
+    check_send_data_len(_instruction_data)?;
+    let commitments = &_instruction_data[SEND_DATA_LEN..];
+    match commitments.len() {
+        0 => Ok((None, None)),
+        POST_WITHDRAW_CALL_COMMITMENT_LEN => Ok((Some(commitments), None)),
+        RELAYER_HANDOFF_COMMITMENT_LEN => Ok((None, Some(commitments))),
+        len if len == POST_WITHDRAW_CALL_COMMITMENT_LEN + RELAYER_HANDOFF_COMMITMENT_LEN => {
+            let (post_withdraw_call, relayer_handoff) =
+                commitments.split_at(POST_WITHDRAW_CALL_COMMITMENT_LEN);
+            Ok((Some(post_withdraw_call), Some(relayer_handoff)))
+        }
+        len => {
+            msg!("Invalid commitments after the send data, length {}.", len);
+            Err(ProgramError::InvalidInstructionData)
+        }
+    }
+}
 
 pub fn try_initialize_tmp_storage_pda(
     tmp_storage_pda: &AccountInfo,
     _instruction_data: &[u8],
     signing_address: &Pubkey,
 ) -> Result<(), ProgramError> {
+    check_send_data_len(_instruction_data)?;
     msg!(
         "Initializing tmp_storage_pda: {}",
         tmp_storage_pda.data.borrow().len()
@@ -346,6 +473,7 @@ pub fn try_initialize_tmp_storage_pda(
     let mut tmp_storage_pda_data = ChecksAndTransferState::unpack(&tmp_storage_pda.data.borrow())?;
     tmp_storage_pda_data.account_type = TMP_STORAGE_ACCOUNT_TYPE;
 
+    check_proof_bytes(&_instruction_data[PROOF_A_B_C_RANGE_START..PROOF_A_B_C_RANGE_END])?;
     let mut groth16_processor = Groth16Processor::new(
         tmp_storage_pda,
         tmp_storage_pda_data.current_instruction_index,
@@ -366,7 +494,8 @@ pub fn try_initialize_tmp_storage_pda(
     let leaf_right = &_instruction_data[160..192];
     let leaf_left = &_instruction_data[192..224];
 
-    let encrypted_utxos = &_instruction_data[593..593 + ENCRYPTED_UTXOS_LENGTH];
+    let ext_data = ExtData::unpack(&_instruction_data[EXT_DATA_RANGE_START..SEND_DATA_LEN])?;
+    let encrypted_utxos = &ext_data.encrypted_utxos[..];
     tmp_storage_pda_data.proof_a_b_c_leaves_and_nullifiers = [
         _instruction_data[PROOF_A_B_C_RANGE_START..PROOF_A_B_C_RANGE_END].to_vec(),
         leaf_right.to_vec(),
@@ -376,51 +505,56 @@ pub fn try_initialize_tmp_storage_pda(
         encrypted_utxos.to_vec(),
     ]
     .concat();
-    tmp_storage_pda_data.recipient = _instruction_data[480..512].to_vec();
-    tmp_storage_pda_data.ext_amount = _instruction_data[512..520].to_vec();
-    let relayer = _instruction_data[520..552].to_vec();
+    tmp_storage_pda_data.recipient = ext_data.recipient.to_bytes().to_vec();
+    tmp_storage_pda_data.ext_amount = ext_data.ext_amount.to_le_bytes().to_vec();
 
     // Check that relayer in integrity hash == signer.
     // In case of deposit the depositor is their own relayer
-    if *signing_address != Pubkey::new(&relayer) {
+    if *signing_address != ext_data.relayer {
         msg!(
             "Specified relayer is not signer. {:?} != {:?}",
             *signing_address,
-            Pubkey::new(&relayer)
+            ext_data.relayer
         );
-        return Err(ProgramError::InvalidAccountData);
+        return Err(LightError::InvalidRelayer.into());
     }
 
-    let fee = _instruction_data[552..560].to_vec();
-    tmp_storage_pda_data.relayer_fee = fee;
+    tmp_storage_pda_data.relayer_fee = ext_data.fee.to_le_bytes().to_vec();
+    tmp_storage_pda_data.merkle_tree_index = ext_data.merkle_tree_index;
 
-    let merkle_tree_pda_pubkey = _instruction_data[560..592].to_vec();
-    tmp_storage_pda_data.merkle_tree_index = _instruction_data[592];
-
-    if merkle_tree_pda_pubkey
-        != MERKLE_TREE_ACC_BYTES_ARRAY
-            [<usize as TryFrom<u8>>::try_from(tmp_storage_pda_data.merkle_tree_index).unwrap()]
-        .0
-        .to_vec()
+    if ext_data.merkle_tree.to_bytes()
+        != get_merkle_tree_acc_bytes(tmp_storage_pda_data.merkle_tree_index)?.0
     {
         msg!(
             "Merkle tree in tx integrity hash not whitelisted or wrong ID. is: {:?}",
-            merkle_tree_pda_pubkey,
+            ext_data.merkle_tree,
         );
-        return Err(ProgramError::InvalidAccountData);
+        return Err(LightError::InvalidMerkleTree.into());
     }
+    // Wallets of the Merkle tree have to be able to decrypt the utxos.
+    check_encrypted_utxos_version(
+        get_merkle_tree_encrypted_utxos_version(tmp_storage_pda_data.merkle_tree_index)?,
+        encrypted_utxos,
+    )?;
 
+    let (post_withdraw_call, relayer_handoff) = send_data_commitments(_instruction_data)?;
+    tmp_storage_pda_data.post_withdraw_call_hash = commitment_hash(post_withdraw_call).to_vec();
+
+    let asset = get_merkle_tree_asset(tmp_storage_pda_data.merkle_tree_index)?;
+    // SOL deposits are funded from the signer of the last instruction, a relayer which
+    // took over would fund the deposit of the user.
+    if relayer_handoff.is_some() && asset == Asset::Sol && ext_data.ext_amount > 0 {
+        msg!("Relayer hand-off is not supported for SOL deposits.");
+        return Err(LightError::RelayerHandoffNotAllowed.into());
+    }
     check_tx_integrity_hash(
-        tmp_storage_pda_data.recipient.to_vec(),
-        tmp_storage_pda_data.ext_amount.to_vec(),
-        relayer.to_vec(),
-        tmp_storage_pda_data.relayer_fee.to_vec(),
-        tmp_storage_pda_data.tx_integrity_hash.to_vec(),
-        tmp_storage_pda_data.merkle_tree_index,
-        encrypted_utxos.to_vec(),
-        merkle_tree_pda_pubkey,
+        &ext_data,
+        &tmp_storage_pda_data.tx_integrity_hash,
+        &asset,
+        post_withdraw_call,
+        relayer_handoff,
     )?;
-    for i in 0..11 {
+    for i in 0..12 {
         tmp_storage_pda_data.changed_constants[i] = true;
     }
     tmp_storage_pda_data.current_instruction_index += 1;
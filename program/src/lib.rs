@@ -1,10 +1,29 @@
 #![allow(clippy::type_complexity, clippy::ptr_arg, clippy::too_many_arguments)]
+// Instruction handlers return errors instead of aborting. Unit tests may unwrap.
+#![cfg_attr(
+    not(test),
+    deny(clippy::unwrap_used, clippy::expect_used, clippy::panic)
+)]
 
+pub mod admin_config;
+pub mod asset;
+pub mod cpi;
+pub mod encrypted_utxos;
+pub mod error;
+pub mod event;
+pub mod global_config;
 pub mod groth16_verifier;
 pub mod instructions;
+pub mod merkle_tree_limits;
+pub mod migration;
 pub mod nullifier_state;
+pub mod pda;
 pub mod poseidon_merkle_tree;
+pub mod post_withdraw_call;
 pub mod processor;
+pub mod protocol_fee;
+pub mod relayer_handoff;
+pub mod send_data;
 pub mod state;
 pub mod user_account;
 pub mod utils;
@@ -30,16 +49,38 @@ security_txt! {
     source_code: "https://github.com/Lightprotocol/light-protocol-program"
 }
 
-use crate::config::{ENCRYPTED_UTXOS_LENGTH, MERKLE_TREE_INIT_AUTHORITY};
+use crate::admin_config::{accept_authority, check_admin, propose_authority, set_admin_multisig};
+use crate::config::ENCRYPTED_UTXOS_LENGTH;
+use crate::error::LightError;
+use crate::global_config::{set_guardian, set_program_mode};
 use crate::groth16_verifier::groth16_processor::Groth16Processor;
 use crate::instructions::create_and_try_initialize_tmp_storage_pda;
+use crate::merkle_tree_limits::set_merkle_tree_limits;
+use crate::migration::migrate_account;
 use crate::poseidon_merkle_tree::processor::MerkleTreeProcessor;
+use crate::protocol_fee::set_protocol_fee_config;
+use crate::relayer_handoff::take_over_computation;
 use crate::state::InstructionIndex;
 use crate::user_account::instructions::initialize_user_account;
 use crate::utils::config;
 
 #[cfg(not(feature = "no-entrypoint"))]
-entrypoint!(process_instruction);
+entrypoint!(program_entrypoint);
+
+#[cfg(not(feature = "no-entrypoint"))]
+fn program_entrypoint(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    use solana_program::program_error::PrintProgramError;
+    if let Err(error) = process_instruction(program_id, accounts, instruction_data) {
+        // Logs the error message of LightErrors before returning the error code.
+        error.print::<LightError>();
+        return Err(error);
+    }
+    Ok(())
+}
 
 #[allow(clippy::clone_double_ref)]
 pub fn process_instruction(
@@ -53,17 +94,25 @@ pub fn process_instruction(
     let signer_account = next_account_info(account)?;
     if !signer_account.is_signer {
         msg!("signer account needs to be passed in first place");
-        return Err(ProgramError::IllegalOwner);
+        return Err(LightError::MissingSigner.into());
     }
+    // Admin instructions are signed by the admin authority (admin_config.rs). The admin config pda
+    // is passed with the accounts of the instruction, signers of an admin multisig after them.
     // Initialize new merkle tree account.
+    // Accounts:
+    // 1. `[writable]` merkle tree
+    // 2. `[]` rent_sysvar_info
+    // 3. `[]` admin config pda
     if _instruction_data.len() >= 9 && _instruction_data[8] == 240 {
         let merkle_tree_storage_acc = next_account_info(account)?;
-        // Check whether signer is merkle_tree_init_authority.
-        if *signer_account.key != Pubkey::new(&MERKLE_TREE_INIT_AUTHORITY) {
-            msg!("Signer is not merkle tree init authority.");
-            return Err(ProgramError::IllegalOwner);
-        }
         let rent_sysvar_info = next_account_info(account)?;
+        let admin_config_account = next_account_info(account)?;
+        check_admin(
+            program_id,
+            admin_config_account,
+            signer_account,
+            account.as_slice(),
+        )?;
         let rent = &Rent::from_account_info(rent_sysvar_info)?;
         if !rent.is_exempt(
             **merkle_tree_storage_acc.lamports.borrow(),
@@ -77,6 +126,190 @@ pub fn process_instruction(
         merkle_tree_processor
             .initialize_new_merkle_tree_from_bytes(&config::INIT_BYTES_MERKLE_TREE_18[..])
     }
+    // Migrate a Merkle tree or user account to the current layout version.
+    // Accounts:
+    // 1. `[writable]` account to migrate
+    // 2. `[]` system_program
+    // 3. `[]` rent_sysvar_info
+    // 4. `[]` admin config pda
+    else if _instruction_data.len() >= 9 && _instruction_data[8] == 241 {
+        let account_to_migrate = next_account_info(account)?;
+        let system_program_info = next_account_info(account)?;
+        let rent_sysvar_info = next_account_info(account)?;
+        let admin_config_account = next_account_info(account)?;
+        check_admin(
+            program_id,
+            admin_config_account,
+            signer_account,
+            account.as_slice(),
+        )?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+        migrate_account(
+            program_id,
+            signer_account,
+            account_to_migrate,
+            system_program_info,
+            rent,
+        )
+    }
+    // Create or update the protocol fee config of an asset.
+    // Accounts:
+    // 1. `[writable]` protocol fee config pda
+    // 2. `[]` system_program
+    // 3. `[]` rent_sysvar_info
+    // 4. `[]` admin config pda
+    else if _instruction_data.len() >= 9 && _instruction_data[8] == 242 {
+        let protocol_fee_config_account = next_account_info(account)?;
+        let system_program_info = next_account_info(account)?;
+        let rent_sysvar_info = next_account_info(account)?;
+        let admin_config_account = next_account_info(account)?;
+        check_admin(
+            program_id,
+            admin_config_account,
+            signer_account,
+            account.as_slice(),
+        )?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+        set_protocol_fee_config(
+            program_id,
+            signer_account,
+            protocol_fee_config_account,
+            system_program_info,
+            rent,
+            &_instruction_data[9..],
+        )
+    }
+    // Create or update the deposit and withdrawal limits of a Merkle tree.
+    // Accounts:
+    // 1. `[writable]` Merkle tree limits pda
+    // 2. `[]` system_program
+    // 3. `[]` rent_sysvar_info
+    // 4. `[]` admin config pda
+    else if _instruction_data.len() >= 9 && _instruction_data[8] == 243 {
+        let merkle_tree_limits_account = next_account_info(account)?;
+        let system_program_info = next_account_info(account)?;
+        let rent_sysvar_info = next_account_info(account)?;
+        let admin_config_account = next_account_info(account)?;
+        check_admin(
+            program_id,
+            admin_config_account,
+            signer_account,
+            account.as_slice(),
+        )?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+        set_merkle_tree_limits(
+            program_id,
+            signer_account,
+            merkle_tree_limits_account,
+            system_program_info,
+            rent,
+            &_instruction_data[9..],
+        )
+    }
+    // Create the global config or replace the guardian.
+    // Accounts:
+    // 1. `[writable]` global config pda
+    // 2. `[]` system_program
+    // 3. `[]` rent_sysvar_info
+    // 4. `[]` admin config pda
+    else if _instruction_data.len() >= 9 && _instruction_data[8] == 244 {
+        let global_config_account = next_account_info(account)?;
+        let system_program_info = next_account_info(account)?;
+        let rent_sysvar_info = next_account_info(account)?;
+        let admin_config_account = next_account_info(account)?;
+        check_admin(
+            program_id,
+            admin_config_account,
+            signer_account,
+            account.as_slice(),
+        )?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+        set_guardian(
+            program_id,
+            signer_account,
+            global_config_account,
+            system_program_info,
+            rent,
+            &_instruction_data[9..],
+        )
+    }
+    // Switch the program mode, signed by the guardian.
+    // Accounts:
+    // 1. `[writable]` global config pda
+    else if _instruction_data.len() >= 9 && _instruction_data[8] == 245 {
+        let global_config_account = next_account_info(account)?;
+        set_program_mode(
+            program_id,
+            signer_account,
+            global_config_account,
+            &_instruction_data[9..],
+        )
+    }
+    // Propose a new admin authority, creates the admin config.
+    // Accounts:
+    // 1. `[writable]` admin config pda
+    // 2. `[]` system_program
+    // 3. `[]` rent_sysvar_info
+    else if _instruction_data.len() >= 9 && _instruction_data[8] == 246 {
+        let admin_config_account = next_account_info(account)?;
+        let system_program_info = next_account_info(account)?;
+        let rent_sysvar_info = next_account_info(account)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+        propose_authority(
+            program_id,
+            signer_account,
+            admin_config_account,
+            system_program_info,
+            rent,
+            account.as_slice(),
+            &_instruction_data[9..],
+        )
+    }
+    // Accept the admin authority, signed by the proposed authority.
+    // Accounts:
+    // 1. `[writable]` admin config pda
+    else if _instruction_data.len() >= 9 && _instruction_data[8] == 247 {
+        let admin_config_account = next_account_info(account)?;
+        accept_authority(program_id, signer_account, admin_config_account)
+    }
+    // Set the admin multisig, creates the admin config.
+    // Accounts:
+    // 1. `[writable]` admin config pda
+    // 2. `[]` system_program
+    // 3. `[]` rent_sysvar_info
+    else if _instruction_data.len() >= 9 && _instruction_data[8] == 248 {
+        let admin_config_account = next_account_info(account)?;
+        let system_program_info = next_account_info(account)?;
+        let rent_sysvar_info = next_account_info(account)?;
+        let rent = &Rent::from_account_info(rent_sysvar_info)?;
+        set_admin_multisig(
+            program_id,
+            signer_account,
+            admin_config_account,
+            system_program_info,
+            rent,
+            account.as_slice(),
+            &_instruction_data[9..],
+        )
+    }
+    // Take over the computation of a tmp storage pda, signed by another relayer
+    // (relayer_handoff.rs). Closes the hand-off pda once the transaction is complete.
+    // Accounts:
+    // 1. `[writable]` tmp_storage_pda
+    // 2. `[writable]` relayer hand-off pda
+    // 3. `[writable]` relayer which sent the data, receives the hand-off pda lamports
+    else if _instruction_data.len() >= 9 && _instruction_data[8] == 249 {
+        let tmp_storage_pda = next_account_info(account)?;
+        let relayer_handoff_account = next_account_info(account)?;
+        let payer_account = next_account_info(account)?;
+        take_over_computation(
+            program_id,
+            signer_account,
+            tmp_storage_pda,
+            relayer_handoff_account,
+            payer_account,
+        )
+    }
     // Initialize new onchain user account.
     else if _instruction_data.len() >= 9 && _instruction_data[8] == 100 {
         let user_account = next_account_info(account)?;
@@ -106,6 +339,12 @@ pub fn process_instruction(
                 // updating the merkle tree.
                 // All data used during computation is passed in as instruction_data with this instruction.
                 // No subsequent instructions read instruction_data.
+                // Accounts:
+                // 1. `[writable]` tmp_storage_pda
+                // 2. `[]` system_program
+                // 3. `[]` rent_sysvar_info
+                // 4. `[]` global_config_pda, does not have to exist
+                // 5. `[writable]` relayer hand-off pda, only with a relayer hand-off commitment
                 // instruction_data:
                 //    [ root,
                 //      public amount,
@@ -126,22 +365,25 @@ pub fn process_instruction(
                     3900u64 + ENCRYPTED_UTXOS_LENGTH as u64, // bytes
                     0_u64,                                   // lamports
                     true,                                    // rent_exempt
-                    &_instruction_data[9..], // Data starts after instruction identifier.
+                    // Data starts after instruction identifier.
+                    _instruction_data
+                        .get(9..)
+                        .ok_or(ProgramError::InvalidInstructionData)?,
                 )
             }
-            Err(_) => Err(ProgramError::InvalidInstructionData),
+            Err(e) => Err(e),
             Ok(tmp_storage_pda_data) => {
                 // Check signer before starting a compute instruction.
                 if tmp_storage_pda_data.signer_pubkey != *signer_account.key {
                     msg!("Wrong signer.");
-                    Err(ProgramError::IllegalOwner)
+                    Err(LightError::InvalidSigner.into())
                 } else if *program_id != *tmp_storage_pda.owner {
                     msg!(
                         "Wrong owner. {:?} != {:?}",
                         *program_id,
                         *tmp_storage_pda.owner
                     );
-                    Err(ProgramError::IllegalOwner)
+                    Err(LightError::InvalidAccountOwner.into())
                 } else {
                     msg!(
                         "current ix index: {}",
@@ -168,8 +410,16 @@ pub fn process_instruction(
                     // 9. `[]` token_program_account
                     // 10. `[]` rent_sysvar_info
                     // 11. `[]` authority
-                    // 12. `[writable]` user_pda_token
-                    // 13. `[writable]` relayer_pda_token
+                    // 12. `[]` protocol_fee_config_pda
+                    // 13. `[writable]` treasury
+                    // 14. `[writable]` merkle_tree_limits_pda
+                    // 15. `[]` global_config_pda
+                    // 16. `[writable]` user_pda_token, the recipient for withdrawals
+                    // 17. `[writable]` relayer_pda_token
+                    // 18.. post withdraw call program and accounts, if there is a post withdraw call
+                    // 16 and 17 are always passed, unused slots (user_pda_token of transfers,
+                    // relayer_pda_token without relayer fee) take any account, i.e. the signer.
+                    // instruction_data: post withdraw call (borsh) after the selector
 
                     if tmp_storage_pda_data.current_instruction_index == ROOT_CHECK
                         || tmp_storage_pda_data.current_instruction_index
@@ -179,6 +429,7 @@ pub fn process_instruction(
                             program_id,
                             accounts,
                             tmp_storage_pda_data.current_instruction_index,
+                            _instruction_data,
                         )?;
                         Ok(())
                     }
@@ -207,7 +458,7 @@ pub fn process_instruction(
                         merkle_tree_processor.process_instruction(accounts)?;
                         Ok(())
                     } else {
-                        Err(ProgramError::InvalidArgument)
+                        Err(LightError::InvalidInstructionIndex.into())
                     }
                 }
             }
@@ -215,13 +466,18 @@ pub fn process_instruction(
     }
 }
 
-const ROOT_CHECK: usize = 1;
-const INSERT_LEAVES_NULLIFIER_AND_TRANSFER: usize = 1501;
-const VERIFICATION_END_INDEX: usize = 1266;
+pub const ROOT_CHECK: usize = 1;
+pub const INSERT_LEAVES_NULLIFIER_AND_TRANSFER: usize = 1501;
+pub const VERIFICATION_END_INDEX: usize = 1266;
+pub const LEAF_RIGHT_START: usize = 256;
+pub const LEAF_RIGHT_END: usize = 288;
+pub const LEAF_LEFT_START: usize = 288;
+pub const LEAF_LEFT_END: usize = 320;
 pub const NULLIFIER_0_START: usize = 320;
 pub const NULLIFIER_0_END: usize = 352;
 pub const NULLIFIER_1_START: usize = 352;
 pub const NULLIFIER_1_END: usize = 384;
+pub const ENCRYPTED_UTXOS_START: usize = 384;
 pub const TWO_LEAVES_PDA_SIZE: u64 = 106 + ENCRYPTED_UTXOS_LENGTH as u64;
 //instruction order
 pub const IX_ORDER: [u8; 1502] = [
@@ -0,0 +1,251 @@
+use crate::error::LightError;
+use crate::instructions::create_and_check_pda;
+use crate::utils::account_header::check_account_header;
+use crate::utils::config::{
+    get_merkle_tree_acc_bytes, MERKLE_TREE_LIMITS_ACCOUNT_TYPE, MERKLE_TREE_LIMITS_ACCOUNT_VERSION,
+};
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    account_info::AccountInfo,
+    msg,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+    sysvar::rent::Rent,
+};
+
+// Deposit and withdrawal limits of a Merkle tree, enforced in the last instruction (1501)
+// of deposits and withdrawals.
+// Every Merkle tree can have a limits pda derived from [merkle_tree_pubkey, b"limits"].
+// A limit of 0 is no limit, trees without limits pda are not limited.
+// - max_deposit: maximum ext_amount of a single deposit
+// - max_total_deposits: cap of MerkleTree.current_total_deposits, the amount held by the
+//   shielded pool of the tree. Deposits add the public amount, withdrawals subtract it.
+//   Deposits made before the total was tracked are not included.
+// - max_epoch_withdrawals: maximum amount withdrawn in an epoch of epoch_length slots,
+//   including relayer and protocol fees.
+pub const MERKLE_TREE_LIMITS_SEED: &[u8] = b"limits";
+// merkle_tree_index, max_deposit, max_total_deposits, epoch_length, max_epoch_withdrawals
+pub const SET_MERKLE_TREE_LIMITS_DATA_LEN: usize = 1 + 8 + 8 + 8 + 8;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MerkleTreeLimits {
+    pub is_initialized: bool,
+    pub account_type: u8,
+    pub merkle_tree_pubkey: Pubkey,
+    pub max_deposit: u64,
+    pub max_total_deposits: u64,
+    pub epoch_length: u64,
+    pub max_epoch_withdrawals: u64,
+    pub current_epoch: u64,
+    pub epoch_withdrawals: u64,
+}
+
+impl Sealed for MerkleTreeLimits {}
+impl IsInitialized for MerkleTreeLimits {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for MerkleTreeLimits {
+    const LEN: usize = 82;
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, MerkleTreeLimits::LEN];
+        let (
+            version,
+            account_type,
+            merkle_tree_pubkey,
+            max_deposit,
+            max_total_deposits,
+            epoch_length,
+            max_epoch_withdrawals,
+            current_epoch,
+            epoch_withdrawals,
+        ) = array_refs![input, 1, 1, 32, 8, 8, 8, 8, 8, 8];
+        check_account_header(version[0], account_type[0], MERKLE_TREE_LIMITS_ACCOUNT_TYPE)?;
+        Ok(MerkleTreeLimits {
+            is_initialized: true,
+            account_type: MERKLE_TREE_LIMITS_ACCOUNT_TYPE,
+            merkle_tree_pubkey: Pubkey::new(merkle_tree_pubkey),
+            max_deposit: u64::from_le_bytes(*max_deposit),
+            max_total_deposits: u64::from_le_bytes(*max_total_deposits),
+            epoch_length: u64::from_le_bytes(*epoch_length),
+            max_epoch_withdrawals: u64::from_le_bytes(*max_epoch_withdrawals),
+            current_epoch: u64::from_le_bytes(*current_epoch),
+            epoch_withdrawals: u64::from_le_bytes(*epoch_withdrawals),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, MerkleTreeLimits::LEN];
+        let (
+            version_dst,
+            account_type_dst,
+            merkle_tree_pubkey_dst,
+            max_deposit_dst,
+            max_total_deposits_dst,
+            epoch_length_dst,
+            max_epoch_withdrawals_dst,
+            current_epoch_dst,
+            epoch_withdrawals_dst,
+        ) = mut_array_refs![dst, 1, 1, 32, 8, 8, 8, 8, 8, 8];
+        *version_dst = [MERKLE_TREE_LIMITS_ACCOUNT_VERSION];
+        *account_type_dst = [MERKLE_TREE_LIMITS_ACCOUNT_TYPE];
+        *merkle_tree_pubkey_dst = self.merkle_tree_pubkey.to_bytes();
+        *max_deposit_dst = self.max_deposit.to_le_bytes();
+        *max_total_deposits_dst = self.max_total_deposits.to_le_bytes();
+        *epoch_length_dst = self.epoch_length.to_le_bytes();
+        *max_epoch_withdrawals_dst = self.max_epoch_withdrawals.to_le_bytes();
+        *current_epoch_dst = self.current_epoch.to_le_bytes();
+        *epoch_withdrawals_dst = self.epoch_withdrawals.to_le_bytes();
+    }
+}
+
+impl MerkleTreeLimits {
+    // deposit_amount is the ext_amount, total_deposits the tree total including this deposit.
+    pub fn check_deposit(
+        &self,
+        deposit_amount: u64,
+        total_deposits: u64,
+    ) -> Result<(), ProgramError> {
+        if self.max_deposit != 0 && deposit_amount > self.max_deposit {
+            msg!(
+                "Deposit {} > max deposit {}.",
+                deposit_amount,
+                self.max_deposit
+            );
+            return Err(LightError::DepositLimitExceeded.into());
+        }
+        if self.max_total_deposits != 0 && total_deposits > self.max_total_deposits {
+            msg!(
+                "Total deposits {} > max total deposits {}.",
+                total_deposits,
+                self.max_total_deposits
+            );
+            return Err(LightError::TotalDepositsLimitExceeded.into());
+        }
+        Ok(())
+    }
+
+    // Adds a withdrawal to the withdrawals of the epoch of slot.
+    pub fn check_and_add_withdrawal(
+        &mut self,
+        withdrawal_amount: u64,
+        slot: u64,
+    ) -> Result<(), ProgramError> {
+        if self.epoch_length == 0 || self.max_epoch_withdrawals == 0 {
+            return Ok(());
+        }
+        let epoch = slot / self.epoch_length;
+        if epoch != self.current_epoch {
+            self.current_epoch = epoch;
+            self.epoch_withdrawals = 0;
+        }
+        let epoch_withdrawals = self
+            .epoch_withdrawals
+            .checked_add(withdrawal_amount)
+            .ok_or(LightError::ArithmeticOverflow)?;
+        if epoch_withdrawals > self.max_epoch_withdrawals {
+            msg!(
+                "Epoch withdrawals {} > max epoch withdrawals {}.",
+                epoch_withdrawals,
+                self.max_epoch_withdrawals
+            );
+            return Err(LightError::WithdrawalLimitExceeded.into());
+        }
+        self.epoch_withdrawals = epoch_withdrawals;
+        Ok(())
+    }
+}
+
+pub fn find_merkle_tree_limits(program_id: &Pubkey, merkle_tree_pubkey: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[&merkle_tree_pubkey.to_bytes(), MERKLE_TREE_LIMITS_SEED],
+        program_id,
+    )
+}
+
+// Loads the limits of the Merkle tree in the last instruction.
+// Returns None if no limits were set for the Merkle tree.
+pub fn load_merkle_tree_limits(
+    program_id: &Pubkey,
+    merkle_tree_pubkey: &Pubkey,
+    merkle_tree_limits_account: &AccountInfo,
+) -> Result<Option<MerkleTreeLimits>, ProgramError> {
+    if *merkle_tree_limits_account.key != find_merkle_tree_limits(program_id, merkle_tree_pubkey).0
+    {
+        msg!("Passed-in limits pda is not the limits pda of the Merkle tree.");
+        return Err(LightError::InvalidPdaAddress.into());
+    }
+    if *merkle_tree_limits_account.owner != *program_id
+        || merkle_tree_limits_account.data_is_empty()
+    {
+        return Ok(None);
+    }
+    Ok(Some(MerkleTreeLimits::unpack(
+        &merkle_tree_limits_account.data.borrow(),
+    )?))
+}
+
+// Creates the limits pda of a Merkle tree or updates the limits of an existing one.
+// instruction_data: [merkle_tree_index, max_deposit, max_total_deposits, epoch_length,
+// max_epoch_withdrawals], u64s little endian
+// The withdrawals of the current epoch are kept when the limits are updated.
+pub fn set_merkle_tree_limits<'a, 'b>(
+    program_id: &Pubkey,
+    signer_account: &'a AccountInfo<'b>,
+    merkle_tree_limits_account: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    rent: &Rent,
+    instruction_data: &[u8],
+) -> Result<(), ProgramError> {
+    if instruction_data.len() < SET_MERKLE_TREE_LIMITS_DATA_LEN {
+        msg!("Merkle tree limits data too short.");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let data = array_ref![instruction_data, 0, SET_MERKLE_TREE_LIMITS_DATA_LEN];
+    let (merkle_tree_index, max_deposit, max_total_deposits, epoch_length, max_epoch_withdrawals) =
+        array_refs![data, 1, 8, 8, 8, 8];
+    let merkle_tree_pubkey = Pubkey::new(&get_merkle_tree_acc_bytes(merkle_tree_index[0])?.0);
+
+    let mut merkle_tree_limits = if *merkle_tree_limits_account.owner != *program_id {
+        msg!("Creating Merkle tree limits.");
+        create_and_check_pda(
+            program_id,
+            signer_account,
+            merkle_tree_limits_account,
+            system_program,
+            rent,
+            &merkle_tree_pubkey.to_bytes(),
+            MERKLE_TREE_LIMITS_SEED,
+            MerkleTreeLimits::LEN as u64, //bytes
+            0,                            //lamports
+            true,                         //rent_exempt
+        )?;
+        MerkleTreeLimits {
+            is_initialized: true,
+            account_type: MERKLE_TREE_LIMITS_ACCOUNT_TYPE,
+            merkle_tree_pubkey,
+            max_deposit: 0,
+            max_total_deposits: 0,
+            epoch_length: 0,
+            max_epoch_withdrawals: 0,
+            current_epoch: 0,
+            epoch_withdrawals: 0,
+        }
+    } else {
+        load_merkle_tree_limits(program_id, &merkle_tree_pubkey, merkle_tree_limits_account)?
+            .ok_or(ProgramError::UninitializedAccount)?
+    };
+    merkle_tree_limits.max_deposit = u64::from_le_bytes(*max_deposit);
+    merkle_tree_limits.max_total_deposits = u64::from_le_bytes(*max_total_deposits);
+    merkle_tree_limits.epoch_length = u64::from_le_bytes(*epoch_length);
+    merkle_tree_limits.max_epoch_withdrawals = u64::from_le_bytes(*max_epoch_withdrawals);
+    MerkleTreeLimits::pack_into_slice(
+        &merkle_tree_limits,
+        &mut merkle_tree_limits_account.data.borrow_mut(),
+    );
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use crate::error::LightError;
+use crate::poseidon_merkle_tree::state::MerkleTree;
+use crate::user_account::state::{UserAccount, SIZE_UTXO};
+use crate::utils::account_header::{account_header, ACCOUNT_HEADER_LEN};
+use crate::utils::config::{
+    MERKLE_TREE_ACCOUNT_TYPE, MERKLE_TREE_ACCOUNT_VERSION, USER_ACCOUNT_TYPE, USER_ACCOUNT_VERSION,
+};
+use solana_program::{
+    account_info::AccountInfo, msg, program::invoke, program_error::ProgramError,
+    program_pack::Pack, pubkey::Pubkey, system_instruction, sysvar::rent::Rent,
+};
+
+// User accounts were initialized with this account type before the header was validated.
+pub const LEGACY_USER_ACCOUNT_TYPE: u8 = 10;
+
+// Version 1 user accounts hold a single utxo: [header, owner_pubkey, enc_utxo].
+pub const USER_ACCOUNT_V1_LEN: usize = 34 + SIZE_UTXO;
+
+// Brings a Merkle tree or user account to the current layout version.
+// The account is resized to the current layout length, the signer tops up
+// the lamports needed to stay rent exempt.
+// Layout upgrades are applied one version at a time.
+pub fn migrate_account<'a, 'b>(
+    program_id: &Pubkey,
+    signer_account: &'a AccountInfo<'b>,
+    account: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    rent: &Rent,
+) -> Result<(), ProgramError> {
+    if *account.owner != *program_id {
+        msg!("Account to migrate is not owned by the program.");
+        return Err(LightError::InvalidAccountOwner.into());
+    }
+    let (version, account_type) = {
+        let data = account.data.borrow();
+        if data.len() < ACCOUNT_HEADER_LEN {
+            msg!("Account is too small to hold a header.");
+            return Err(ProgramError::InvalidAccountData);
+        }
+        (data[0], data[1])
+    };
+    if version == 0 {
+        msg!("Account is not initialized.");
+        return Err(ProgramError::UninitializedAccount);
+    }
+
+    let (new_account_type, current_version, new_len) = match account_type {
+        MERKLE_TREE_ACCOUNT_TYPE => (
+            MERKLE_TREE_ACCOUNT_TYPE,
+            MERKLE_TREE_ACCOUNT_VERSION,
+            MerkleTree::LEN,
+        ),
+        USER_ACCOUNT_TYPE | LEGACY_USER_ACCOUNT_TYPE => {
+            (USER_ACCOUNT_TYPE, USER_ACCOUNT_VERSION, UserAccount::LEN)
+        }
+        _ => {
+            msg!("Accounts of type {} cannot be migrated.", account_type);
+            return Err(LightError::AccountNotMigratable.into());
+        }
+    };
+
+    if version > current_version {
+        msg!("Unsupported account version {}.", version);
+        return Err(LightError::UnsupportedAccountVersion.into());
+    }
+    if version == current_version
+        && account_type == new_account_type
+        && account.data_len() == new_len
+    {
+        msg!("Account is already at version {}.", current_version);
+        return Err(LightError::AccountAlreadyCurrentVersion.into());
+    }
+    msg!(
+        "Migrating account type {} from version {} to {}.",
+        account_type,
+        version,
+        current_version
+    );
+
+    if account.data_len() != new_len {
+        let minimum_balance = rent.minimum_balance(new_len);
+        if account.lamports() < minimum_balance {
+            invoke(
+                &system_instruction::transfer(
+                    signer_account.key,
+                    account.key,
+                    minimum_balance - account.lamports(),
+                ),
+                &[
+                    signer_account.clone(),
+                    account.clone(),
+                    system_program.clone(),
+                ],
+            )?;
+        }
+        account.realloc(new_len, true)?;
+    }
+
+    let mut data = account.data.borrow_mut();
+    let mut from_version = version;
+    while from_version < current_version {
+        upgrade_layout(new_account_type, from_version, &mut data)?;
+        from_version += 1;
+    }
+    data[..ACCOUNT_HEADER_LEN].copy_from_slice(&account_header(new_account_type)?);
+    Ok(())
+}
+
+// Rewrites an account from layout version from_version to from_version + 1.
+// data is already resized to the current layout length.
+// Version 1 is the first versioned layout for every account type,
+// upgrades are added here whenever a layout changes.
+fn upgrade_layout(account_type: u8, from_version: u8, data: &mut [u8]) -> Result<(), ProgramError> {
+    match (account_type, from_version) {
+        // Version 2 user accounts hold UTXO_CAPACITY utxos. The owner and the utxo of a
+        // version 1 account keep their offsets, the utxo becomes the first one.
+        (USER_ACCOUNT_TYPE, 1) => {
+            for byte in data[USER_ACCOUNT_V1_LEN..].iter_mut() {
+                *byte = 0;
+            }
+            Ok(())
+        }
+        _ => {
+            msg!(
+                "No layout upgrade for account type {} from version {}.",
+                account_type,
+                from_version
+            );
+            Err(LightError::MissingLayoutUpgrade.into())
+        }
+    }
+}
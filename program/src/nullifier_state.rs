@@ -1,3 +1,6 @@
+use crate::error::LightError;
+use crate::utils::account_header::check_account_header_uninitialized;
+use crate::utils::config::{NULLIFIER_ACCOUNT_TYPE, NULLIFIER_ACCOUNT_VERSION};
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use solana_program::program_pack::IsInitialized;
 use solana_program::program_pack::Pack;
@@ -25,21 +28,21 @@ impl Pack for NullifierState {
 
         let (is_initialized, _account_type) = array_refs![input, 1, 1];
 
-        if is_initialized[0] != 0 {
+        if check_account_header_uninitialized(is_initialized[0]).is_err() {
             msg!("nullifier already spent");
-            panic!();
+            return Err(LightError::NullifierAlreadySpent.into());
         }
         Ok(NullifierState {
             is_initialized: true,
-            account_type: 3,
+            account_type: NULLIFIER_ACCOUNT_TYPE,
         })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, NullifierState::LEN];
         let (is_initialized_dst, account_type_dst) = mut_array_refs![dst, 1, 1];
-        *is_initialized_dst = [1];
-        *account_type_dst = [3];
+        *is_initialized_dst = [NULLIFIER_ACCOUNT_VERSION];
+        *account_type_dst = [NULLIFIER_ACCOUNT_TYPE];
         msg!("packed inserted_nullifier");
     }
 }
@@ -0,0 +1,59 @@
+use solana_program::pubkey::Pubkey;
+
+// Seeds of the pdas of a shielded transaction. The program checks passed-in pdas against
+// these seeds (create_and_check_pda_with_seeds), clients derive the addresses with the
+// find functions below.
+// - tmp storage pda: [tx_integrity_hash, signer, b"storage"]
+// - nullifier pda: [nullifier, b"nf"]
+// - leaves pda: [nullifier0, b"leaves"]
+// - escrow pda: [tmp_storage_pda, b"escrow"], receives SOL deposits
+// - authority: [program_id], delegate of SPL deposits and owner of the Merkle tree tokens
+pub const TMP_STORAGE_SEED: &[u8] = b"storage";
+pub const NULLIFIER_SEED: &[u8] = b"nf";
+pub const LEAVES_SEED: &[u8] = b"leaves";
+pub const ESCROW_SEED: &[u8] = b"escrow";
+
+pub fn tmp_storage_seeds<'a>(tx_integrity_hash: &'a [u8], signer: &'a Pubkey) -> [&'a [u8]; 3] {
+    [tx_integrity_hash, signer.as_ref(), TMP_STORAGE_SEED]
+}
+
+pub fn nullifier_seeds(nullifier: &[u8]) -> [&[u8]; 2] {
+    [nullifier, NULLIFIER_SEED]
+}
+
+// The leaves pda is derived from the first nullifier of the transaction.
+pub fn leaves_seeds(nullifier0: &[u8]) -> [&[u8]; 2] {
+    [nullifier0, LEAVES_SEED]
+}
+
+pub fn escrow_seeds(tmp_storage_pda: &Pubkey) -> [&[u8]; 2] {
+    [tmp_storage_pda.as_ref(), ESCROW_SEED]
+}
+
+pub fn authority_seeds(program_id: &Pubkey) -> [&[u8]; 1] {
+    [program_id.as_ref()]
+}
+
+pub fn find_tmp_storage_pda(
+    program_id: &Pubkey,
+    tx_integrity_hash: &[u8],
+    signer: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&tmp_storage_seeds(tx_integrity_hash, signer), program_id)
+}
+
+pub fn find_nullifier_pda(program_id: &Pubkey, nullifier: &[u8]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&nullifier_seeds(nullifier), program_id)
+}
+
+pub fn find_leaves_pda(program_id: &Pubkey, nullifier0: &[u8]) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&leaves_seeds(nullifier0), program_id)
+}
+
+pub fn find_escrow_pda(program_id: &Pubkey, tmp_storage_pda: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&escrow_seeds(tmp_storage_pda), program_id)
+}
+
+pub fn find_authority_pda(program_id: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&authority_seeds(program_id), program_id)
+}
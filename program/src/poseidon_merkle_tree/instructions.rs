@@ -1,5 +1,6 @@
 use solana_program::{msg, program_error::ProgramError};
 
+use crate::error::LightError;
 use crate::poseidon_merkle_tree::state::{MerkleTree, TmpStoragePda};
 use crate::utils::config::ZERO_BYTES_MERKLE_TREE_18;
 
@@ -23,7 +24,7 @@ pub fn insert_0_double(
 
     if tmp_storage_account.current_index == 262144 {
         msg!("Merkle tree full");
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(LightError::MerkleTreeFull.into());
     }
     tmp_storage_account.left = tmp_storage_account.leaf_left.clone();
     tmp_storage_account.right = tmp_storage_account.leaf_right.clone();
@@ -1,5 +1,4 @@
 use crate::poseidon_merkle_tree::poseidon_round_constants_split;
-use ark_crypto_primitives::Error;
 use ark_ed_on_bn254::Fq;
 use ark_ff::{
     bytes::{FromBytes, ToBytes},
@@ -8,7 +7,7 @@ use ark_ff::{
 use ark_std::Zero;
 use arkworks_gadgets::poseidon::{sbox::PoseidonSbox, PoseidonError, PoseidonParameters, Rounds};
 use arkworks_gadgets::utils;
-use solana_program::program_error::ProgramError;
+use solana_program::{msg, program_error::ProgramError};
 use std::ops::{Add, AddAssign, Mul};
 
 //configuration for the poseidon hash to be compatible with circom bn254 with 2 inputs
@@ -35,10 +34,11 @@ pub fn permute_instruction_first(
     let mds = poseidon_round_constants_split::get_mds_poseidon_circom_bn254_x5_3();
     let params = PoseidonParameters::<Fq>::new(rounds, mds);
     //parsing poseidon inputs to Fq elements
-    let mut state_new = prepare_inputs(&params, left_input, right_input).unwrap();
+    let mut state_new = prepare_inputs(&params, left_input, right_input)?;
 
     //performing the first 4 full round permutations
-    state_new = permute_custom_split(&params, state_new, *current_round, 4).unwrap();
+    state_new = permute_custom_split(&params, state_new, *current_round, 4)
+        .map_err(|_| ProgramError::InvalidArgument)?;
 
     *current_round += 4;
     //incrementing round index to fetch the right parameters next iteration
@@ -65,9 +65,10 @@ pub fn permute_instruction_6(
 
     let mut state_new = Vec::new();
     for i in state.iter() {
-        state_new.push(<Fq as FromBytes>::read(&i[..]).unwrap());
+        state_new.push(<Fq as FromBytes>::read(&i[..])?);
     }
-    let state_new = permute_custom_split(&params, state_new, *current_round, 6).unwrap();
+    let state_new = permute_custom_split(&params, state_new, *current_round, 6)
+        .map_err(|_| ProgramError::InvalidArgument)?;
 
     *current_round += 6;
     //incrementing round index to fetch the right parameters next iteration
@@ -94,9 +95,10 @@ pub fn permute_instruction_3(
 
     let mut state_new = Vec::new();
     for i in state.iter() {
-        state_new.push(<Fq as FromBytes>::read(&i[..]).unwrap());
+        state_new.push(<Fq as FromBytes>::read(&i[..])?);
     }
-    let state_new = permute_custom_split(&params, state_new, *current_round, 3).unwrap();
+    let state_new = permute_custom_split(&params, state_new, *current_round, 3)
+        .map_err(|_| ProgramError::InvalidArgument)?;
 
     *current_round += 3;
     //incrementing round index to fetch the right parameters next iteration
@@ -123,9 +125,10 @@ pub fn permute_instruction_last(
 
     let mut state_new = Vec::new();
     for i in state.iter() {
-        state_new.push(<Fq as FromBytes>::read(&i[..]).unwrap());
+        state_new.push(<Fq as FromBytes>::read(&i[..])?);
     }
-    state_new = permute_custom_split(&params, state_new, *current_round, 4).unwrap();
+    state_new = permute_custom_split(&params, state_new, *current_round, 4)
+        .map_err(|_| ProgramError::InvalidArgument)?;
 
     //reset round and index for next hash
     *current_round = 0;
@@ -143,28 +146,31 @@ pub fn prepare_inputs(
     _parameters: &PoseidonParameters<Fq>,
     left_input: &[u8],
     right_input: &[u8],
-) -> Result<Vec<Fq>, Error> {
+) -> Result<Vec<Fq>, ProgramError> {
     //modified from arkworks_gadgets
 
     const INPUT_SIZE_BITS: usize =
         ark_ff::biginteger::BigInteger256::NUM_LIMBS * 8 * PoseidonCircomRounds3::WIDTH * 8;
     const LEFT_INPUT_SIZE_BITS: usize = INPUT_SIZE_BITS / 2;
-    assert_eq!(left_input.len(), right_input.len());
-    assert!(left_input.len() * 8 <= LEFT_INPUT_SIZE_BITS);
+    if left_input.len() != right_input.len() || left_input.len() * 8 > LEFT_INPUT_SIZE_BITS {
+        msg!("Invalid poseidon input length {}.", left_input.len());
+        return Err(ProgramError::InvalidArgument);
+    }
     let chained: Vec<_> = left_input
         .iter()
         .chain(right_input.iter())
         .copied()
         .collect();
 
-    let f_inputs = utils::to_field_elements(&chained)?;
+    let f_inputs = utils::to_field_elements(&chained).map_err(|_| ProgramError::InvalidArgument)?;
     if f_inputs.len() >= PoseidonCircomRounds3::WIDTH {
-        panic!(
+        msg!(
             "incorrect input length {:?} for width {:?} -- input bits {:?}",
             f_inputs.len(),
             PoseidonCircomRounds3::WIDTH,
             chained.len()
         );
+        return Err(ProgramError::InvalidArgument);
     }
 
     let mut buffer = vec![Fq::zero()];
@@ -1,14 +1,15 @@
+use crate::error::LightError;
 use crate::poseidon_merkle_tree::instructions::*;
 use crate::poseidon_merkle_tree::instructions_poseidon::{
     permute_instruction_3, permute_instruction_6, permute_instruction_first,
     permute_instruction_last,
 };
-use crate::utils::config::MERKLE_TREE_ACC_BYTES_ARRAY;
+use crate::utils::config::get_merkle_tree_acc_bytes;
 
 use crate::poseidon_merkle_tree::state::{
     InitMerkleTreeBytes, MerkleTree, TmpStoragePda, TwoLeavesBytesPda,
 };
-use crate::{IX_ORDER, TWO_LEAVES_PDA_SIZE};
+use crate::IX_ORDER;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     clock::Clock,
@@ -19,7 +20,6 @@ use solana_program::{
     sysvar::rent::Rent,
     sysvar::Sysvar,
 };
-use std::convert::TryFrom;
 
 const MERKLE_TREE_UPDATE_START: u8 = 14;
 const MERKLE_TREE_UPDATE_LEVEL: u8 = 25;
@@ -77,8 +77,15 @@ impl<'a, 'b> MerkleTreeProcessor<'a, 'b> {
         &mut self,
         init_bytes: &[u8],
     ) -> Result<(), ProgramError> {
+        let merkle_tree_pda = self
+            .merkle_tree_pda
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
         let mut unpacked_init_merkle_tree =
-            InitMerkleTreeBytes::unpack(&self.merkle_tree_pda.unwrap().data.borrow())?;
+            InitMerkleTreeBytes::unpack(&merkle_tree_pda.data.borrow())?;
+        if init_bytes.len() < unpacked_init_merkle_tree.bytes.len() {
+            msg!("merkle tree init bytes too short");
+            return Err(LightError::MerkleTreeInitFailed.into());
+        }
 
         for i in 0..unpacked_init_merkle_tree.bytes.len() {
             unpacked_init_merkle_tree.bytes[i] = init_bytes[i];
@@ -86,11 +93,11 @@ impl<'a, 'b> MerkleTreeProcessor<'a, 'b> {
 
         InitMerkleTreeBytes::pack_into_slice(
             &unpacked_init_merkle_tree,
-            &mut self.merkle_tree_pda.unwrap().data.borrow_mut(),
+            &mut merkle_tree_pda.data.borrow_mut(),
         );
         if unpacked_init_merkle_tree.bytes[0..init_bytes.len()] != init_bytes[..] {
             msg!("merkle tree init failed");
-            return Err(ProgramError::InvalidAccountData);
+            return Err(LightError::MerkleTreeInitFailed.into());
         }
         Ok(())
     }
@@ -99,18 +106,20 @@ impl<'a, 'b> MerkleTreeProcessor<'a, 'b> {
         let account = &mut accounts.iter();
         let _signer = next_account_info(account)?;
         let _tmp_storage_pda = next_account_info(account)?;
-        let mut tmp_storage_pda_data =
-            TmpStoragePda::unpack(&self.tmp_storage_pda.unwrap().data.borrow())?;
+        let tmp_storage_pda = self
+            .tmp_storage_pda
+            .ok_or(ProgramError::NotEnoughAccountKeys)?;
+        let mut tmp_storage_pda_data = TmpStoragePda::unpack(&tmp_storage_pda.data.borrow())?;
         msg!(
             "tmp_storage_pda_data.current_instruction_index {}",
             tmp_storage_pda_data.current_instruction_index
         );
 
-        if tmp_storage_pda_data.current_instruction_index < IX_ORDER.len()
-            && (IX_ORDER[tmp_storage_pda_data.current_instruction_index]
-                == MERKLE_TREE_UPDATE_START
-                || IX_ORDER[tmp_storage_pda_data.current_instruction_index]
-                    == MERKLE_TREE_UPDATE_LEVEL)
+        let instruction_id = *IX_ORDER
+            .get(tmp_storage_pda_data.current_instruction_index)
+            .ok_or(LightError::ComputationFinished)?;
+
+        if instruction_id == MERKLE_TREE_UPDATE_START || instruction_id == MERKLE_TREE_UPDATE_LEVEL
         {
             let merkle_tree_pda = next_account_info(account)?;
             let mut merkle_tree_pda_data = MerkleTree::unpack(&merkle_tree_pda.data.borrow())?;
@@ -128,7 +137,7 @@ impl<'a, 'b> MerkleTreeProcessor<'a, 'b> {
             )?;
 
             _process_instruction(
-                IX_ORDER[tmp_storage_pda_data.current_instruction_index],
+                instruction_id,
                 &mut tmp_storage_pda_data,
                 &mut merkle_tree_pda_data,
             )?;
@@ -137,9 +146,7 @@ impl<'a, 'b> MerkleTreeProcessor<'a, 'b> {
                 &merkle_tree_pda_data,
                 &mut merkle_tree_pda.data.borrow_mut(),
             );
-        } else if tmp_storage_pda_data.current_instruction_index < IX_ORDER.len()
-            && IX_ORDER[tmp_storage_pda_data.current_instruction_index] == LOCK_START
-        {
+        } else if instruction_id == LOCK_START {
             let merkle_tree_pda = next_account_info(account)?;
             let mut merkle_tree_pda_data = MerkleTree::unpack(&merkle_tree_pda.data.borrow())?;
             let current_slot = <Clock as Sysvar>::get()?.slot;
@@ -164,7 +171,7 @@ impl<'a, 'b> MerkleTreeProcessor<'a, 'b> {
                 );
             } else if merkle_tree_pda_data.time_locked + LOCK_DURATION > current_slot {
                 msg!("Contract is still locked.");
-                return Err(ProgramError::InvalidInstructionData);
+                return Err(LightError::MerkleTreeLocked.into());
             } else {
                 merkle_tree_pda_data.time_locked = <Clock as Sysvar>::get()?.slot;
                 merkle_tree_pda_data.pubkey_locked = _tmp_storage_pda.key.to_bytes().to_vec();
@@ -180,10 +187,10 @@ impl<'a, 'b> MerkleTreeProcessor<'a, 'b> {
                 &merkle_tree_pda_data,
                 &mut merkle_tree_pda.data.borrow_mut(),
             );
-        } else if IX_ORDER[tmp_storage_pda_data.current_instruction_index] == HASH_0
-            || IX_ORDER[tmp_storage_pda_data.current_instruction_index] == HASH_1
-            || IX_ORDER[tmp_storage_pda_data.current_instruction_index] == HASH_2
-            || IX_ORDER[tmp_storage_pda_data.current_instruction_index] == HASH_3
+        } else if instruction_id == HASH_0
+            || instruction_id == HASH_1
+            || instruction_id == HASH_2
+            || instruction_id == HASH_3
         {
             let merkle_tree_pda = next_account_info(account)?;
             merkle_tree_pubkey_check(
@@ -194,16 +201,13 @@ impl<'a, 'b> MerkleTreeProcessor<'a, 'b> {
             )?;
             //hash instructions do not need the merkle tree
             _process_instruction(
-                IX_ORDER[tmp_storage_pda_data.current_instruction_index],
+                instruction_id,
                 &mut tmp_storage_pda_data,
                 &mut self.unpacked_merkle_tree,
             )?;
-        } else if IX_ORDER[tmp_storage_pda_data.current_instruction_index] == ROOT_INSERT {
+        } else if instruction_id == ROOT_INSERT {
             //inserting root and creating leave pda accounts
-            msg!(
-                "Instruction: {}",
-                IX_ORDER[tmp_storage_pda_data.current_instruction_index]
-            );
+            msg!("Instruction: {}", instruction_id);
             let leaf_pda = next_account_info(account)?;
             let mut leaf_pda_account_data = TwoLeavesBytesPda::unpack(&leaf_pda.data.borrow())?;
             let _nullifer0 = next_account_info(account)?;
@@ -235,22 +239,22 @@ impl<'a, 'b> MerkleTreeProcessor<'a, 'b> {
 
             //check leaves account is rent exempt
             //let rent = Rent::default();
-            if !rent.is_exempt(
-                **leaf_pda.lamports.borrow(),
-                usize::try_from(TWO_LEAVES_PDA_SIZE).unwrap(),
-            ) {
+            if !rent.is_exempt(**leaf_pda.lamports.borrow(), TwoLeavesBytesPda::LEN) {
                 msg!("Leaves account is not rent-exempt.");
-                return Err(ProgramError::InvalidAccountData);
+                return Err(ProgramError::AccountNotRentExempt);
             }
             //save leaves into pda account
             leaf_pda_account_data.leaf_left = tmp_storage_pda_data.leaf_left.clone();
             leaf_pda_account_data.leaf_right = tmp_storage_pda_data.leaf_right.clone();
             //increased by 2 because we're inserting 2 leaves at once
-            leaf_pda_account_data.left_leaf_index = merkle_tree_pda_data.next_index - 2;
-            leaf_pda_account_data.merkle_tree_pubkey = MERKLE_TREE_ACC_BYTES_ARRAY
-                [<usize as TryFrom<u8>>::try_from(tmp_storage_pda_data.merkle_tree_index).unwrap()]
-            .0
-            .to_vec();
+            leaf_pda_account_data.left_leaf_index = merkle_tree_pda_data
+                .next_index
+                .checked_sub(2)
+                .ok_or(LightError::ArithmeticOverflow)?;
+            leaf_pda_account_data.merkle_tree_pubkey =
+                get_merkle_tree_acc_bytes(tmp_storage_pda_data.merkle_tree_index)?
+                    .0
+                    .to_vec();
             leaf_pda_account_data.encrypted_utxos = tmp_storage_pda_data.encrypted_utxos.clone();
 
             msg!("Lock set at slot: {}", merkle_tree_pda_data.time_locked);
@@ -270,7 +274,7 @@ impl<'a, 'b> MerkleTreeProcessor<'a, 'b> {
         tmp_storage_pda_data.current_instruction_index += 1;
         TmpStoragePda::pack_into_slice(
             &tmp_storage_pda_data,
-            &mut self.tmp_storage_pda.unwrap().data.borrow_mut(),
+            &mut tmp_storage_pda.data.borrow_mut(),
         );
 
         Ok(())
@@ -323,27 +327,19 @@ fn merkle_tree_pubkey_check(
     merkle_tree_pda_owner: Pubkey,
     program_id: Pubkey,
 ) -> Result<(), ProgramError> {
-    if account_pubkey
-        != solana_program::pubkey::Pubkey::new(
-            &MERKLE_TREE_ACC_BYTES_ARRAY
-                [<usize as TryFrom<u8>>::try_from(merkle_tree_index).unwrap()]
-            .0,
-        )
-    {
+    let merkle_tree_pubkey =
+        solana_program::pubkey::Pubkey::new(&get_merkle_tree_acc_bytes(merkle_tree_index)?.0);
+    if account_pubkey != merkle_tree_pubkey {
         msg!(
             "invalid merkle tree {:?}, {:?}",
             account_pubkey,
-            solana_program::pubkey::Pubkey::new(
-                &MERKLE_TREE_ACC_BYTES_ARRAY
-                    [<usize as TryFrom<u8>>::try_from(merkle_tree_index).unwrap()]
-                .0
-            )
+            merkle_tree_pubkey
         );
-        return Err(ProgramError::InvalidAccountData);
+        return Err(LightError::InvalidMerkleTree.into());
     }
     if merkle_tree_pda_owner != program_id {
         msg!("Invalid merkle tree owner.");
-        return Err(ProgramError::IllegalOwner);
+        return Err(LightError::InvalidAccountOwner.into());
     }
     Ok(())
 }
@@ -355,7 +351,7 @@ fn pubkey_check(
 ) -> Result<(), ProgramError> {
     if account_pubkey0 != account_pubkey1 {
         msg!(&msg);
-        return Err(ProgramError::InvalidInstructionData);
+        return Err(LightError::MerkleTreeLocked.into());
     }
 
     Ok(())
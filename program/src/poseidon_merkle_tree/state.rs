@@ -1,4 +1,9 @@
-use crate::config::{ENCRYPTED_UTXOS_LENGTH, MERKLE_TREE_ACCOUNT_TYPE};
+use crate::config::{
+    ENCRYPTED_UTXOS_LENGTH, LEAVES_PDA_ACCOUNT_TYPE, LEAVES_PDA_ACCOUNT_VERSION,
+    MERKLE_TREE_ACCOUNT_TYPE, TMP_STORAGE_ACCOUNT_TYPE,
+};
+use crate::error::LightError;
+use crate::utils::account_header::{check_account_header, check_account_header_uninitialized};
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use byteorder::{ByteOrder, LittleEndian};
 use solana_program::{
@@ -6,7 +11,6 @@ use solana_program::{
     program_error::ProgramError,
     program_pack::{IsInitialized, Pack, Sealed},
 };
-use std::convert::TryInto;
 
 #[allow(unused_variables)]
 #[derive(Debug)]
@@ -52,14 +56,7 @@ impl Pack for MerkleTree {
             time_locked,
         ) = array_refs![input, 1, 1, 8, 576, 8, 8, 8, 16000, 8, 32, 8];
 
-        if 1u8 != is_initialized[0] {
-            msg!("merkle tree account is not initialized");
-            return Err(ProgramError::UninitializedAccount);
-        }
-        if account_type[0] != MERKLE_TREE_ACCOUNT_TYPE {
-            msg!("Account is not of type Merkle tree.");
-            return Err(ProgramError::InvalidAccountData);
-        }
+        check_account_header(is_initialized[0], account_type[0], MERKLE_TREE_ACCOUNT_TYPE)?;
         let mut tmp_subtree_vec = vec![vec![0u8; 32]; 18];
 
         for (i, bytes) in filled_subtrees.chunks(32).enumerate() {
@@ -67,6 +64,16 @@ impl Pack for MerkleTree {
         }
 
         let current_root_index = usize::from_le_bytes(*current_root_index);
+        let root_history_size = usize::from_le_bytes(*root_history_size);
+        // The current root is read from and written to the roots range at current_root_index.
+        if root_history_size > roots.len() / 32 || current_root_index >= root_history_size {
+            msg!(
+                "Current root index {} out of the root history of size {}.",
+                current_root_index,
+                root_history_size
+            );
+            return Err(LightError::InvalidMerkleTree.into());
+        }
 
         let mut tmp_roots_vec = vec![0u8; 32];
         let current_root_start_range = current_root_index * 32;
@@ -87,7 +94,7 @@ impl Pack for MerkleTree {
             filled_subtrees: tmp_subtree_vec,
             current_root_index,
             next_index,
-            root_history_size: usize::from_le_bytes(*root_history_size),
+            root_history_size,
             roots: tmp_roots_vec.to_vec(),
             current_total_deposits: LittleEndian::read_u64(current_total_deposits),
             inserted_leaf: false,
@@ -97,6 +104,7 @@ impl Pack for MerkleTree {
         })
     }
 
+    // pubkey_locked is set from unpacked 32 bytes or a pubkey.
     fn pack_into_slice(&self, dst: &mut [u8]) {
         //if self.inserted_leaf {
         let dst = array_mut_ref![dst, 0, MerkleTree::LEN];
@@ -135,16 +143,13 @@ impl Pack for MerkleTree {
             }
         }
 
-        LittleEndian::write_u64(
-            current_root_index_dst,
-            self.current_root_index.try_into().unwrap(),
-        );
-        LittleEndian::write_u64(next_index_dst, self.next_index.try_into().unwrap());
+        LittleEndian::write_u64(current_root_index_dst, self.current_root_index as u64);
+        LittleEndian::write_u64(next_index_dst, self.next_index as u64);
         LittleEndian::write_u64(
             current_total_deposits_dst,
             self.current_total_deposits, //.try_into().unwrap(),
         );
-        *pubkey_locked_dst = self.pubkey_locked.clone().try_into().unwrap();
+        pubkey_locked_dst.copy_from_slice(&self.pubkey_locked);
 
         LittleEndian::write_u64(time_locked_dst, self.time_locked); // TODO: check if removing try_into().unwrap() has sideeffects
     }
@@ -169,10 +174,7 @@ impl Pack for InitMerkleTreeBytes {
 
         let (bytes, _left_over) = array_refs![input, 642, 16016];
 
-        if bytes[0] != 0 {
-            msg!("Tree is already initialized");
-            return Err(ProgramError::InvalidAccountData);
-        }
+        check_account_header_uninitialized(bytes[0])?;
 
         Ok(InitMerkleTreeBytes {
             is_initialized: true,
@@ -180,12 +182,13 @@ impl Pack for InitMerkleTreeBytes {
         })
     }
 
+    // bytes are the 642 init bytes of a height 18 tree.
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, InitMerkleTreeBytes::LEN];
 
         let (bytes_dst, _left_over_dst) = mut_array_refs![dst, 642, 16016];
 
-        *bytes_dst = self.bytes.clone().try_into().unwrap();
+        bytes_dst.copy_from_slice(&self.bytes);
     }
 }
 
@@ -221,7 +224,8 @@ impl Pack for TmpStoragePda {
         let input = array_ref![input, 0, TmpStoragePda::LEN];
 
         let (
-            _is_initialized,
+            is_initialized,
+            account_type,
             _unused_remainder0,
             merkle_tree_index,
             _unused_remainder0_1,
@@ -244,7 +248,8 @@ impl Pack for TmpStoragePda {
         ) = array_refs![
             input,
             1,
-            2,
+            1,
+            1,
             1,
             208,
             8,
@@ -263,6 +268,7 @@ impl Pack for TmpStoragePda {
             32,
             ENCRYPTED_UTXOS_LENGTH
         ];
+        check_account_header(is_initialized[0], account_type[0], TMP_STORAGE_ACCOUNT_TYPE)?;
 
         let mut parsed_state = Vec::new();
         for i in state.chunks(32) {
@@ -287,6 +293,7 @@ impl Pack for TmpStoragePda {
         })
     }
 
+    // The state ranges keep the lengths they are unpacked with.
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, TmpStoragePda::LEN];
 
@@ -344,13 +351,13 @@ impl Pack for TmpStoragePda {
         *current_round_dst = usize::to_le_bytes(self.current_round);
         *current_round_index_dst = usize::to_le_bytes(self.current_round_index);
 
-        *leaf_left_dst = self.leaf_left.clone().try_into().unwrap();
+        leaf_left_dst.copy_from_slice(&self.leaf_left);
 
-        *leaf_right_dst = self.leaf_right.clone().try_into().unwrap();
-        *left_dst = self.left.clone().try_into().unwrap();
+        leaf_right_dst.copy_from_slice(&self.leaf_right);
+        left_dst.copy_from_slice(&self.left);
 
-        *right_dst = self.right.clone().try_into().unwrap();
-        *current_level_hash_dst = self.current_level_hash.clone().try_into().unwrap();
+        right_dst.copy_from_slice(&self.right);
+        current_level_hash_dst.copy_from_slice(&self.current_level_hash);
 
         *current_index_dst = usize::to_le_bytes(self.current_index);
         *current_level_dst = usize::to_le_bytes(self.current_level);
@@ -392,14 +399,10 @@ impl Pack for TwoLeavesBytesPda {
             _encrypted_utxos,
         ) = array_refs![input, 1, 1, 8, 32, 32, 32, ENCRYPTED_UTXOS_LENGTH];
         //check that account was not initialized before
-        //assert_eq!(is_initialized[0], 0);
-        if is_initialized[0] != 0 {
-            msg!("Leaf pda is already initialized");
-            return Err(ProgramError::InvalidAccountData);
-        }
+        check_account_header_uninitialized(is_initialized[0])?;
         Ok(TwoLeavesBytesPda {
             is_initialized: true,
-            account_type: 4,
+            account_type: LEAVES_PDA_ACCOUNT_TYPE,
             leaf_right: vec![0u8; 32],
             leaf_left: vec![0u8; 32],
             merkle_tree_pubkey: vec![0u8; 32],
@@ -408,6 +411,8 @@ impl Pack for TwoLeavesBytesPda {
         })
     }
 
+    // Leaves and the Merkle tree pubkey are 32 bytes, encrypted utxos are copied from the
+    // ENCRYPTED_UTXOS_LENGTH range of the tmp storage.
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, TwoLeavesBytesPda::LEN];
         let (
@@ -420,13 +425,13 @@ impl Pack for TwoLeavesBytesPda {
             encrypted_utxos_dst,
         ) = mut_array_refs![dst, 1, 1, 8, 32, 32, 32, ENCRYPTED_UTXOS_LENGTH];
 
-        *is_initialized_dst = [1];
-        *account_type_dst = [4];
-        *leaf_right_dst = self.leaf_right.clone().try_into().unwrap();
-        *leaf_left_dst = self.leaf_left.clone().try_into().unwrap();
-        *merkle_tree_pubkey_dst = self.merkle_tree_pubkey.clone().try_into().unwrap();
+        *is_initialized_dst = [LEAVES_PDA_ACCOUNT_VERSION];
+        *account_type_dst = [LEAVES_PDA_ACCOUNT_TYPE];
+        leaf_right_dst.copy_from_slice(&self.leaf_right);
+        leaf_left_dst.copy_from_slice(&self.leaf_left);
+        merkle_tree_pubkey_dst.copy_from_slice(&self.merkle_tree_pubkey);
         *left_leaf_index_dst = usize::to_le_bytes(self.left_leaf_index);
-        *encrypted_utxos_dst = self.encrypted_utxos.clone().try_into().unwrap();
+        encrypted_utxos_dst.copy_from_slice(&self.encrypted_utxos);
         msg!("packed inserted_leaves");
     }
 }
@@ -1,5 +1,7 @@
 use crate::config::MERKLE_TREE_ACCOUNT_TYPE;
-use crate::utils::config::MERKLE_TREE_ACC_BYTES_ARRAY;
+use crate::error::LightError;
+use crate::utils::account_header::check_account_header;
+use crate::utils::config::get_merkle_tree_acc_bytes;
 use arrayref::{array_ref, array_refs};
 use solana_program::{
     account_info::AccountInfo,
@@ -8,7 +10,6 @@ use solana_program::{
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
 };
-use std::convert::TryFrom;
 
 // max roots that can be checked within one ix memory budget.
 const ROOT_HISTORY_SIZE: u64 = 593;
@@ -47,14 +48,7 @@ impl Pack for MerkleTreeRoots {
             _unused_remainder,
         ) = array_refs![input, 1, 1, 8, 576, 8, 8, 8, 16000, 48];
 
-        if is_initialized[0] != 1u8 {
-            msg!("Merkle Tree is not initialized");
-            return Err(ProgramError::InvalidAccountData);
-        }
-        if account_type[0] != MERKLE_TREE_ACCOUNT_TYPE {
-            msg!("Account is not of type Merkle tree.");
-            return Err(ProgramError::InvalidAccountData);
-        }
+        check_account_header(is_initialized[0], account_type[0], MERKLE_TREE_ACCOUNT_TYPE)?;
 
         Ok(MerkleTreeRoots {
             is_initialized: true,
@@ -74,36 +68,28 @@ pub fn check_root_hash_exists(
     program_id: &Pubkey,
     merkle_tree_index: u8,
 ) -> Result<u8, ProgramError> {
-    let merkle_tree_pda_data = MerkleTreeRoots::unpack(&merkle_tree_pda.data.borrow()).unwrap();
+    let merkle_tree_pda_data = MerkleTreeRoots::unpack(&merkle_tree_pda.data.borrow())?;
+    let merkle_tree_pubkey =
+        solana_program::pubkey::Pubkey::new(&get_merkle_tree_acc_bytes(merkle_tree_index)?.0);
     msg!("Passed-in merkle_tree_pda pubkey: {:?}", *merkle_tree_pda);
     msg!(
         "Checks against hardcoded merkle_tree_pda pubkey: {:?}",
-        solana_program::pubkey::Pubkey::new(
-            &MERKLE_TREE_ACC_BYTES_ARRAY
-                [<usize as TryFrom<u8>>::try_from(merkle_tree_index).unwrap()]
-            .0
-        )
+        merkle_tree_pubkey
     );
 
-    if *merkle_tree_pda.key
-        != solana_program::pubkey::Pubkey::new(
-            &MERKLE_TREE_ACC_BYTES_ARRAY
-                [<usize as TryFrom<u8>>::try_from(merkle_tree_index).unwrap()]
-            .0,
-        )
-    {
+    if *merkle_tree_pda.key != merkle_tree_pubkey {
         msg!("Merkle tree account pubkey is incorrect.");
-        return Err(ProgramError::InvalidArgument);
+        return Err(LightError::InvalidMerkleTree.into());
     }
 
     if *merkle_tree_pda.owner != *program_id {
         msg!("Merkle tree account owner is incorrect.");
-        return Err(ProgramError::IllegalOwner);
+        return Err(LightError::InvalidAccountOwner.into());
     }
 
     if merkle_tree_pda_data.root_history_size > ROOT_HISTORY_SIZE {
         msg!("Root history size too large.");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(LightError::InvalidRootHistorySize.into());
     }
     msg!("Looking for root: {:?}", *root_bytes);
     let mut found_root = 0u8;
@@ -116,7 +102,7 @@ pub fn check_root_hash_exists(
     }
     if found_root != 1 {
         msg!("Did not find root.");
-        return Err(ProgramError::InvalidAccountData);
+        return Err(LightError::RootNotFound.into());
     }
 
     Ok(found_root)
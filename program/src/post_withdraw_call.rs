@@ -0,0 +1,114 @@
+use crate::error::LightError;
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    keccak, msg,
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+
+// A post withdraw call is an optional instruction which is invoked after a withdrawal in the last
+// instruction (1501), i.e. to swap the withdrawn funds without a second, linkable transaction.
+// The client appends the commitment (program id || account metas hash || instruction data hash)
+// to the send data, it is part of the tx integrity hash. The last instruction passes the call
+// itself, it is checked against the commitment and invoked after all other state changes.
+// The call is invoked without signer seeds and its accounts can't be signers,
+// so neither the program authority nor the relayer sign for it.
+pub const POST_WITHDRAW_CALL_COMMITMENT_LEN: usize = 96;
+// Stored in the tmp storage pda if there is no post withdraw call.
+pub const NO_POST_WITHDRAW_CALL: [u8; 32] = [0u8; 32];
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Eq, PartialEq)]
+pub struct PostWithdrawCallAccount {
+    pub pubkey: Pubkey,
+    pub is_writable: bool,
+}
+
+#[derive(BorshSerialize, BorshDeserialize, Clone, Debug, Eq, PartialEq)]
+pub struct PostWithdrawCall {
+    pub program_id: Pubkey,
+    pub accounts: Vec<PostWithdrawCallAccount>,
+    pub data: Vec<u8>,
+}
+
+impl PostWithdrawCall {
+    // keccak(pubkey || is_writable) over all accounts in order.
+    pub fn account_metas_hash(&self) -> [u8; 32] {
+        let input: Vec<u8> = self
+            .accounts
+            .iter()
+            .flat_map(|account| {
+                let mut meta = account.pubkey.to_bytes().to_vec();
+                meta.push(account.is_writable as u8);
+                meta
+            })
+            .collect();
+        keccak::hash(&input).to_bytes()
+    }
+
+    pub fn data_hash(&self) -> [u8; 32] {
+        keccak::hash(&self.data).to_bytes()
+    }
+
+    pub fn commitment(&self) -> [u8; POST_WITHDRAW_CALL_COMMITMENT_LEN] {
+        let mut commitment = [0u8; POST_WITHDRAW_CALL_COMMITMENT_LEN];
+        commitment[..32].copy_from_slice(&self.program_id.to_bytes());
+        commitment[32..64].copy_from_slice(&self.account_metas_hash());
+        commitment[64..].copy_from_slice(&self.data_hash());
+        commitment
+    }
+
+    pub fn instruction(&self) -> Instruction {
+        let accounts = self
+            .accounts
+            .iter()
+            .map(|account| {
+                if account.is_writable {
+                    AccountMeta::new(account.pubkey, false)
+                } else {
+                    AccountMeta::new_readonly(account.pubkey, false)
+                }
+            })
+            .collect();
+        Instruction {
+            program_id: self.program_id,
+            accounts,
+            data: self.data.clone(),
+        }
+    }
+
+    pub fn decode(data: &[u8]) -> Result<Self, ProgramError> {
+        PostWithdrawCall::try_from_slice(data).map_err(|_| ProgramError::InvalidInstructionData)
+    }
+}
+
+// Hash stored in the tmp storage pda, commitment is the send data after SEND_DATA_LEN.
+pub fn commitment_hash(commitment: Option<&[u8]>) -> [u8; 32] {
+    match commitment {
+        Some(commitment) => keccak::hash(commitment).to_bytes(),
+        None => NO_POST_WITHDRAW_CALL,
+    }
+}
+
+// Checks the call passed in with the last instruction against the stored commitment hash
+// and invokes it. account_infos are the program account and the accounts of the call.
+pub fn check_and_invoke(
+    program_id: &Pubkey,
+    stored_commitment_hash: &[u8],
+    call_data: &[u8],
+    account_infos: &[AccountInfo],
+) -> ProgramResult {
+    let call = PostWithdrawCall::decode(call_data)?;
+    if commitment_hash(Some(&call.commitment()[..]))[..] != *stored_commitment_hash {
+        msg!("Post withdraw call is not the call in the tx integrity hash.");
+        return Err(LightError::InvalidPostWithdrawCall.into());
+    }
+    if call.program_id == *program_id {
+        msg!("Post withdraw call cannot invoke the Light program.");
+        return Err(LightError::InvalidPostWithdrawCall.into());
+    }
+    invoke(&call.instruction(), account_infos)
+}
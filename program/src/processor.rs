@@ -1,11 +1,22 @@
+use crate::asset::Asset;
+use crate::error::LightError;
+use crate::event::{LightEvent, TransactionEvent};
+use crate::global_config::check_program_mode;
 use crate::instructions::{
-    check_and_insert_nullifier, check_external_amount, close_account, create_and_check_pda,
-    sol_transfer, token_transfer,
+    check_and_insert_nullifier, check_external_amount, close_account,
+    create_and_check_pda_with_seeds, parse_ext_amount, sol_transfer, token_transfer,
 };
+use crate::merkle_tree_limits::{load_merkle_tree_limits, MerkleTreeLimits};
+use crate::pda::{escrow_seeds, find_authority_pda, leaves_seeds};
 use crate::poseidon_merkle_tree::processor::MerkleTreeProcessor;
+use crate::poseidon_merkle_tree::state::MerkleTree;
 use crate::poseidon_merkle_tree::state_roots::check_root_hash_exists;
+use crate::post_withdraw_call::{check_and_invoke, NO_POST_WITHDRAW_CALL};
+use crate::protocol_fee::load_protocol_fee_config;
 use crate::state::ChecksAndTransferState;
-use crate::utils::config::MERKLE_TREE_ACC_BYTES_ARRAY;
+use crate::utils::config::{
+    get_merkle_tree_acc_bytes, get_merkle_tree_asset, ENCRYPTED_UTXOS_LENGTH,
+};
 
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
@@ -13,12 +24,13 @@ use solana_program::{
     program_error::ProgramError,
     program_pack::Pack,
     pubkey::Pubkey,
-    sysvar::{rent::Rent, Sysvar},
+    sysvar::{clock::Clock, rent::Rent, Sysvar},
 };
 
-use std::convert::{TryFrom, TryInto};
+use std::convert::TryInto;
 
 use crate::{
+    ENCRYPTED_UTXOS_START, LEAF_LEFT_END, LEAF_LEFT_START, LEAF_RIGHT_END, LEAF_RIGHT_START,
     NULLIFIER_0_END, NULLIFIER_0_START, NULLIFIER_1_END, NULLIFIER_1_START, TWO_LEAVES_PDA_SIZE,
 };
 // Processor for deposit and withdraw logic.
@@ -27,6 +39,7 @@ pub fn process_instruction(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     current_instruction_index: usize,
+    instruction_data: &[u8],
 ) -> Result<(), ProgramError> {
     let account = &mut accounts.iter();
     let signer_account = next_account_info(account)?;
@@ -66,67 +79,68 @@ pub fn process_instruction(
 
         let authority = next_account_info(account)?;
         let authority_seed = program_id.to_bytes();
-        let (expected_authority_pubkey, authority_bump_seed) =
-            Pubkey::find_program_address(&[&authority_seed], program_id);
+        let (expected_authority_pubkey, authority_bump_seed) = find_authority_pda(program_id);
 
         if expected_authority_pubkey != *authority.key {
             msg!("Invalid passed-in authority.");
-            return Err(ProgramError::InvalidArgument);
+            return Err(LightError::InvalidAuthority.into());
         }
+        // `[]` protocol fee config pda of the Merkle tree asset, does not have to exist
+        let protocol_fee_config_account = next_account_info(account)?;
+        // `[writable]` treasury, only used if a protocol fee is charged
+        let treasury_account = next_account_info(account)?;
+        // `[writable]` limits pda of the Merkle tree, does not have to exist
+        let merkle_tree_limits_account = next_account_info(account)?;
+        // `[]` global config pda, does not have to exist
+        let global_config_account = next_account_info(account)?;
+        // `[writable]` user token account of deposits (the escrow pda for SOL) or recipient of
+        // withdrawals, not read for transfers
+        let user_pda_token = next_account_info(account)?;
+        // `[writable]` relayer token account, only read if the relayer fee is not 0
+        let relayer_pda_token = next_account_info(account)?;
+        // The program mode could have changed since the send data instruction.
+        check_program_mode(
+            program_id,
+            global_config_account,
+            parse_ext_amount(&tmp_storage_pda_data.ext_amount)?,
+        )?;
 
         if tmp_storage_pda_data.found_root != 1u8 {
             msg!("Root was not found. {}", tmp_storage_pda_data.found_root);
-            return Err(ProgramError::InvalidArgument);
+            return Err(LightError::RootNotFound.into());
         }
 
-        if *merkle_tree_pda.key
-            != solana_program::pubkey::Pubkey::new(
-                &MERKLE_TREE_ACC_BYTES_ARRAY[<usize as TryFrom<u8>>::try_from(
-                    tmp_storage_pda_data.merkle_tree_index,
-                )
-                .unwrap()]
-                .0,
-            )
-        {
+        let merkle_tree_acc_bytes =
+            get_merkle_tree_acc_bytes(tmp_storage_pda_data.merkle_tree_index)?;
+        if *merkle_tree_pda.key != solana_program::pubkey::Pubkey::new(&merkle_tree_acc_bytes.0) {
             msg!(
                 "Passed-in Merkle tree account is invalid. {:?} != {:?}",
                 *merkle_tree_pda.key,
-                solana_program::pubkey::Pubkey::new(
-                    &MERKLE_TREE_ACC_BYTES_ARRAY[<usize as TryFrom<u8>>::try_from(
-                        tmp_storage_pda_data.merkle_tree_index
-                    )
-                    .unwrap()]
-                    .0
-                )
+                solana_program::pubkey::Pubkey::new(&merkle_tree_acc_bytes.0)
             );
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(LightError::InvalidMerkleTree.into());
         }
         if *merkle_tree_pda.owner != *program_id {
             msg!("Invalid merkle tree owner.");
-            return Err(ProgramError::IllegalOwner);
+            return Err(LightError::InvalidAccountOwner.into());
         }
 
         if *merkle_tree_pda_token.key
-            != solana_program::pubkey::Pubkey::new(
-                &MERKLE_TREE_ACC_BYTES_ARRAY[<usize as TryFrom<u8>>::try_from(
-                    tmp_storage_pda_data.merkle_tree_index,
-                )
-                .unwrap()]
-                .1,
-            )
+            != solana_program::pubkey::Pubkey::new(&merkle_tree_acc_bytes.1)
         {
             msg!(
                 "Passed-in Merkle tree token account is invalid. {:?} != {:?}",
                 merkle_tree_pda_token.key.to_bytes(),
-                &MERKLE_TREE_ACC_BYTES_ARRAY[<usize as TryFrom<u8>>::try_from(
-                    tmp_storage_pda_data.merkle_tree_index
-                )
-                .unwrap()]
-                .1
+                merkle_tree_acc_bytes.1
             );
-            return Err(ProgramError::InvalidInstructionData);
+            return Err(LightError::InvalidMerkleTreeTokenAccount.into());
         }
 
+        // The Merkle tree holds one asset, all token accounts have to hold the same asset.
+        let asset = get_merkle_tree_asset(tmp_storage_pda_data.merkle_tree_index)?;
+        asset.check_pool_account(program_id, merkle_tree_pda_token)?;
+        asset.check_token_program(token_program_account)?;
+
         msg!("Starting nullifier check.");
         tmp_storage_pda_data.account_type = check_and_insert_nullifier(
             program_id,
@@ -155,33 +169,57 @@ pub fn process_instruction(
             "nullifier1_pda inserted: {}",
             tmp_storage_pda_data.account_type
         );
-        let (pub_amount_checked, relayer_fee) = check_external_amount(&tmp_storage_pda_data)?;
-        let ext_amount =
-            i64::from_le_bytes(tmp_storage_pda_data.ext_amount.clone().try_into().unwrap());
+        let protocol_fee_config =
+            load_protocol_fee_config(program_id, &asset, protocol_fee_config_account)?;
+        let fee_schedule = protocol_fee_config
+            .as_ref()
+            .map(|protocol_fee_config| protocol_fee_config.fee_schedule)
+            .unwrap_or_default();
+        let (pub_amount_checked, relayer_fee, protocol_fee) =
+            check_external_amount(&tmp_storage_pda_data, &fee_schedule)?;
+        let ext_amount = parse_ext_amount(&tmp_storage_pda_data.ext_amount)?;
+        let has_post_withdraw_call =
+            tmp_storage_pda_data.post_withdraw_call_hash[..] != NO_POST_WITHDRAW_CALL;
+        if has_post_withdraw_call && ext_amount >= 0 {
+            msg!("Post withdraw calls are only executed for withdrawals.");
+            return Err(LightError::InvalidPostWithdrawCall.into());
+        }
+        if ext_amount != 0 {
+            update_total_deposits(
+                program_id,
+                merkle_tree_pda,
+                merkle_tree_limits_account,
+                ext_amount,
+                pub_amount_checked,
+                relayer_fee
+                    .checked_add(protocol_fee)
+                    .ok_or(LightError::ArithmeticOverflow)?,
+            )?;
+        }
         msg!("0 != pub_amount_checked: 0 != {}", pub_amount_checked);
 
         if 0 != pub_amount_checked {
             if ext_amount > 0 {
-                let user_pda_token = next_account_info(account)?;
-
-                if tmp_storage_pda_data.merkle_tree_index == 0 {
+                if asset == Asset::Sol {
                     // Create escrow account which is program owned.
                     // The ext_amount is transferred since we might want to charge relayer fees.
-                    create_and_check_pda(
+                    create_and_check_pda_with_seeds(
                         program_id,
                         signer_account,
                         user_pda_token,
                         system_program_account,
                         rent,
-                        &tmp_storage_pda.key.to_bytes(),
-                        &b"escrow"[..],
-                        0,                                                    //bytes
-                        <u64 as TryFrom<i64>>::try_from(ext_amount).unwrap() - rent.minimum_balance(0), // amount
-                        true,                                                 //rent_exempt
+                        &escrow_seeds(tmp_storage_pda.key),
+                        0, //bytes
+                        pub_amount_checked
+                            .checked_sub(rent.minimum_balance(0))
+                            .ok_or(LightError::InvalidDepositAmount)?, // amount
+                        true, //rent_exempt
                     )?;
                     // Close escrow account to make deposit to shielded pool.
                     close_account(user_pda_token, merkle_tree_pda_token)?;
                 } else {
+                    asset.check_token_account(user_pda_token)?;
                     token_transfer(
                         token_program_account,
                         user_pda_token,
@@ -189,25 +227,24 @@ pub fn process_instruction(
                         authority,
                         &authority_seed[..],
                         &[authority_bump_seed],
-                        <u64 as TryFrom<i64>>::try_from(ext_amount).unwrap(),
+                        pub_amount_checked,
                     )?;
                     msg!("Deposited {}", pub_amount_checked);
                 }
             } else if ext_amount < 0 {
-                let recipient_account = next_account_info(account)?;
+                let recipient_account = user_pda_token;
                 if *recipient_account.key
                     != solana_program::pubkey::Pubkey::new(&tmp_storage_pda_data.recipient)
                 {
                     msg!("Recipient has to be address specified in tx integrity hash.");
-                    return Err(ProgramError::InvalidInstructionData);
+                    return Err(LightError::InvalidRecipient.into());
                 }
 
-                // Checking for wrapped sol and Merkle tree index can only be 0. This does
-                // not allow multiple Merkle trees for wSol.
-                if tmp_storage_pda_data.merkle_tree_index == 0 {
+                if asset == Asset::Sol {
                     sol_transfer(merkle_tree_pda_token, recipient_account, pub_amount_checked)?;
                 } else {
                     msg!("withdrawing tokens");
+                    asset.check_token_account(recipient_account)?;
 
                     token_transfer(
                         token_program_account,
@@ -225,14 +262,13 @@ pub fn process_instruction(
         if relayer_fee > 0 {
             if Pubkey::new(&tmp_storage_pda_data.signing_address) != *signer_account.key {
                 msg!("Wrong relayer.");
-                return Err(ProgramError::InvalidArgument);
+                return Err(LightError::InvalidRelayer.into());
             }
-            let relayer_pda_token = next_account_info(account)?;
-
-            if tmp_storage_pda_data.merkle_tree_index == 0 {
+            if asset == Asset::Sol {
                 sol_transfer(merkle_tree_pda_token, relayer_pda_token, relayer_fee)?;
             } else {
                 msg!("withdrawing tokens");
+                asset.check_token_account(relayer_pda_token)?;
 
                 token_transfer(
                     token_program_account,
@@ -246,16 +282,42 @@ pub fn process_instruction(
             }
         }
 
+        if protocol_fee > 0 {
+            // A protocol fee is only charged if the fee config exists.
+            let protocol_fee_config =
+                protocol_fee_config.ok_or(LightError::InvalidProtocolFeeConfig)?;
+            if *treasury_account.key != protocol_fee_config.treasury {
+                msg!("Wrong treasury.");
+                return Err(LightError::InvalidTreasury.into());
+            }
+            if asset == Asset::Sol {
+                sol_transfer(merkle_tree_pda_token, treasury_account, protocol_fee)?;
+            } else {
+                asset.check_token_account(treasury_account)?;
+                token_transfer(
+                    token_program_account,
+                    merkle_tree_pda_token,
+                    treasury_account,
+                    authority,
+                    &authority_seed[..],
+                    &[authority_bump_seed],
+                    protocol_fee,
+                )?;
+            }
+            msg!("Protocol fee {}", protocol_fee);
+        }
+
         msg!("Creating two_leaves_pda.");
-        create_and_check_pda(
+        create_and_check_pda_with_seeds(
             program_id,
             signer_account,
             two_leaves_pda,
             system_program_account,
             rent,
-            &tmp_storage_pda_data.proof_a_b_c_leaves_and_nullifiers
-                [NULLIFIER_0_START..NULLIFIER_0_END],
-            &b"leaves"[..],
+            &leaves_seeds(
+                &tmp_storage_pda_data.proof_a_b_c_leaves_and_nullifiers
+                    [NULLIFIER_0_START..NULLIFIER_0_END],
+            ),
             TWO_LEAVES_PDA_SIZE, //bytes
             0,                   //lamports
             true,                //rent_exempt
@@ -265,9 +327,140 @@ pub fn process_instruction(
         let mut merkle_tree_processor =
             MerkleTreeProcessor::new(Some(tmp_storage_pda), None, *program_id)?;
         merkle_tree_processor.process_instruction(accounts)?;
+
+        emit_transaction_event(
+            &tmp_storage_pda_data,
+            merkle_tree_pda,
+            ext_amount,
+            pub_amount_checked,
+            relayer_fee,
+            protocol_fee,
+        )?;
         // Close tmp account.
         close_account(tmp_storage_pda, signer_account)?;
+
+        // Accounts:
+        // `[]` post withdraw call program
+        // post withdraw call accounts
+        if has_post_withdraw_call {
+            msg!("Invoking post withdraw call.");
+            check_and_invoke(
+                program_id,
+                &tmp_storage_pda_data.post_withdraw_call_hash,
+                instruction_data
+                    .get(9..)
+                    .ok_or(ProgramError::InvalidInstructionData)?,
+                account.as_slice(),
+            )?;
+        }
     }
 
     Ok(())
 }
+
+// Tracks the amount held by the shielded pool of the Merkle tree in current_total_deposits
+// and checks it against the limits of the Merkle tree.
+// Fees are paid from the pool, deposits add ext_amount - fees, withdrawals subtract
+// |ext_amount| + fees.
+fn update_total_deposits(
+    program_id: &Pubkey,
+    merkle_tree_pda: &AccountInfo,
+    merkle_tree_limits_account: &AccountInfo,
+    ext_amount: i64,
+    pub_amount_checked: u64,
+    fees: u64,
+) -> Result<(), ProgramError> {
+    let mut merkle_tree_limits =
+        load_merkle_tree_limits(program_id, merkle_tree_pda.key, merkle_tree_limits_account)?;
+    let mut merkle_tree_pda_data = MerkleTree::unpack(&merkle_tree_pda.data.borrow())?;
+    if ext_amount > 0 {
+        let deposit_amount = pub_amount_checked
+            .checked_sub(fees)
+            .ok_or(LightError::InvalidDepositAmount)?;
+        merkle_tree_pda_data.current_total_deposits = merkle_tree_pda_data
+            .current_total_deposits
+            .checked_add(deposit_amount)
+            .ok_or(LightError::ArithmeticOverflow)?;
+        if let Some(merkle_tree_limits) = &merkle_tree_limits {
+            merkle_tree_limits.check_deposit(
+                pub_amount_checked,
+                merkle_tree_pda_data.current_total_deposits,
+            )?;
+        }
+    } else {
+        let withdrawal_amount = pub_amount_checked
+            .checked_add(fees)
+            .ok_or(LightError::ArithmeticOverflow)?;
+        // Deposits made before the total was tracked are not included.
+        merkle_tree_pda_data.current_total_deposits = merkle_tree_pda_data
+            .current_total_deposits
+            .saturating_sub(withdrawal_amount);
+        if let Some(merkle_tree_limits) = &mut merkle_tree_limits {
+            merkle_tree_limits
+                .check_and_add_withdrawal(withdrawal_amount, <Clock as Sysvar>::get()?.slot)?;
+            MerkleTreeLimits::pack_into_slice(
+                merkle_tree_limits,
+                &mut merkle_tree_limits_account.data.borrow_mut(),
+            );
+        }
+    }
+    msg!(
+        "Total deposits {}",
+        merkle_tree_pda_data.current_total_deposits
+    );
+    MerkleTree::pack_into_slice(
+        &merkle_tree_pda_data,
+        &mut merkle_tree_pda.data.borrow_mut(),
+    );
+    Ok(())
+}
+
+fn emit_transaction_event(
+    tmp_storage_pda_data: &ChecksAndTransferState,
+    merkle_tree_pda: &AccountInfo,
+    ext_amount: i64,
+    public_amount: u64,
+    relayer_fee: u64,
+    protocol_fee: u64,
+) -> Result<(), ProgramError> {
+    let merkle_tree_pda_data = MerkleTree::unpack(&merkle_tree_pda.data.borrow())?;
+    let bytes = &tmp_storage_pda_data.proof_a_b_c_leaves_and_nullifiers;
+    let to_array = |range: std::ops::Range<usize>| -> Result<[u8; 32], ProgramError> {
+        bytes
+            .get(range)
+            .and_then(|slice| slice.try_into().ok())
+            .ok_or(ProgramError::InvalidAccountData)
+    };
+
+    let event = TransactionEvent {
+        merkle_tree_pubkey: merkle_tree_pda.key.to_bytes(),
+        root: merkle_tree_pda_data.roots[..]
+            .try_into()
+            .map_err(|_| ProgramError::InvalidAccountData)?,
+        root_index: merkle_tree_pda_data.current_root_index as u64,
+        left_leaf_index: (merkle_tree_pda_data.next_index as u64)
+            .checked_sub(2)
+            .ok_or(LightError::ArithmeticOverflow)?,
+        leaf_left: to_array(LEAF_LEFT_START..LEAF_LEFT_END)?,
+        leaf_right: to_array(LEAF_RIGHT_START..LEAF_RIGHT_END)?,
+        nullifiers: [
+            to_array(NULLIFIER_0_START..NULLIFIER_0_END)?,
+            to_array(NULLIFIER_1_START..NULLIFIER_1_END)?,
+        ],
+        encrypted_utxos: bytes
+            .get(ENCRYPTED_UTXOS_START..ENCRYPTED_UTXOS_START + ENCRYPTED_UTXOS_LENGTH)
+            .ok_or(ProgramError::InvalidAccountData)?
+            .to_vec(),
+        public_amount,
+        relayer_fee,
+        protocol_fee,
+    };
+    let event = if ext_amount > 0 {
+        LightEvent::Deposit(event)
+    } else if ext_amount < 0 {
+        LightEvent::Withdrawal(event)
+    } else {
+        LightEvent::Transfer(event)
+    };
+    event.emit()
+}
@@ -0,0 +1,196 @@
+use crate::asset::Asset;
+use crate::error::LightError;
+use crate::instructions::create_and_check_pda;
+use crate::utils::account_header::check_account_header;
+use crate::utils::config::{PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE, PROTOCOL_FEE_CONFIG_ACCOUNT_VERSION};
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    account_info::AccountInfo,
+    msg,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+    sysvar::rent::Rent,
+};
+use std::convert::TryFrom;
+
+// Protocol fee charged on deposits and withdrawals, it is transferred from the pool to a
+// treasury in the last instruction (1501).
+// Every asset has its own fee config pda derived from [asset_id, b"fee"]. The fee is fee_bps
+// basis points of |ext_amount| but at least min_fee, for deposits the amount the user
+// transfers (fees included), for withdrawals the amount the recipient receives (fees excluded).
+// Assets without fee config pay no protocol fee, transfers (ext_amount == 0) are free.
+// Like the relayer fee the protocol fee is part of the public amount:
+// deposit:    ext_amount = pub_amount + relayer_fee + protocol_fee
+// withdrawal: pub_amount = |ext_amount| + relayer_fee + protocol_fee
+// Clients read the fee config before generating the proof. If the fee config changes
+// before the last instruction the transaction fails.
+pub const PROTOCOL_FEE_SEED: &[u8] = b"fee";
+pub const MAX_FEE_BPS: u16 = 10_000;
+// asset_id, treasury, fee_bps, min_fee
+pub const SET_PROTOCOL_FEE_DATA_LEN: usize = 32 + 32 + 2 + 8;
+
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct FeeSchedule {
+    pub fee_bps: u16,
+    pub min_fee: u64,
+}
+
+impl FeeSchedule {
+    // Fee for an external amount, rounded down.
+    pub fn fee(&self, amount: u64) -> Result<u64, ProgramError> {
+        // amount * fee_bps fits into u128, the result is at most amount
+        let bps_fee = u128::from(amount) * u128::from(self.fee_bps) / u128::from(MAX_FEE_BPS);
+        let bps_fee = u64::try_from(bps_fee).map_err(|_| LightError::ArithmeticOverflow)?;
+        Ok(bps_fee.max(self.min_fee))
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProtocolFeeConfig {
+    pub is_initialized: bool,
+    pub account_type: u8,
+    pub asset_id: [u8; 32],
+    pub treasury: Pubkey,
+    pub fee_schedule: FeeSchedule,
+}
+
+impl Sealed for ProtocolFeeConfig {}
+impl IsInitialized for ProtocolFeeConfig {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for ProtocolFeeConfig {
+    const LEN: usize = 76;
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, ProtocolFeeConfig::LEN];
+        let (version, account_type, asset_id, treasury, fee_bps, min_fee) =
+            array_refs![input, 1, 1, 32, 32, 2, 8];
+        check_account_header(
+            version[0],
+            account_type[0],
+            PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE,
+        )?;
+        Ok(ProtocolFeeConfig {
+            is_initialized: true,
+            account_type: PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE,
+            asset_id: *asset_id,
+            treasury: Pubkey::new(treasury),
+            fee_schedule: FeeSchedule {
+                fee_bps: u16::from_le_bytes(*fee_bps),
+                min_fee: u64::from_le_bytes(*min_fee),
+            },
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, ProtocolFeeConfig::LEN];
+        let (version_dst, account_type_dst, asset_id_dst, treasury_dst, fee_bps_dst, min_fee_dst) =
+            mut_array_refs![dst, 1, 1, 32, 32, 2, 8];
+        *version_dst = [PROTOCOL_FEE_CONFIG_ACCOUNT_VERSION];
+        *account_type_dst = [PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE];
+        *asset_id_dst = self.asset_id;
+        *treasury_dst = self.treasury.to_bytes();
+        *fee_bps_dst = self.fee_schedule.fee_bps.to_le_bytes();
+        *min_fee_dst = self.fee_schedule.min_fee.to_le_bytes();
+    }
+}
+
+pub fn find_protocol_fee_config(program_id: &Pubkey, asset: &Asset) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&asset.asset_id(), PROTOCOL_FEE_SEED], program_id)
+}
+
+// Loads the fee config of the asset in the last instruction.
+// Returns None if no fee config was created for the asset.
+pub fn load_protocol_fee_config(
+    program_id: &Pubkey,
+    asset: &Asset,
+    protocol_fee_config_account: &AccountInfo,
+) -> Result<Option<ProtocolFeeConfig>, ProgramError> {
+    if *protocol_fee_config_account.key != find_protocol_fee_config(program_id, asset).0 {
+        msg!("Passed-in protocol fee config is not the fee config of the asset.");
+        return Err(LightError::InvalidPdaAddress.into());
+    }
+    if *protocol_fee_config_account.owner != *program_id
+        || protocol_fee_config_account.data_is_empty()
+    {
+        return Ok(None);
+    }
+    let protocol_fee_config =
+        ProtocolFeeConfig::unpack(&protocol_fee_config_account.data.borrow())?;
+    if protocol_fee_config.asset_id != asset.asset_id() {
+        msg!("Protocol fee config asset does not match the Merkle tree asset.");
+        return Err(LightError::InvalidProtocolFeeConfig.into());
+    }
+    Ok(Some(protocol_fee_config))
+}
+
+// Creates the fee config pda of an asset or updates an existing one.
+// instruction_data: [asset_id, treasury, fee_bps (u16 le), min_fee (u64 le)]
+// The treasury of SPL assets has to be a token account of the mint,
+// it is checked when the fee is transferred.
+pub fn set_protocol_fee_config<'a, 'b>(
+    program_id: &Pubkey,
+    signer_account: &'a AccountInfo<'b>,
+    protocol_fee_config_account: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    rent: &Rent,
+    instruction_data: &[u8],
+) -> Result<(), ProgramError> {
+    if instruction_data.len() < SET_PROTOCOL_FEE_DATA_LEN {
+        msg!("Protocol fee config data too short.");
+        return Err(ProgramError::InvalidInstructionData);
+    }
+    let data = array_ref![instruction_data, 0, SET_PROTOCOL_FEE_DATA_LEN];
+    let (asset_id, treasury, fee_bps, min_fee) = array_refs![data, 32, 32, 2, 8];
+    let fee_schedule = FeeSchedule {
+        fee_bps: u16::from_le_bytes(*fee_bps),
+        min_fee: u64::from_le_bytes(*min_fee),
+    };
+    if fee_schedule.fee_bps > MAX_FEE_BPS {
+        msg!(
+            "Fee {} bps exceeds {} bps.",
+            fee_schedule.fee_bps,
+            MAX_FEE_BPS
+        );
+        return Err(LightError::InvalidProtocolFeeConfig.into());
+    }
+
+    if *protocol_fee_config_account.owner != *program_id {
+        msg!("Creating protocol fee config.");
+        create_and_check_pda(
+            program_id,
+            signer_account,
+            protocol_fee_config_account,
+            system_program,
+            rent,
+            &asset_id[..],
+            PROTOCOL_FEE_SEED,
+            ProtocolFeeConfig::LEN as u64, //bytes
+            0,                             //lamports
+            true,                          //rent_exempt
+        )?;
+    } else {
+        let asset = Asset::from_asset_id(*asset_id);
+        if *protocol_fee_config_account.key != find_protocol_fee_config(program_id, &asset).0 {
+            msg!("Passed-in protocol fee config is not the fee config of the asset.");
+            return Err(LightError::InvalidPdaAddress.into());
+        }
+        ProtocolFeeConfig::unpack(&protocol_fee_config_account.data.borrow())?;
+    }
+    let protocol_fee_config = ProtocolFeeConfig {
+        is_initialized: true,
+        account_type: PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE,
+        asset_id: *asset_id,
+        treasury: Pubkey::new(treasury),
+        fee_schedule,
+    };
+    ProtocolFeeConfig::pack_into_slice(
+        &protocol_fee_config,
+        &mut protocol_fee_config_account.data.borrow_mut(),
+    );
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use crate::error::LightError;
+use crate::instructions::{close_account, create_and_check_pda};
+use crate::state::{ChecksAndTransferState, InstructionIndex};
+use crate::utils::account_header::check_account_header;
+use crate::utils::config::{RELAYER_HANDOFF_ACCOUNT_TYPE, RELAYER_HANDOFF_ACCOUNT_VERSION};
+use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
+use solana_program::{
+    account_info::AccountInfo,
+    clock::Clock,
+    msg,
+    program_error::ProgramError,
+    program_pack::{IsInitialized, Pack, Sealed},
+    pubkey::Pubkey,
+    sysvar::{rent::Rent, Sysvar},
+};
+use std::convert::TryInto;
+
+// Optional relayer hand-off, lets another relayer resume a computation if the relayer
+// which sent the data stops before the last instruction.
+// The client appends the hand-off commitment (idle slots || number of relayers || relayers)
+// to the send data after the post withdraw call commitment, it is part of the tx integrity
+// hash. The send data instruction stores it in the hand-off pda (derived from the tmp storage
+// pda and b"handoff").
+// - Pre-authorized relayers can take over the computation at any time.
+// - With idle_slots > 0 anyone can take over once the computation did not progress for
+//   idle_slots. Progress is observed by take over attempts, an attempt which finds the
+//   computation progressed records the instruction index and slot instead of taking over.
+// Taking over replaces the signer of the tmp storage pda. The signer of the last instruction
+// receives the relayer fee and the tmp storage lamports, recipient and amounts are fixed by
+// the tx integrity hash.
+// SOL deposits are funded by the signer of the last instruction, send data rejects a hand-off
+// commitment for them.
+pub const RELAYER_HANDOFF_SEED: &[u8] = b"handoff";
+pub const MAX_HANDOFF_RELAYERS: usize = 4;
+pub const RELAYER_HANDOFF_COMMITMENT_LEN: usize = 9 + 32 * MAX_HANDOFF_RELAYERS;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RelayerHandoffCommitment {
+    // 0 if only the relayers can take over.
+    pub idle_slots: u64,
+    pub relayers: Vec<Pubkey>,
+}
+
+impl RelayerHandoffCommitment {
+    pub fn commitment(&self) -> [u8; RELAYER_HANDOFF_COMMITMENT_LEN] {
+        let mut commitment = [0u8; RELAYER_HANDOFF_COMMITMENT_LEN];
+        commitment[..8].copy_from_slice(&self.idle_slots.to_le_bytes());
+        commitment[8] = self.relayers.len() as u8;
+        for (relayer_dst, relayer) in commitment[9..].chunks_mut(32).zip(self.relayers.iter()) {
+            relayer_dst.copy_from_slice(&relayer.to_bytes());
+        }
+        commitment
+    }
+
+    pub fn decode(commitment: &[u8]) -> Result<Self, ProgramError> {
+        let commitment: &[u8; RELAYER_HANDOFF_COMMITMENT_LEN] = commitment
+            .try_into()
+            .map_err(|_| ProgramError::InvalidInstructionData)?;
+        let (idle_slots, relayers_len, relayers) =
+            array_refs![commitment, 8, 1, 32 * MAX_HANDOFF_RELAYERS];
+        let relayers_len = usize::from(relayers_len[0]);
+        if relayers_len > MAX_HANDOFF_RELAYERS {
+            msg!("Too many hand-off relayers {}.", relayers_len);
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        Ok(RelayerHandoffCommitment {
+            idle_slots: u64::from_le_bytes(*idle_slots),
+            relayers: relayers
+                .chunks(32)
+                .take(relayers_len)
+                .map(Pubkey::new)
+                .collect(),
+        })
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct RelayerHandoff {
+    pub is_initialized: bool,
+    pub account_type: u8,
+    pub idle_slots: u64,
+    pub relayers: Vec<Pubkey>,
+    // Relayer which sent the data, receives the hand-off pda lamports when it is closed.
+    pub payer: Pubkey,
+    // Instruction index and slot of the last observed progress.
+    pub last_instruction_index: u64,
+    pub last_slot: u64,
+}
+
+impl Sealed for RelayerHandoff {}
+impl IsInitialized for RelayerHandoff {
+    fn is_initialized(&self) -> bool {
+        self.is_initialized
+    }
+}
+
+impl Pack for RelayerHandoff {
+    const LEN: usize = 187;
+
+    fn unpack_from_slice(input: &[u8]) -> Result<Self, ProgramError> {
+        let input = array_ref![input, 0, RelayerHandoff::LEN];
+        let (version, account_type, commitment, payer, last_instruction_index, last_slot) =
+            array_refs![input, 1, 1, RELAYER_HANDOFF_COMMITMENT_LEN, 32, 8, 8];
+        check_account_header(version[0], account_type[0], RELAYER_HANDOFF_ACCOUNT_TYPE)?;
+        let commitment = RelayerHandoffCommitment::decode(commitment)?;
+        Ok(RelayerHandoff {
+            is_initialized: true,
+            account_type: RELAYER_HANDOFF_ACCOUNT_TYPE,
+            idle_slots: commitment.idle_slots,
+            relayers: commitment.relayers,
+            payer: Pubkey::new(payer),
+            last_instruction_index: u64::from_le_bytes(*last_instruction_index),
+            last_slot: u64::from_le_bytes(*last_slot),
+        })
+    }
+
+    fn pack_into_slice(&self, dst: &mut [u8]) {
+        let dst = array_mut_ref![dst, 0, RelayerHandoff::LEN];
+        let (
+            version_dst,
+            account_type_dst,
+            commitment_dst,
+            payer_dst,
+            last_instruction_index_dst,
+            last_slot_dst,
+        ) = mut_array_refs![dst, 1, 1, RELAYER_HANDOFF_COMMITMENT_LEN, 32, 8, 8];
+        *version_dst = [RELAYER_HANDOFF_ACCOUNT_VERSION];
+        *account_type_dst = [RELAYER_HANDOFF_ACCOUNT_TYPE];
+        *commitment_dst = RelayerHandoffCommitment {
+            idle_slots: self.idle_slots,
+            relayers: self.relayers.clone(),
+        }
+        .commitment();
+        *payer_dst = self.payer.to_bytes();
+        *last_instruction_index_dst = self.last_instruction_index.to_le_bytes();
+        *last_slot_dst = self.last_slot.to_le_bytes();
+    }
+}
+
+pub fn find_relayer_handoff(program_id: &Pubkey, tmp_storage_pda: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[&tmp_storage_pda.to_bytes(), RELAYER_HANDOFF_SEED],
+        program_id,
+    )
+}
+
+// Creates the hand-off pda in the send data instruction, after the tmp storage pda
+// was initialized and the commitment was checked with the tx integrity hash.
+pub fn create_relayer_handoff<'a, 'b>(
+    program_id: &Pubkey,
+    signer_account: &'a AccountInfo<'b>,
+    tmp_storage_pda: &'a AccountInfo<'b>,
+    relayer_handoff_account: &'a AccountInfo<'b>,
+    system_program: &'a AccountInfo<'b>,
+    rent: &Rent,
+    commitment: &[u8],
+) -> Result<(), ProgramError> {
+    let commitment = RelayerHandoffCommitment::decode(commitment)?;
+    create_and_check_pda(
+        program_id,
+        signer_account,
+        relayer_handoff_account,
+        system_program,
+        rent,
+        &tmp_storage_pda.key.to_bytes(),
+        RELAYER_HANDOFF_SEED,
+        RelayerHandoff::LEN as u64, //bytes
+        0,                          //lamports
+        true,                       //rent_exempt
+    )?;
+    let relayer_handoff = RelayerHandoff {
+        is_initialized: true,
+        account_type: RELAYER_HANDOFF_ACCOUNT_TYPE,
+        idle_slots: commitment.idle_slots,
+        relayers: commitment.relayers,
+        payer: *signer_account.key,
+        last_instruction_index: InstructionIndex::unpack(&tmp_storage_pda.data.borrow())?
+            .current_instruction_index as u64,
+        last_slot: <Clock as Sysvar>::get()?.slot,
+    };
+    RelayerHandoff::pack_into_slice(
+        &relayer_handoff,
+        &mut relayer_handoff_account.data.borrow_mut(),
+    );
+    Ok(())
+}
+
+// Takes over the computation of the tmp storage pda, called by another relayer.
+// Once the transaction is complete the hand-off pda is closed to the payer instead.
+pub fn take_over_computation(
+    program_id: &Pubkey,
+    signer_account: &AccountInfo,
+    tmp_storage_pda: &AccountInfo,
+    relayer_handoff_account: &AccountInfo,
+    payer_account: &AccountInfo,
+) -> Result<(), ProgramError> {
+    if *relayer_handoff_account.key != find_relayer_handoff(program_id, tmp_storage_pda.key).0 {
+        msg!("Passed-in relayer hand-off is invalid.");
+        return Err(LightError::InvalidPdaAddress.into());
+    }
+    if *relayer_handoff_account.owner != *program_id {
+        msg!("Relayer hand-off does not exist.");
+        return Err(LightError::InvalidAccountOwner.into());
+    }
+    let mut relayer_handoff = RelayerHandoff::unpack(&relayer_handoff_account.data.borrow())?;
+
+    // The last instruction closed the tmp storage pda.
+    if *tmp_storage_pda.owner != *program_id || tmp_storage_pda.data_is_empty() {
+        if *payer_account.key != relayer_handoff.payer {
+            msg!("Wrong payer.");
+            return Err(LightError::InvalidRelayer.into());
+        }
+        msg!("Transaction is complete, closing relayer hand-off.");
+        return close_account(relayer_handoff_account, payer_account);
+    }
+
+    let instruction_index = InstructionIndex::unpack(&tmp_storage_pda.data.borrow())?;
+    if instruction_index.signer_pubkey == *signer_account.key {
+        msg!("Signer is already the relayer.");
+        return Err(LightError::InvalidRelayer.into());
+    }
+    let current_instruction_index = instruction_index.current_instruction_index as u64;
+    let current_slot = <Clock as Sysvar>::get()?.slot;
+    if !relayer_handoff.relayers.contains(signer_account.key) {
+        if relayer_handoff.idle_slots == 0 {
+            msg!("Signer is not a hand-off relayer.");
+            return Err(LightError::RelayerHandoffNotAllowed.into());
+        }
+        if current_instruction_index != relayer_handoff.last_instruction_index {
+            // Not idle, the computation progressed since the last observation.
+            msg!(
+                "Computation progressed to instruction {}, idle since slot {}.",
+                current_instruction_index,
+                current_slot
+            );
+            relayer_handoff.last_instruction_index = current_instruction_index;
+            relayer_handoff.last_slot = current_slot;
+            RelayerHandoff::pack_into_slice(
+                &relayer_handoff,
+                &mut relayer_handoff_account.data.borrow_mut(),
+            );
+            return Ok(());
+        }
+        let idle_until = relayer_handoff
+            .last_slot
+            .checked_add(relayer_handoff.idle_slots)
+            .ok_or(LightError::ArithmeticOverflow)?;
+        if current_slot < idle_until {
+            msg!(
+                "Computation is idle since slot {}, open at slot {}.",
+                relayer_handoff.last_slot,
+                idle_until
+            );
+            return Err(LightError::RelayerHandoffNotAllowed.into());
+        }
+    }
+
+    msg!(
+        "Relayer {:?} takes over from {:?} at instruction {}.",
+        signer_account.key,
+        instruction_index.signer_pubkey,
+        current_instruction_index
+    );
+    let mut tmp_storage_pda_data = ChecksAndTransferState::unpack(&tmp_storage_pda.data.borrow())?;
+    tmp_storage_pda_data.signing_address = signer_account.key.to_bytes().to_vec();
+    tmp_storage_pda_data.changed_constants[3] = true;
+    ChecksAndTransferState::pack_into_slice(
+        &tmp_storage_pda_data,
+        &mut tmp_storage_pda.data.borrow_mut(),
+    );
+    // The new relayer gets idle_slots before it can be replaced as well.
+    relayer_handoff.last_instruction_index = current_instruction_index;
+    relayer_handoff.last_slot = current_slot;
+    RelayerHandoff::pack_into_slice(
+        &relayer_handoff,
+        &mut relayer_handoff_account.data.borrow_mut(),
+    );
+    Ok(())
+}
@@ -0,0 +1,207 @@
+use crate::asset::Asset;
+use crate::instructions::{
+    send_data_commitments, PROOF_A_B_C_RANGE_END, PROOF_A_B_C_RANGE_START, SEND_DATA_LEN,
+};
+use crate::post_withdraw_call::POST_WITHDRAW_CALL_COMMITMENT_LEN;
+use crate::relayer_handoff::RELAYER_HANDOFF_COMMITMENT_LEN;
+use crate::utils::config::ENCRYPTED_UTXOS_LENGTH;
+use ark_ed_on_bn254::Fq;
+use ark_ff::{BigInteger, PrimeField};
+use arrayref::{array_ref, array_refs};
+use solana_program::{keccak, msg, program_error::ProgramError, pubkey::Pubkey};
+use std::convert::TryInto;
+
+// Typed send data (instruction data without selector):
+// - public inputs, 0..224
+// - proof a, b and c, 224..480
+// - external data, 480..SEND_DATA_LEN
+// - optional post withdraw call and relayer hand-off commitments
+// The tx integrity hash binds the external data and the commitments to the proof, it is the
+// keccak hash of
+// recipient || ext_amount || relayer || fee || merkle_tree || merkle_tree_index ||
+// encrypted_utxos || asset id (SPL Merkle trees only) || commitments
+// read big endian and reduced into the field. The public input is 32 bytes little endian.
+pub const EXT_DATA_RANGE_START: usize = PROOF_A_B_C_RANGE_END;
+pub const EXT_DATA_LEN: usize = SEND_DATA_LEN - EXT_DATA_RANGE_START;
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PublicInputs {
+    pub root: [u8; 32],
+    pub public_amount: [u8; 32],
+    pub tx_integrity_hash: [u8; 32],
+    pub nullifiers: [[u8; 32]; 2],
+    // leaf_right, leaf_left
+    pub leaves: [[u8; 32]; 2],
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ExtData {
+    pub recipient: Pubkey,
+    pub ext_amount: i64,
+    pub relayer: Pubkey,
+    pub fee: u64,
+    pub merkle_tree: Pubkey,
+    pub merkle_tree_index: u8,
+    pub encrypted_utxos: Vec<u8>,
+}
+
+impl ExtData {
+    pub fn unpack(ext_data: &[u8]) -> Result<Self, ProgramError> {
+        if ext_data.len() != EXT_DATA_LEN {
+            msg!(
+                "External data length {} != {}",
+                ext_data.len(),
+                EXT_DATA_LEN
+            );
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let ext_data = array_ref![ext_data, 0, EXT_DATA_LEN];
+        let (recipient, ext_amount, relayer, fee, merkle_tree, merkle_tree_index, encrypted_utxos) =
+            array_refs![ext_data, 32, 8, 32, 8, 32, 1, ENCRYPTED_UTXOS_LENGTH];
+        Ok(ExtData {
+            recipient: Pubkey::new_from_array(*recipient),
+            ext_amount: i64::from_le_bytes(*ext_amount),
+            relayer: Pubkey::new_from_array(*relayer),
+            fee: u64::from_le_bytes(*fee),
+            merkle_tree: Pubkey::new_from_array(*merkle_tree),
+            merkle_tree_index: merkle_tree_index[0],
+            encrypted_utxos: encrypted_utxos.to_vec(),
+        })
+    }
+
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
+        if self.encrypted_utxos.len() != ENCRYPTED_UTXOS_LENGTH {
+            msg!(
+                "Invalid encrypted utxos length {}.",
+                self.encrypted_utxos.len()
+            );
+            return Err(ProgramError::InvalidArgument);
+        }
+        Ok([
+            &self.recipient.to_bytes()[..],
+            &self.ext_amount.to_le_bytes()[..],
+            &self.relayer.to_bytes()[..],
+            &self.fee.to_le_bytes()[..],
+            &self.merkle_tree.to_bytes()[..],
+            &[self.merkle_tree_index][..],
+            &self.encrypted_utxos[..],
+        ]
+        .concat())
+    }
+
+    // Tx integrity hash for a Merkle tree of asset, as 32 bytes little endian.
+    pub fn hash(
+        &self,
+        asset: &Asset,
+        post_withdraw_call: Option<&[u8]>,
+        relayer_handoff: Option<&[u8]>,
+    ) -> Result<[u8; 32], ProgramError> {
+        // Sol keeps the hash layout without asset id.
+        let asset_id = match asset {
+            Asset::Sol => vec![],
+            Asset::Spl(_) => asset.asset_id().to_vec(),
+        };
+        let hash = keccak::hashv(&[
+            &self.pack()?[..],
+            &asset_id[..],
+            post_withdraw_call.unwrap_or_default(),
+            relayer_handoff.unwrap_or_default(),
+        ]);
+        Fq::from_be_bytes_mod_order(&hash.0)
+            .into_repr()
+            .to_bytes_le()
+            .try_into()
+            .map_err(|_| ProgramError::InvalidArgument)
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SendData {
+    pub public_inputs: PublicInputs,
+    // proof a, b and c
+    pub proof: Vec<u8>,
+    pub ext_data: ExtData,
+    pub post_withdraw_call: Option<[u8; POST_WITHDRAW_CALL_COMMITMENT_LEN]>,
+    pub relayer_handoff: Option<[u8; RELAYER_HANDOFF_COMMITMENT_LEN]>,
+}
+
+impl SendData {
+    pub fn unpack(send_data: &[u8]) -> Result<Self, ProgramError> {
+        let (post_withdraw_call, relayer_handoff) = send_data_commitments(send_data)?;
+        let public_inputs = array_ref![send_data, 0, PROOF_A_B_C_RANGE_START];
+        let (
+            root,
+            public_amount,
+            tx_integrity_hash,
+            nullifier_0,
+            nullifier_1,
+            leaf_right,
+            leaf_left,
+        ) = array_refs![public_inputs, 32, 32, 32, 32, 32, 32, 32];
+        Ok(SendData {
+            public_inputs: PublicInputs {
+                root: *root,
+                public_amount: *public_amount,
+                tx_integrity_hash: *tx_integrity_hash,
+                nullifiers: [*nullifier_0, *nullifier_1],
+                leaves: [*leaf_right, *leaf_left],
+            },
+            proof: send_data[PROOF_A_B_C_RANGE_START..PROOF_A_B_C_RANGE_END].to_vec(),
+            ext_data: ExtData::unpack(&send_data[EXT_DATA_RANGE_START..SEND_DATA_LEN])?,
+            post_withdraw_call: post_withdraw_call
+                .map(|commitment| {
+                    commitment
+                        .try_into()
+                        .map_err(|_| ProgramError::InvalidInstructionData)
+                })
+                .transpose()?,
+            relayer_handoff: relayer_handoff
+                .map(|commitment| {
+                    commitment
+                        .try_into()
+                        .map_err(|_| ProgramError::InvalidInstructionData)
+                })
+                .transpose()?,
+        })
+    }
+
+    // Instruction data of the send data instruction without selector.
+    pub fn pack(&self) -> Result<Vec<u8>, ProgramError> {
+        if self.proof.len() != PROOF_A_B_C_RANGE_END - PROOF_A_B_C_RANGE_START {
+            msg!("Invalid proof length {}.", self.proof.len());
+            return Err(ProgramError::InvalidArgument);
+        }
+        let mut send_data = [
+            &self.public_inputs.root[..],
+            &self.public_inputs.public_amount[..],
+            &self.public_inputs.tx_integrity_hash[..],
+            &self.public_inputs.nullifiers[0][..],
+            &self.public_inputs.nullifiers[1][..],
+            &self.public_inputs.leaves[0][..],
+            &self.public_inputs.leaves[1][..],
+            &self.proof[..],
+            &self.ext_data.pack()?[..],
+        ]
+        .concat();
+        if let Some(post_withdraw_call) = &self.post_withdraw_call {
+            send_data.extend_from_slice(post_withdraw_call);
+        }
+        if let Some(relayer_handoff) = &self.relayer_handoff {
+            send_data.extend_from_slice(relayer_handoff);
+        }
+        Ok(send_data)
+    }
+
+    // Tx integrity hash of the external data and the commitments.
+    pub fn tx_integrity_hash(&self, asset: &Asset) -> Result<[u8; 32], ProgramError> {
+        self.ext_data.hash(
+            asset,
+            self.post_withdraw_call
+                .as_ref()
+                .map(|commitment| &commitment[..]),
+            self.relayer_handoff
+                .as_ref()
+                .map(|commitment| &commitment[..]),
+        )
+    }
+}
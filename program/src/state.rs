@@ -1,3 +1,5 @@
+use crate::error::LightError;
+use crate::utils::account_header::check_account_header;
 use crate::utils::config::{ENCRYPTED_UTXOS_LENGTH, TMP_STORAGE_ACCOUNT_TYPE};
 use crate::IX_ORDER;
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
@@ -7,7 +9,6 @@ use solana_program::{
     program_pack::{IsInitialized, Pack, Sealed},
     pubkey::Pubkey,
 };
-use std::convert::TryInto;
 
 #[derive(Clone)]
 pub struct ChecksAndTransferState {
@@ -24,8 +25,10 @@ pub struct ChecksAndTransferState {
     pub tx_integrity_hash: Vec<u8>, // is calculated on-chain from recipient, ext_amount, signing_address,
     pub current_instruction_index: usize,
     pub proof_a_b_c_leaves_and_nullifiers: Vec<u8>,
+    // keccak hash of the post withdraw call commitment, zero if there is no call
+    pub post_withdraw_call_hash: Vec<u8>,
     // set changed_constants to true to pack specified values other values will not be packed
-    pub changed_constants: [bool; 11],
+    pub changed_constants: [bool; 12],
 }
 impl Sealed for ChecksAndTransferState {}
 impl IsInitialized for ChecksAndTransferState {
@@ -51,7 +54,7 @@ impl Pack for ChecksAndTransferState {
             ext_amount,
             amount,
             root_hash,
-            _unused, // is commit hash until changed
+            post_withdraw_call_hash,
             tx_integrity_hash,
             current_instruction_index,
             //220
@@ -76,9 +79,12 @@ impl Pack for ChecksAndTransferState {
             384 + ENCRYPTED_UTXOS_LENGTH
         ];
 
-        if _is_initialized[0] != 0u8 && account_type[0] != TMP_STORAGE_ACCOUNT_TYPE {
-            msg!("Wrong account type.");
-            return Err(ProgramError::InvalidAccountData);
+        if _is_initialized[0] != 0u8 {
+            check_account_header(
+                _is_initialized[0],
+                account_type[0],
+                TMP_STORAGE_ACCOUNT_TYPE,
+            )?;
         }
 
         Ok(ChecksAndTransferState {
@@ -95,12 +101,15 @@ impl Pack for ChecksAndTransferState {
             root_hash: root_hash.to_vec(),                 //8
             tx_integrity_hash: tx_integrity_hash.to_vec(), //10
             proof_a_b_c_leaves_and_nullifiers: proof_a_b_c_leaves_and_nullifiers.to_vec(), //11
+            post_withdraw_call_hash: post_withdraw_call_hash.to_vec(), //12
 
             current_instruction_index: usize::from_le_bytes(*current_instruction_index),
-            changed_constants: [false; 11],
+            changed_constants: [false; 12],
         })
     }
 
+    // The byte fields are only set from ranges of the same length (unpack_from_slice,
+    // try_initialize_tmp_storage_pda), Pack::pack_into_slice cannot return an error.
     fn pack_into_slice(&self, dst: &mut [u8]) {
         let dst = array_mut_ref![dst, 0, ChecksAndTransferState::LEN];
 
@@ -116,7 +125,7 @@ impl Pack for ChecksAndTransferState {
             ext_amount_dst,
             amount_dst,
             root_hash_dst,
-            _unused_dst,
+            post_withdraw_call_hash_dst,
             tx_integrity_hash_dst,
             //variables
             current_instruction_index_dst,
@@ -151,25 +160,24 @@ impl Pack for ChecksAndTransferState {
                 } else if i == 2 {
                     *merkle_tree_index_dst = [self.merkle_tree_index; 1];
                 } else if i == 3 {
-                    *signing_address_dst = self.signing_address.clone().try_into().unwrap();
+                    signing_address_dst.copy_from_slice(&self.signing_address);
                 } else if i == 4 {
-                    *relayer_fee_dst = self.relayer_fee.clone().try_into().unwrap();
+                    relayer_fee_dst.copy_from_slice(&self.relayer_fee);
                 } else if i == 5 {
-                    *recipient_dst = self.recipient.clone().try_into().unwrap();
+                    recipient_dst.copy_from_slice(&self.recipient);
                 } else if i == 6 {
-                    *ext_amount_dst = self.ext_amount.clone().try_into().unwrap();
+                    ext_amount_dst.copy_from_slice(&self.ext_amount);
                 } else if i == 7 {
-                    *amount_dst = self.amount.clone().try_into().unwrap();
+                    amount_dst.copy_from_slice(&self.amount);
                 } else if i == 8 {
-                    *root_hash_dst = self.root_hash.clone().try_into().unwrap();
+                    root_hash_dst.copy_from_slice(&self.root_hash);
                 } else if i == 9 {
-                    *tx_integrity_hash_dst = self.tx_integrity_hash.clone().try_into().unwrap();
+                    tx_integrity_hash_dst.copy_from_slice(&self.tx_integrity_hash);
                 } else if i == 10 {
-                    *proof_a_b_c_leaves_and_nullifiers_dst = self
-                        .proof_a_b_c_leaves_and_nullifiers
-                        .clone()
-                        .try_into()
-                        .unwrap();
+                    proof_a_b_c_leaves_and_nullifiers_dst
+                        .copy_from_slice(&self.proof_a_b_c_leaves_and_nullifiers);
+                } else if i == 11 {
+                    post_withdraw_call_hash_dst.copy_from_slice(&self.post_withdraw_call_hash);
                 }
             }
         }
@@ -212,17 +220,14 @@ impl Pack for InstructionIndex {
         if is_initialized[0] == 0 {
             Err(ProgramError::UninitializedAccount)
         } else {
-            if account_type[0] != TMP_STORAGE_ACCOUNT_TYPE {
-                msg!("Wrong account type tmp storage.");
-                return Err(ProgramError::InvalidAccountData);
-            }
+            check_account_header(is_initialized[0], account_type[0], TMP_STORAGE_ACCOUNT_TYPE)?;
 
             if IX_ORDER.len() <= usize::from_le_bytes(*current_instruction_index) {
                 msg!(
                     "Computation has finished at instruction index {}.",
                     usize::from_le_bytes(*current_instruction_index)
                 );
-                return Err(ProgramError::InvalidAccountData);
+                return Err(LightError::ComputationFinished.into());
             }
 
             Ok(InstructionIndex {
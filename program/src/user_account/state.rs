@@ -1,4 +1,5 @@
-use crate::config::USER_ACCOUNT_TYPE;
+use crate::config::{USER_ACCOUNT_TYPE, USER_ACCOUNT_VERSION};
+use crate::utils::account_header::check_account_header_uninitialized;
 use arrayref::{array_mut_ref, array_ref, array_refs, mut_array_refs};
 use solana_program::{
     program_error::ProgramError,
@@ -7,7 +8,7 @@ use solana_program::{
 };
 
 pub const SIZE_UTXO: usize = 64; // todo: rename to skp+ekp
-pub const UTXO_CAPACITY: usize = 1; // amount of utxos that can be stored in the user account at once
+pub const UTXO_CAPACITY: usize = 4; // amount of utxos that can be stored in the user account at once
 
 #[derive(Debug, Clone)]
 pub struct UserAccount {
@@ -36,18 +37,15 @@ impl Pack for UserAccount {
         let (is_initialized, _account_type, owner_pubkey, enc_utxos) =
             array_refs![input, 1, 1, 32, SIZE_UTXO * UTXO_CAPACITY];
 
-        if is_initialized[0] == 0 {
-            Ok(UserAccount {
-                is_initialized: true,
-                account_type: USER_ACCOUNT_TYPE,
-                owner_pubkey: solana_program::pubkey::Pubkey::new(owner_pubkey),
-                modified_ranges: Vec::new(),
-                enc_utxos: enc_utxos.to_vec(),
-                mode_init: true,
-            })
-        } else {
-            Err(ProgramError::InvalidAccountData)
-        }
+        check_account_header_uninitialized(is_initialized[0])?;
+        Ok(UserAccount {
+            is_initialized: true,
+            account_type: USER_ACCOUNT_TYPE,
+            owner_pubkey: solana_program::pubkey::Pubkey::new(owner_pubkey),
+            modified_ranges: Vec::new(),
+            enc_utxos: enc_utxos.to_vec(),
+            mode_init: true,
+        })
     }
 
     fn pack_into_slice(&self, dst: &mut [u8]) {
@@ -56,8 +54,8 @@ impl Pack for UserAccount {
             mut_array_refs![dst, 1, 1, 32, SIZE_UTXO * UTXO_CAPACITY];
 
         if self.mode_init {
-            dst_is_initialized[0] = 1;
-            dst_account_type[0] = 10;
+            dst_is_initialized[0] = USER_ACCOUNT_VERSION;
+            dst_account_type[0] = self.account_type;
             for (i, x) in dst_enc_utxos.iter_mut().enumerate() {
                 *x = self.enc_utxos[i]
             }
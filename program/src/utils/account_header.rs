@@ -0,0 +1,87 @@
+use crate::error::LightError;
+use crate::utils::config::{
+    ADMIN_CONFIG_ACCOUNT_TYPE, ADMIN_CONFIG_ACCOUNT_VERSION, GLOBAL_CONFIG_ACCOUNT_TYPE,
+    GLOBAL_CONFIG_ACCOUNT_VERSION, LEAVES_PDA_ACCOUNT_TYPE, LEAVES_PDA_ACCOUNT_VERSION,
+    MERKLE_TREE_ACCOUNT_TYPE, MERKLE_TREE_ACCOUNT_VERSION, MERKLE_TREE_LIMITS_ACCOUNT_TYPE,
+    MERKLE_TREE_LIMITS_ACCOUNT_VERSION, NULLIFIER_ACCOUNT_TYPE, NULLIFIER_ACCOUNT_VERSION,
+    PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE, PROTOCOL_FEE_CONFIG_ACCOUNT_VERSION,
+    RELAYER_HANDOFF_ACCOUNT_TYPE, RELAYER_HANDOFF_ACCOUNT_VERSION, TMP_STORAGE_ACCOUNT_TYPE,
+    TMP_STORAGE_ACCOUNT_VERSION, USER_ACCOUNT_TYPE, USER_ACCOUNT_VERSION,
+};
+use solana_program::{msg, program_error::ProgramError};
+
+// Every program account starts with the same two byte header:
+// [version, account_type]
+// The first byte used to be a plain is_initialized flag. It now holds the layout
+// version the account was written with, 0 still means uninitialized.
+// Accounts created before the version was introduced are version 1.
+pub const ACCOUNT_HEADER_LEN: usize = 2;
+
+pub fn current_account_version(account_type: u8) -> Result<u8, ProgramError> {
+    match account_type {
+        TMP_STORAGE_ACCOUNT_TYPE => Ok(TMP_STORAGE_ACCOUNT_VERSION),
+        MERKLE_TREE_ACCOUNT_TYPE => Ok(MERKLE_TREE_ACCOUNT_VERSION),
+        NULLIFIER_ACCOUNT_TYPE => Ok(NULLIFIER_ACCOUNT_VERSION),
+        LEAVES_PDA_ACCOUNT_TYPE => Ok(LEAVES_PDA_ACCOUNT_VERSION),
+        USER_ACCOUNT_TYPE => Ok(USER_ACCOUNT_VERSION),
+        PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE => Ok(PROTOCOL_FEE_CONFIG_ACCOUNT_VERSION),
+        MERKLE_TREE_LIMITS_ACCOUNT_TYPE => Ok(MERKLE_TREE_LIMITS_ACCOUNT_VERSION),
+        GLOBAL_CONFIG_ACCOUNT_TYPE => Ok(GLOBAL_CONFIG_ACCOUNT_VERSION),
+        ADMIN_CONFIG_ACCOUNT_TYPE => Ok(ADMIN_CONFIG_ACCOUNT_VERSION),
+        RELAYER_HANDOFF_ACCOUNT_TYPE => Ok(RELAYER_HANDOFF_ACCOUNT_VERSION),
+        _ => {
+            msg!("Unknown account type {}.", account_type);
+            Err(LightError::InvalidAccountType.into())
+        }
+    }
+}
+
+// Checks that an account is initialized, has the expected account type
+// and was written with the current layout version.
+// Accounts with an older version have to be migrated first.
+pub fn check_account_header(
+    version: u8,
+    account_type: u8,
+    expected_account_type: u8,
+) -> Result<(), ProgramError> {
+    if version == 0 {
+        msg!("Account is not initialized.");
+        return Err(ProgramError::UninitializedAccount);
+    }
+    if account_type != expected_account_type {
+        msg!(
+            "Wrong account type. {} != {}",
+            account_type,
+            expected_account_type
+        );
+        return Err(LightError::InvalidAccountType.into());
+    }
+    let current_version = current_account_version(expected_account_type)?;
+    if version < current_version {
+        msg!(
+            "Account version {} is outdated, migrate to version {}.",
+            version,
+            current_version
+        );
+        return Err(LightError::OutdatedAccountVersion.into());
+    }
+    if version > current_version {
+        msg!("Unsupported account version {}.", version);
+        return Err(LightError::UnsupportedAccountVersion.into());
+    }
+    Ok(())
+}
+
+// Checks that an account has not been initialized yet.
+pub fn check_account_header_uninitialized(version: u8) -> Result<(), ProgramError> {
+    if version != 0 {
+        msg!("Account is already initialized.");
+        return Err(ProgramError::AccountAlreadyInitialized);
+    }
+    Ok(())
+}
+
+// Returns the header an account of account_type is written with.
+pub fn account_header(account_type: u8) -> Result<[u8; ACCOUNT_HEADER_LEN], ProgramError> {
+    Ok([current_account_version(account_type)?, account_type])
+}
@@ -1,3 +1,10 @@
+use crate::asset::{Asset, NATIVE_SOL_ASSET_ID};
+use crate::encrypted_utxos::UNVERSIONED_ENCRYPTED_UTXOS;
+use crate::error::LightError;
+#[cfg(all(feature = "test-merkle-trees", not(target_arch = "bpf")))]
+use crate::{encrypted_utxos::ENCRYPTED_UTXOS_VERSION_1, utils::test_merkle_trees};
+use solana_program::{msg, program_error::ProgramError};
+
 pub const INIT_BYTES_MERKLE_TREE_18: [u8; 642] = [
     1, 2, 18, 0, 0, 0, 0, 0, 0, 0, 40, 66, 58, 227, 48, 224, 249, 227, 188, 18, 133, 168, 156, 214,
     220, 144, 244, 144, 67, 82, 76, 6, 135, 78, 64, 186, 52, 113, 234, 47, 27, 32, 227, 42, 164,
@@ -84,9 +91,87 @@ const MERKLE_TREE_TOKEN_ACC_BYTES_0: [u8; 32] = [
     179, 244, 89, 176, 19, 93, 136, 149, 231, 179, 213,
 ];
 
+#[cfg(not(all(feature = "test-merkle-trees", not(target_arch = "bpf"))))]
 pub const MERKLE_TREE_ACC_BYTES_ARRAY: [([u8; 32], [u8; 32]); 1] =
     [(MERKLE_TREE_ACC_BYTES_0, MERKLE_TREE_TOKEN_ACC_BYTES_0)];
 
+// The program tests whitelist the Merkle trees of test_merkle_trees.rs after the others.
+#[cfg(all(feature = "test-merkle-trees", not(target_arch = "bpf")))]
+pub const MERKLE_TREE_ACC_BYTES_ARRAY: [([u8; 32], [u8; 32]); 3] = [
+    (MERKLE_TREE_ACC_BYTES_0, MERKLE_TREE_TOKEN_ACC_BYTES_0),
+    test_merkle_trees::SOL_MERKLE_TREE_ACC_BYTES,
+    test_merkle_trees::USDC_MERKLE_TREE_ACC_BYTES,
+];
+
+// Returns the whitelisted (Merkle tree, Merkle tree token) account bytes for merkle_tree_index.
+pub fn get_merkle_tree_acc_bytes(
+    merkle_tree_index: u8,
+) -> Result<&'static ([u8; 32], [u8; 32]), ProgramError> {
+    let merkle_tree_acc_bytes_array: &'static [([u8; 32], [u8; 32])] = &MERKLE_TREE_ACC_BYTES_ARRAY;
+    match merkle_tree_acc_bytes_array.get(usize::from(merkle_tree_index)) {
+        Some(merkle_tree_acc_bytes) => Ok(merkle_tree_acc_bytes),
+        None => {
+            msg!(
+                "Merkle tree index {} is not whitelisted.",
+                merkle_tree_index
+            );
+            Err(LightError::InvalidMerkleTree.into())
+        }
+    }
+}
+
+// Asset id of every whitelisted Merkle tree, same order as MERKLE_TREE_ACC_BYTES_ARRAY.
+#[cfg(not(all(feature = "test-merkle-trees", not(target_arch = "bpf"))))]
+pub const MERKLE_TREE_ASSET_ARRAY: [[u8; 32]; 1] = [NATIVE_SOL_ASSET_ID];
+
+#[cfg(all(feature = "test-merkle-trees", not(target_arch = "bpf")))]
+pub const MERKLE_TREE_ASSET_ARRAY: [[u8; 32]; 3] =
+    [NATIVE_SOL_ASSET_ID, NATIVE_SOL_ASSET_ID, USDC_MINT];
+
+// EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v
+pub const USDC_MINT: [u8; 32] = [
+    198, 250, 122, 243, 190, 219, 173, 58, 61, 101, 243, 106, 171, 201, 116, 49, 177, 187, 228,
+    194, 210, 246, 224, 228, 124, 166, 2, 3, 69, 47, 93, 97,
+];
+
+pub fn get_merkle_tree_asset(merkle_tree_index: u8) -> Result<Asset, ProgramError> {
+    match MERKLE_TREE_ASSET_ARRAY.get(usize::from(merkle_tree_index)) {
+        Some(asset_id) => Ok(Asset::from_asset_id(*asset_id)),
+        None => {
+            msg!("Merkle tree index {} has no asset.", merkle_tree_index);
+            Err(LightError::InvalidMerkleTree.into())
+        }
+    }
+}
+
+// Encrypted utxos version of every whitelisted Merkle tree, same order as
+// MERKLE_TREE_ACC_BYTES_ARRAY. Trees whitelisted before the envelope are unversioned, their
+// encrypted utxos are not checked. Wallets move to a version 1 tree once one is whitelisted,
+// unversioned trees can't be migrated since their leaves pdas were written without the envelope.
+#[cfg(not(all(feature = "test-merkle-trees", not(target_arch = "bpf"))))]
+pub const MERKLE_TREE_ENCRYPTED_UTXOS_VERSION_ARRAY: [u8; 1] = [UNVERSIONED_ENCRYPTED_UTXOS];
+
+#[cfg(all(feature = "test-merkle-trees", not(target_arch = "bpf")))]
+pub const MERKLE_TREE_ENCRYPTED_UTXOS_VERSION_ARRAY: [u8; 3] = [
+    UNVERSIONED_ENCRYPTED_UTXOS,
+    ENCRYPTED_UTXOS_VERSION_1,
+    ENCRYPTED_UTXOS_VERSION_1,
+];
+
+pub fn get_merkle_tree_encrypted_utxos_version(merkle_tree_index: u8) -> Result<u8, ProgramError> {
+    match MERKLE_TREE_ENCRYPTED_UTXOS_VERSION_ARRAY.get(usize::from(merkle_tree_index)) {
+        Some(version) => Ok(*version),
+        None => {
+            msg!(
+                "Merkle tree index {} has no encrypted utxos version.",
+                merkle_tree_index
+            );
+            Err(LightError::InvalidMerkleTree.into())
+        }
+    }
+}
+
+// Admin authority until the admin config is created, see admin_config.rs.
 pub const MERKLE_TREE_INIT_AUTHORITY: [u8; 32] = [
     2, 99, 226, 251, 88, 66, 92, 33, 25, 216, 211, 185, 112, 203, 212, 238, 105, 144, 72, 121, 176,
     253, 106, 168, 115, 158, 154, 188, 62, 255, 166, 81,
@@ -98,5 +183,23 @@ pub const MERKLE_TREE_ACCOUNT_TYPE: u8 = 2;
 pub const NULLIFIER_ACCOUNT_TYPE: u8 = 3;
 pub const LEAVES_PDA_ACCOUNT_TYPE: u8 = 4;
 pub const USER_ACCOUNT_TYPE: u8 = 5;
+pub const PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE: u8 = 6;
+pub const MERKLE_TREE_LIMITS_ACCOUNT_TYPE: u8 = 7;
+pub const GLOBAL_CONFIG_ACCOUNT_TYPE: u8 = 8;
+pub const ADMIN_CONFIG_ACCOUNT_TYPE: u8 = 9;
+// 10 is the legacy user account type, see migration.rs.
+pub const RELAYER_HANDOFF_ACCOUNT_TYPE: u8 = 11;
+// current layout version per account type, written into the first header byte
+pub const TMP_STORAGE_ACCOUNT_VERSION: u8 = 1;
+pub const MERKLE_TREE_ACCOUNT_VERSION: u8 = 1;
+pub const NULLIFIER_ACCOUNT_VERSION: u8 = 1;
+pub const LEAVES_PDA_ACCOUNT_VERSION: u8 = 1;
+// 2: user accounts hold UTXO_CAPACITY utxos instead of one, see migration.rs.
+pub const USER_ACCOUNT_VERSION: u8 = 2;
+pub const PROTOCOL_FEE_CONFIG_ACCOUNT_VERSION: u8 = 1;
+pub const MERKLE_TREE_LIMITS_ACCOUNT_VERSION: u8 = 1;
+pub const GLOBAL_CONFIG_ACCOUNT_VERSION: u8 = 1;
+pub const ADMIN_CONFIG_ACCOUNT_VERSION: u8 = 1;
+pub const RELAYER_HANDOFF_ACCOUNT_VERSION: u8 = 1;
 //
 pub const ENCRYPTED_UTXOS_LENGTH: usize = 222;
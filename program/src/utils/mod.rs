@@ -1,2 +1,7 @@
+pub mod account_header;
 pub mod config;
 pub mod prepared_verifying_key;
+#[cfg(all(feature = "test-merkle-trees", not(target_arch = "bpf")))]
+pub mod test_merkle_trees;
+#[cfg(all(feature = "test-verifying-key", not(target_arch = "bpf")))]
+pub mod test_verifying_key;
@@ -0,0 +1,27 @@
+// Merkle trees of the program tests, whitelisted after the Merkle trees of config.rs. Their
+// accounts only exist in the tests. Only compiled off-chain with the test-merkle-trees feature,
+// see MERKLE_TREE_ACC_BYTES_ARRAY.
+
+// SOL Merkle tree of version 1 encrypted utxos.
+pub const SOL_MERKLE_TREE_ACC_BYTES: ([u8; 32], [u8; 32]) = (
+    [
+        162, 237, 84, 49, 107, 169, 163, 35, 49, 1, 211, 251, 50, 159, 109, 35, 43, 212, 42, 91,
+        156, 177, 6, 104, 248, 243, 213, 121, 240, 238, 125, 79,
+    ],
+    [
+        190, 229, 153, 130, 29, 72, 230, 199, 18, 235, 166, 30, 91, 94, 68, 61, 57, 178, 83, 126,
+        155, 130, 26, 41, 33, 64, 113, 2, 237, 67, 228, 115,
+    ],
+);
+
+// USDC Merkle tree of version 1 encrypted utxos, the token account is owned by the authority pda.
+pub const USDC_MERKLE_TREE_ACC_BYTES: ([u8; 32], [u8; 32]) = (
+    [
+        18, 242, 242, 60, 5, 134, 68, 123, 52, 121, 60, 76, 81, 151, 14, 123, 11, 243, 132, 43, 18,
+        54, 105, 185, 243, 194, 98, 178, 234, 233, 10, 88,
+    ],
+    [
+        249, 102, 145, 164, 24, 243, 154, 127, 210, 196, 109, 41, 34, 124, 128, 169, 130, 82, 155,
+        194, 101, 63, 226, 148, 56, 36, 211, 143, 198, 188, 103, 235,
+    ],
+);
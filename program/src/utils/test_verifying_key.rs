@@ -0,0 +1,81 @@
+use crate::groth16_verifier::parsers::parse_f_to_bytes;
+use ark_ec::{
+    models::bn::{g1::G1Affine, g2::G2Affine, Bn, G2Prepared},
+    PairingEngine,
+};
+use std::sync::RwLock;
+
+// Verifying key of the program tests, proofs of a local Groth16 setup (light-client
+// circuit::setup) verify against it instead of the hardcoded key of prepared_verifying_key.rs.
+// Only compiled off-chain with the test-verifying-key feature, the key is global to the
+// process and applies to every test of a test binary.
+
+pub type EllCoeff = (ark_bn254::Fq2, ark_bn254::Fq2, ark_bn254::Fq2);
+
+#[derive(Clone, Debug)]
+pub struct TestVerifyingKey {
+    pub gamma_abc_g1: Vec<G1Affine<ark_bn254::Parameters>>,
+    pub gamma_g2_neg_pc: Vec<EllCoeff>,
+    pub delta_g2_neg_pc: Vec<EllCoeff>,
+    // e(alpha_g1, beta_g2) in the layout of the final exponentiation result.
+    pub alpha_g1_beta_g2: Vec<u8>,
+}
+
+impl TestVerifyingKey {
+    // Prepares the key like ark_groth16::prepare_verifying_key.
+    pub fn new(
+        alpha_g1: G1Affine<ark_bn254::Parameters>,
+        beta_g2: G2Affine<ark_bn254::Parameters>,
+        gamma_g2: G2Affine<ark_bn254::Parameters>,
+        delta_g2: G2Affine<ark_bn254::Parameters>,
+        gamma_abc_g1: Vec<G1Affine<ark_bn254::Parameters>>,
+    ) -> Self {
+        let mut alpha_g1_beta_g2 = vec![0u8; 384];
+        parse_f_to_bytes(
+            <Bn<ark_bn254::Parameters> as PairingEngine>::pairing(alpha_g1, beta_g2),
+            &mut alpha_g1_beta_g2,
+        );
+        TestVerifyingKey {
+            gamma_abc_g1,
+            gamma_g2_neg_pc: G2Prepared::<ark_bn254::Parameters>::from(-gamma_g2).ell_coeffs,
+            delta_g2_neg_pc: G2Prepared::<ark_bn254::Parameters>::from(-delta_g2).ell_coeffs,
+            alpha_g1_beta_g2,
+        }
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref TEST_VERIFYING_KEY: RwLock<Option<TestVerifyingKey>> = RwLock::new(None);
+}
+
+// None restores the hardcoded verifying key.
+pub fn set_test_verifying_key(verifying_key: Option<TestVerifyingKey>) {
+    if let Ok(mut test_verifying_key) = TEST_VERIFYING_KEY.write() {
+        *test_verifying_key = verifying_key;
+    }
+}
+
+pub fn test_verifying_key() -> Option<TestVerifyingKey> {
+    TEST_VERIFYING_KEY
+        .read()
+        .ok()
+        .and_then(|test_verifying_key| test_verifying_key.clone())
+}
+
+pub fn gamma_abc_g1() -> Option<Vec<G1Affine<ark_bn254::Parameters>>> {
+    test_verifying_key().map(|verifying_key| verifying_key.gamma_abc_g1)
+}
+
+pub fn gamma_g2_neg_pc(id: u8) -> Option<EllCoeff> {
+    test_verifying_key()
+        .and_then(|verifying_key| verifying_key.gamma_g2_neg_pc.get(usize::from(id)).copied())
+}
+
+pub fn delta_g2_neg_pc(id: u8) -> Option<EllCoeff> {
+    test_verifying_key()
+        .and_then(|verifying_key| verifying_key.delta_g2_neg_pc.get(usize::from(id)).copied())
+}
+
+pub fn alpha_g1_beta_g2() -> Option<Vec<u8>> {
+    test_verifying_key().map(|verifying_key| verifying_key.alpha_g1_beta_g2)
+}
@@ -0,0 +1,269 @@
+use light_protocol_program::admin_config::{find_admin_config, AdminConfig};
+use light_protocol_program::error::LightError;
+use light_protocol_program::merkle_tree_limits::find_merkle_tree_limits;
+use light_protocol_program::process_instruction;
+use light_protocol_program::utils::config::{
+    ADMIN_CONFIG_ACCOUNT_TYPE, MERKLE_TREE_ACC_BYTES_ARRAY,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use solana_program_test::*;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+
+mod test_utils;
+use crate::test_utils::{custom_error, fund, process_with_signers, program_id, PRIVATE_KEY};
+
+fn admin_config_accounts(signer: &Pubkey) -> Vec<AccountMeta> {
+    vec![
+        AccountMeta::new(*signer, true),
+        AccountMeta::new(find_admin_config(&program_id()).0, false),
+        AccountMeta::new_readonly(system_program::id(), false),
+        AccountMeta::new_readonly(sysvar::rent::id(), false),
+    ]
+}
+
+fn propose_authority_instruction(signer: &Pubkey, authority: &Pubkey) -> Instruction {
+    Instruction::new_with_bincode(
+        program_id(),
+        &[vec![246u8], authority.to_bytes().to_vec()].concat(),
+        admin_config_accounts(signer),
+    )
+}
+
+fn accept_authority_instruction(signer: &Pubkey) -> Instruction {
+    Instruction::new_with_bincode(
+        program_id(),
+        &[247u8],
+        vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(find_admin_config(&program_id()).0, false),
+        ],
+    )
+}
+
+fn set_admin_multisig_instruction(
+    signer: &Pubkey,
+    threshold: u8,
+    signers: &[Pubkey],
+) -> Instruction {
+    let mut data = vec![248u8, threshold, signers.len() as u8];
+    for signer in signers.iter() {
+        data.extend_from_slice(&signer.to_bytes());
+    }
+    Instruction::new_with_bincode(program_id(), &data, admin_config_accounts(signer))
+}
+
+// Admin instruction used to check who is admin.
+fn set_limits_instruction(signer: &Pubkey) -> Instruction {
+    let merkle_tree_pubkey = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0);
+    let mut data = vec![243u8, 0u8];
+    data.extend_from_slice(&[0u8; 32]);
+    Instruction::new_with_bincode(
+        program_id(),
+        &data,
+        vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(
+                find_merkle_tree_limits(&program_id(), &merkle_tree_pubkey).0,
+                false,
+            ),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(find_admin_config(&program_id()).0, false),
+        ],
+    )
+}
+
+// Appends multisig signers to an admin instruction.
+fn with_co_signers(mut instruction: Instruction, co_signers: &[(Pubkey, bool)]) -> Instruction {
+    for (co_signer, is_signer) in co_signers.iter() {
+        instruction
+            .accounts
+            .push(AccountMeta::new_readonly(*co_signer, *is_signer));
+    }
+    instruction
+}
+
+async fn get_admin_config(program_context: &mut ProgramTestContext) -> AdminConfig {
+    let account = program_context
+        .banks_client
+        .get_account(find_admin_config(&program_id()).0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, program_id());
+    AdminConfig::unpack(&account.data).unwrap()
+}
+
+// Starts the program and funds the authority and keypairs.
+async fn start(keypairs: &[&Keypair]) -> ProgramTestContext {
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    let authority = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    fund(&mut program_context, &authority.pubkey()).await;
+    for keypair in keypairs.iter() {
+        fund(&mut program_context, &keypair.pubkey()).await;
+    }
+    program_context
+}
+
+#[test]
+fn admin_config_should_roundtrip() {
+    let admin_config = AdminConfig {
+        is_initialized: true,
+        account_type: ADMIN_CONFIG_ACCOUNT_TYPE,
+        authority: Pubkey::new_unique(),
+        pending_authority: Pubkey::new_unique(),
+        threshold: 2,
+        signers: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+    };
+    let mut data = vec![0u8; AdminConfig::LEN];
+    AdminConfig::pack_into_slice(&admin_config, &mut data);
+    assert_eq!(AdminConfig::unpack(&data).unwrap(), admin_config);
+
+    // the threshold can't exceed the number of signers
+    data[66] = 3;
+    assert!(AdminConfig::unpack(&data).is_err());
+}
+
+#[tokio::test]
+async fn authority_should_be_transferred_in_two_steps() {
+    let new_authority = Keypair::new();
+    let mut program_context = start(&[&new_authority]).await;
+    let authority = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    let payer = Keypair::from_bytes(&program_context.payer.to_bytes()).unwrap();
+
+    // without admin config the merkle tree init authority is the admin
+    let instruction = set_limits_instruction(&authority.pubkey());
+    process_with_signers(&mut program_context, instruction, &[&authority])
+        .await
+        .unwrap();
+    let instruction = propose_authority_instruction(&payer.pubkey(), &payer.pubkey());
+    let error = process_with_signers(&mut program_context, instruction, &[&payer])
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidAuthority));
+
+    let instruction = propose_authority_instruction(&authority.pubkey(), &new_authority.pubkey());
+    process_with_signers(&mut program_context, instruction, &[&authority])
+        .await
+        .unwrap();
+    let admin_config = get_admin_config(&mut program_context).await;
+    assert_eq!(admin_config.authority, authority.pubkey());
+    assert_eq!(admin_config.pending_authority, new_authority.pubkey());
+
+    // the proposed authority is not the admin before it accepts
+    let instruction = set_limits_instruction(&new_authority.pubkey());
+    let error = process_with_signers(&mut program_context, instruction, &[&new_authority])
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidAuthority));
+    // and only the proposed authority can accept
+    let instruction = accept_authority_instruction(&payer.pubkey());
+    let error = process_with_signers(&mut program_context, instruction, &[&payer])
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidAuthority));
+
+    let instruction = accept_authority_instruction(&new_authority.pubkey());
+    process_with_signers(&mut program_context, instruction, &[&new_authority])
+        .await
+        .unwrap();
+    let admin_config = get_admin_config(&mut program_context).await;
+    assert_eq!(admin_config.authority, new_authority.pubkey());
+    assert_eq!(admin_config.pending_authority, Pubkey::default());
+
+    // the merkle tree init authority is replaced
+    let instruction = set_limits_instruction(&authority.pubkey());
+    let error = process_with_signers(&mut program_context, instruction, &[&authority])
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidAuthority));
+    let instruction = set_limits_instruction(&new_authority.pubkey());
+    process_with_signers(&mut program_context, instruction, &[&new_authority])
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn admin_multisig_should_require_threshold_signers() {
+    let signer_a = Keypair::new();
+    let signer_b = Keypair::new();
+    let signer_c = Keypair::new();
+    let mut program_context = start(&[&signer_a, &signer_b, &signer_c]).await;
+    let authority = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    let signers = [signer_a.pubkey(), signer_b.pubkey(), signer_c.pubkey()];
+
+    // more signatures than signers
+    let instruction = set_admin_multisig_instruction(&authority.pubkey(), 4, &signers);
+    let error = process_with_signers(&mut program_context, instruction, &[&authority])
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidAdminMultisig));
+
+    // 2 of 3
+    let instruction = set_admin_multisig_instruction(&authority.pubkey(), 2, &signers);
+    process_with_signers(&mut program_context, instruction, &[&authority])
+        .await
+        .unwrap();
+    let admin_config = get_admin_config(&mut program_context).await;
+    assert_eq!(admin_config.threshold, 2);
+    assert_eq!(admin_config.signers, signers.to_vec());
+
+    // the authority key alone and one signer are not sufficient
+    for keypair in [&authority, &signer_a].iter() {
+        let instruction = set_limits_instruction(&keypair.pubkey());
+        let error = process_with_signers(&mut program_context, instruction, &[*keypair])
+            .await
+            .unwrap_err();
+        assert_eq!(custom_error(error), Some(LightError::NotEnoughAdminSigners));
+    }
+    // a co-signer has to sign
+    let instruction = with_co_signers(
+        set_limits_instruction(&signer_a.pubkey()),
+        &[(signer_b.pubkey(), false)],
+    );
+    let error = process_with_signers(&mut program_context, instruction, &[&signer_a])
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::NotEnoughAdminSigners));
+
+    let instruction = with_co_signers(
+        set_limits_instruction(&signer_a.pubkey()),
+        &[(signer_b.pubkey(), true)],
+    );
+    process_with_signers(&mut program_context, instruction, &[&signer_a, &signer_b])
+        .await
+        .unwrap();
+
+    // the multisig transfers the authority, accepting disables the multisig
+    let new_authority = Keypair::new();
+    fund(&mut program_context, &new_authority.pubkey()).await;
+    let instruction = with_co_signers(
+        propose_authority_instruction(&signer_c.pubkey(), &new_authority.pubkey()),
+        &[(signer_a.pubkey(), true)],
+    );
+    process_with_signers(&mut program_context, instruction, &[&signer_c, &signer_a])
+        .await
+        .unwrap();
+    let instruction = accept_authority_instruction(&new_authority.pubkey());
+    process_with_signers(&mut program_context, instruction, &[&new_authority])
+        .await
+        .unwrap();
+    let admin_config = get_admin_config(&mut program_context).await;
+    assert_eq!(admin_config.authority, new_authority.pubkey());
+    assert_eq!(admin_config.threshold, 0);
+    assert!(admin_config.signers.is_empty());
+    let instruction = set_limits_instruction(&new_authority.pubkey());
+    process_with_signers(&mut program_context, instruction, &[&new_authority])
+        .await
+        .unwrap();
+}
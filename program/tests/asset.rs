@@ -0,0 +1,94 @@
+use ark_ed_on_bn254::Fq;
+use ark_ff::{BigInteger, PrimeField};
+use light_protocol_program::asset::{Asset, NATIVE_SOL_ASSET_ID};
+use light_protocol_program::error::LightError;
+use light_protocol_program::instructions::check_tx_integrity_hash;
+use light_protocol_program::send_data::ExtData;
+use light_protocol_program::utils::config::{
+    get_merkle_tree_asset, ENCRYPTED_UTXOS_LENGTH, MERKLE_TREE_ACC_BYTES_ARRAY, USDC_MINT,
+};
+use solana_program::pubkey::Pubkey;
+
+// Tx integrity hash as the client computes it, little endian field element of the keccak hash.
+fn tx_integrity_hash(input: &[Vec<u8>]) -> Vec<u8> {
+    let hash = solana_program::keccak::hash(&input.concat()).to_bytes();
+    Fq::from_be_bytes_mod_order(&hash).into_repr().to_bytes_le()
+}
+
+fn check_hash(tx_integrity_hash: Vec<u8>, asset: Asset) -> Result<(), LightError> {
+    check_tx_integrity_hash(&ext_data(), &tx_integrity_hash, &asset, None, None)
+        .map_err(|e| LightError::from_program_error(&e).unwrap())
+}
+
+fn hash_input() -> Vec<Vec<u8>> {
+    vec![
+        vec![1u8; 32],
+        vec![2u8; 8],
+        vec![3u8; 32],
+        vec![4u8; 8],
+        MERKLE_TREE_ACC_BYTES_ARRAY[0].0.to_vec(),
+        vec![0u8],
+        vec![5u8; ENCRYPTED_UTXOS_LENGTH],
+    ]
+}
+
+// External data of hash_input.
+fn ext_data() -> ExtData {
+    ExtData {
+        recipient: Pubkey::new(&[1u8; 32]),
+        ext_amount: i64::from_le_bytes([2u8; 8]),
+        relayer: Pubkey::new(&[3u8; 32]),
+        fee: u64::from_le_bytes([4u8; 8]),
+        merkle_tree: Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0),
+        merkle_tree_index: 0,
+        encrypted_utxos: vec![5u8; ENCRYPTED_UTXOS_LENGTH],
+    }
+}
+
+#[test]
+fn asset_id_should_roundtrip() {
+    assert_eq!(Asset::from_asset_id(NATIVE_SOL_ASSET_ID), Asset::Sol);
+    assert_eq!(Asset::Sol.asset_id(), Pubkey::default().to_bytes());
+    let mint = Pubkey::new_unique();
+    assert_eq!(Asset::from_asset_id(mint.to_bytes()), Asset::Spl(mint));
+    assert_eq!(Asset::Spl(mint).asset_id(), mint.to_bytes());
+}
+
+#[test]
+fn merkle_tree_assets_should_be_configured() {
+    assert_eq!(get_merkle_tree_asset(0), Ok(Asset::Sol));
+    assert_eq!(get_merkle_tree_asset(1), Ok(Asset::Sol));
+    assert_eq!(
+        get_merkle_tree_asset(2),
+        Ok(Asset::Spl(Pubkey::new(&USDC_MINT)))
+    );
+    assert_eq!(
+        get_merkle_tree_asset(MERKLE_TREE_ACC_BYTES_ARRAY.len() as u8),
+        Err(LightError::InvalidMerkleTree.into())
+    );
+}
+
+#[test]
+fn tx_integrity_hash_should_bind_asset() {
+    let mint = Pubkey::new_unique();
+    // sol keeps the hash layout without asset id
+    let sol_hash = tx_integrity_hash(&hash_input());
+    assert_eq!(check_hash(sol_hash.clone(), Asset::Sol), Ok(()));
+    assert_eq!(
+        check_hash(sol_hash, Asset::Spl(mint)),
+        Err(LightError::InvalidTxIntegrityHash)
+    );
+
+    let mut spl_input = hash_input();
+    spl_input.push(mint.to_bytes().to_vec());
+    let spl_hash = tx_integrity_hash(&spl_input);
+    assert_eq!(check_hash(spl_hash.clone(), Asset::Spl(mint)), Ok(()));
+    assert_eq!(
+        check_hash(spl_hash.clone(), Asset::Spl(Pubkey::new_unique())),
+        Err(LightError::InvalidTxIntegrityHash)
+    );
+    assert_eq!(
+        check_hash(spl_hash, Asset::Sol),
+        Err(LightError::InvalidTxIntegrityHash)
+    );
+}
@@ -0,0 +1,123 @@
+use ark_ff::{biginteger::BigInteger256, bytes::ToBytes, fields::FpParameters};
+use light_protocol_program::error::LightError;
+use light_protocol_program::groth16_verifier::parsers::check_proof_bytes;
+use light_protocol_program::instructions::{
+    check_external_amount, check_send_data_len, SEND_DATA_LEN,
+};
+use light_protocol_program::protocol_fee::FeeSchedule;
+use light_protocol_program::utils::config::{
+    get_merkle_tree_acc_bytes, MERKLE_TREE_ACC_BYTES_ARRAY,
+};
+use solana_program::program_error::ProgramError;
+
+mod test_utils;
+use crate::test_utils::{negative_pub_amount, tmp_storage_state};
+
+#[test]
+fn merkle_tree_index_out_of_bounds_should_fail() {
+    for (merkle_tree_index, merkle_tree_acc_bytes) in MERKLE_TREE_ACC_BYTES_ARRAY.iter().enumerate()
+    {
+        assert_eq!(
+            get_merkle_tree_acc_bytes(merkle_tree_index as u8).unwrap(),
+            merkle_tree_acc_bytes
+        );
+    }
+    let out_of_bounds = MERKLE_TREE_ACC_BYTES_ARRAY.len() as u8;
+    for merkle_tree_index in [out_of_bounds, out_of_bounds + 1, u8::MAX].iter() {
+        assert_eq!(
+            get_merkle_tree_acc_bytes(*merkle_tree_index),
+            Err(LightError::InvalidMerkleTree.into())
+        );
+    }
+}
+
+#[test]
+fn check_external_amount_should_succeed() {
+    let state = tmp_storage_state(BigInteger256::from(90), 100, 10);
+    assert_eq!(
+        check_external_amount(&state, &FeeSchedule::default()),
+        Ok((100, 10, 0))
+    );
+
+    let state = tmp_storage_state(negative_pub_amount(110), -100, 10);
+    assert_eq!(
+        check_external_amount(&state, &FeeSchedule::default()),
+        Ok((100, 10, 0))
+    );
+
+    let state = tmp_storage_state(BigInteger256::from(0), 0, 0);
+    assert_eq!(
+        check_external_amount(&state, &FeeSchedule::default()),
+        Ok((0, 0, 0))
+    );
+}
+
+#[test]
+fn check_external_amount_overflow_should_fail() {
+    // pub_amount + relayer_fee overflows u64
+    let state = tmp_storage_state(BigInteger256::from(1), i64::MAX, u64::MAX);
+    assert_eq!(
+        check_external_amount(&state, &FeeSchedule::default()),
+        Err(LightError::ArithmeticOverflow.into())
+    );
+
+    // i64::MIN cannot be negated
+    let state = tmp_storage_state(negative_pub_amount(5), i64::MIN, 0);
+    assert_eq!(
+        check_external_amount(&state, &FeeSchedule::default()),
+        Err(LightError::ArithmeticOverflow.into())
+    );
+
+    // withdrawal amount + relayer_fee overflows u64
+    let state = tmp_storage_state(negative_pub_amount(5), -1, u64::MAX);
+    assert_eq!(
+        check_external_amount(&state, &FeeSchedule::default()),
+        Err(LightError::ArithmeticOverflow.into())
+    );
+}
+
+#[test]
+fn check_external_amount_mismatch_should_fail() {
+    let state = tmp_storage_state(BigInteger256::from(90), 100, 0);
+    assert_eq!(
+        check_external_amount(&state, &FeeSchedule::default()),
+        Err(LightError::InvalidDepositAmount.into())
+    );
+
+    let state = tmp_storage_state(negative_pub_amount(90), -100, 0);
+    assert_eq!(
+        check_external_amount(&state, &FeeSchedule::default()),
+        Err(LightError::InvalidWithdrawalAmount.into())
+    );
+}
+
+#[test]
+fn short_send_data_should_fail() {
+    assert_eq!(check_send_data_len(&vec![0u8; SEND_DATA_LEN]), Ok(()));
+    for len in [0, 9, 593, SEND_DATA_LEN - 1].iter() {
+        assert_eq!(
+            check_send_data_len(&vec![0u8; *len]),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+}
+
+#[test]
+fn non_canonical_proof_bytes_should_fail() {
+    assert_eq!(check_proof_bytes(&[0u8; 256]), Ok(()));
+    // Every coordinate of the proof has to be smaller than the base field modulus.
+    for coordinate in 0..8 {
+        let mut proof = [0u8; 256];
+        ark_bn254::FqParameters::MODULUS
+            .write(&mut proof[coordinate * 32..(coordinate + 1) * 32])
+            .unwrap();
+        assert_eq!(
+            check_proof_bytes(&proof),
+            Err(ProgramError::InvalidInstructionData)
+        );
+    }
+    assert_eq!(
+        check_proof_bytes(&[0u8; 255]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
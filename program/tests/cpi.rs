@@ -0,0 +1,351 @@
+use light_client::merkle_tree::MerkleTreeIndexer;
+use light_client::LightTransaction;
+use light_protocol_program::asset::Asset;
+use light_protocol_program::cpi::{
+    compute_instruction, invoke_transact_signed, last_instruction, send_data_instruction,
+    Settlement,
+};
+use light_protocol_program::error::LightError;
+use light_protocol_program::global_config::find_global_config;
+use light_protocol_program::merkle_tree_limits::find_merkle_tree_limits;
+use light_protocol_program::pda::{find_authority_pda, find_escrow_pda};
+use light_protocol_program::process_instruction;
+use light_protocol_program::protocol_fee::find_protocol_fee_config;
+use light_protocol_program::state::InstructionIndex;
+use light_protocol_program::utils::config::MERKLE_TREE_ACC_BYTES_ARRAY;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account, signature::Signer, signer::keypair::Keypair, transport::TransportError,
+};
+
+mod test_utils;
+use crate::test_utils::local_proof::{
+    deposit, insert_leaves, process_packed, sol_transaction, withdrawal,
+};
+use crate::test_utils::{custom_error, process, program_id, start_with_merkle_tree};
+
+const CALLER_SIGNER_SEED: &[u8] = b"light_signer";
+
+fn caller_program_id() -> Pubkey {
+    Pubkey::new(&[7u8; 32])
+}
+
+fn caller_signer() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[CALLER_SIGNER_SEED], &caller_program_id())
+}
+
+// Mock caller program, forwards its instruction data to the Light program and signs with its pda.
+// Accounts:
+// 0. `[writable]` caller signer pda
+// 1. `[]` Light program
+// 2.. accounts of the Light instruction after the signer
+fn mock_caller_process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let (signer, bump) = caller_signer();
+    let mut account_metas = vec![AccountMeta::new(signer, true)];
+    for account in accounts[2..].iter() {
+        account_metas.push(AccountMeta {
+            pubkey: *account.key,
+            is_signer: false,
+            is_writable: account.is_writable,
+        });
+    }
+    let instruction = Instruction {
+        program_id: *accounts[1].key,
+        accounts: account_metas,
+        data: instruction_data.to_vec(),
+    };
+    invoke_transact_signed(&instruction, accounts, &[CALLER_SIGNER_SEED, &[bump]])
+}
+
+// Wraps a Light instruction signed by the caller signer pda into a mock caller instruction.
+fn caller_instruction(light_instruction: Instruction) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(caller_signer().0, false),
+        AccountMeta::new_readonly(light_instruction.program_id, false),
+    ];
+    accounts.extend_from_slice(&light_instruction.accounts[1..]);
+    Instruction {
+        program_id: caller_program_id(),
+        accounts,
+        data: light_instruction.data,
+    }
+}
+
+// Starts the program with the mock caller, its funded signer pda and the SOL Merkle tree.
+async fn start_with_caller() -> ProgramTestContext {
+    let mut program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    program_test.add_program(
+        "mock_caller",
+        caller_program_id(),
+        processor!(mock_caller_process_instruction),
+    );
+    program_test.add_account(
+        caller_signer().0,
+        Account::new(10_000_000_000, 0, &system_program::id()),
+    );
+    start_with_merkle_tree(program_test).await
+}
+
+// Sends all instructions of the transaction through the mock caller, the payer of the test
+// pays the transaction fees.
+async fn settle_through_caller(
+    program_context: &mut ProgramTestContext,
+    transaction: &LightTransaction,
+) -> Result<(), TransportError> {
+    let payer = Keypair::from_bytes(&program_context.payer.to_bytes()).unwrap();
+    let instructions: Vec<Instruction> = transaction
+        .instructions()
+        .unwrap()
+        .into_iter()
+        .map(caller_instruction)
+        .collect();
+    process_packed(program_context, &instructions, &payer).await
+}
+
+async fn balance(program_context: &mut ProgramTestContext, pubkey: Pubkey) -> u64 {
+    program_context
+        .banks_client
+        .get_balance(pubkey)
+        .await
+        .unwrap()
+}
+
+async fn check_merkle_tree(program_context: &mut ProgramTestContext, indexer: &MerkleTreeIndexer) {
+    let merkle_tree_account = program_context
+        .banks_client
+        .get_account(Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0))
+        .await
+        .unwrap()
+        .unwrap();
+    indexer.verify_onchain(&merkle_tree_account.data).unwrap();
+}
+
+// The caller signer pda is the relayer in the tx integrity hash, it funds the deposit and
+// withdraws to an account of the choice of the caller. Every instruction is invoked by the
+// caller with invoke_transact_signed.
+#[tokio::test]
+async fn cpi_transact_should_succeed() {
+    let mut program_context = start_with_caller().await;
+    let (signer, _) = caller_signer();
+    let merkle_tree_token = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1);
+    let mut indexer =
+        MerkleTreeIndexer::new(Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0)).unwrap();
+    let signer_balance = balance(&mut program_context, signer).await;
+    let pool_balance = balance(&mut program_context, merkle_tree_token).await;
+
+    let mut deposit_transaction = sol_transaction(&signer, &signer, 1_000_000_000, 0);
+    let mut utxo = deposit(&mut deposit_transaction, &indexer, 0);
+    settle_through_caller(&mut program_context, &deposit_transaction)
+        .await
+        .unwrap();
+    utxo.index = insert_leaves(&mut indexer, &deposit_transaction);
+    check_merkle_tree(&mut program_context, &indexer).await;
+    assert_eq!(
+        balance(&mut program_context, merkle_tree_token).await,
+        pool_balance + 1_000_000_000
+    );
+    // The pda paid the deposit and the rent of the leaves and nullifier pdas, the tmp storage
+    // pda was closed into it.
+    let pdas = deposit_transaction.pdas();
+    let mut pdas_rent = 0;
+    for pda in [pdas.leaves, pdas.nullifiers[0], pdas.nullifiers[1]] {
+        pdas_rent += balance(&mut program_context, pda).await;
+    }
+    assert_eq!(
+        balance(&mut program_context, signer).await,
+        signer_balance - 1_000_000_000 - pdas_rent
+    );
+    assert!(program_context
+        .banks_client
+        .get_account(pdas.tmp_storage)
+        .await
+        .unwrap()
+        .is_none());
+
+    let recipient = Pubkey::new_unique();
+    let mut withdrawal_transaction = sol_transaction(&signer, &recipient, -400_000_000, 0);
+    withdrawal(&mut withdrawal_transaction, utxo, &indexer, 0);
+    settle_through_caller(&mut program_context, &withdrawal_transaction)
+        .await
+        .unwrap();
+    insert_leaves(&mut indexer, &withdrawal_transaction);
+    check_merkle_tree(&mut program_context, &indexer).await;
+    assert_eq!(balance(&mut program_context, recipient).await, 400_000_000);
+    assert_eq!(
+        balance(&mut program_context, merkle_tree_token).await,
+        pool_balance + 600_000_000
+    );
+}
+
+#[tokio::test]
+async fn cpi_transact_should_be_bound_to_the_caller_signer() {
+    let mut program_context = start_with_caller().await;
+    let payer = Keypair::from_bytes(&program_context.payer.to_bytes()).unwrap();
+    let (signer, _) = caller_signer();
+    let indexer = MerkleTreeIndexer::new(Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0)).unwrap();
+
+    // The caller signer pda can't send data which commits to another relayer.
+    let relayer = Keypair::new();
+    let mut transaction = sol_transaction(&relayer.pubkey(), &signer, 1_000_000_000, 0);
+    deposit(&mut transaction, &indexer, 0);
+    let instruction =
+        send_data_instruction(&program_id(), &signer, &transaction.send_data().unwrap()).unwrap();
+    let error = process(
+        &mut program_context,
+        caller_instruction(instruction),
+        &payer,
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidRelayer));
+
+    // A transaction sent by the pda can only be continued by the caller program.
+    let mut transaction = sol_transaction(&signer, &signer, 1_000_000_000, 0);
+    deposit(&mut transaction, &indexer, 0);
+    let instructions = transaction.instructions().unwrap();
+    process(
+        &mut program_context,
+        caller_instruction(instructions[0].clone()),
+        &payer,
+    )
+    .await
+    .unwrap();
+    let tmp_storage = transaction.pdas().tmp_storage;
+    let instruction = compute_instruction(
+        &program_id(),
+        &payer.pubkey(),
+        &tmp_storage,
+        &transaction.ext_data.merkle_tree,
+        1,
+    );
+    assert!(process(&mut program_context, instruction, &payer)
+        .await
+        .is_err());
+    process(
+        &mut program_context,
+        caller_instruction(instructions[1].clone()),
+        &payer,
+    )
+    .await
+    .unwrap();
+    let tmp_storage_account = program_context
+        .banks_client
+        .get_account(tmp_storage)
+        .await
+        .unwrap()
+        .unwrap();
+    let instruction_index = InstructionIndex::unpack(&tmp_storage_account.data).unwrap();
+    assert_eq!(instruction_index.signer_pubkey, signer);
+    assert_eq!(instruction_index.current_instruction_index, 2);
+}
+
+#[test]
+fn last_instruction_accounts_should_match() {
+    let program_id = program_id();
+    let signer = caller_signer().0;
+    let tmp_storage_pda = Pubkey::new_unique();
+    let merkle_tree = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0);
+    let merkle_tree_token = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1);
+    let nullifiers = [[1u8; 32], [2u8; 32]];
+    let escrow = find_escrow_pda(&program_id, &tmp_storage_pda).0;
+    let recipient = Pubkey::new_unique();
+    let relayer_token = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+
+    let deposit = last_instruction(
+        &program_id,
+        &signer,
+        &tmp_storage_pda,
+        &nullifiers,
+        &merkle_tree,
+        &merkle_tree_token,
+        &Asset::Sol,
+        Settlement::Deposit { user_token: escrow },
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let keys: Vec<Pubkey> = deposit.accounts.iter().map(|meta| meta.pubkey).collect();
+    assert_eq!(
+        keys,
+        vec![
+            signer,
+            tmp_storage_pda,
+            Pubkey::find_program_address(&[&nullifiers[0], &b"leaves"[..]], &program_id).0,
+            Pubkey::find_program_address(&[&nullifiers[0], &b"nf"[..]], &program_id).0,
+            Pubkey::find_program_address(&[&nullifiers[1], &b"nf"[..]], &program_id).0,
+            merkle_tree,
+            merkle_tree_token,
+            system_program::id(),
+            spl_token::id(),
+            sysvar::rent::id(),
+            find_authority_pda(&program_id).0,
+            find_protocol_fee_config(&program_id, &Asset::Sol).0,
+            // no treasury without protocol fee
+            signer,
+            find_merkle_tree_limits(&program_id, &merkle_tree).0,
+            find_global_config(&program_id).0,
+            escrow,
+            // no relayer token without relayer fee
+            signer,
+        ]
+    );
+    assert!(deposit.accounts[0].is_signer);
+    // data[8] is not an admin selector
+    assert_eq!(deposit.data[8], 21);
+
+    let withdrawal = last_instruction(
+        &program_id,
+        &signer,
+        &tmp_storage_pda,
+        &nullifiers,
+        &merkle_tree,
+        &merkle_tree_token,
+        &Asset::Sol,
+        Settlement::Withdrawal { recipient },
+        Some(&relayer_token),
+        Some(&treasury),
+        None,
+    )
+    .unwrap();
+    assert_eq!(withdrawal.accounts.len(), 17);
+    assert_eq!(withdrawal.accounts[12].pubkey, treasury);
+    assert_eq!(withdrawal.accounts[15].pubkey, recipient);
+    assert_eq!(withdrawal.accounts[16].pubkey, relayer_token);
+
+    let transfer = last_instruction(
+        &program_id,
+        &signer,
+        &tmp_storage_pda,
+        &nullifiers,
+        &merkle_tree,
+        &merkle_tree_token,
+        &Asset::Sol,
+        Settlement::Transfer,
+        Some(&relayer_token),
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(transfer.accounts.len(), 17);
+    // transfers don't read the user token slot
+    assert_eq!(transfer.accounts[15].pubkey, signer);
+    assert_eq!(transfer.accounts[16].pubkey, relayer_token);
+}
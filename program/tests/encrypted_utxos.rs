@@ -0,0 +1,148 @@
+use light_client::merkle_tree::MerkleTreeIndexer;
+use light_client::scanner::UtxoScanner;
+use light_protocol_program::encrypted_utxos::{
+    check_encrypted_utxos_version, encrypted_utxo_range, ENCRYPTED_UTXOS_PADDING_START,
+    ENCRYPTED_UTXOS_VERSION_1, UNVERSIONED_ENCRYPTED_UTXOS,
+};
+use light_protocol_program::error::LightError;
+use light_protocol_program::process_instruction;
+use light_protocol_program::utils::config::{
+    get_merkle_tree_encrypted_utxos_version, ENCRYPTED_UTXOS_LENGTH, MERKLE_TREE_ACC_BYTES_ARRAY,
+};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+use solana_program_test::*;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+
+mod test_utils;
+use crate::test_utils::local_proof::{
+    deposit, deposit_utxos, encrypt_outputs, insert_leaves, local_encryption_keypair,
+    local_keypair, prove, settle, transaction,
+};
+use crate::test_utils::{
+    custom_error, fund, program_id, read_send_data, start_with_merkle_tree_index,
+};
+
+#[test]
+fn envelope_should_fill_the_encrypted_utxos() {
+    assert_eq!(encrypted_utxo_range(0), 33..113);
+    assert_eq!(encrypted_utxo_range(1), 113..193);
+    assert_eq!(ENCRYPTED_UTXOS_PADDING_START, 193);
+    assert!(ENCRYPTED_UTXOS_PADDING_START <= ENCRYPTED_UTXOS_LENGTH);
+}
+
+#[test]
+fn unversioned_merkle_trees_should_accept_fixture_utxos() {
+    // The fixture tree was whitelisted before the envelope.
+    assert_eq!(
+        get_merkle_tree_encrypted_utxos_version(0),
+        Ok(UNVERSIONED_ENCRYPTED_UTXOS)
+    );
+    for index in 1..MERKLE_TREE_ACC_BYTES_ARRAY.len() {
+        assert_eq!(
+            get_merkle_tree_encrypted_utxos_version(index as u8),
+            Ok(ENCRYPTED_UTXOS_VERSION_1)
+        );
+    }
+    assert!(
+        get_merkle_tree_encrypted_utxos_version(MERKLE_TREE_ACC_BYTES_ARRAY.len() as u8).is_err()
+    );
+    for file in ["deposit.txt", "withdraw.txt", "internal_transfer.txt"].iter() {
+        let send_data = read_send_data(file);
+        let encrypted_utxos = &send_data[593..593 + ENCRYPTED_UTXOS_LENGTH];
+        check_encrypted_utxos_version(UNVERSIONED_ENCRYPTED_UTXOS, encrypted_utxos).unwrap();
+        assert_eq!(
+            check_encrypted_utxos_version(ENCRYPTED_UTXOS_VERSION_1, encrypted_utxos),
+            Err(LightError::InvalidEncryptedUtxosVersion.into())
+        );
+    }
+}
+
+#[test]
+fn version_1_envelopes_should_be_checked() {
+    let mut encrypted_utxos = vec![7u8; ENCRYPTED_UTXOS_LENGTH];
+    encrypted_utxos[0] = ENCRYPTED_UTXOS_VERSION_1;
+    for byte in encrypted_utxos[ENCRYPTED_UTXOS_PADDING_START..].iter_mut() {
+        *byte = 0;
+    }
+    check_encrypted_utxos_version(ENCRYPTED_UTXOS_VERSION_1, &encrypted_utxos).unwrap();
+
+    let mut invalid_padding = encrypted_utxos.clone();
+    invalid_padding[ENCRYPTED_UTXOS_LENGTH - 1] = 1;
+    assert_eq!(
+        check_encrypted_utxos_version(ENCRYPTED_UTXOS_VERSION_1, &invalid_padding),
+        Err(LightError::InvalidEncryptedUtxosVersion.into())
+    );
+    let mut unknown_version = encrypted_utxos.clone();
+    unknown_version[0] = 2;
+    assert_eq!(
+        check_encrypted_utxos_version(ENCRYPTED_UTXOS_VERSION_1, &unknown_version),
+        Err(LightError::InvalidEncryptedUtxosVersion.into())
+    );
+    // Merkle trees can't require unknown versions.
+    assert_eq!(
+        check_encrypted_utxos_version(2, &unknown_version),
+        Err(LightError::InvalidEncryptedUtxosVersion.into())
+    );
+    assert_eq!(
+        check_encrypted_utxos_version(ENCRYPTED_UTXOS_VERSION_1, &encrypted_utxos[1..]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+}
+
+// The send data instruction of the version 1 tree rejects encrypted utxos without the envelope
+// even if they are bound by the proof, wallets can decrypt the utxos of accepted transactions.
+#[tokio::test]
+async fn version_1_merkle_tree_should_reject_invalid_envelopes() {
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = start_with_merkle_tree_index(program_test, 1).await;
+    let merkle_tree = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[1].0);
+    let relayer = Keypair::new();
+    fund(&mut program_context, &relayer.pubkey()).await;
+    let mut indexer = MerkleTreeIndexer::new(merkle_tree).unwrap();
+
+    let mut invalid_version =
+        transaction(1, &relayer.pubkey(), &relayer.pubkey(), 1_000_000_000, 0);
+    let (inputs, outputs) = deposit_utxos(&invalid_version, 0);
+    encrypt_outputs(&mut invalid_version, &outputs);
+    invalid_version.ext_data.encrypted_utxos[0] = 2;
+    prove(&mut invalid_version, inputs, outputs, &indexer, 0);
+    let error = settle(&mut program_context, &invalid_version, &relayer)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        custom_error(error),
+        Some(LightError::InvalidEncryptedUtxosVersion)
+    );
+    assert!(program_context
+        .banks_client
+        .get_account(invalid_version.pdas().tmp_storage)
+        .await
+        .unwrap()
+        .is_none());
+
+    let mut valid_version = transaction(1, &relayer.pubkey(), &relayer.pubkey(), 1_000_000_000, 0);
+    let utxo = deposit(&mut valid_version, &indexer, 0);
+    settle(&mut program_context, &valid_version, &relayer)
+        .await
+        .unwrap();
+    let left_leaf_index = insert_leaves(&mut indexer, &valid_version);
+    let leaves_account = program_context
+        .banks_client
+        .get_account(valid_version.pdas().leaves)
+        .await
+        .unwrap()
+        .unwrap();
+    let mut scanner = UtxoScanner::new(
+        program_id(),
+        merkle_tree,
+        local_keypair(),
+        local_encryption_keypair(),
+    );
+    assert_eq!(scanner.scan([&leaves_account.data[..]]).unwrap(), 1);
+    assert_eq!(scanner.utxos[0].amount, utxo.amount);
+    assert_eq!(scanner.utxos[0].index, left_leaf_index);
+}
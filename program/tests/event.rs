@@ -0,0 +1,36 @@
+use light_protocol_program::event::{LightEvent, TransactionEvent};
+use light_protocol_program::utils::config::ENCRYPTED_UTXOS_LENGTH;
+
+fn transaction_event() -> TransactionEvent {
+    TransactionEvent {
+        merkle_tree_pubkey: [1u8; 32],
+        root: [2u8; 32],
+        root_index: 3,
+        left_leaf_index: 4,
+        leaf_left: [5u8; 32],
+        leaf_right: [6u8; 32],
+        nullifiers: [[7u8; 32], [8u8; 32]],
+        encrypted_utxos: vec![9u8; ENCRYPTED_UTXOS_LENGTH],
+        public_amount: 10,
+        relayer_fee: 11,
+        protocol_fee: 12,
+    }
+}
+
+#[test]
+fn light_event_should_roundtrip() {
+    use borsh::BorshSerialize;
+
+    let events = [
+        LightEvent::Deposit(transaction_event()),
+        LightEvent::Transfer(transaction_event()),
+        LightEvent::Withdrawal(transaction_event()),
+    ];
+    for (tag, event) in events.iter().enumerate() {
+        let bytes = event.try_to_vec().unwrap();
+        // the enum tag encodes the direction
+        assert_eq!(bytes[0], tag as u8);
+        assert_eq!(LightEvent::decode(&bytes).unwrap(), *event);
+        assert_eq!(*event.transaction_event(), transaction_event());
+    }
+}
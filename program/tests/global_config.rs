@@ -0,0 +1,311 @@
+use light_protocol_program::admin_config::find_admin_config;
+use light_protocol_program::asset::Asset;
+use light_protocol_program::cpi::{last_instruction, send_data_instruction, Settlement};
+use light_protocol_program::error::LightError;
+use light_protocol_program::global_config::{find_global_config, GlobalConfig, ProgramMode};
+use light_protocol_program::pda::{find_escrow_pda, find_tmp_storage_pda};
+use light_protocol_program::process_instruction;
+use light_protocol_program::utils::config::{
+    GLOBAL_CONFIG_ACCOUNT_TYPE, MERKLE_TREE_ACC_BYTES_ARRAY,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    system_program, sysvar,
+};
+use solana_program_test::*;
+use solana_sdk::{
+    account::Account, signature::Signer, signer::keypair::Keypair, transport::TransportError,
+};
+
+mod test_utils;
+use crate::test_utils::{
+    custom_error, fund, process, program_id, read_send_data, CURRENT_INSTRUCTION_INDEX_START,
+    PRIVATE_KEY, PRIV_KEY_DEPOSIT,
+};
+
+fn set_guardian_instruction(signer: &Pubkey, guardian: &Pubkey) -> Instruction {
+    Instruction::new_with_bincode(
+        program_id(),
+        &[vec![244u8], guardian.to_bytes().to_vec()].concat(),
+        vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(find_global_config(&program_id()).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(find_admin_config(&program_id()).0, false),
+        ],
+    )
+}
+
+fn set_program_mode_instruction(signer: &Pubkey, mode: ProgramMode) -> Instruction {
+    Instruction::new_with_bincode(
+        program_id(),
+        &[245u8, mode as u8],
+        vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(find_global_config(&program_id()).0, false),
+        ],
+    )
+}
+
+async fn get_global_config(program_context: &mut ProgramTestContext) -> GlobalConfig {
+    let account = program_context
+        .banks_client
+        .get_account(find_global_config(&program_id()).0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, program_id());
+    GlobalConfig::unpack(&account.data).unwrap()
+}
+
+// Starts the program, the authority sets guardian as guardian and the guardian switches to mode.
+async fn start_in_mode(guardian: &Keypair, mode: ProgramMode) -> ProgramTestContext {
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    let authority = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    fund(&mut program_context, &authority.pubkey()).await;
+    fund(&mut program_context, &guardian.pubkey()).await;
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    fund(&mut program_context, &relayer.pubkey()).await;
+
+    let instruction = set_guardian_instruction(&authority.pubkey(), &guardian.pubkey());
+    process(&mut program_context, instruction, &authority)
+        .await
+        .unwrap();
+    let instruction = set_program_mode_instruction(&guardian.pubkey(), mode);
+    process(&mut program_context, instruction, guardian)
+        .await
+        .unwrap();
+    assert_eq!(get_global_config(&mut program_context).await.mode, mode);
+    program_context
+}
+
+// Sends the send data of file, signed by the relayer committed to in the file.
+async fn send_data(
+    program_context: &mut ProgramTestContext,
+    file: &str,
+) -> Result<(), TransportError> {
+    let relayer = if file == "deposit.txt" {
+        Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap()
+    } else {
+        Keypair::from_bytes(&PRIVATE_KEY).unwrap()
+    };
+    let instruction =
+        send_data_instruction(&program_id(), &relayer.pubkey(), &read_send_data(file)).unwrap();
+    process(program_context, instruction, &relayer).await
+}
+
+#[test]
+fn program_modes_should_allow_transactions() {
+    // deposit, transfer, withdrawal
+    let ext_amounts = [1, 0, -1];
+    let modes = [
+        (ProgramMode::Active, [true, true, true]),
+        (ProgramMode::Paused, [false, false, false]),
+        (ProgramMode::WithdrawOnly, [false, false, true]),
+        (ProgramMode::DepositOnly, [true, false, false]),
+    ];
+    for (mode, allowed) in modes.iter() {
+        assert_eq!(ProgramMode::from_u8(*mode as u8), Ok(*mode));
+        for (ext_amount, allowed) in ext_amounts.iter().zip(allowed.iter()) {
+            let expected = if *allowed {
+                Ok(())
+            } else {
+                Err(LightError::TransactionNotAllowed.into())
+            };
+            assert_eq!(mode.check_transaction(*ext_amount), expected);
+        }
+    }
+    assert!(ProgramMode::from_u8(4).is_err());
+}
+
+#[tokio::test]
+async fn active_mode_should_allow_all_transactions() {
+    let guardian = Keypair::new();
+    let mut program_context = start_in_mode(&guardian, ProgramMode::Active).await;
+    send_data(&mut program_context, "deposit.txt")
+        .await
+        .unwrap();
+    send_data(&mut program_context, "withdraw.txt")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn paused_mode_should_reject_all_transactions() {
+    let guardian = Keypair::new();
+    let mut program_context = start_in_mode(&guardian, ProgramMode::Paused).await;
+    for file in ["deposit.txt", "internal_transfer.txt", "withdraw.txt"].iter() {
+        let error = send_data(&mut program_context, file).await.unwrap_err();
+        assert_eq!(custom_error(error), Some(LightError::TransactionNotAllowed));
+    }
+
+    // the guardian unpauses the program
+    let instruction = set_program_mode_instruction(&guardian.pubkey(), ProgramMode::Active);
+    process(&mut program_context, instruction, &guardian)
+        .await
+        .unwrap();
+    send_data(&mut program_context, "deposit.txt")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn withdraw_only_mode_should_reject_deposits_and_transfers() {
+    let guardian = Keypair::new();
+    let mut program_context = start_in_mode(&guardian, ProgramMode::WithdrawOnly).await;
+    for file in ["deposit.txt", "internal_transfer.txt"].iter() {
+        let error = send_data(&mut program_context, file).await.unwrap_err();
+        assert_eq!(custom_error(error), Some(LightError::TransactionNotAllowed));
+    }
+    send_data(&mut program_context, "withdraw.txt")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn deposit_only_mode_should_reject_withdrawals_and_transfers() {
+    let guardian = Keypair::new();
+    let mut program_context = start_in_mode(&guardian, ProgramMode::DepositOnly).await;
+    for file in ["withdraw.txt", "internal_transfer.txt"].iter() {
+        let error = send_data(&mut program_context, file).await.unwrap_err();
+        assert_eq!(custom_error(error), Some(LightError::TransactionNotAllowed));
+    }
+    send_data(&mut program_context, "deposit.txt")
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn only_guardian_should_switch_program_mode() {
+    let guardian = Keypair::new();
+    let mut program_context = start_in_mode(&guardian, ProgramMode::Active).await;
+    let authority = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    let payer = Keypair::from_bytes(&program_context.payer.to_bytes()).unwrap();
+
+    // neither the merkle tree init authority nor anyone else
+    for signer in [&authority, &payer].iter() {
+        let instruction = set_program_mode_instruction(&signer.pubkey(), ProgramMode::Paused);
+        let error = process(&mut program_context, instruction, signer)
+            .await
+            .unwrap_err();
+        assert_eq!(custom_error(error), Some(LightError::InvalidGuardian));
+    }
+    // only the authority sets the guardian
+    let instruction = set_guardian_instruction(&payer.pubkey(), &payer.pubkey());
+    let error = process(&mut program_context, instruction, &payer)
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidAuthority));
+
+    // the authority replaces the guardian, the mode is kept
+    let new_guardian = Keypair::new();
+    fund(&mut program_context, &new_guardian.pubkey()).await;
+    let instruction = set_guardian_instruction(&authority.pubkey(), &new_guardian.pubkey());
+    process(&mut program_context, instruction, &authority)
+        .await
+        .unwrap();
+    let instruction = set_program_mode_instruction(&guardian.pubkey(), ProgramMode::Paused);
+    let error = process(&mut program_context, instruction, &guardian)
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidGuardian));
+    let instruction = set_program_mode_instruction(&new_guardian.pubkey(), ProgramMode::Paused);
+    process(&mut program_context, instruction, &new_guardian)
+        .await
+        .unwrap();
+    let global_config = get_global_config(&mut program_context).await;
+    assert_eq!(global_config.guardian, new_guardian.pubkey());
+    assert_eq!(global_config.mode, ProgramMode::Paused);
+}
+
+#[tokio::test]
+async fn paused_mode_should_stop_transactions_in_progress() {
+    let send_data = read_send_data("deposit.txt");
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let tmp_storage_pda =
+        find_tmp_storage_pda(&program_id(), &send_data[64..96], &relayer.pubkey()).0;
+
+    // the send data instruction succeeds before the program is paused
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    fund(&mut program_context, &relayer.pubkey()).await;
+    let instruction = send_data_instruction(&program_id(), &relayer.pubkey(), &send_data).unwrap();
+    process(&mut program_context, instruction, &relayer)
+        .await
+        .unwrap();
+    let mut tmp_storage_account = program_context
+        .banks_client
+        .get_account(tmp_storage_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    // skip the proof verification
+    tmp_storage_account.data[CURRENT_INSTRUCTION_INDEX_START..CURRENT_INSTRUCTION_INDEX_START + 8]
+        .copy_from_slice(&1501usize.to_le_bytes());
+
+    let mut global_config_data = vec![0u8; GlobalConfig::LEN];
+    GlobalConfig::pack_into_slice(
+        &GlobalConfig {
+            is_initialized: true,
+            account_type: GLOBAL_CONFIG_ACCOUNT_TYPE,
+            guardian: Pubkey::new_unique(),
+            mode: ProgramMode::Paused,
+        },
+        &mut global_config_data,
+    );
+    let mut program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    program_test.add_account(tmp_storage_pda, tmp_storage_account);
+    program_test.add_account(
+        find_global_config(&program_id()).0,
+        Account {
+            lamports: Rent::default().minimum_balance(GlobalConfig::LEN),
+            data: global_config_data,
+            owner: program_id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+    let mut program_context = program_test.start_with_context().await;
+    fund(&mut program_context, &relayer.pubkey()).await;
+
+    let mut nullifiers = [[0u8; 32]; 2];
+    nullifiers[0].copy_from_slice(&send_data[96..128]);
+    nullifiers[1].copy_from_slice(&send_data[128..160]);
+    let instruction = last_instruction(
+        &program_id(),
+        &relayer.pubkey(),
+        &tmp_storage_pda,
+        &nullifiers,
+        &Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0),
+        &Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1),
+        &Asset::Sol,
+        Settlement::Deposit {
+            user_token: find_escrow_pda(&program_id(), &tmp_storage_pda).0,
+        },
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    let error = process(&mut program_context, instruction, &relayer)
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::TransactionNotAllowed));
+}
@@ -0,0 +1,33 @@
+use light_protocol_program::error::LightError;
+use solana_program::program_error::ProgramError;
+
+#[test]
+fn light_error_codes_should_roundtrip() {
+    let errors = [
+        LightError::MissingSigner,
+        LightError::RootNotFound,
+        LightError::MerkleTreeLocked,
+        LightError::NullifierAlreadySpent,
+        LightError::InvalidTxIntegrityHash,
+        LightError::MissingLayoutUpgrade,
+    ];
+    for error in errors.iter() {
+        let program_error: ProgramError = (*error).into();
+        assert_eq!(program_error, ProgramError::Custom(*error as u32));
+        assert_eq!(LightError::from_program_error(&program_error), Some(*error));
+        assert_eq!(LightError::from_code(*error as u32), Some(*error));
+    }
+    // codes are stable
+    assert_eq!(LightError::RootNotFound as u32, 9);
+    assert_eq!(LightError::MerkleTreeLocked as u32, 13);
+    assert_eq!(LightError::NullifierAlreadySpent as u32, 16);
+}
+
+#[test]
+fn unknown_error_codes_should_not_decode() {
+    assert_eq!(LightError::from_code(1000), None);
+    assert_eq!(
+        LightError::from_program_error(&ProgramError::InvalidArgument),
+        None
+    );
+}
@@ -0,0 +1,110 @@
+use light_client::merkle_tree::MerkleTreeIndexer;
+use light_protocol_program::process_instruction;
+use light_protocol_program::utils::config::MERKLE_TREE_ACC_BYTES_ARRAY;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+
+mod test_utils;
+use crate::test_utils::local_proof::{deposit, insert_leaves, settle, sol_transaction, withdrawal};
+use crate::test_utils::{fund, program_id, start_with_merkle_tree};
+
+// Settles transactions proven with light-client against the verifying key of its local setup,
+// all 1502 instructions are processed by the program.
+#[tokio::test]
+async fn local_deposit_and_withdrawal_should_succeed() {
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = start_with_merkle_tree(program_test).await;
+    let merkle_tree = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0);
+    let merkle_tree_token = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1);
+    let relayer = Keypair::new();
+    fund(&mut program_context, &relayer.pubkey()).await;
+    let mut indexer = MerkleTreeIndexer::new(merkle_tree).unwrap();
+    let pool_balance = program_context
+        .banks_client
+        .get_balance(merkle_tree_token)
+        .await
+        .unwrap();
+
+    let mut deposit_transaction =
+        sol_transaction(&relayer.pubkey(), &relayer.pubkey(), 1_000_000_000, 0);
+    let mut utxo = deposit(&mut deposit_transaction, &indexer, 0);
+    settle(&mut program_context, &deposit_transaction, &relayer)
+        .await
+        .unwrap();
+    utxo.index = insert_leaves(&mut indexer, &deposit_transaction);
+    let merkle_tree_account = program_context
+        .banks_client
+        .get_account(merkle_tree)
+        .await
+        .unwrap()
+        .unwrap();
+    indexer.verify_onchain(&merkle_tree_account.data).unwrap();
+    assert_eq!(
+        program_context
+            .banks_client
+            .get_balance(merkle_tree_token)
+            .await
+            .unwrap(),
+        pool_balance + 1_000_000_000
+    );
+    let pdas = deposit_transaction.pdas();
+    for pda in [pdas.leaves, pdas.nullifiers[0], pdas.nullifiers[1]] {
+        assert!(program_context
+            .banks_client
+            .get_account(pda)
+            .await
+            .unwrap()
+            .is_some());
+    }
+    assert!(program_context
+        .banks_client
+        .get_account(pdas.tmp_storage)
+        .await
+        .unwrap()
+        .is_none());
+
+    // The deposited utxo is spent against the root after the deposit.
+    let recipient = Pubkey::new_unique();
+    let mut withdrawal_transaction =
+        sol_transaction(&relayer.pubkey(), &recipient, -400_000_000, 0);
+    withdrawal(&mut withdrawal_transaction, utxo, &indexer, 0);
+    settle(&mut program_context, &withdrawal_transaction, &relayer)
+        .await
+        .unwrap();
+    insert_leaves(&mut indexer, &withdrawal_transaction);
+    let merkle_tree_account = program_context
+        .banks_client
+        .get_account(merkle_tree)
+        .await
+        .unwrap()
+        .unwrap();
+    indexer.verify_onchain(&merkle_tree_account.data).unwrap();
+    assert_eq!(
+        program_context
+            .banks_client
+            .get_balance(recipient)
+            .await
+            .unwrap(),
+        400_000_000
+    );
+    assert_eq!(
+        program_context
+            .banks_client
+            .get_balance(merkle_tree_token)
+            .await
+            .unwrap(),
+        pool_balance + 600_000_000
+    );
+
+    // The deposited utxo can't be spent twice.
+    let mut double_spend = sol_transaction(&relayer.pubkey(), &recipient, -400_000_000, 0);
+    withdrawal(&mut double_spend, utxo, &indexer, 0);
+    assert!(settle(&mut program_context, &double_spend, &relayer)
+        .await
+        .is_err());
+}
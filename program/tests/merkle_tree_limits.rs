@@ -0,0 +1,172 @@
+use light_protocol_program::admin_config::find_admin_config;
+use light_protocol_program::error::LightError;
+use light_protocol_program::merkle_tree_limits::{find_merkle_tree_limits, MerkleTreeLimits};
+use light_protocol_program::process_instruction;
+use light_protocol_program::utils::config::{
+    MERKLE_TREE_ACC_BYTES_ARRAY, MERKLE_TREE_LIMITS_ACCOUNT_TYPE,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use solana_program_test::*;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+
+mod test_utils;
+use crate::test_utils::{custom_error, process, program_id, PRIVATE_KEY};
+
+fn merkle_tree_limits(
+    max_deposit: u64,
+    max_total_deposits: u64,
+    epoch_length: u64,
+    max_epoch_withdrawals: u64,
+) -> MerkleTreeLimits {
+    MerkleTreeLimits {
+        is_initialized: true,
+        account_type: MERKLE_TREE_LIMITS_ACCOUNT_TYPE,
+        merkle_tree_pubkey: Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0),
+        max_deposit,
+        max_total_deposits,
+        epoch_length,
+        max_epoch_withdrawals,
+        current_epoch: 0,
+        epoch_withdrawals: 0,
+    }
+}
+
+fn set_limits_instruction(signer: &Pubkey, limits: [u64; 4]) -> Instruction {
+    let merkle_tree_pubkey = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0);
+    let mut data = vec![243u8, 0u8];
+    for limit in limits.iter() {
+        data.extend_from_slice(&limit.to_le_bytes());
+    }
+    Instruction::new_with_bincode(
+        program_id(),
+        &data,
+        vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(
+                find_merkle_tree_limits(&program_id(), &merkle_tree_pubkey).0,
+                false,
+            ),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(find_admin_config(&program_id()).0, false),
+        ],
+    )
+}
+
+async fn get_merkle_tree_limits(program_context: &mut ProgramTestContext) -> MerkleTreeLimits {
+    let merkle_tree_pubkey = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0);
+    let account = program_context
+        .banks_client
+        .get_account(find_merkle_tree_limits(&program_id(), &merkle_tree_pubkey).0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, program_id());
+    MerkleTreeLimits::unpack(&account.data).unwrap()
+}
+
+#[test]
+fn deposit_limits_should_be_enforced() {
+    let limits = merkle_tree_limits(100, 1000, 0, 0);
+    assert_eq!(limits.check_deposit(100, 1000), Ok(()));
+    assert_eq!(
+        limits.check_deposit(101, 101),
+        Err(LightError::DepositLimitExceeded.into())
+    );
+    assert_eq!(
+        limits.check_deposit(100, 1001),
+        Err(LightError::TotalDepositsLimitExceeded.into())
+    );
+    // 0 is no limit
+    let limits = merkle_tree_limits(0, 0, 0, 0);
+    assert_eq!(limits.check_deposit(u64::MAX, u64::MAX), Ok(()));
+}
+
+#[test]
+fn epoch_withdrawals_should_be_limited() {
+    let mut limits = merkle_tree_limits(0, 0, 100, 1000);
+    assert_eq!(limits.check_and_add_withdrawal(600, 10), Ok(()));
+    assert_eq!(limits.check_and_add_withdrawal(400, 99), Ok(()));
+    assert_eq!(
+        limits.check_and_add_withdrawal(1, 99),
+        Err(LightError::WithdrawalLimitExceeded.into())
+    );
+    assert_eq!(limits.epoch_withdrawals, 1000);
+    // the next epoch starts at slot 100
+    assert_eq!(limits.check_and_add_withdrawal(1000, 100), Ok(()));
+    assert_eq!(limits.current_epoch, 1);
+    assert_eq!(limits.epoch_withdrawals, 1000);
+
+    // without epoch length withdrawals are not limited
+    let mut limits = merkle_tree_limits(0, 0, 0, 1000);
+    assert_eq!(limits.check_and_add_withdrawal(u64::MAX, 10), Ok(()));
+}
+
+#[test]
+fn merkle_tree_limits_should_roundtrip() {
+    let mut limits = merkle_tree_limits(1, 2, 3, 4);
+    limits.current_epoch = 5;
+    limits.epoch_withdrawals = 6;
+    let mut data = vec![0u8; MerkleTreeLimits::LEN];
+    MerkleTreeLimits::pack_into_slice(&limits, &mut data);
+    assert_eq!(MerkleTreeLimits::unpack(&data).unwrap(), limits);
+    assert!(MerkleTreeLimits::unpack(&[0u8; MerkleTreeLimits::LEN]).is_err());
+}
+
+#[tokio::test]
+async fn set_merkle_tree_limits_should_succeed() {
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    let authority = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    let transaction = solana_sdk::system_transaction::transfer(
+        &program_context.payer,
+        &authority.pubkey(),
+        10_000_000_000,
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let instruction = set_limits_instruction(&authority.pubkey(), [100, 1000, 10, 500]);
+    process(&mut program_context, instruction, &authority)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_merkle_tree_limits(&mut program_context).await,
+        merkle_tree_limits(100, 1000, 10, 500)
+    );
+
+    // limits are updated
+    let instruction = set_limits_instruction(&authority.pubkey(), [0, 2000, 10, 0]);
+    process(&mut program_context, instruction, &authority)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_merkle_tree_limits(&mut program_context).await,
+        merkle_tree_limits(0, 2000, 10, 0)
+    );
+
+    // only the authority can set limits
+    let payer = Keypair::from_bytes(&program_context.payer.to_bytes()).unwrap();
+    let instruction = set_limits_instruction(&payer.pubkey(), [1, 1, 1, 1]);
+    let error = process(&mut program_context, instruction, &payer)
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidAuthority));
+    assert_eq!(
+        get_merkle_tree_limits(&mut program_context).await,
+        merkle_tree_limits(0, 2000, 10, 0)
+    );
+}
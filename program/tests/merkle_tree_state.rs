@@ -0,0 +1,50 @@
+use light_protocol_program::error::LightError;
+use light_protocol_program::poseidon_merkle_tree::state::MerkleTree;
+use light_protocol_program::utils::config::INIT_BYTES_MERKLE_TREE_18;
+use solana_program::program_pack::Pack;
+
+// Offsets in the Merkle tree account.
+const CURRENT_ROOT_INDEX_START: usize = 586;
+const ROOT_HISTORY_SIZE_START: usize = 602;
+
+fn merkle_tree_data(current_root_index: u64, root_history_size: u64) -> Vec<u8> {
+    let mut data = vec![0u8; MerkleTree::LEN];
+    data[..INIT_BYTES_MERKLE_TREE_18.len()].copy_from_slice(&INIT_BYTES_MERKLE_TREE_18);
+    data[CURRENT_ROOT_INDEX_START..CURRENT_ROOT_INDEX_START + 8]
+        .copy_from_slice(&current_root_index.to_le_bytes());
+    data[ROOT_HISTORY_SIZE_START..ROOT_HISTORY_SIZE_START + 8]
+        .copy_from_slice(&root_history_size.to_le_bytes());
+    data
+}
+
+#[test]
+fn unpack_merkle_tree_should_succeed() {
+    for current_root_index in [0u64, 1, 499].iter() {
+        let mut data = merkle_tree_data(*current_root_index, 500);
+        let merkle_tree = MerkleTree::unpack_from_slice(&data).unwrap();
+        assert_eq!(merkle_tree.current_root_index, *current_root_index as usize);
+        assert_eq!(merkle_tree.root_history_size, 500);
+        merkle_tree.pack_into_slice(&mut data);
+    }
+}
+
+// Regression inputs of the unpack_merkle_tree and process_instruction fuzz targets,
+// the current root index used to slice the roots without a bounds check.
+#[test]
+fn current_root_index_out_of_bounds_should_fail() {
+    for (current_root_index, root_history_size) in [
+        (500u64, 500u64),
+        (u64::MAX, 500),
+        (0, 0),
+        (500, 501),
+        (0, u64::MAX),
+    ]
+    .iter()
+    {
+        let data = merkle_tree_data(*current_root_index, *root_history_size);
+        assert_eq!(
+            MerkleTree::unpack_from_slice(&data).unwrap_err(),
+            LightError::InvalidMerkleTree.into()
+        );
+    }
+}
@@ -47,9 +47,9 @@ pub mod tests {
         let pvk = prepare_verifying_key(&pvk_unprepped);
         let mut ix_data = read_test_data(String::from("deposit.txt"));
         ix_data = ix_data[9..].to_vec();
-        let proof_a = parse_x_group_affine_from_bytes(&ix_data[224..288].to_vec());
-        let proof_b = parse_proof_b_from_bytes(&ix_data[288..416].to_vec());
-        let proof_c = parse_x_group_affine_from_bytes(&ix_data[416..480].to_vec());
+        let proof_a = parse_x_group_affine_from_bytes(&ix_data[224..288].to_vec()).unwrap();
+        let proof_b = parse_proof_b_from_bytes(&ix_data[288..416].to_vec()).unwrap();
+        let proof_c = parse_x_group_affine_from_bytes(&ix_data[416..480].to_vec()).unwrap();
         let mut public_inputs = Vec::new();
         for input in ix_data[..224].chunks(32) {
             public_inputs.push(<Fr as FromBytes>::read(&*input).unwrap());
@@ -73,9 +73,9 @@ pub mod tests {
 
         let mut ix_data = read_test_data(String::from("deposit.txt"));
         ix_data = ix_data[9..].to_vec();
-        let proof_a = parse_x_group_affine_from_bytes(&ix_data[224..288].to_vec());
-        let proof_b = parse_proof_b_from_bytes(&ix_data[288..416].to_vec());
-        let proof_c = parse_x_group_affine_from_bytes(&ix_data[416..480].to_vec());
+        let proof_a = parse_x_group_affine_from_bytes(&ix_data[224..288].to_vec()).unwrap();
+        let proof_b = parse_proof_b_from_bytes(&ix_data[288..416].to_vec()).unwrap();
+        let proof_c = parse_x_group_affine_from_bytes(&ix_data[416..480].to_vec()).unwrap();
         let mut public_inputs = Vec::new();
         for input in ix_data[..224].chunks(32) {
             public_inputs.push(<Fr as FromBytes>::read(&*input).unwrap());
@@ -109,6 +109,20 @@ pub mod tests {
         Ok(())
     }
 
+    #[test]
+    fn non_canonical_proof_bytes_should_fail() {
+        use light_protocol_program::error::LightError;
+        use solana_program::program_error::ProgramError;
+
+        let expected: ProgramError = LightError::InvalidFieldElement.into();
+        // 0xff.. is larger than the Fq modulus.
+        assert_eq!(
+            parse_x_group_affine_from_bytes(&vec![0xffu8; 64]),
+            Err(expected.clone())
+        );
+        assert_eq!(parse_proof_b_from_bytes(&vec![0xffu8; 128]), Err(expected));
+    }
+
     #[allow(clippy::let_and_return)]
     fn final_exponentiation_custom(
         f: &<ark_ec::models::bn::Bn<ark_bn254::Parameters> as ark_ec::PairingEngine>::Fqk,
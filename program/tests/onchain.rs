@@ -6,6 +6,9 @@ use crate::test_utils::tests::{
     create_and_start_program_var, get_proof_from_bytes, get_public_inputs_from_bytes,
     get_ref_value, get_vk_from_file, read_test_data, restart_program,
 };
+// A consistent signer and relayer is necessary, transactions would get rejected otherwise.
+use crate::test_utils::program_data::{record_program_data, recorded_events};
+use crate::test_utils::{PRIVATE_KEY, PRIV_KEY_DEPOSIT};
 use crate::tokio::time::timeout;
 use ark_bn254::Fq;
 use ark_ec::ProjectiveCurve;
@@ -13,9 +16,19 @@ use ark_ff::BigInteger;
 use ark_ff::PrimeField;
 use ark_groth16::{prepare_inputs, prepare_verifying_key};
 use ark_std::{test_rng, UniformRand};
+use light_protocol_program::admin_config::find_admin_config;
+use light_protocol_program::asset::Asset;
+use light_protocol_program::event::LightEvent;
+use light_protocol_program::global_config::find_global_config;
+use light_protocol_program::merkle_tree_limits::find_merkle_tree_limits;
+use light_protocol_program::migration::USER_ACCOUNT_V1_LEN;
+use light_protocol_program::pda::{
+    find_escrow_pda, find_leaves_pda, find_nullifier_pda, find_tmp_storage_pda,
+};
 use light_protocol_program::poseidon_merkle_tree::state::MerkleTree;
 use light_protocol_program::poseidon_merkle_tree::state::TmpStoragePda;
-use light_protocol_program::user_account::state::{SIZE_UTXO, UTXO_CAPACITY};
+use light_protocol_program::protocol_fee::find_protocol_fee_config;
+use light_protocol_program::user_account::state::{UserAccount, SIZE_UTXO, UTXO_CAPACITY};
 use light_protocol_program::utils::{config, prepared_verifying_key::*};
 use light_protocol_program::{
     groth16_verifier::{
@@ -25,7 +38,7 @@ use light_protocol_program::{
     },
     process_instruction,
     state::ChecksAndTransferState,
-    utils::config::{ENCRYPTED_UTXOS_LENGTH, MERKLE_TREE_ACC_BYTES_ARRAY},
+    utils::config::{ENCRYPTED_UTXOS_LENGTH, MERKLE_TREE_ACC_BYTES_ARRAY, USER_ACCOUNT_VERSION},
     IX_ORDER,
 };
 use serde_json::Result;
@@ -48,20 +61,6 @@ use {
     },
     std::str::FromStr,
 };
-// is necessary to have a consistent signer and relayer otherwise transactions would get rejected
-const PRIVATE_KEY: [u8; 64] = [
-    17, 34, 231, 31, 83, 147, 93, 173, 61, 164, 25, 0, 204, 82, 234, 91, 202, 187, 228, 110, 146,
-    97, 112, 131, 180, 164, 96, 220, 57, 207, 65, 107, 2, 99, 226, 251, 88, 66, 92, 33, 25, 216,
-    211, 185, 112, 203, 212, 238, 105, 144, 72, 121, 176, 253, 106, 168, 115, 158, 154, 188, 62,
-    255, 166, 81,
-];
-const PRIV_KEY_DEPOSIT: [u8; 64] = [
-    70, 5, 178, 190, 139, 224, 161, 74, 134, 130, 14, 189, 253, 51, 249, 124, 255, 116, 66, 87,
-    146, 202, 196, 243, 68, 129, 95, 145, 97, 170, 145, 61, 221, 240, 113, 237, 127, 131, 46, 151,
-    40, 236, 223, 8, 124, 162, 170, 56, 71, 105, 233, 43, 196, 129, 63, 145, 13, 2, 210, 251, 197,
-    109, 226, 3,
-];
-
 mod test_utils;
 
 async fn compute_prepared_inputs(
@@ -255,6 +254,7 @@ pub async fn initialize_merkle_tree(
                 AccountMeta::new(signer_keypair.pubkey(), true),
                 AccountMeta::new(*merkle_tree_pda_pubkey, false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_admin_config(program_id).0, false),
             ],
         )],
         Some(&signer_keypair.pubkey()),
@@ -523,15 +523,18 @@ async fn create_pubkeys_from_ix_data(
     ix_data: &Vec<u8>,
     program_id: &Pubkey,
 ) -> (Pubkey, Pubkey, Pubkey, Pubkey) {
-    // Creates pubkeys for all the PDAs we'll use
-    let tmp_storage_pda_pubkey =
-        Pubkey::find_program_address(&[&ix_data[73..105], &b"storage"[..]], &program_id).0;
-    let two_leaves_pda_pubkey =
-        Pubkey::find_program_address(&[&ix_data[105..137], &b"leaves"[..]], program_id).0;
+    // Creates pubkeys for all the PDAs we'll use, the tmp storage pda is bound to the relayer
+    // in the send data (ix_data[529..561]).
+    let tmp_storage_pda_pubkey = find_tmp_storage_pda(
+        &program_id,
+        &ix_data[73..105],
+        &Pubkey::new(&ix_data[529..561]),
+    ).0;
+    let two_leaves_pda_pubkey = find_leaves_pda(program_id, &ix_data[105..137]).0;
 
-    let nf_pubkey0 = Pubkey::find_program_address(&[&ix_data[105..137], &b"nf"[..]], program_id).0;
+    let nf_pubkey0 = find_nullifier_pda(program_id, &ix_data[105..137]).0;
 
-    let nf_pubkey1 = Pubkey::find_program_address(&[&ix_data[137..169], &b"nf"[..]], program_id).0;
+    let nf_pubkey1 = find_nullifier_pda(program_id, &ix_data[137..169]).0;
     (
         tmp_storage_pda_pubkey,
         two_leaves_pda_pubkey,
@@ -583,6 +586,7 @@ async fn transact(
                 AccountMeta::new(*tmp_storage_pda_pubkey, false),
                 AccountMeta::new_readonly(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_global_config(program_id).0, false),
             ],
         )],
         Some(&signer_pubkey),
@@ -761,14 +765,16 @@ pub async fn last_tx(
     println!("recipient_pubkey_option: {:?}", recipient_pubkey_option);
 
     println!("recipient_pubkey_option: {:?}", recipient_pubkey_option);
+    // no protocol fee config exists, the signer is passed as treasury
+    let protocol_fee_config_pubkey = find_protocol_fee_config(program_id, &Asset::Sol).0;
+    // no limits are set for the Merkle tree
+    let merkle_tree_limits_pubkey = find_merkle_tree_limits(program_id, merkle_tree_pda_pubkey).0;
+    // no global config exists, the program is active
+    let global_config_pubkey = find_global_config(program_id).0;
     let mut ix_vec = Vec::new();
     //deposit case mint wrapped sol tokens and approve a program owned authority
     if recipient_pubkey_option.is_none() && relayer_pda_token_pubkey_option.is_none() {
-        let user_ecrow_acc = Pubkey::find_program_address(
-            &[&tmp_storage_pda_pubkey.to_bytes(), &b"escrow"[..]],
-            &program_id,
-        )
-        .0;
+        let user_ecrow_acc = find_escrow_pda(program_id, tmp_storage_pda_pubkey).0;
 
         if amount.is_some() {
             let signer_account = program_context
@@ -809,6 +815,10 @@ pub async fn last_tx(
                 AccountMeta::new_readonly(spl_token::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
                 AccountMeta::new(*expected_authority_pubkey, false),
+                AccountMeta::new_readonly(protocol_fee_config_pubkey, false),
+                AccountMeta::new(signer_keypair.pubkey(), false),
+                AccountMeta::new(merkle_tree_limits_pubkey, false),
+                AccountMeta::new_readonly(global_config_pubkey, false),
                 AccountMeta::new(user_ecrow_acc, false),
                 AccountMeta::new(*expected_authority_pubkey, false),
             ],
@@ -844,6 +854,12 @@ pub async fn last_tx(
                 AccountMeta::new_readonly(spl_token::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
                 AccountMeta::new(*expected_authority_pubkey, false),
+                AccountMeta::new_readonly(protocol_fee_config_pubkey, false),
+                AccountMeta::new(signer_keypair.pubkey(), false),
+                AccountMeta::new(merkle_tree_limits_pubkey, false),
+                AccountMeta::new_readonly(global_config_pubkey, false),
+                // transfers don't read the user token slot
+                AccountMeta::new(signer_keypair.pubkey(), false),
                 AccountMeta::new(*relayer_pda_token_pubkey_option.unwrap(), false),
             ],
         ));
@@ -879,6 +895,10 @@ pub async fn last_tx(
                 AccountMeta::new_readonly(spl_token::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
                 AccountMeta::new(*expected_authority_pubkey, false),
+                AccountMeta::new_readonly(protocol_fee_config_pubkey, false),
+                AccountMeta::new(signer_keypair.pubkey(), false),
+                AccountMeta::new(merkle_tree_limits_pubkey, false),
+                AccountMeta::new_readonly(global_config_pubkey, false),
                 AccountMeta::new(*recipient_pubkey_option.unwrap(), false),
                 AccountMeta::new(*relayer_pda_token_pubkey_option.unwrap(), false),
             ],
@@ -1207,6 +1227,7 @@ async fn deposit_should_succeed() {
     // start program
     let mut program_context =
         create_and_start_program_var(&accounts_vector, None, &program_id, &signer_pubkey).await;
+    record_program_data();
     let _merkle_tree_pda = program_context
         .banks_client
         .get_account(merkle_tree_pda_pubkey)
@@ -1314,6 +1335,40 @@ async fn deposit_should_succeed() {
         .await
         .expect("get_account")
         .unwrap();
+    let merkle_tree = MerkleTree::unpack(&merkle_tree_account_data.data).unwrap();
+    // the deposit pays no fees, the whole amount is tracked as deposit of the tree
+    assert_eq!(merkle_tree.current_total_deposits, amount);
+
+    // 1501 emits the event of the deposit with the new root
+    let nullifiers: [[u8; 32]; 2] = [
+        ix_withdraw_data[105..137].try_into().unwrap(),
+        ix_withdraw_data[137..169].try_into().unwrap(),
+    ];
+    let events: Vec<LightEvent> = recorded_events()
+        .into_iter()
+        .filter(|event| event.transaction_event().nullifiers == nullifiers)
+        .collect();
+    assert_eq!(events.len(), 1);
+    let event = match &events[0] {
+        LightEvent::Deposit(event) => event,
+        event => panic!("Expected a deposit event, got {:?}", event),
+    };
+    assert_eq!(event.merkle_tree_pubkey, merkle_tree_pda_pubkey.to_bytes());
+    assert_eq!(event.root[..], merkle_tree.roots[..]);
+    assert_eq!(event.root_index, merkle_tree.current_root_index as u64);
+    assert_eq!(event.left_leaf_index, 0);
+    assert_eq!(event.leaf_left[..], ix_withdraw_data[192 + 9..224 + 9]);
+    assert_eq!(event.leaf_right[..], ix_withdraw_data[160 + 9..192 + 9]);
+    assert_eq!(
+        event.encrypted_utxos[..],
+        ix_withdraw_data[593 + 9..593 + 9 + ENCRYPTED_UTXOS_LENGTH]
+    );
+    assert_eq!(event.public_amount, amount);
+    assert_eq!(
+        event.relayer_fee,
+        u64::from_le_bytes(ix_withdraw_data[561..569].try_into().unwrap())
+    );
+    assert_eq!(event.protocol_fee, 0);
 
     let path = "tests/merkle_tree_account_data_after_deposit.rs";
     let mut output = File::create(path).ok().unwrap();
@@ -2126,8 +2181,11 @@ async fn compute_prepared_inputs_should_succeed() {
     // state we'd have at the exact instruction we're starting the test at (ix 466 for millerloop)
     // read proof, public inputs from test file, prepare_inputs
     let ix_data = read_test_data(String::from("deposit.txt"));
-    let tmp_storage_pda_pubkey =
-        Pubkey::find_program_address(&[&ix_data[73..105], &b"storage"[..]], &program_id).0;
+    let tmp_storage_pda_pubkey = find_tmp_storage_pda(
+        &program_id,
+        &ix_data[73..105],
+        &Pubkey::new(&ix_data[529..561]),
+    ).0;
     // Pick the data we need from the test file. 9.. bc of input structure
 
     let prepared_inputs_ref = get_ref_value("prepared_inputs");
@@ -2180,6 +2238,7 @@ async fn compute_prepared_inputs_should_succeed() {
                 AccountMeta::new(tmp_storage_pda_pubkey, false),
                 AccountMeta::new_readonly(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_global_config(&program_id).0, false),
             ],
         )],
         Some(&signer_pubkey),
@@ -2266,8 +2325,11 @@ async fn compute_miller_output_should_succeed() {
     // read proof, public inputs from test file, prepare_inputs
     let ix_data = read_test_data(String::from("deposit.txt"));
     //create pubkey for tmporary storage account
-    let tmp_storage_pda_pubkey =
-        Pubkey::find_program_address(&[&ix_data[73..105], &b"storage"[..]], &program_id).0;
+    let tmp_storage_pda_pubkey = find_tmp_storage_pda(
+        &program_id,
+        &ix_data[73..105],
+        &Pubkey::new(&ix_data[529..561]),
+    ).0;
 
     let account_state = get_mock_state("miller_output", &signer_keypair);
     let mut accounts_vector = Vec::new();
@@ -2316,8 +2378,11 @@ async fn compute_final_exponentiation_should_succeed() {
     let program_id = Pubkey::from_str("TransferLamports111111111111111111111111111").unwrap();
     let ix_data = read_test_data(String::from("deposit.txt"));
     //create pubkey for tmporary storage account
-    let tmp_storage_pda_pubkey =
-        Pubkey::find_program_address(&[&ix_data[73..105], &b"storage"[..]], &program_id).0;
+    let tmp_storage_pda_pubkey = find_tmp_storage_pda(
+        &program_id,
+        &ix_data[73..105],
+        &Pubkey::new(&ix_data[529..561]),
+    ).0;
     let signer_keypair = solana_sdk::signer::keypair::Keypair::from_bytes(&PRIVATE_KEY).unwrap();
     let signer_pubkey = signer_keypair.pubkey();
     let f_ref = get_ref_value("final_exponentiation");
@@ -2388,8 +2453,11 @@ async fn submit_proof_with_wrong_root_should_not_succeed() {
     let signer_keypair = solana_sdk::signer::keypair::Keypair::from_bytes(&PRIVATE_KEY).unwrap();
     let signer_pubkey = signer_keypair.pubkey();
 
-    let tmp_storage_pda_pubkey =
-        Pubkey::find_program_address(&[&ix_data[73..105], &b"storage"[..]], &program_id).0;
+    let tmp_storage_pda_pubkey = find_tmp_storage_pda(
+        &program_id,
+        &ix_data[73..105],
+        &Pubkey::new(&ix_data[529..561]),
+    ).0;
 
     let mut nullifier_pubkeys = Vec::new();
     let pubkey_from_seed =
@@ -2452,6 +2520,7 @@ async fn submit_proof_with_wrong_root_should_not_succeed() {
                 AccountMeta::new(tmp_storage_pda_pubkey, false),
                 AccountMeta::new_readonly(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_global_config(&program_id).0, false),
             ],
         )],
         Some(&signer_pubkey),
@@ -2536,8 +2605,11 @@ async fn search_root_at_last_root_index_should_succeed() {
         solana_sdk::signer::keypair::Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
     let signer_pubkey = signer_keypair.pubkey();
 
-    let tmp_storage_pda_pubkey =
-        Pubkey::find_program_address(&[&ix_data[73..105], &b"storage"[..]], &program_id).0;
+    let tmp_storage_pda_pubkey = find_tmp_storage_pda(
+        &program_id,
+        &ix_data[73..105],
+        &Pubkey::new(&ix_data[529..561]),
+    ).0;
 
     let mut nullifier_pubkeys = Vec::new();
     let pubkey_from_seed =
@@ -2568,6 +2640,7 @@ async fn search_root_at_last_root_index_should_succeed() {
                 AccountMeta::new(tmp_storage_pda_pubkey, false),
                 AccountMeta::new_readonly(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_global_config(&program_id).0, false),
             ],
         )],
         Some(&signer_pubkey),
@@ -2635,8 +2708,11 @@ async fn signer_acc_not_in_first_place_should_not_succeed() {
     let signer_keypair = solana_sdk::signer::keypair::Keypair::from_bytes(&PRIVATE_KEY).unwrap();
     let signer_pubkey = signer_keypair.pubkey();
 
-    let tmp_storage_pda_pubkey =
-        Pubkey::find_program_address(&[&ix_data[73..105], &b"storage"[..]], &program_id).0;
+    let tmp_storage_pda_pubkey = find_tmp_storage_pda(
+        &program_id,
+        &ix_data[73..105],
+        &Pubkey::new(&ix_data[529..561]),
+    ).0;
 
     let mut nullifier_pubkeys = Vec::new();
     let pubkey_from_seed =
@@ -2700,6 +2776,7 @@ async fn signer_acc_not_in_first_place_should_not_succeed() {
                 AccountMeta::new(tmp_storage_pda_pubkey, false),
                 AccountMeta::new(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_global_config(&program_id).0, false),
             ],
         )],
         Some(&signer_pubkey),
@@ -2770,8 +2847,11 @@ async fn submit_proof_with_wrong_signer_should_not_succeed() {
     let signer_keypair = solana_sdk::signer::keypair::Keypair::from_bytes(&PRIVATE_KEY).unwrap();
     let signer_pubkey = signer_keypair.pubkey();
 
-    let tmp_storage_pda_pubkey =
-        Pubkey::find_program_address(&[&ix_data[73..105], &b"storage"[..]], &program_id).0;
+    let tmp_storage_pda_pubkey = find_tmp_storage_pda(
+        &program_id,
+        &ix_data[73..105],
+        &Pubkey::new(&ix_data[529..561]),
+    ).0;
 
     let mut nullifier_pubkeys = Vec::new();
     let pubkey_from_seed =
@@ -2834,6 +2914,7 @@ async fn submit_proof_with_wrong_signer_should_not_succeed() {
                 AccountMeta::new(tmp_storage_pda_pubkey, false),
                 AccountMeta::new(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_global_config(&program_id).0, false),
             ],
         )],
         Some(&signer_pubkey),
@@ -3160,6 +3241,7 @@ async fn wrong_merkle_tree_should_not_succeed() {
                 AccountMeta::new(tmp_storage_pda_pubkey, false),
                 AccountMeta::new_readonly(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_global_config(&program_id).0, false),
             ],
         )],
         Some(&signer_pubkey),
@@ -3232,6 +3314,7 @@ async fn wrong_integrity_hash_should_not_succeed() {
                 AccountMeta::new(tmp_storage_pda_pubkey, false),
                 AccountMeta::new_readonly(solana_program::system_program::id(), false),
                 AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_global_config(&program_id).0, false),
             ],
         )],
         Some(&signer_pubkey),
@@ -3348,6 +3431,8 @@ async fn merkle_tree_init_with_wrong_signer_should_not_succeed() {
             vec![
                 AccountMeta::new(signer_keypair.pubkey(), true),
                 AccountMeta::new(merkle_tree_pda_pubkey, false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_admin_config(&program_id).0, false),
             ],
         )],
         Some(&signer_keypair.pubkey()),
@@ -3445,7 +3530,7 @@ async fn user_account_onchain_test() {
         .await
         .expect("get_account")
         .unwrap();
-    assert_eq!(1u8, user_account_data_init.data[0]);
+    assert_eq!(USER_ACCOUNT_VERSION, user_account_data_init.data[0]);
 
     assert_eq!(
         signer_keypair.pubkey(),
@@ -3569,7 +3654,7 @@ async fn test_user_account_checks() {
         .await
         .expect("get_account")
         .unwrap();
-    assert_eq!(1u8, user_account_data_init.data[0]);
+    assert_eq!(USER_ACCOUNT_VERSION, user_account_data_init.data[0]);
 
     assert_eq!(
         signer_keypair.pubkey(),
@@ -3603,7 +3688,7 @@ async fn test_user_account_checks() {
         .await
         .expect("get_account")
         .unwrap();
-    assert_eq!(1u8, user_account_data_init.data[0]);
+    assert_eq!(USER_ACCOUNT_VERSION, user_account_data_init.data[0]);
     assert_eq!(
         signer_keypair.pubkey(),
         Pubkey::new(&user_account_data_init.data[2..34])
@@ -3639,3 +3724,186 @@ async fn test_user_account_checks() {
     assert_eq!(vec![0u8; 64], user_account_data_modified.data[34..98]);
     println!("user account was not modified success");*/
 }
+
+#[tokio::test]
+async fn migrate_legacy_user_account_should_succeed() {
+    let program_id = Pubkey::from_str("TransferLamports111111111111111111111111111").unwrap();
+    let signer_keypair = solana_sdk::signer::keypair::Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    let signer_pubkey = signer_keypair.pubkey();
+    let user_account_pubkey = Pubkey::new_unique();
+    let owner_pubkey = Pubkey::new_unique();
+
+    // user account as it was written before the account header was validated
+    let mut legacy_user_account = vec![0u8; USER_ACCOUNT_V1_LEN];
+    legacy_user_account[0] = 1;
+    legacy_user_account[1] = 10;
+    legacy_user_account[2..34].copy_from_slice(&owner_pubkey.to_bytes());
+
+    let mut accounts_vector = Vec::new();
+    accounts_vector.push((
+        &user_account_pubkey,
+        USER_ACCOUNT_V1_LEN,
+        Some(legacy_user_account),
+    ));
+    let mut program_context =
+        create_and_start_program_var(&accounts_vector, None, &program_id, &signer_pubkey).await;
+
+    // a signer which is not the authority cannot migrate accounts
+    let unauthorized_keypair = Keypair::new();
+    let mut transaction = solana_sdk::system_transaction::transfer(
+        &program_context.payer,
+        &unauthorized_keypair.pubkey(),
+        10000000000,
+        program_context.last_blockhash,
+    );
+    transaction.sign(&[&program_context.payer], program_context.last_blockhash);
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+    let mut transaction = Transaction::new_with_payer(
+        &[Instruction::new_with_bincode(
+            program_id,
+            &[vec![241u8, 0u8], usize::to_le_bytes(1000).to_vec()].concat(),
+            vec![
+                AccountMeta::new(unauthorized_keypair.pubkey(), true),
+                AccountMeta::new(user_account_pubkey, false),
+                AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_admin_config(&program_id).0, false),
+            ],
+        )],
+        Some(&unauthorized_keypair.pubkey()),
+    );
+    transaction.sign(&[&unauthorized_keypair], program_context.last_blockhash);
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("Signer is not program authority.");
+
+    let mut transaction = Transaction::new_with_payer(
+        &[Instruction::new_with_bincode(
+            program_id,
+            &[vec![241u8, 0u8], usize::to_le_bytes(1001).to_vec()].concat(),
+            vec![
+                AccountMeta::new(signer_pubkey, true),
+                AccountMeta::new(user_account_pubkey, false),
+                AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_admin_config(&program_id).0, false),
+            ],
+        )],
+        Some(&signer_pubkey),
+    );
+    transaction.sign(&[&signer_keypair], program_context.last_blockhash);
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let user_account = program_context
+        .banks_client
+        .get_account(user_account_pubkey)
+        .await
+        .expect("get_account")
+        .unwrap();
+    // version
+    assert_eq!(USER_ACCOUNT_VERSION, user_account.data[0]);
+    // account type
+    assert_eq!(5u8, user_account.data[1]);
+    assert_eq!(owner_pubkey, Pubkey::new(&user_account.data[2..34]));
+    assert_eq!(UserAccount::LEN, user_account.data.len());
+
+    // migrating an account which is already at the current version fails
+    let mut transaction = Transaction::new_with_payer(
+        &[Instruction::new_with_bincode(
+            program_id,
+            &[vec![241u8, 0u8], usize::to_le_bytes(1002).to_vec()].concat(),
+            vec![
+                AccountMeta::new(signer_pubkey, true),
+                AccountMeta::new(user_account_pubkey, false),
+                AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_admin_config(&program_id).0, false),
+            ],
+        )],
+        Some(&signer_pubkey),
+    );
+    transaction.sign(&[&signer_keypair], program_context.last_blockhash);
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .expect_err("Account is already at the current version.");
+}
+
+#[tokio::test]
+async fn migrate_version_1_user_account_should_succeed() {
+    let program_id = Pubkey::from_str("TransferLamports111111111111111111111111111").unwrap();
+    let signer_keypair = solana_sdk::signer::keypair::Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    let signer_pubkey = signer_keypair.pubkey();
+    let user_account_pubkey = Pubkey::new_unique();
+    let owner_pubkey = Pubkey::new_unique();
+
+    // version 1 user account holding a single utxo
+    let mut user_account_v1 = vec![0u8; USER_ACCOUNT_V1_LEN];
+    user_account_v1[0] = 1;
+    user_account_v1[1] = 5;
+    user_account_v1[2..34].copy_from_slice(&owner_pubkey.to_bytes());
+    user_account_v1[34..].copy_from_slice(&[7u8; SIZE_UTXO]);
+
+    let mut accounts_vector = Vec::new();
+    accounts_vector.push((
+        &user_account_pubkey,
+        USER_ACCOUNT_V1_LEN,
+        Some(user_account_v1),
+    ));
+    let mut program_context =
+        create_and_start_program_var(&accounts_vector, None, &program_id, &signer_pubkey).await;
+
+    let mut transaction = Transaction::new_with_payer(
+        &[Instruction::new_with_bincode(
+            program_id,
+            &[vec![241u8, 0u8], usize::to_le_bytes(1000).to_vec()].concat(),
+            vec![
+                AccountMeta::new(signer_pubkey, true),
+                AccountMeta::new(user_account_pubkey, false),
+                AccountMeta::new_readonly(solana_program::system_program::id(), false),
+                AccountMeta::new_readonly(sysvar::rent::id(), false),
+                AccountMeta::new_readonly(find_admin_config(&program_id).0, false),
+            ],
+        )],
+        Some(&signer_pubkey),
+    );
+    transaction.sign(&[&signer_keypair], program_context.last_blockhash);
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let user_account = program_context
+        .banks_client
+        .get_account(user_account_pubkey)
+        .await
+        .expect("get_account")
+        .unwrap();
+    assert_eq!(UserAccount::LEN, user_account.data.len());
+    assert_eq!(34 + SIZE_UTXO * UTXO_CAPACITY, user_account.data.len());
+    assert!(Rent::default().is_exempt(user_account.lamports, UserAccount::LEN));
+    assert_eq!(USER_ACCOUNT_VERSION, user_account.data[0]);
+    assert_eq!(5u8, user_account.data[1]);
+    assert_eq!(owner_pubkey, Pubkey::new(&user_account.data[2..34]));
+    // the utxo of the version 1 account is the first utxo, the added utxos are empty
+    assert_eq!(
+        vec![7u8; SIZE_UTXO],
+        user_account.data[34..USER_ACCOUNT_V1_LEN]
+    );
+    assert_eq!(
+        vec![0u8; SIZE_UTXO * (UTXO_CAPACITY - 1)],
+        user_account.data[USER_ACCOUNT_V1_LEN..]
+    );
+}
@@ -0,0 +1,204 @@
+use light_protocol_program::asset::Asset;
+use light_protocol_program::cpi::{last_instruction, send_data_instruction, Settlement};
+use light_protocol_program::error::LightError;
+use light_protocol_program::pda::{
+    find_authority_pda, find_escrow_pda, find_leaves_pda, find_nullifier_pda, find_tmp_storage_pda,
+};
+use light_protocol_program::poseidon_merkle_tree::state::MerkleTree;
+use light_protocol_program::process_instruction;
+use light_protocol_program::state::ChecksAndTransferState;
+use light_protocol_program::utils::config::MERKLE_TREE_ACC_BYTES_ARRAY;
+use solana_program::{instruction::AccountMeta, program_pack::Pack, pubkey::Pubkey, rent::Rent};
+use solana_program_test::*;
+use solana_sdk::{account::Account, signature::Signer, signer::keypair::Keypair};
+
+mod test_utils;
+use crate::test_utils::{custom_error, process, program_id, read_send_data, PRIV_KEY_DEPOSIT};
+
+async fn start(accounts: Vec<(Pubkey, Account)>, relayer: &Keypair) -> ProgramTestContext {
+    let mut program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    for (pubkey, account) in accounts {
+        program_test.add_account(pubkey, account);
+    }
+    let mut program_context = program_test.start_with_context().await;
+    let transaction = solana_sdk::system_transaction::transfer(
+        &program_context.payer,
+        &relayer.pubkey(),
+        10_000_000_000,
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+    program_context
+}
+
+fn program_account(len: usize) -> Account {
+    Account {
+        lamports: Rent::default().minimum_balance(len),
+        data: vec![0u8; len],
+        owner: program_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+#[test]
+fn pdas_should_be_derived_from_documented_seeds() {
+    let send_data = read_send_data("deposit.txt");
+    let relayer = Pubkey::new(&send_data[520..552]);
+    let tx_integrity_hash = &send_data[64..96];
+    let nullifier0 = &send_data[96..128];
+    let nullifier1 = &send_data[128..160];
+
+    let tmp_storage_pda = Pubkey::find_program_address(
+        &[tx_integrity_hash, &relayer.to_bytes(), &b"storage"[..]],
+        &program_id(),
+    );
+    assert_eq!(
+        find_tmp_storage_pda(&program_id(), tx_integrity_hash, &relayer),
+        tmp_storage_pda
+    );
+    assert_eq!(
+        find_nullifier_pda(&program_id(), nullifier1),
+        Pubkey::find_program_address(&[nullifier1, &b"nf"[..]], &program_id())
+    );
+    assert_eq!(
+        find_leaves_pda(&program_id(), nullifier0),
+        Pubkey::find_program_address(&[nullifier0, &b"leaves"[..]], &program_id())
+    );
+    assert_eq!(
+        find_escrow_pda(&program_id(), &tmp_storage_pda.0),
+        Pubkey::find_program_address(
+            &[&tmp_storage_pda.0.to_bytes(), &b"escrow"[..]],
+            &program_id()
+        )
+    );
+    assert_eq!(
+        find_authority_pda(&program_id()),
+        Pubkey::find_program_address(&[&program_id().to_bytes()], &program_id())
+    );
+}
+
+#[tokio::test]
+async fn tmp_storage_pda_should_be_checked_on_chain() {
+    let send_data = read_send_data("deposit.txt");
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let mut program_context = start(vec![], &relayer).await;
+
+    // derived without the signer
+    let mut instruction =
+        send_data_instruction(&program_id(), &relayer.pubkey(), &send_data).unwrap();
+    instruction.accounts[1] = AccountMeta::new(
+        Pubkey::find_program_address(&[&send_data[64..96], &b"storage"[..]], &program_id()).0,
+        false,
+    );
+    let error = process(&mut program_context, instruction, &relayer)
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidPdaAddress));
+
+    let instruction = send_data_instruction(&program_id(), &relayer.pubkey(), &send_data).unwrap();
+    process(&mut program_context, instruction, &relayer)
+        .await
+        .unwrap();
+    let tmp_storage_pda =
+        find_tmp_storage_pda(&program_id(), &send_data[64..96], &relayer.pubkey()).0;
+    let tmp_storage_account = program_context
+        .banks_client
+        .get_account(tmp_storage_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tmp_storage_account.owner, program_id());
+}
+
+#[tokio::test]
+async fn nullifier_pdas_should_be_checked_on_chain() {
+    let send_data = read_send_data("deposit.txt");
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let tmp_storage_pda =
+        find_tmp_storage_pda(&program_id(), &send_data[64..96], &relayer.pubkey()).0;
+
+    let mut program_context = start(vec![], &relayer).await;
+    let instruction = send_data_instruction(&program_id(), &relayer.pubkey(), &send_data).unwrap();
+    process(&mut program_context, instruction, &relayer)
+        .await
+        .unwrap();
+    let mut tmp_storage_account = program_context
+        .banks_client
+        .get_account(tmp_storage_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    // skip the root check and the proof verification
+    let mut tmp_storage_pda_data =
+        ChecksAndTransferState::unpack(&tmp_storage_account.data).unwrap();
+    tmp_storage_pda_data.found_root = 1;
+    tmp_storage_pda_data.changed_constants[1] = true;
+    tmp_storage_pda_data.current_instruction_index = 1501;
+    ChecksAndTransferState::pack_into_slice(&tmp_storage_pda_data, &mut tmp_storage_account.data);
+
+    let merkle_tree = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0);
+    let merkle_tree_token = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1);
+    let mut program_context = start(
+        vec![
+            (tmp_storage_pda, tmp_storage_account),
+            (merkle_tree, program_account(MerkleTree::LEN)),
+            (merkle_tree_token, program_account(0)),
+        ],
+        &relayer,
+    )
+    .await;
+
+    let mut nullifiers = [[0u8; 32]; 2];
+    nullifiers[0].copy_from_slice(&send_data[96..128]);
+    nullifiers[1].copy_from_slice(&send_data[128..160]);
+    let instruction = last_instruction(
+        &program_id(),
+        &relayer.pubkey(),
+        &tmp_storage_pda,
+        &nullifiers,
+        &merkle_tree,
+        &merkle_tree_token,
+        &Asset::Sol,
+        Settlement::Deposit {
+            user_token: find_escrow_pda(&program_id(), &tmp_storage_pda).0,
+        },
+        None,
+        None,
+        None,
+    )
+    .unwrap();
+    assert_eq!(
+        instruction.accounts[3].pubkey,
+        find_nullifier_pda(&program_id(), &nullifiers[0]).0
+    );
+    assert_eq!(
+        instruction.accounts[4].pubkey,
+        find_nullifier_pda(&program_id(), &nullifiers[1]).0
+    );
+
+    let mut swapped_nullifier0 = instruction.clone();
+    swapped_nullifier0.accounts[3] = instruction.accounts[4].clone();
+    let mut swapped_nullifier1 = instruction.clone();
+    swapped_nullifier1.accounts[4] = instruction.accounts[3].clone();
+    for invalid_instruction in [swapped_nullifier0, swapped_nullifier1].iter() {
+        let error = process(&mut program_context, invalid_instruction.clone(), &relayer)
+            .await
+            .unwrap_err();
+        assert_eq!(custom_error(error), Some(LightError::InvalidPdaAddress));
+    }
+
+    // Both nullifier pdas are accepted, the instruction fails later on the empty Merkle tree.
+    let error = process(&mut program_context, instruction, &relayer)
+        .await
+        .unwrap_err();
+    assert_ne!(custom_error(error), Some(LightError::InvalidPdaAddress));
+}
@@ -0,0 +1,444 @@
+use ark_ed_on_bn254::Fq;
+use ark_ff::{BigInteger, PrimeField};
+use light_client::merkle_tree::MerkleTreeIndexer;
+use light_client::utxo::Utxo;
+use light_protocol_program::asset::Asset;
+use light_protocol_program::cpi::{last_instruction, Settlement};
+use light_protocol_program::error::LightError;
+use light_protocol_program::instructions::check_tx_integrity_hash;
+use light_protocol_program::post_withdraw_call::{
+    check_and_invoke, commitment_hash, PostWithdrawCall, PostWithdrawCallAccount,
+    NO_POST_WITHDRAW_CALL, POST_WITHDRAW_CALL_COMMITMENT_LEN,
+};
+use light_protocol_program::process_instruction;
+use light_protocol_program::send_data::ExtData;
+use light_protocol_program::state::ChecksAndTransferState;
+use light_protocol_program::utils::config::{ENCRYPTED_UTXOS_LENGTH, MERKLE_TREE_ACC_BYTES_ARRAY};
+use light_protocol_program::INSERT_LEAVES_NULLIFIER_AND_TRANSFER;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, keccak, program_error::ProgramError,
+    program_pack::Pack, pubkey::Pubkey, rent::Rent,
+};
+use solana_program_test::*;
+use solana_sdk::{account::Account, signature::Signer, signer::keypair::Keypair};
+
+mod test_utils;
+use crate::test_utils::local_proof::{
+    deposit, insert_leaves, process_packed, settle, sol_transaction, withdrawal,
+};
+use crate::test_utils::{custom_error, fund, program_id, start_with_merkle_tree};
+
+fn post_withdraw_call() -> PostWithdrawCall {
+    PostWithdrawCall {
+        program_id: Pubkey::new(&[9u8; 32]),
+        accounts: vec![
+            PostWithdrawCallAccount {
+                pubkey: Pubkey::new(&[10u8; 32]),
+                is_writable: true,
+            },
+            PostWithdrawCallAccount {
+                pubkey: Pubkey::new(&[11u8; 32]),
+                is_writable: false,
+            },
+        ],
+        data: vec![1, 2, 3],
+    }
+}
+
+fn hash_input() -> Vec<Vec<u8>> {
+    vec![
+        vec![1u8; 32],
+        vec![2u8; 8],
+        vec![3u8; 32],
+        vec![4u8; 8],
+        MERKLE_TREE_ACC_BYTES_ARRAY[0].0.to_vec(),
+        vec![0u8],
+        vec![5u8; ENCRYPTED_UTXOS_LENGTH],
+    ]
+}
+
+// External data of hash_input.
+fn ext_data() -> ExtData {
+    ExtData {
+        recipient: Pubkey::new(&[1u8; 32]),
+        ext_amount: i64::from_le_bytes([2u8; 8]),
+        relayer: Pubkey::new(&[3u8; 32]),
+        fee: u64::from_le_bytes([4u8; 8]),
+        merkle_tree: Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0),
+        merkle_tree_index: 0,
+        encrypted_utxos: vec![5u8; ENCRYPTED_UTXOS_LENGTH],
+    }
+}
+
+fn check_hash(input: &[Vec<u8>], post_withdraw_call: Option<&[u8]>) -> Result<(), LightError> {
+    let hash = keccak::hash(&input.concat()).to_bytes();
+    let tx_integrity_hash = Fq::from_be_bytes_mod_order(&hash).into_repr().to_bytes_le();
+    check_tx_integrity_hash(
+        &ext_data(),
+        &tx_integrity_hash,
+        &Asset::Sol,
+        post_withdraw_call,
+        None,
+    )
+    .map_err(|e| LightError::from_program_error(&e).unwrap())
+}
+
+#[test]
+fn commitment_should_match_layout() {
+    let call = post_withdraw_call();
+    let commitment = call.commitment();
+    assert_eq!(commitment.len(), POST_WITHDRAW_CALL_COMMITMENT_LEN);
+    assert_eq!(commitment[..32], call.program_id.to_bytes());
+    let metas = [
+        [[10u8; 32].to_vec(), vec![1u8]].concat(),
+        [[11u8; 32].to_vec(), vec![0u8]].concat(),
+    ]
+    .concat();
+    assert_eq!(commitment[32..64], keccak::hash(&metas).to_bytes());
+    assert_eq!(commitment[64..], keccak::hash(&[1, 2, 3]).to_bytes());
+
+    let instruction = call.instruction();
+    assert!(instruction.accounts.iter().all(|meta| !meta.is_signer));
+    assert!(instruction.accounts[0].is_writable);
+    assert!(!instruction.accounts[1].is_writable);
+}
+
+#[test]
+fn post_withdraw_call_should_be_part_of_tx_integrity_hash() {
+    let commitment = post_withdraw_call().commitment();
+    // without a call the hash is unchanged
+    assert_eq!(check_hash(&hash_input(), None), Ok(()));
+    let mut input = hash_input();
+    input.push(commitment.to_vec());
+    assert_eq!(check_hash(&input, Some(&commitment[..])), Ok(()));
+    // a call can't be added or removed after the proof was generated
+    assert_eq!(
+        check_hash(&hash_input(), Some(&commitment[..])),
+        Err(LightError::InvalidTxIntegrityHash)
+    );
+    assert_eq!(
+        check_hash(&input, None),
+        Err(LightError::InvalidTxIntegrityHash)
+    );
+}
+
+#[test]
+fn other_post_withdraw_call_should_not_be_invoked() {
+    use borsh::BorshSerialize;
+
+    let call = post_withdraw_call();
+    let stored_hash = commitment_hash(Some(&call.commitment()[..]));
+    assert_ne!(stored_hash, NO_POST_WITHDRAW_CALL);
+
+    let mut other_call = post_withdraw_call();
+    other_call.data = vec![1, 2, 4];
+    assert_eq!(
+        check_and_invoke(
+            &Pubkey::new_unique(),
+            &stored_hash,
+            &other_call.try_to_vec().unwrap(),
+            &[],
+        ),
+        Err(LightError::InvalidPostWithdrawCall.into())
+    );
+    let mut other_call = post_withdraw_call();
+    other_call.accounts[1].is_writable = true;
+    assert_eq!(
+        check_and_invoke(
+            &Pubkey::new_unique(),
+            &stored_hash,
+            &other_call.try_to_vec().unwrap(),
+            &[],
+        ),
+        Err(LightError::InvalidPostWithdrawCall.into())
+    );
+    // the program can't invoke itself
+    assert_eq!(
+        check_and_invoke(
+            &call.program_id,
+            &stored_hash,
+            &call.try_to_vec().unwrap(),
+            &[],
+        ),
+        Err(LightError::InvalidPostWithdrawCall.into())
+    );
+}
+
+#[test]
+fn post_withdraw_call_hash_should_be_stored() {
+    let mut tmp_storage_pda_data =
+        ChecksAndTransferState::unpack_from_slice(&[0u8; ChecksAndTransferState::LEN]).unwrap();
+    assert_eq!(
+        tmp_storage_pda_data.post_withdraw_call_hash,
+        NO_POST_WITHDRAW_CALL
+    );
+
+    let stored_hash = commitment_hash(Some(&post_withdraw_call().commitment()[..]));
+    tmp_storage_pda_data.post_withdraw_call_hash = stored_hash.to_vec();
+    tmp_storage_pda_data.changed_constants[11] = true;
+    let mut data = vec![0u8; ChecksAndTransferState::LEN];
+    ChecksAndTransferState::pack_into_slice(&tmp_storage_pda_data, &mut data);
+    let tmp_storage_pda_data = ChecksAndTransferState::unpack_from_slice(&data).unwrap();
+    assert_eq!(tmp_storage_pda_data.post_withdraw_call_hash, stored_hash);
+}
+
+#[test]
+fn last_instruction_should_append_post_withdraw_call() {
+    let program_id = Pubkey::new_unique();
+    let signer = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let call = post_withdraw_call();
+    let instruction = last_instruction(
+        &program_id,
+        &signer,
+        &Pubkey::new_unique(),
+        &[[1u8; 32], [2u8; 32]],
+        &Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0),
+        &Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1),
+        &Asset::Sol,
+        Settlement::Withdrawal { recipient },
+        None,
+        None,
+        Some(&call),
+    )
+    .unwrap();
+    assert_eq!(instruction.accounts[15].pubkey, recipient);
+    // no relayer fee, the signer fills the relayer token slot
+    assert_eq!(instruction.accounts[16].pubkey, signer);
+    assert_eq!(instruction.accounts[17].pubkey, call.program_id);
+    assert_eq!(instruction.accounts[18..], call.instruction().accounts[..]);
+    // bincode length prefix and selector
+    assert_eq!(instruction.data[8], 21);
+    assert_eq!(
+        PostWithdrawCall::decode(&instruction.data[9..]).unwrap(),
+        call
+    );
+}
+
+fn target_program_id() -> Pubkey {
+    Pubkey::new(&[12u8; 32])
+}
+
+fn target_account() -> Pubkey {
+    Pubkey::new(&[13u8; 32])
+}
+
+// Mock target of post withdraw calls, stores the lamports of account 1 in account 0.
+// Instruction data [1] fails.
+fn mock_target_process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    if instruction_data == [1] {
+        return Err(ProgramError::Custom(1));
+    }
+    let lamports = accounts[1].lamports();
+    accounts[0].data.borrow_mut()[..8].copy_from_slice(&lamports.to_le_bytes());
+    Ok(())
+}
+
+// Call of the mock target which reads the balance of recipient.
+fn target_call(recipient: &Pubkey, data: Vec<u8>) -> PostWithdrawCall {
+    PostWithdrawCall {
+        program_id: target_program_id(),
+        accounts: vec![
+            PostWithdrawCallAccount {
+                pubkey: target_account(),
+                is_writable: true,
+            },
+            PostWithdrawCallAccount {
+                pubkey: *recipient,
+                is_writable: false,
+            },
+        ],
+        data,
+    }
+}
+
+// Starts with a settled deposit of 1 SOL, returns the relayer, the indexer and the utxo.
+async fn start_with_deposit() -> (ProgramTestContext, Keypair, MerkleTreeIndexer, Utxo) {
+    let mut program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    program_test.add_program(
+        "mock_target",
+        target_program_id(),
+        processor!(mock_target_process_instruction),
+    );
+    program_test.add_account(
+        target_account(),
+        Account::new(Rent::default().minimum_balance(8), 8, &target_program_id()),
+    );
+    let mut program_context = start_with_merkle_tree(program_test).await;
+    let relayer = Keypair::new();
+    fund(&mut program_context, &relayer.pubkey()).await;
+    let mut indexer =
+        MerkleTreeIndexer::new(Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0)).unwrap();
+    let mut transaction = sol_transaction(&relayer.pubkey(), &relayer.pubkey(), 1_000_000_000, 0);
+    let mut utxo = deposit(&mut transaction, &indexer, 0);
+    settle(&mut program_context, &transaction, &relayer)
+        .await
+        .unwrap();
+    utxo.index = insert_leaves(&mut indexer, &transaction);
+    (program_context, relayer, indexer, utxo)
+}
+
+async fn target_data(program_context: &mut ProgramTestContext) -> Vec<u8> {
+    program_context
+        .banks_client
+        .get_account(target_account())
+        .await
+        .unwrap()
+        .unwrap()
+        .data
+}
+
+#[tokio::test]
+async fn post_withdraw_call_should_be_invoked_with_the_withdrawal() {
+    let (mut program_context, relayer, indexer, utxo) = start_with_deposit().await;
+    let recipient = Pubkey::new_unique();
+    let mut transaction = sol_transaction(&relayer.pubkey(), &recipient, -400_000_000, 0);
+    transaction.post_withdraw_call = Some(target_call(&recipient, vec![0]));
+    withdrawal(&mut transaction, utxo, &indexer, 0);
+    settle(&mut program_context, &transaction, &relayer)
+        .await
+        .unwrap();
+    // The call ran after the withdrawal in the same instruction.
+    assert_eq!(
+        target_data(&mut program_context).await,
+        400_000_000u64.to_le_bytes().to_vec()
+    );
+}
+
+// The relayer token slot is always passed, the call accounts start at the same position with
+// and without relayer fee.
+#[tokio::test]
+async fn post_withdraw_call_should_be_invoked_with_and_without_relayer_fee() {
+    let (mut program_context, relayer, mut indexer, utxo) = start_with_deposit().await;
+    let recipient = Pubkey::new_unique();
+    let relayer_token = Pubkey::new_unique();
+    let mut transaction = sol_transaction(&relayer.pubkey(), &recipient, -300_000_000, 10_000_000);
+    transaction.relayer_token = Some(relayer_token);
+    transaction.post_withdraw_call = Some(target_call(&recipient, vec![0]));
+    let mut change = withdrawal(&mut transaction, utxo, &indexer, 0);
+    settle(&mut program_context, &transaction, &relayer)
+        .await
+        .unwrap();
+    change.index = insert_leaves(&mut indexer, &transaction);
+    assert_eq!(
+        program_context
+            .banks_client
+            .get_balance(relayer_token)
+            .await
+            .unwrap(),
+        10_000_000
+    );
+    assert_eq!(
+        target_data(&mut program_context).await,
+        300_000_000u64.to_le_bytes().to_vec()
+    );
+
+    let mut transaction = sol_transaction(&relayer.pubkey(), &recipient, -200_000_000, 0);
+    transaction.post_withdraw_call = Some(target_call(&recipient, vec![0]));
+    withdrawal(&mut transaction, change, &indexer, 0);
+    let last = transaction
+        .instruction(INSERT_LEAVES_NULLIFIER_AND_TRANSFER, &relayer.pubkey())
+        .unwrap();
+    assert_eq!(last.accounts[16].pubkey, relayer.pubkey());
+    assert_eq!(last.accounts[17].pubkey, target_program_id());
+    settle(&mut program_context, &transaction, &relayer)
+        .await
+        .unwrap();
+    assert_eq!(
+        target_data(&mut program_context).await,
+        500_000_000u64.to_le_bytes().to_vec()
+    );
+}
+
+#[tokio::test]
+async fn failing_post_withdraw_call_should_revert_the_withdrawal() {
+    let (mut program_context, relayer, indexer, utxo) = start_with_deposit().await;
+    let recipient = Pubkey::new_unique();
+    let mut transaction = sol_transaction(&relayer.pubkey(), &recipient, -400_000_000, 0);
+    transaction.post_withdraw_call = Some(target_call(&recipient, vec![1]));
+    withdrawal(&mut transaction, utxo, &indexer, 0);
+    assert!(settle(&mut program_context, &transaction, &relayer)
+        .await
+        .is_err());
+    assert_eq!(
+        program_context
+            .banks_client
+            .get_balance(recipient)
+            .await
+            .unwrap(),
+        0
+    );
+    let pdas = transaction.pdas();
+    for pda in [pdas.leaves, pdas.nullifiers[0], pdas.nullifiers[1]] {
+        assert!(program_context
+            .banks_client
+            .get_account(pda)
+            .await
+            .unwrap()
+            .is_none());
+    }
+    // The transaction is not settled, its tmp storage pda is still open.
+    assert!(program_context
+        .banks_client
+        .get_account(pdas.tmp_storage)
+        .await
+        .unwrap()
+        .is_some());
+    let merkle_tree_account = program_context
+        .banks_client
+        .get_account(transaction.ext_data.merkle_tree)
+        .await
+        .unwrap()
+        .unwrap();
+    indexer.verify_onchain(&merkle_tree_account.data).unwrap();
+}
+
+#[tokio::test]
+async fn uncommitted_post_withdraw_call_should_be_rejected() {
+    let (mut program_context, relayer, indexer, utxo) = start_with_deposit().await;
+    let recipient = Pubkey::new_unique();
+    let mut transaction = sol_transaction(&relayer.pubkey(), &recipient, -400_000_000, 0);
+    transaction.post_withdraw_call = Some(target_call(&recipient, vec![0]));
+    withdrawal(&mut transaction, utxo, &indexer, 0);
+    let instructions = transaction.instructions().unwrap();
+    let (last, instructions) = instructions.split_last().unwrap();
+    process_packed(&mut program_context, instructions, &relayer)
+        .await
+        .unwrap();
+
+    let mut other_accounts = target_call(&recipient, vec![0]);
+    other_accounts.accounts[1].pubkey = relayer.pubkey();
+    let mut other_data = target_call(&recipient, vec![0]);
+    other_data.data = vec![2];
+    for other_call in [other_accounts, other_data] {
+        let mut other_transaction = transaction.clone();
+        other_transaction.post_withdraw_call = Some(other_call);
+        let other_last = other_transaction
+            .instruction(INSERT_LEAVES_NULLIFIER_AND_TRANSFER, &relayer.pubkey())
+            .unwrap();
+        let error = process_packed(&mut program_context, &[other_last], &relayer)
+            .await
+            .unwrap_err();
+        assert_eq!(
+            custom_error(error),
+            Some(LightError::InvalidPostWithdrawCall)
+        );
+    }
+    assert_eq!(target_data(&mut program_context).await, vec![0u8; 8]);
+
+    process_packed(&mut program_context, &[last.clone()], &relayer)
+        .await
+        .unwrap();
+    assert_eq!(
+        target_data(&mut program_context).await,
+        400_000_000u64.to_le_bytes().to_vec()
+    );
+}
@@ -0,0 +1,468 @@
+use ark_ff::biginteger::BigInteger256;
+use light_client::merkle_tree::MerkleTreeIndexer;
+use light_protocol_program::admin_config::find_admin_config;
+use light_protocol_program::asset::Asset;
+use light_protocol_program::error::LightError;
+use light_protocol_program::instructions::check_external_amount;
+use light_protocol_program::process_instruction;
+use light_protocol_program::protocol_fee::{
+    find_protocol_fee_config, load_protocol_fee_config, FeeSchedule, ProtocolFeeConfig,
+};
+use light_protocol_program::utils::config::{
+    MERKLE_TREE_ACC_BYTES_ARRAY, PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE, USDC_MINT,
+};
+use light_protocol_program::INSERT_LEAVES_NULLIFIER_AND_TRANSFER;
+use solana_program::{
+    account_info::AccountInfo,
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    system_program, sysvar,
+};
+use solana_program_test::*;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+
+mod test_utils;
+use crate::test_utils::local_proof::{
+    deposit, insert_leaves, process_packed, settle, transaction, withdrawal,
+};
+use crate::test_utils::{
+    add_token_account, custom_error, fund, fund_lamports, negative_pub_amount, process, program_id,
+    start_with_merkle_tree_index, tmp_storage_state, PRIVATE_KEY,
+};
+
+fn set_protocol_fee_instruction(
+    signer: &Pubkey,
+    asset: &Asset,
+    treasury: &Pubkey,
+    fee_bps: u16,
+    min_fee: u64,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        program_id(),
+        &[
+            vec![242u8],
+            asset.asset_id().to_vec(),
+            treasury.to_bytes().to_vec(),
+            fee_bps.to_le_bytes().to_vec(),
+            min_fee.to_le_bytes().to_vec(),
+        ]
+        .concat(),
+        vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(find_protocol_fee_config(&program_id(), asset).0, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(find_admin_config(&program_id()).0, false),
+        ],
+    )
+}
+
+async fn get_protocol_fee_config(
+    program_context: &mut ProgramTestContext,
+    asset: &Asset,
+) -> ProtocolFeeConfig {
+    let account = program_context
+        .banks_client
+        .get_account(find_protocol_fee_config(&program_id(), asset).0)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(account.owner, program_id());
+    ProtocolFeeConfig::unpack(&account.data).unwrap()
+}
+
+async fn token_amount(program_context: &mut ProgramTestContext, token_account: &Pubkey) -> u64 {
+    let account = program_context
+        .banks_client
+        .get_account(*token_account)
+        .await
+        .unwrap()
+        .unwrap();
+    spl_token::state::Account::unpack(&account.data)
+        .unwrap()
+        .amount
+}
+
+#[test]
+fn fee_schedule_should_apply_bps_and_minimum() {
+    assert_eq!(FeeSchedule::default().fee(u64::MAX), Ok(0));
+    let fee_schedule = FeeSchedule {
+        fee_bps: 30,
+        min_fee: 5,
+    };
+    // 0.3% rounded down
+    assert_eq!(fee_schedule.fee(100_000), Ok(300));
+    assert_eq!(fee_schedule.fee(1_999), Ok(5));
+    assert_eq!(fee_schedule.fee(0), Ok(5));
+    let fee_schedule = FeeSchedule {
+        fee_bps: 10_000,
+        min_fee: 0,
+    };
+    assert_eq!(fee_schedule.fee(u64::MAX), Ok(u64::MAX));
+}
+
+#[test]
+fn check_external_amount_should_include_protocol_fee() {
+    let fee_schedule = FeeSchedule {
+        fee_bps: 100,
+        min_fee: 2,
+    };
+    // deposit: ext_amount = pub_amount + relayer_fee + protocol_fee
+    let state = tmp_storage_state(BigInteger256::from(980), 1000, 10);
+    assert_eq!(
+        check_external_amount(&state, &fee_schedule),
+        Ok((1000, 10, 10))
+    );
+    // withdrawal: pub_amount = |ext_amount| + relayer_fee + protocol_fee
+    let state = tmp_storage_state(negative_pub_amount(1020), -1000, 10);
+    assert_eq!(
+        check_external_amount(&state, &fee_schedule),
+        Ok((1000, 10, 10))
+    );
+    // the minimum applies to small amounts
+    let state = tmp_storage_state(negative_pub_amount(102), -100, 0);
+    assert_eq!(
+        check_external_amount(&state, &fee_schedule),
+        Ok((100, 0, 2))
+    );
+    // transfers are free
+    let state = tmp_storage_state(BigInteger256::from(0), 0, 10);
+    assert_eq!(check_external_amount(&state, &fee_schedule), Ok((0, 10, 0)));
+}
+
+#[test]
+fn missing_protocol_fee_should_fail() {
+    let fee_schedule = FeeSchedule {
+        fee_bps: 100,
+        min_fee: 0,
+    };
+    // proofs generated without protocol fee
+    let state = tmp_storage_state(BigInteger256::from(990), 1000, 10);
+    assert_eq!(
+        check_external_amount(&state, &fee_schedule),
+        Err(LightError::InvalidDepositAmount.into())
+    );
+    let state = tmp_storage_state(negative_pub_amount(1010), -1000, 10);
+    assert_eq!(
+        check_external_amount(&state, &fee_schedule),
+        Err(LightError::InvalidWithdrawalAmount.into())
+    );
+    // and without a fee config the protocol fee can't be paid
+    let state = tmp_storage_state(BigInteger256::from(980), 1000, 10);
+    assert_eq!(
+        check_external_amount(&state, &FeeSchedule::default()),
+        Err(LightError::InvalidDepositAmount.into())
+    );
+}
+
+#[test]
+fn protocol_fee_config_should_load_for_sol_and_spl() {
+    let program_id = program_id();
+    for asset in [Asset::Sol, Asset::Spl(Pubkey::new_unique())].iter() {
+        let key = find_protocol_fee_config(&program_id, asset).0;
+        let protocol_fee_config = ProtocolFeeConfig {
+            is_initialized: true,
+            account_type: PROTOCOL_FEE_CONFIG_ACCOUNT_TYPE,
+            asset_id: asset.asset_id(),
+            treasury: Pubkey::new_unique(),
+            fee_schedule: FeeSchedule {
+                fee_bps: 25,
+                min_fee: 1000,
+            },
+        };
+        let mut data = vec![0u8; ProtocolFeeConfig::LEN];
+        ProtocolFeeConfig::pack_into_slice(&protocol_fee_config, &mut data);
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &program_id,
+            false,
+            0,
+        );
+        assert_eq!(
+            load_protocol_fee_config(&program_id, asset, &account),
+            Ok(Some(protocol_fee_config))
+        );
+
+        // the pda of another asset is rejected
+        let other_asset = Asset::Spl(Pubkey::new_unique());
+        assert_eq!(
+            load_protocol_fee_config(&program_id, &other_asset, &account),
+            Err(LightError::InvalidPdaAddress.into())
+        );
+
+        // no fee config was created
+        let mut lamports = 0;
+        let mut data = vec![];
+        let system_program_id = system_program::id();
+        let account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &system_program_id,
+            false,
+            0,
+        );
+        assert_eq!(
+            load_protocol_fee_config(&program_id, asset, &account),
+            Ok(None)
+        );
+    }
+}
+
+#[tokio::test]
+async fn set_protocol_fee_config_should_succeed() {
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    let authority = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    let transaction = solana_sdk::system_transaction::transfer(
+        &program_context.payer,
+        &authority.pubkey(),
+        10_000_000_000,
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    let sol_treasury = Pubkey::new_unique();
+    let spl_treasury = Pubkey::new_unique();
+    let mint = Pubkey::new_unique();
+    for (asset, treasury) in [(Asset::Sol, sol_treasury), (Asset::Spl(mint), spl_treasury)].iter() {
+        let instruction = set_protocol_fee_instruction(&authority.pubkey(), asset, treasury, 30, 5);
+        process(&mut program_context, instruction, &authority)
+            .await
+            .unwrap();
+        let protocol_fee_config = get_protocol_fee_config(&mut program_context, asset).await;
+        assert_eq!(protocol_fee_config.asset_id, asset.asset_id());
+        assert_eq!(protocol_fee_config.treasury, *treasury);
+        assert_eq!(
+            protocol_fee_config.fee_schedule,
+            FeeSchedule {
+                fee_bps: 30,
+                min_fee: 5
+            }
+        );
+    }
+
+    // an existing fee config is updated, 0 disables the fee
+    let instruction =
+        set_protocol_fee_instruction(&authority.pubkey(), &Asset::Sol, &sol_treasury, 0, 0);
+    process(&mut program_context, instruction, &authority)
+        .await
+        .unwrap();
+    let protocol_fee_config = get_protocol_fee_config(&mut program_context, &Asset::Sol).await;
+    assert_eq!(protocol_fee_config.fee_schedule, FeeSchedule::default());
+
+    // more than 100%
+    let instruction = set_protocol_fee_instruction(
+        &authority.pubkey(),
+        &Asset::Spl(mint),
+        &spl_treasury,
+        10_001,
+        0,
+    );
+    let error = process(&mut program_context, instruction, &authority)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        custom_error(error),
+        Some(LightError::InvalidProtocolFeeConfig)
+    );
+
+    // only the authority can set the fee
+    let payer = Keypair::from_bytes(&program_context.payer.to_bytes()).unwrap();
+    let instruction = set_protocol_fee_instruction(
+        &payer.pubkey(),
+        &Asset::Spl(mint),
+        &payer.pubkey(),
+        10_000,
+        0,
+    );
+    let error = process(&mut program_context, instruction, &payer)
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidAuthority));
+    let protocol_fee_config =
+        get_protocol_fee_config(&mut program_context, &Asset::Spl(mint)).await;
+    assert_eq!(protocol_fee_config.treasury, spl_treasury);
+}
+
+// The fee of a withdrawal is charged on the amount the recipient receives, it is paid from the
+// pool to the treasury at the last instruction.
+#[tokio::test]
+async fn protocol_fee_should_be_paid_on_sol_withdrawals() {
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = start_with_merkle_tree_index(program_test, 0).await;
+    let merkle_tree = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0);
+    let merkle_tree_token = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1);
+    let authority = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    let relayer = Keypair::new();
+    fund(&mut program_context, &relayer.pubkey()).await;
+    let treasury = Pubkey::new_unique();
+    fund_lamports(&mut program_context, &treasury, 1_000_000_000).await;
+    let mut indexer = MerkleTreeIndexer::new(merkle_tree).unwrap();
+
+    // deposited before the fee config exists, without protocol fee
+    let mut deposit_transaction =
+        transaction(0, &relayer.pubkey(), &relayer.pubkey(), 1_000_000_000, 0);
+    let mut utxo = deposit(&mut deposit_transaction, &indexer, 0);
+    settle(&mut program_context, &deposit_transaction, &relayer)
+        .await
+        .unwrap();
+    utxo.index = insert_leaves(&mut indexer, &deposit_transaction);
+
+    let instruction =
+        set_protocol_fee_instruction(&authority.pubkey(), &Asset::Sol, &treasury, 100, 0);
+    process(&mut program_context, instruction, &authority)
+        .await
+        .unwrap();
+    let pool_balance = program_context
+        .banks_client
+        .get_balance(merkle_tree_token)
+        .await
+        .unwrap();
+
+    // 1% of the 0.4 SOL the recipient receives
+    let protocol_fee = 4_000_000;
+    let recipient = Pubkey::new_unique();
+    let mut withdrawal_transaction = transaction(0, &relayer.pubkey(), &recipient, -400_000_000, 0);
+    withdrawal_transaction.treasury = Some(treasury);
+    let change = withdrawal(&mut withdrawal_transaction, utxo, &indexer, protocol_fee);
+    assert_eq!(change.amount, 1_000_000_000 - 400_000_000 - protocol_fee);
+
+    let instructions = withdrawal_transaction.instructions().unwrap();
+    let (last, instructions) = instructions.split_last().unwrap();
+    process_packed(&mut program_context, instructions, &relayer)
+        .await
+        .unwrap();
+    // the treasury of the fee config has to be passed
+    let mut wrong_treasury = withdrawal_transaction.clone();
+    wrong_treasury.treasury = Some(relayer.pubkey());
+    let wrong_last = wrong_treasury
+        .instruction(INSERT_LEAVES_NULLIFIER_AND_TRANSFER, &relayer.pubkey())
+        .unwrap();
+    let error = process_packed(&mut program_context, &[wrong_last], &relayer)
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidTreasury));
+
+    process_packed(&mut program_context, &[last.clone()], &relayer)
+        .await
+        .unwrap();
+    assert_eq!(
+        program_context
+            .banks_client
+            .get_balance(recipient)
+            .await
+            .unwrap(),
+        400_000_000
+    );
+    assert_eq!(
+        program_context
+            .banks_client
+            .get_balance(treasury)
+            .await
+            .unwrap(),
+        1_000_000_000 + protocol_fee
+    );
+    assert_eq!(
+        program_context
+            .banks_client
+            .get_balance(merkle_tree_token)
+            .await
+            .unwrap(),
+        pool_balance - 400_000_000 - protocol_fee
+    );
+}
+
+// The fee of a deposit is charged on the amount the user transfers, the pool keeps the
+// deposited utxo amount.
+#[tokio::test]
+async fn protocol_fee_should_be_paid_on_spl_deposits() {
+    let mint = Pubkey::new(&USDC_MINT);
+    let user = Keypair::new();
+    let user_token = Pubkey::new_unique();
+    let treasury = Pubkey::new_unique();
+    let mut program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    add_token_account(
+        &mut program_test,
+        user_token,
+        mint,
+        user.pubkey(),
+        1_000_000,
+    );
+    add_token_account(&mut program_test, treasury, mint, Pubkey::new_unique(), 0);
+    let mut program_context = start_with_merkle_tree_index(program_test, 2).await;
+    let merkle_tree = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[2].0);
+    let merkle_tree_token = Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[2].1);
+    let authority = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    fund(&mut program_context, &user.pubkey()).await;
+    let mut indexer = MerkleTreeIndexer::new(merkle_tree).unwrap();
+
+    let instruction =
+        set_protocol_fee_instruction(&authority.pubkey(), &Asset::Spl(mint), &treasury, 100, 0);
+    process(&mut program_context, instruction, &authority)
+        .await
+        .unwrap();
+    // the authority pda transfers the deposit from the user token account
+    let mut deposit_transaction = transaction(2, &user.pubkey(), &user.pubkey(), 1_000_000, 0);
+    deposit_transaction.user_token = Some(user_token);
+    deposit_transaction.treasury = Some(treasury);
+    let approve = spl_token::instruction::approve(
+        &spl_token::id(),
+        &user_token,
+        &deposit_transaction.pdas().authority,
+        &user.pubkey(),
+        &[],
+        1_000_000,
+    )
+    .unwrap();
+    process(&mut program_context, approve, &user).await.unwrap();
+
+    // 1% of the 1_000_000 tokens the user transfers
+    let protocol_fee = 10_000;
+    let utxo = deposit(&mut deposit_transaction, &indexer, protocol_fee);
+    assert_eq!(utxo.amount, 1_000_000 - protocol_fee);
+    settle(&mut program_context, &deposit_transaction, &user)
+        .await
+        .unwrap();
+    insert_leaves(&mut indexer, &deposit_transaction);
+    let merkle_tree_account = program_context
+        .banks_client
+        .get_account(merkle_tree)
+        .await
+        .unwrap()
+        .unwrap();
+    indexer.verify_onchain(&merkle_tree_account.data).unwrap();
+    assert_eq!(token_amount(&mut program_context, &user_token).await, 0);
+    assert_eq!(
+        token_amount(&mut program_context, &treasury).await,
+        protocol_fee
+    );
+    assert_eq!(
+        token_amount(&mut program_context, &merkle_tree_token).await,
+        1_000_000 - protocol_fee
+    );
+}
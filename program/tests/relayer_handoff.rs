@@ -0,0 +1,466 @@
+use light_protocol_program::asset::Asset;
+use light_protocol_program::cpi::{last_instruction, send_data_instruction, Settlement};
+use light_protocol_program::error::LightError;
+use light_protocol_program::instructions::send_data_commitments;
+use light_protocol_program::pda::{find_escrow_pda, find_tmp_storage_pda};
+use light_protocol_program::post_withdraw_call::POST_WITHDRAW_CALL_COMMITMENT_LEN;
+use light_protocol_program::process_instruction;
+use light_protocol_program::relayer_handoff::{
+    find_relayer_handoff, RelayerHandoff, RelayerHandoffCommitment, RELAYER_HANDOFF_COMMITMENT_LEN,
+};
+use light_protocol_program::state::InstructionIndex;
+use light_protocol_program::utils::config::{
+    MERKLE_TREE_ACC_BYTES_ARRAY, RELAYER_HANDOFF_ACCOUNT_TYPE,
+};
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+};
+use solana_program_test::*;
+use solana_sdk::{account::Account, signature::Signer, signer::keypair::Keypair};
+
+mod test_utils;
+use crate::test_utils::{
+    custom_error, fund, process, program_id, read_send_data, CURRENT_INSTRUCTION_INDEX_START,
+    PRIVATE_KEY, PRIV_KEY_DEPOSIT,
+};
+
+// nonce is not read, it only makes repeated take over attempts unique.
+fn take_over_instruction(
+    signer: &Pubkey,
+    tmp_storage_pda: &Pubkey,
+    payer: &Pubkey,
+    nonce: u8,
+) -> Instruction {
+    Instruction::new_with_bincode(
+        program_id(),
+        &[249u8, nonce],
+        vec![
+            AccountMeta::new(*signer, true),
+            AccountMeta::new(*tmp_storage_pda, false),
+            AccountMeta::new(
+                find_relayer_handoff(&program_id(), tmp_storage_pda).0,
+                false,
+            ),
+            AccountMeta::new(*payer, false),
+        ],
+    )
+}
+
+fn relayer_handoff_account(relayer_handoff: &RelayerHandoff) -> Account {
+    let mut data = vec![0u8; RelayerHandoff::LEN];
+    RelayerHandoff::pack_into_slice(relayer_handoff, &mut data);
+    Account {
+        lamports: Rent::default().minimum_balance(RelayerHandoff::LEN),
+        data,
+        owner: program_id(),
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+// Sends the data of deposit.txt and returns the tmp storage account, the relayer stalls after it.
+async fn stalled_tmp_storage_account(relayer: &Keypair) -> (Pubkey, Account) {
+    let send_data = read_send_data("deposit.txt");
+    let tmp_storage_pda =
+        find_tmp_storage_pda(&program_id(), &send_data[64..96], &relayer.pubkey()).0;
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    fund(&mut program_context, &relayer.pubkey()).await;
+    let instruction = send_data_instruction(&program_id(), &relayer.pubkey(), &send_data).unwrap();
+    process(&mut program_context, instruction, relayer)
+        .await
+        .unwrap();
+    let tmp_storage_account = program_context
+        .banks_client
+        .get_account(tmp_storage_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    (tmp_storage_pda, tmp_storage_account)
+}
+
+// Starts the program with the tmp storage account and the hand-off pda, funds the keypairs.
+async fn start(
+    tmp_storage_pda: &Pubkey,
+    tmp_storage_account: Option<Account>,
+    relayer_handoff: &RelayerHandoff,
+    keypairs: &[&Keypair],
+) -> ProgramTestContext {
+    let mut program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    if let Some(tmp_storage_account) = tmp_storage_account {
+        program_test.add_account(*tmp_storage_pda, tmp_storage_account);
+    }
+    program_test.add_account(
+        find_relayer_handoff(&program_id(), tmp_storage_pda).0,
+        relayer_handoff_account(relayer_handoff),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    for keypair in keypairs.iter() {
+        fund(&mut program_context, &keypair.pubkey()).await;
+    }
+    program_context
+}
+
+async fn get_signer(program_context: &mut ProgramTestContext, tmp_storage_pda: &Pubkey) -> Pubkey {
+    let account = program_context
+        .banks_client
+        .get_account(*tmp_storage_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    InstructionIndex::unpack(&account.data)
+        .unwrap()
+        .signer_pubkey
+}
+
+async fn get_relayer_handoff(
+    program_context: &mut ProgramTestContext,
+    tmp_storage_pda: &Pubkey,
+) -> RelayerHandoff {
+    let account = program_context
+        .banks_client
+        .get_account(find_relayer_handoff(&program_id(), tmp_storage_pda).0)
+        .await
+        .unwrap()
+        .unwrap();
+    RelayerHandoff::unpack(&account.data).unwrap()
+}
+
+fn relayer_handoff(
+    payer: &Pubkey,
+    idle_slots: u64,
+    relayers: Vec<Pubkey>,
+    last_instruction_index: u64,
+) -> RelayerHandoff {
+    RelayerHandoff {
+        is_initialized: true,
+        account_type: RELAYER_HANDOFF_ACCOUNT_TYPE,
+        idle_slots,
+        relayers,
+        payer: *payer,
+        last_instruction_index,
+        last_slot: 0,
+    }
+}
+
+#[test]
+fn relayer_handoff_should_roundtrip() {
+    let commitment = RelayerHandoffCommitment {
+        idle_slots: 150,
+        relayers: vec![Pubkey::new_unique(), Pubkey::new_unique()],
+    };
+    let bytes = commitment.commitment();
+    assert_eq!(&bytes[..8], &150u64.to_le_bytes());
+    assert_eq!(bytes[8], 2);
+    assert_eq!(
+        RelayerHandoffCommitment::decode(&bytes).unwrap(),
+        commitment
+    );
+    // at most 4 relayers
+    let mut too_many = bytes;
+    too_many[8] = 5;
+    assert!(RelayerHandoffCommitment::decode(&too_many).is_err());
+    assert!(RelayerHandoffCommitment::decode(&bytes[1..]).is_err());
+
+    let relayer_handoff = RelayerHandoff {
+        is_initialized: true,
+        account_type: RELAYER_HANDOFF_ACCOUNT_TYPE,
+        idle_slots: 150,
+        relayers: commitment.relayers,
+        payer: Pubkey::new_unique(),
+        last_instruction_index: 900,
+        last_slot: 1234,
+    };
+    let mut data = vec![0u8; RelayerHandoff::LEN];
+    RelayerHandoff::pack_into_slice(&relayer_handoff, &mut data);
+    assert_eq!(RelayerHandoff::unpack(&data).unwrap(), relayer_handoff);
+}
+
+#[test]
+fn send_data_commitments_should_be_split_by_length() {
+    let send_data = read_send_data("deposit.txt");
+    let post_withdraw_call = vec![1u8; POST_WITHDRAW_CALL_COMMITMENT_LEN];
+    let relayer_handoff = vec![2u8; RELAYER_HANDOFF_COMMITMENT_LEN];
+
+    assert_eq!(send_data_commitments(&send_data).unwrap(), (None, None));
+    let data = [send_data.clone(), post_withdraw_call.clone()].concat();
+    assert_eq!(
+        send_data_commitments(&data).unwrap(),
+        (Some(&post_withdraw_call[..]), None)
+    );
+    let data = [send_data.clone(), relayer_handoff.clone()].concat();
+    assert_eq!(
+        send_data_commitments(&data).unwrap(),
+        (None, Some(&relayer_handoff[..]))
+    );
+    let data = [
+        send_data.clone(),
+        post_withdraw_call.clone(),
+        relayer_handoff.clone(),
+    ]
+    .concat();
+    assert_eq!(
+        send_data_commitments(&data).unwrap(),
+        (Some(&post_withdraw_call[..]), Some(&relayer_handoff[..]))
+    );
+    let data = [send_data, vec![0u8; 1]].concat();
+    assert!(send_data_commitments(&data).is_err());
+}
+
+#[tokio::test]
+async fn relayer_handoff_should_be_committed() {
+    let send_data = read_send_data("withdraw.txt");
+    let relayer = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    fund(&mut program_context, &relayer.pubkey()).await;
+
+    // withdraw.txt was hashed without hand-off commitment
+    let commitment = RelayerHandoffCommitment {
+        idle_slots: 150,
+        relayers: vec![],
+    };
+    let send_data = [send_data, commitment.commitment().to_vec()].concat();
+    let instruction = send_data_instruction(&program_id(), &relayer.pubkey(), &send_data).unwrap();
+    assert_eq!(instruction.accounts.len(), 6);
+    let error = process(&mut program_context, instruction, &relayer)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        custom_error(error),
+        Some(LightError::InvalidTxIntegrityHash)
+    );
+}
+
+// The last instruction funds SOL deposits from its signer, a relayer which took over
+// would pay the deposit of the user.
+#[tokio::test]
+async fn sol_deposits_should_not_be_handed_off() {
+    let send_data = read_send_data("deposit.txt");
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    fund(&mut program_context, &relayer.pubkey()).await;
+
+    let commitment = RelayerHandoffCommitment {
+        idle_slots: 150,
+        relayers: vec![Pubkey::new_unique()],
+    };
+    let send_data = [send_data, commitment.commitment().to_vec()].concat();
+    let instruction = send_data_instruction(&program_id(), &relayer.pubkey(), &send_data).unwrap();
+    let error = process(&mut program_context, instruction, &relayer)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        custom_error(error),
+        Some(LightError::RelayerHandoffNotAllowed)
+    );
+}
+
+#[tokio::test]
+async fn authorized_relayer_should_take_over() {
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let backup_relayer = Keypair::new();
+    let stranger = Keypair::new();
+    let (tmp_storage_pda, mut tmp_storage_account) = stalled_tmp_storage_account(&relayer).await;
+    // skip the proof verification
+    tmp_storage_account.data[CURRENT_INSTRUCTION_INDEX_START..CURRENT_INSTRUCTION_INDEX_START + 8]
+        .copy_from_slice(&1501usize.to_le_bytes());
+    let relayer_handoff =
+        relayer_handoff(&relayer.pubkey(), 0, vec![backup_relayer.pubkey()], 1501);
+    let mut program_context = start(
+        &tmp_storage_pda,
+        Some(tmp_storage_account),
+        &relayer_handoff,
+        &[&relayer, &backup_relayer, &stranger],
+    )
+    .await;
+
+    // without idle slots only the hand-off relayers can take over
+    let instruction =
+        take_over_instruction(&stranger.pubkey(), &tmp_storage_pda, &relayer.pubkey(), 0);
+    let error = process(&mut program_context, instruction, &stranger)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        custom_error(error),
+        Some(LightError::RelayerHandoffNotAllowed)
+    );
+
+    let instruction = take_over_instruction(
+        &backup_relayer.pubkey(),
+        &tmp_storage_pda,
+        &relayer.pubkey(),
+        1,
+    );
+    process(&mut program_context, instruction, &backup_relayer)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_signer(&mut program_context, &tmp_storage_pda).await,
+        backup_relayer.pubkey()
+    );
+
+    // the last instruction is signed by the backup relayer now
+    let send_data = read_send_data("deposit.txt");
+    let mut nullifiers = [[0u8; 32]; 2];
+    nullifiers[0].copy_from_slice(&send_data[96..128]);
+    nullifiers[1].copy_from_slice(&send_data[128..160]);
+    for (keypair, expected_error) in [
+        (&relayer, LightError::InvalidSigner),
+        (&backup_relayer, LightError::RootNotFound),
+    ]
+    .iter()
+    {
+        let instruction = last_instruction(
+            &program_id(),
+            &keypair.pubkey(),
+            &tmp_storage_pda,
+            &nullifiers,
+            &Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0),
+            &Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].1),
+            &Asset::Sol,
+            Settlement::Deposit {
+                user_token: find_escrow_pda(&program_id(), &tmp_storage_pda).0,
+            },
+            None,
+            None,
+            None,
+        )
+        .unwrap();
+        let error = process(&mut program_context, instruction, keypair)
+            .await
+            .unwrap_err();
+        assert_eq!(custom_error(error), Some(*expected_error));
+    }
+}
+
+#[tokio::test]
+async fn anyone_should_take_over_idle_computation() {
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let stranger = Keypair::new();
+    let (tmp_storage_pda, tmp_storage_account) = stalled_tmp_storage_account(&relayer).await;
+    // the last observed instruction index is behind, the computation progressed since
+    let relayer_handoff = relayer_handoff(&relayer.pubkey(), 1000, vec![], 0);
+    let mut program_context = start(
+        &tmp_storage_pda,
+        Some(tmp_storage_account),
+        &relayer_handoff,
+        &[&relayer, &stranger],
+    )
+    .await;
+
+    // the first attempt records the progress
+    let instruction =
+        take_over_instruction(&stranger.pubkey(), &tmp_storage_pda, &relayer.pubkey(), 2);
+    process(&mut program_context, instruction, &stranger)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_signer(&mut program_context, &tmp_storage_pda).await,
+        relayer.pubkey()
+    );
+    let relayer_handoff = get_relayer_handoff(&mut program_context, &tmp_storage_pda).await;
+    assert_eq!(relayer_handoff.last_instruction_index, 1);
+    let idle_until = relayer_handoff.last_slot + 1000;
+
+    // the computation is not idle for 1000 slots yet
+    let instruction =
+        take_over_instruction(&stranger.pubkey(), &tmp_storage_pda, &relayer.pubkey(), 3);
+    let error = process(&mut program_context, instruction, &stranger)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        custom_error(error),
+        Some(LightError::RelayerHandoffNotAllowed)
+    );
+
+    program_context.warp_to_slot(idle_until + 1).unwrap();
+    let instruction =
+        take_over_instruction(&stranger.pubkey(), &tmp_storage_pda, &relayer.pubkey(), 4);
+    process(&mut program_context, instruction, &stranger)
+        .await
+        .unwrap();
+    assert_eq!(
+        get_signer(&mut program_context, &tmp_storage_pda).await,
+        stranger.pubkey()
+    );
+    // the original relayer has to wait for the computation to be idle as well
+    let instruction =
+        take_over_instruction(&relayer.pubkey(), &tmp_storage_pda, &relayer.pubkey(), 5);
+    let error = process(&mut program_context, instruction, &relayer)
+        .await
+        .unwrap_err();
+    assert_eq!(
+        custom_error(error),
+        Some(LightError::RelayerHandoffNotAllowed)
+    );
+}
+
+#[tokio::test]
+async fn relayer_handoff_should_be_closed_after_transaction() {
+    let relayer = Keypair::new();
+    let stranger = Keypair::new();
+    // the last instruction closed the tmp storage pda
+    let tmp_storage_pda = Pubkey::new_unique();
+    let relayer_handoff = relayer_handoff(&relayer.pubkey(), 1000, vec![], 1);
+    let mut program_context = start(
+        &tmp_storage_pda,
+        None,
+        &relayer_handoff,
+        &[&relayer, &stranger],
+    )
+    .await;
+    let relayer_handoff_pda = find_relayer_handoff(&program_id(), &tmp_storage_pda).0;
+
+    let instruction =
+        take_over_instruction(&stranger.pubkey(), &tmp_storage_pda, &stranger.pubkey(), 6);
+    let error = process(&mut program_context, instruction, &stranger)
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidRelayer));
+
+    let relayer_lamports = program_context
+        .banks_client
+        .get_balance(relayer.pubkey())
+        .await
+        .unwrap();
+    let instruction =
+        take_over_instruction(&stranger.pubkey(), &tmp_storage_pda, &relayer.pubkey(), 7);
+    process(&mut program_context, instruction, &stranger)
+        .await
+        .unwrap();
+    assert!(program_context
+        .banks_client
+        .get_account(relayer_handoff_pda)
+        .await
+        .unwrap()
+        .is_none());
+    assert_eq!(
+        program_context
+            .banks_client
+            .get_balance(relayer.pubkey())
+            .await
+            .unwrap(),
+        relayer_lamports + Rent::default().minimum_balance(RelayerHandoff::LEN)
+    );
+}
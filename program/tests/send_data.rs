@@ -0,0 +1,120 @@
+use light_protocol_program::asset::Asset;
+use light_protocol_program::instructions::{check_tx_integrity_hash, SEND_DATA_LEN};
+use light_protocol_program::post_withdraw_call::POST_WITHDRAW_CALL_COMMITMENT_LEN;
+use light_protocol_program::relayer_handoff::RELAYER_HANDOFF_COMMITMENT_LEN;
+use light_protocol_program::send_data::{ExtData, SendData, EXT_DATA_LEN, EXT_DATA_RANGE_START};
+use light_protocol_program::utils::config::{ENCRYPTED_UTXOS_LENGTH, MERKLE_TREE_ACC_BYTES_ARRAY};
+use solana_program::{program_error::ProgramError, pubkey::Pubkey};
+
+mod test_utils;
+use crate::test_utils::read_send_data;
+
+#[test]
+fn fixture_send_data_should_roundtrip() {
+    for file in ["deposit.txt", "internal_transfer.txt"].iter() {
+        let bytes = read_send_data(file);
+        let send_data = SendData::unpack(&bytes).unwrap();
+        assert_eq!(send_data.pack().unwrap(), bytes);
+
+        let public_inputs = &send_data.public_inputs;
+        assert_eq!(public_inputs.root[..], bytes[0..32]);
+        assert_eq!(public_inputs.tx_integrity_hash[..], bytes[64..96]);
+        assert_eq!(public_inputs.nullifiers[1][..], bytes[128..160]);
+        assert_eq!(public_inputs.leaves[0][..], bytes[160..192]);
+        assert_eq!(public_inputs.leaves[1][..], bytes[192..224]);
+
+        let ext_data = &send_data.ext_data;
+        assert_eq!(ext_data.recipient.to_bytes()[..], bytes[480..512]);
+        assert_eq!(ext_data.ext_amount.to_le_bytes()[..], bytes[512..520]);
+        assert_eq!(ext_data.relayer.to_bytes()[..], bytes[520..552]);
+        assert_eq!(ext_data.fee.to_le_bytes()[..], bytes[552..560]);
+        assert_eq!(
+            ext_data.merkle_tree,
+            Pubkey::new(&MERKLE_TREE_ACC_BYTES_ARRAY[0].0)
+        );
+        assert_eq!(ext_data.merkle_tree_index, bytes[592]);
+        assert_eq!(ext_data.encrypted_utxos[..], bytes[593..SEND_DATA_LEN]);
+        assert_eq!(send_data.post_withdraw_call, None);
+        assert_eq!(send_data.relayer_handoff, None);
+
+        // the fixtures were hashed by the client of the circuit
+        assert_eq!(
+            send_data.tx_integrity_hash(&Asset::Sol).unwrap(),
+            public_inputs.tx_integrity_hash
+        );
+        assert_eq!(
+            check_tx_integrity_hash(
+                ext_data,
+                &public_inputs.tx_integrity_hash,
+                &Asset::Sol,
+                None,
+                None
+            ),
+            Ok(())
+        );
+    }
+}
+
+#[test]
+fn commitments_should_be_appended_in_order() {
+    let mut send_data = SendData::unpack(&read_send_data("deposit.txt")).unwrap();
+    let without_commitments = send_data.tx_integrity_hash(&Asset::Sol).unwrap();
+    send_data.post_withdraw_call = Some([1u8; POST_WITHDRAW_CALL_COMMITMENT_LEN]);
+    send_data.relayer_handoff = Some([2u8; RELAYER_HANDOFF_COMMITMENT_LEN]);
+
+    let bytes = send_data.pack().unwrap();
+    assert_eq!(
+        bytes.len(),
+        SEND_DATA_LEN + POST_WITHDRAW_CALL_COMMITMENT_LEN + RELAYER_HANDOFF_COMMITMENT_LEN
+    );
+    assert_eq!(bytes[SEND_DATA_LEN], 1);
+    assert_eq!(bytes[bytes.len() - 1], 2);
+    assert_eq!(SendData::unpack(&bytes).unwrap(), send_data);
+
+    let with_commitments = send_data.tx_integrity_hash(&Asset::Sol).unwrap();
+    assert_ne!(with_commitments, without_commitments);
+    assert_eq!(
+        send_data.ext_data.hash(
+            &Asset::Sol,
+            Some(&[1u8; POST_WITHDRAW_CALL_COMMITMENT_LEN]),
+            Some(&[2u8; RELAYER_HANDOFF_COMMITMENT_LEN])
+        ),
+        Ok(with_commitments)
+    );
+    // spl Merkle trees bind the mint
+    assert_ne!(
+        send_data
+            .tx_integrity_hash(&Asset::Spl(Pubkey::new_unique()))
+            .unwrap(),
+        with_commitments
+    );
+}
+
+#[test]
+fn invalid_send_data_should_be_rejected() {
+    let bytes = read_send_data("deposit.txt");
+    assert_eq!(
+        SendData::unpack(&bytes[..SEND_DATA_LEN - 1]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    assert_eq!(
+        SendData::unpack(&[&bytes[..], &[0u8; 10][..]].concat()),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    assert_eq!(
+        ExtData::unpack(&bytes[EXT_DATA_RANGE_START..SEND_DATA_LEN - 1]),
+        Err(ProgramError::InvalidInstructionData)
+    );
+    assert_eq!(EXT_DATA_LEN, 113 + ENCRYPTED_UTXOS_LENGTH);
+
+    let mut send_data = SendData::unpack(&bytes).unwrap();
+    send_data.proof.pop();
+    assert_eq!(send_data.pack(), Err(ProgramError::InvalidArgument));
+    let mut ext_data = SendData::unpack(&bytes).unwrap().ext_data;
+    ext_data.encrypted_utxos.pop();
+    assert_eq!(ext_data.pack(), Err(ProgramError::InvalidArgument));
+    assert_eq!(
+        ext_data.hash(&Asset::Sol, None, None),
+        Err(ProgramError::InvalidArgument)
+    );
+}
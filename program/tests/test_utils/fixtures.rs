@@ -0,0 +1,49 @@
+// Fixtures of the program tests in tests/test_data, shared with the client and cli tests
+// which include this file with #[path = "../../tests/test_utils/fixtures.rs"].
+#![allow(dead_code)]
+
+use serde_json::Value;
+use solana_program::pubkey::Pubkey;
+use std::{fs, path::Path, str::FromStr};
+
+// Merkle tree init authority, also the relayer committed to in withdraw.txt.
+pub const PRIVATE_KEY: [u8; 64] = [
+    17, 34, 231, 31, 83, 147, 93, 173, 61, 164, 25, 0, 204, 82, 234, 91, 202, 187, 228, 110, 146,
+    97, 112, 131, 180, 164, 96, 220, 57, 207, 65, 107, 2, 99, 226, 251, 88, 66, 92, 33, 25, 216,
+    211, 185, 112, 203, 212, 238, 105, 144, 72, 121, 176, 253, 106, 168, 115, 158, 154, 188, 62,
+    255, 166, 81,
+];
+// Relayer committed to in deposit.txt.
+pub const PRIV_KEY_DEPOSIT: [u8; 64] = [
+    70, 5, 178, 190, 139, 224, 161, 74, 134, 130, 14, 189, 253, 51, 249, 124, 255, 116, 66, 87,
+    146, 202, 196, 243, 68, 129, 95, 145, 97, 170, 145, 61, 221, 240, 113, 237, 127, 131, 46, 151,
+    40, 236, 223, 8, 124, 162, 170, 56, 71, 105, 233, 43, 196, 129, 63, 145, 13, 2, 210, 251, 197,
+    109, 226, 3,
+];
+
+pub fn program_id() -> Pubkey {
+    Pubkey::from_str("TransferLamports111111111111111111112111111").unwrap()
+}
+
+// Program tests run in program/, client and cli tests in program/client and program/cli.
+pub fn test_data_path(file: &str) -> String {
+    ["./tests/test_data", "../tests/test_data"]
+        .iter()
+        .map(|dir| format!("{}/{}", dir, file))
+        .find(|path| Path::new(path).exists())
+        .unwrap()
+}
+
+// Send data of a fixture, the instruction data without the 9 bytes of bincode length prefix
+// and selector.
+pub fn read_send_data(file: &str) -> Vec<u8> {
+    let contents = fs::read_to_string(test_data_path(file)).unwrap();
+    let v: Value = serde_json::from_str(&contents).unwrap();
+    let bytes: Vec<u8> = v["bytes"][0]
+        .as_str()
+        .unwrap()
+        .split(',')
+        .map(|byte| byte.trim().parse::<u8>().unwrap())
+        .collect();
+    bytes[9..].to_vec()
+}
@@ -0,0 +1,249 @@
+// Transactions of the whitelisted Merkle trees proven with the circuit of light-client. The proving key
+// is generated once per test binary and replaces the verifying key of the program, test
+// binaries which use it can't verify the proofs of the fixtures.
+use ark_bn254::Bn254;
+use ark_groth16::ProvingKey;
+use ark_std::{rand::rngs::StdRng, test_rng};
+use lazy_static::lazy_static;
+use light_client::circuit::{
+    proof_bytes, public_amount, set_program_verifying_key, setup, TransactionCircuit,
+};
+use light_client::encryption::{encrypt_utxos, EncryptionKeypair};
+use light_client::merkle_tree::{MerkleProof, MerkleTreeIndexer};
+use light_client::utxo::{from_bytes, Keypair, Utxo};
+use light_client::{pack_instructions, ExtData, LightTransaction, PublicInputs};
+use light_protocol_program::utils::config::{
+    get_merkle_tree_asset, ENCRYPTED_UTXOS_LENGTH, MERKLE_TREE_ACC_BYTES_ARRAY,
+};
+use solana_program::{instruction::Instruction, pubkey::Pubkey};
+use solana_program_test::ProgramTestContext;
+use solana_sdk::{
+    signature::Signer, signer::keypair::Keypair as SignerKeypair, transaction::Transaction,
+    transport::TransportError,
+};
+use std::sync::{Mutex, MutexGuard};
+
+use super::program_id;
+
+// Instructions per transaction, verification instructions are packed into few transactions.
+pub const MAX_INSTRUCTIONS: usize = 16;
+
+lazy_static! {
+    static ref PROVING_KEY: ProvingKey<Bn254> = {
+        let proving_key = setup(&mut test_rng()).unwrap();
+        set_program_verifying_key(Some(&proving_key.vk));
+        proving_key
+    };
+    // Shared by the tests of a binary, utxos of different transactions don't share blindings.
+    static ref RNG: Mutex<StdRng> = Mutex::new(test_rng());
+}
+
+fn rng() -> MutexGuard<'static, StdRng> {
+    RNG.lock().unwrap()
+}
+
+pub fn local_proving_key() -> &'static ProvingKey<Bn254> {
+    &PROVING_KEY
+}
+
+// Owner of the utxos of the tests.
+pub fn local_keypair() -> Keypair {
+    Keypair::from_seed(b"local proof").unwrap()
+}
+
+pub fn local_encryption_keypair() -> EncryptionKeypair {
+    EncryptionKeypair::from_seed(b"local proof")
+}
+
+// Transaction of the SOL Merkle tree signed by relayer, without proof and outputs.
+pub fn sol_transaction(
+    relayer: &Pubkey,
+    recipient: &Pubkey,
+    ext_amount: i64,
+    relayer_fee: u64,
+) -> LightTransaction {
+    transaction(0, relayer, recipient, ext_amount, relayer_fee)
+}
+
+// Transaction of the whitelisted Merkle tree of merkle_tree_index signed by relayer, without
+// proof and outputs. Relayer fees are paid to relayer, the token accounts of SPL trees are
+// set by the caller.
+pub fn transaction(
+    merkle_tree_index: u8,
+    relayer: &Pubkey,
+    recipient: &Pubkey,
+    ext_amount: i64,
+    relayer_fee: u64,
+) -> LightTransaction {
+    let (merkle_tree, merkle_tree_token) = &MERKLE_TREE_ACC_BYTES_ARRAY[merkle_tree_index as usize];
+    LightTransaction {
+        program_id: program_id(),
+        proof: vec![0u8; 256],
+        public_inputs: PublicInputs {
+            root: [0u8; 32],
+            public_amount: [0u8; 32],
+            tx_integrity_hash: [0u8; 32],
+            nullifiers: [[0u8; 32]; 2],
+            leaves: [[0u8; 32]; 2],
+        },
+        ext_data: ExtData {
+            recipient: *recipient,
+            ext_amount,
+            relayer: *relayer,
+            fee: relayer_fee,
+            merkle_tree: Pubkey::new(merkle_tree),
+            merkle_tree_index,
+            encrypted_utxos: vec![0u8; ENCRYPTED_UTXOS_LENGTH],
+        },
+        asset: get_merkle_tree_asset(merkle_tree_index).unwrap(),
+        merkle_tree_token: Pubkey::new(merkle_tree_token),
+        user_token: None,
+        relayer_token: if relayer_fee > 0 {
+            Some(*relayer)
+        } else {
+            None
+        },
+        treasury: None,
+        post_withdraw_call: None,
+        relayer_handoff: None,
+    }
+}
+
+// Encrypts the outputs to the local encryption keypair.
+pub fn encrypt_outputs(transaction: &mut LightTransaction, outputs: &[Utxo; 2]) {
+    let public_key = local_encryption_keypair().public_key;
+    transaction.ext_data.encrypted_utxos = encrypt_utxos(
+        [(&outputs[0], &public_key), (&outputs[1], &public_key)],
+        &mut *rng(),
+    )
+    .unwrap();
+}
+
+// Proves the transaction of the local keypair from inputs to outputs against the root of
+// indexer. The external data and the commitments are final, they are bound by the proof.
+pub fn prove(
+    transaction: &mut LightTransaction,
+    inputs: [Utxo; 2],
+    outputs: [Utxo; 2],
+    indexer: &MerkleTreeIndexer,
+    protocol_fee: u64,
+) {
+    let input_proofs: Vec<Option<MerkleProof>> = inputs
+        .iter()
+        .map(|input| (input.amount != 0).then(|| indexer.proof(input.index).unwrap()))
+        .collect();
+    let circuit = TransactionCircuit::new(
+        &local_keypair(),
+        inputs,
+        [input_proofs[0].as_ref(), input_proofs[1].as_ref()],
+        outputs,
+        &indexer.root(),
+        public_amount(
+            transaction.ext_data.ext_amount,
+            transaction.ext_data.fee + protocol_fee,
+        ),
+        from_bytes(&transaction.tx_integrity_hash().unwrap()).unwrap(),
+    )
+    .unwrap();
+    let proof = circuit.prove(local_proving_key(), &mut *rng()).unwrap();
+    transaction.proof = proof_bytes(&proof);
+    transaction.public_inputs = circuit.public_inputs_bytes().unwrap();
+}
+
+// Deposit of the external amount minus fees into a new utxo of the local keypair.
+pub fn deposit(
+    transaction: &mut LightTransaction,
+    indexer: &MerkleTreeIndexer,
+    protocol_fee: u64,
+) -> Utxo {
+    let (inputs, outputs) = deposit_utxos(transaction, protocol_fee);
+    encrypt_outputs(transaction, &outputs);
+    prove(transaction, inputs, outputs, indexer, protocol_fee);
+    outputs[0]
+}
+
+// Dummy inputs and outputs of a deposit, the deposited utxo is output 0.
+pub fn deposit_utxos(transaction: &LightTransaction, protocol_fee: u64) -> ([Utxo; 2], [Utxo; 2]) {
+    let keypair = local_keypair();
+    let amount = transaction.ext_data.ext_amount as u64 - transaction.ext_data.fee - protocol_fee;
+    let mut rng = rng();
+    (
+        [
+            Utxo::dummy(&keypair, &mut *rng),
+            Utxo::dummy(&keypair, &mut *rng),
+        ],
+        [
+            Utxo::new(amount, keypair.public_key, &mut *rng),
+            Utxo::dummy(&keypair, &mut *rng),
+        ],
+    )
+}
+
+// Withdrawal of the external amount plus fees from input, returns the change utxo.
+pub fn withdrawal(
+    transaction: &mut LightTransaction,
+    input: Utxo,
+    indexer: &MerkleTreeIndexer,
+    protocol_fee: u64,
+) -> Utxo {
+    let keypair = local_keypair();
+    let amount = input.amount
+        - transaction.ext_data.ext_amount.unsigned_abs()
+        - transaction.ext_data.fee
+        - protocol_fee;
+    let (inputs, outputs) = {
+        let mut rng = rng();
+        (
+            [input, Utxo::dummy(&keypair, &mut *rng)],
+            [
+                Utxo::new(amount, keypair.public_key, &mut *rng),
+                Utxo::dummy(&keypair, &mut *rng),
+            ],
+        )
+    };
+    encrypt_outputs(transaction, &outputs);
+    prove(transaction, inputs, outputs, indexer, protocol_fee);
+    outputs[0]
+}
+
+// Inserts the leaves of a settled transaction like the program, returns the index of the
+// left leaf, the index of the first output.
+pub fn insert_leaves(indexer: &mut MerkleTreeIndexer, transaction: &LightTransaction) -> u64 {
+    let left_leaf_index = indexer.next_index();
+    let leaves = &transaction.public_inputs.leaves;
+    indexer
+        .insert(left_leaf_index, leaves[1], leaves[0])
+        .unwrap();
+    left_leaf_index
+}
+
+// Sends instructions in order, packed into transactions paid by payer.
+pub async fn process_packed(
+    program_context: &mut ProgramTestContext,
+    instructions: &[Instruction],
+    payer: &SignerKeypair,
+) -> Result<(), TransportError> {
+    for instructions in pack_instructions(instructions, &payer.pubkey(), MAX_INSTRUCTIONS) {
+        let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+        transaction.sign(&[payer], program_context.last_blockhash);
+        program_context
+            .banks_client
+            .process_transaction(transaction)
+            .await?;
+    }
+    Ok(())
+}
+
+// Sends all instructions of the transaction signed by its relayer.
+pub async fn settle(
+    program_context: &mut ProgramTestContext,
+    transaction: &LightTransaction,
+    relayer: &SignerKeypair,
+) -> Result<(), TransportError> {
+    process_packed(
+        program_context,
+        &transaction.instructions().unwrap(),
+        relayer,
+    )
+    .await
+}
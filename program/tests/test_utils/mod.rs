@@ -1,3 +1,199 @@
+// Helpers shared by the program tests, test files declare `mod test_utils;`.
+#![allow(dead_code)]
+
+pub mod fixtures;
+pub mod local_proof;
+pub mod program_data;
+
+pub use fixtures::*;
+
+use ark_ed_on_bn254::FqParameters;
+use ark_ff::{biginteger::BigInteger256, bytes::ToBytes, fields::FpParameters, BigInteger};
+use light_protocol_program::admin_config::find_admin_config;
+use light_protocol_program::asset::Asset;
+use light_protocol_program::error::LightError;
+use light_protocol_program::pda::find_authority_pda;
+use light_protocol_program::state::ChecksAndTransferState;
+use light_protocol_program::utils::config::{get_merkle_tree_asset, MERKLE_TREE_ACC_BYTES_ARRAY};
+use solana_program::{
+    instruction::{AccountMeta, Instruction, InstructionError},
+    program_pack::Pack,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar,
+};
+use solana_program_test::{ProgramTest, ProgramTestContext};
+use solana_sdk::{
+    account::Account,
+    signature::Signer,
+    signer::keypair::Keypair,
+    transaction::{Transaction, TransactionError},
+    transport::TransportError,
+};
+
+// Offset of the current instruction index in the tmp storage account.
+pub const CURRENT_INSTRUCTION_INDEX_START: usize = 212;
+// Offsets of the checked inputs in the tmp storage account.
+pub const RELAYER_FEE_START: usize = 36;
+pub const EXT_AMOUNT_START: usize = 76;
+pub const AMOUNT_START: usize = 84;
+
+pub async fn process(
+    program_context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signer: &Keypair,
+) -> Result<(), TransportError> {
+    process_with_signers(program_context, instruction, &[signer]).await
+}
+
+// The first signer pays the transaction fee.
+pub async fn process_with_signers(
+    program_context: &mut ProgramTestContext,
+    instruction: Instruction,
+    signers: &[&Keypair],
+) -> Result<(), TransportError> {
+    let mut transaction = Transaction::new_with_payer(&[instruction], Some(&signers[0].pubkey()));
+    transaction.sign(&signers.to_vec(), program_context.last_blockhash);
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+}
+
+pub fn custom_error(error: TransportError) -> Option<LightError> {
+    match error {
+        TransportError::TransactionError(TransactionError::InstructionError(
+            _,
+            InstructionError::Custom(code),
+        )) => LightError::from_code(code),
+        _ => None,
+    }
+}
+
+pub async fn fund(program_context: &mut ProgramTestContext, pubkey: &Pubkey) {
+    fund_lamports(program_context, pubkey, 10_000_000_000).await
+}
+
+pub async fn fund_lamports(
+    program_context: &mut ProgramTestContext,
+    pubkey: &Pubkey,
+    lamports: u64,
+) {
+    let transaction = solana_sdk::system_transaction::transfer(
+        &program_context.payer,
+        pubkey,
+        lamports,
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+}
+
+// Starts program_test with the SOL Merkle tree (index 0) and its pool token account, the Merkle
+// tree is initialized by the authority.
+pub async fn start_with_merkle_tree(program_test: ProgramTest) -> ProgramTestContext {
+    start_with_merkle_tree_index(program_test, 0).await
+}
+
+// Starts program_test with the whitelisted Merkle tree of merkle_tree_index and its empty pool
+// token account, a token account of the authority pda for SPL trees.
+pub async fn start_with_merkle_tree_index(
+    mut program_test: ProgramTest,
+    merkle_tree_index: u8,
+) -> ProgramTestContext {
+    let (merkle_tree, merkle_tree_token) = &MERKLE_TREE_ACC_BYTES_ARRAY[merkle_tree_index as usize];
+    let merkle_tree = Pubkey::new(merkle_tree);
+    program_test.add_account(
+        merkle_tree,
+        Account::new(Rent::default().minimum_balance(16658), 16658, &program_id()),
+    );
+    match get_merkle_tree_asset(merkle_tree_index).unwrap() {
+        Asset::Sol => program_test.add_account(
+            Pubkey::new(merkle_tree_token),
+            Account::new(Rent::default().minimum_balance(0), 0, &program_id()),
+        ),
+        Asset::Spl(mint) => add_token_account(
+            &mut program_test,
+            Pubkey::new(merkle_tree_token),
+            mint,
+            find_authority_pda(&program_id()).0,
+            0,
+        ),
+    }
+    let mut program_context = program_test.start_with_context().await;
+
+    let authority = Keypair::from_bytes(&PRIVATE_KEY).unwrap();
+    fund(&mut program_context, &authority.pubkey()).await;
+    let init_merkle_tree = Instruction::new_with_bincode(
+        program_id(),
+        &[vec![240u8, 0u8], usize::to_le_bytes(1000).to_vec()].concat(),
+        vec![
+            AccountMeta::new(authority.pubkey(), true),
+            AccountMeta::new(merkle_tree, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+            AccountMeta::new_readonly(find_admin_config(&program_id()).0, false),
+        ],
+    );
+    process(&mut program_context, init_merkle_tree, &authority)
+        .await
+        .unwrap();
+    program_context
+}
+
+// Adds an initialized token account of mint with amount tokens.
+pub fn add_token_account(
+    program_test: &mut ProgramTest,
+    token_account: Pubkey,
+    mint: Pubkey,
+    owner: Pubkey,
+    amount: u64,
+) {
+    let mut data = vec![0u8; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint,
+        owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..spl_token::state::Account::default()
+    }
+    .pack_into_slice(&mut data);
+    program_test.add_account(
+        token_account,
+        Account {
+            lamports: Rent::default().minimum_balance(data.len()),
+            data,
+            owner: spl_token::id(),
+            executable: false,
+            rent_epoch: 0,
+        },
+    );
+}
+
+// Tmp storage state with the checked inputs of check_external_amount.
+pub fn tmp_storage_state(
+    pub_amount: BigInteger256,
+    ext_amount: i64,
+    relayer_fee: u64,
+) -> ChecksAndTransferState {
+    let mut data = vec![0u8; ChecksAndTransferState::LEN];
+    data[RELAYER_FEE_START..RELAYER_FEE_START + 8].copy_from_slice(&relayer_fee.to_le_bytes());
+    data[EXT_AMOUNT_START..EXT_AMOUNT_START + 8].copy_from_slice(&ext_amount.to_le_bytes());
+    pub_amount
+        .write(&mut data[AMOUNT_START..AMOUNT_START + 32])
+        .unwrap();
+    ChecksAndTransferState::unpack_from_slice(&data).unwrap()
+}
+
+// Public amount of a withdrawal of amount, the field element p - amount.
+pub fn negative_pub_amount(amount: u64) -> BigInteger256 {
+    let mut pub_amount = FqParameters::MODULUS;
+    pub_amount.sub_noborrow(&BigInteger256::from(amount));
+    pub_amount
+}
+
 #[cfg(test)]
 #[allow(dead_code)]
 pub mod tests {
@@ -81,9 +277,9 @@ pub mod tests {
     pub fn get_proof_from_bytes(
         proof_bytes: &Vec<u8>,
     ) -> ark_groth16::data_structures::Proof<ark_ec::models::bn::Bn<ark_bn254::Parameters>> {
-        let proof_a = parse_x_group_affine_from_bytes(&proof_bytes[0..64].to_vec());
-        let proof_b = parse_proof_b_from_bytes(&proof_bytes[64..192].to_vec());
-        let proof_c = parse_x_group_affine_from_bytes(&proof_bytes[192..256].to_vec());
+        let proof_a = parse_x_group_affine_from_bytes(&proof_bytes[0..64].to_vec()).unwrap();
+        let proof_b = parse_proof_b_from_bytes(&proof_bytes[64..192].to_vec()).unwrap();
+        let proof_c = parse_x_group_affine_from_bytes(&proof_bytes[192..256].to_vec()).unwrap();
         let proof =
             ark_groth16::data_structures::Proof::<ark_ec::models::bn::Bn<ark_bn254::Parameters>> {
                 a: proof_a,
@@ -0,0 +1,105 @@
+// Records the data native programs log with sol_log_data, program-test does not add it to the
+// transaction logs. The recorder wraps the syscall stubs of program-test, it is installed once
+// per test binary after the first program test started. Tests of a binary run in parallel,
+// they have to pick their data, i.e. by the nullifiers of an event.
+use lazy_static::lazy_static;
+use light_client::event::from_log_message;
+use light_protocol_program::event::LightEvent;
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::Instruction,
+    program_stubs::{set_syscall_stubs, SyscallStubs},
+    pubkey::Pubkey,
+};
+use std::sync::{Mutex, Once};
+
+lazy_static! {
+    static ref PROGRAM_DATA: Mutex<Vec<String>> = Mutex::new(Vec::new());
+}
+
+struct ProgramDataRecorder {
+    stubs: Box<dyn SyscallStubs>,
+}
+
+impl SyscallStubs for ProgramDataRecorder {
+    fn sol_log(&self, message: &str) {
+        self.stubs.sol_log(message)
+    }
+    fn sol_log_compute_units(&self) {
+        self.stubs.sol_log_compute_units()
+    }
+    fn sol_invoke_signed(
+        &self,
+        instruction: &Instruction,
+        account_infos: &[AccountInfo],
+        signers_seeds: &[&[&[u8]]],
+    ) -> ProgramResult {
+        self.stubs
+            .sol_invoke_signed(instruction, account_infos, signers_seeds)
+    }
+    fn sol_get_clock_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.stubs.sol_get_clock_sysvar(var_addr)
+    }
+    fn sol_get_epoch_schedule_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.stubs.sol_get_epoch_schedule_sysvar(var_addr)
+    }
+    fn sol_get_fees_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.stubs.sol_get_fees_sysvar(var_addr)
+    }
+    fn sol_get_rent_sysvar(&self, var_addr: *mut u8) -> u64 {
+        self.stubs.sol_get_rent_sysvar(var_addr)
+    }
+    unsafe fn sol_memcpy(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.stubs.sol_memcpy(dst, src, n)
+    }
+    unsafe fn sol_memmove(&self, dst: *mut u8, src: *const u8, n: usize) {
+        self.stubs.sol_memmove(dst, src, n)
+    }
+    unsafe fn sol_memcmp(&self, s1: *const u8, s2: *const u8, n: usize, result: *mut i32) {
+        self.stubs.sol_memcmp(s1, s2, n, result)
+    }
+    unsafe fn sol_memset(&self, s: *mut u8, c: u8, n: usize) {
+        self.stubs.sol_memset(s, c, n)
+    }
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        self.stubs.sol_get_return_data()
+    }
+    fn sol_set_return_data(&self, data: &[u8]) {
+        self.stubs.sol_set_return_data(data)
+    }
+    // Recorded like the runtime logs it.
+    fn sol_log_data(&self, data: &[&[u8]]) {
+        let fields: Vec<String> = data.iter().map(base64::encode).collect();
+        PROGRAM_DATA
+            .lock()
+            .unwrap()
+            .push(format!("Program data: {}", fields.join(" ")));
+        self.stubs.sol_log_data(data)
+    }
+}
+
+// Call after the program test started, program-test installs its stubs when it starts.
+pub fn record_program_data() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        let stubs = set_syscall_stubs(Box::new(DefaultStubs));
+        set_syscall_stubs(Box::new(ProgramDataRecorder { stubs }));
+    });
+}
+
+// Placeholder while the recorder swaps in the stubs of program-test.
+struct DefaultStubs;
+
+impl SyscallStubs for DefaultStubs {}
+
+// All recorded events of the test binary.
+pub fn recorded_events() -> Vec<LightEvent> {
+    PROGRAM_DATA
+        .lock()
+        .unwrap()
+        .iter()
+        .map(String::as_str)
+        .filter_map(from_log_message)
+        .collect()
+}
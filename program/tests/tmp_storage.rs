@@ -0,0 +1,82 @@
+use light_protocol_program::cpi::send_data_instruction;
+use light_protocol_program::error::LightError;
+use light_protocol_program::pda::find_tmp_storage_pda;
+use light_protocol_program::process_instruction;
+use light_protocol_program::state::InstructionIndex;
+use solana_program::{instruction::AccountMeta, program_pack::Pack, pubkey::Pubkey};
+use solana_program_test::*;
+use solana_sdk::{signature::Signer, signer::keypair::Keypair};
+
+mod test_utils;
+use crate::test_utils::{
+    custom_error, fund, process, program_id, read_send_data, PRIV_KEY_DEPOSIT,
+};
+
+// Someone who sees a pending send data transaction of the relayer tries to lock out
+// the relayer before its transaction lands.
+#[tokio::test]
+async fn send_data_should_not_be_griefed() {
+    let send_data = read_send_data("deposit.txt");
+    let relayer = Keypair::from_bytes(&PRIV_KEY_DEPOSIT).unwrap();
+    let attacker = Keypair::new();
+    let tmp_storage_pda =
+        find_tmp_storage_pda(&program_id(), &send_data[64..96], &relayer.pubkey()).0;
+    assert_ne!(
+        tmp_storage_pda,
+        find_tmp_storage_pda(&program_id(), &send_data[64..96], &attacker.pubkey()).0
+    );
+
+    let program_test = ProgramTest::new(
+        "light_protocol_program",
+        program_id(),
+        processor!(process_instruction),
+    );
+    let mut program_context = program_test.start_with_context().await;
+    fund(&mut program_context, &relayer.pubkey()).await;
+    fund(&mut program_context, &attacker.pubkey()).await;
+
+    // The attacker replays the send data from its own key.
+    let instruction = send_data_instruction(&program_id(), &attacker.pubkey(), &send_data).unwrap();
+    let error = process(&mut program_context, instruction, &attacker)
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidRelayer));
+
+    // The attacker passes the tmp storage pda of the relayer.
+    let mut instruction =
+        send_data_instruction(&program_id(), &attacker.pubkey(), &send_data).unwrap();
+    instruction.accounts[1] = AccountMeta::new(tmp_storage_pda, false);
+    let error = process(&mut program_context, instruction, &attacker)
+        .await
+        .unwrap_err();
+    assert_eq!(custom_error(error), Some(LightError::InvalidPdaAddress));
+
+    // The attacker transfers lamports to the tmp storage pda address.
+    let transaction = solana_sdk::system_transaction::transfer(
+        &attacker,
+        &tmp_storage_pda,
+        1_000_000,
+        program_context.last_blockhash,
+    );
+    program_context
+        .banks_client
+        .process_transaction(transaction)
+        .await
+        .unwrap();
+
+    // The relayer can still send the data.
+    let instruction = send_data_instruction(&program_id(), &relayer.pubkey(), &send_data).unwrap();
+    process(&mut program_context, instruction, &relayer)
+        .await
+        .unwrap();
+    let tmp_storage_account = program_context
+        .banks_client
+        .get_account(tmp_storage_pda)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(tmp_storage_account.owner, program_id());
+    let instruction_index = InstructionIndex::unpack(&tmp_storage_account.data).unwrap();
+    assert_eq!(instruction_index.signer_pubkey, relayer.pubkey());
+    assert_eq!(instruction_index.current_instruction_index, 1);
+}